@@ -7,7 +7,8 @@ mod error;
 mod routes;
 mod state;
 
-use database::Database;
+use database::{Database, DatabaseConfig};
+use shutdown_signal::shutdown_signal;
 use tower_http::services::ServeDir;
 use tracing::info;
 
@@ -26,11 +27,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!(addr = %config.addr, "Starting admin web server");
 
     // Connect to database
-    let db = Database::connect(&config.database_url).await?;
+    let db =
+        Database::connect_with_config(&config.database_url, &DatabaseConfig::from_env()).await?;
     db.migrate().await?;
 
     // Build application state
-    let state = AppState::new(db, config.proton);
+    let state = AppState::new(db.clone(), config.proton);
 
     // Build router
     let app = routes::router()
@@ -40,7 +42,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Start server
     info!(addr = %config.addr, "Admin web server listening");
     let listener = tokio::net::TcpListener::bind(config.addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    // In-flight requests have drained; release the database pool.
+    db.close().await;
+    info!("Admin web server stopped");
 
     Ok(())
 }
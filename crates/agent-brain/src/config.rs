@@ -11,6 +11,72 @@ pub struct AgentBrainConfig {
     pub sqlite_url: String,
     /// Default language to store for new users.
     pub default_language: String,
+    /// First-contact onboarding flow.
+    pub onboarding: OnboardingConfig,
+}
+
+/// Messages and step toggles for first-contact onboarding.
+///
+/// New users get `welcome_message` followed by the prompt for the first
+/// enabled step; each reply advances to the next step until
+/// `complete_message`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnboardingConfig {
+    /// Run onboarding for new users at all.
+    pub enabled: bool,
+    /// Greeting sent on first contact.
+    pub welcome_message: String,
+    /// Ask for the user's preferred language.
+    pub ask_language: bool,
+    /// Prompt for the language step.
+    pub language_prompt: String,
+    /// Ask for the user's region.
+    pub ask_region: bool,
+    /// Prompt for the region step.
+    pub region_prompt: String,
+    /// Sent once every enabled step is answered.
+    pub complete_message: String,
+}
+
+impl Default for OnboardingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            welcome_message:
+                "Welcome! Send a message to get started, or 'help' for commands.".to_string(),
+            ask_language: true,
+            language_prompt: "Which language should I use? (e.g. English, Arabic, Farsi)"
+                .to_string(),
+            ask_region: false,
+            region_prompt: "Which region are you interested in? (e.g. Iran, Syria)".to_string(),
+            complete_message: "You're all set. Send 'help' for commands.".to_string(),
+        }
+    }
+}
+
+impl OnboardingConfig {
+    /// Load onboarding settings from environment variables.
+    ///
+    /// Optional env vars:
+    /// - `AMAN_ONBOARDING_ENABLED` (default: true)
+    /// - `AMAN_ONBOARDING_WELCOME`
+    /// - `AMAN_ONBOARDING_ASK_LANGUAGE` (default: true)
+    /// - `AMAN_ONBOARDING_LANGUAGE_PROMPT`
+    /// - `AMAN_ONBOARDING_ASK_REGION` (default: false)
+    /// - `AMAN_ONBOARDING_REGION_PROMPT`
+    /// - `AMAN_ONBOARDING_COMPLETE`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: env_flag("AMAN_ONBOARDING_ENABLED", defaults.enabled),
+            welcome_message: env_text("AMAN_ONBOARDING_WELCOME", defaults.welcome_message),
+            ask_language: env_flag("AMAN_ONBOARDING_ASK_LANGUAGE", defaults.ask_language),
+            language_prompt: env_text("AMAN_ONBOARDING_LANGUAGE_PROMPT", defaults.language_prompt),
+            ask_region: env_flag("AMAN_ONBOARDING_ASK_REGION", defaults.ask_region),
+            region_prompt: env_text("AMAN_ONBOARDING_REGION_PROMPT", defaults.region_prompt),
+            complete_message: env_text("AMAN_ONBOARDING_COMPLETE", defaults.complete_message),
+        }
+    }
 }
 
 impl AgentBrainConfig {
@@ -21,6 +87,7 @@ impl AgentBrainConfig {
         Self {
             sqlite_url,
             default_language: "English".to_string(),
+            onboarding: OnboardingConfig::default(),
         }
     }
 
@@ -31,6 +98,7 @@ impl AgentBrainConfig {
     ///
     /// Optional env vars:
     /// - `AMAN_DEFAULT_LANGUAGE` (default: English)
+    /// - `AMAN_ONBOARDING_*` (see [`OnboardingConfig::from_env`])
     pub fn from_env() -> Result<Self, BrainError> {
         let sqlite_path = env::var("SQLITE_PATH")
             .unwrap_or_else(|_| "./data/aman.db".to_string());
@@ -41,10 +109,24 @@ impl AgentBrainConfig {
         Ok(Self {
             sqlite_url,
             default_language,
+            onboarding: OnboardingConfig::from_env(),
         })
     }
 }
 
+fn env_flag(key: &str, default: bool) -> bool {
+    env::var(key)
+        .map(|value| matches!(value.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on"))
+        .unwrap_or(default)
+}
+
+fn env_text(key: &str, default: String) -> String {
+    env::var(key)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or(default)
+}
+
 fn sqlite_url_from_path(path: &str) -> String {
     if path.starts_with("sqlite:") {
         path.to_string()
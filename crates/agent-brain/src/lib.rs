@@ -12,8 +12,9 @@
 //! │           AGENT-BRAIN               │
 //! │                                     │
 //! │  1. Ensure user exists in DB        │
-//! │  2. Parse command                   │
-//! │  3. Return appropriate response     │
+//! │  2. Run onboarding for new users    │
+//! │  3. Parse command                   │
+//! │  4. Return appropriate response     │
 //! └─────────────────────────────────────┘
 //!        ↓
 //! Response back to user
@@ -50,12 +51,20 @@
 //! |----------|---------|-------------|
 //! | `SQLITE_PATH` | `./data/aman.db` | SQLite database path |
 //! | `AMAN_DEFAULT_LANGUAGE` | `English` | Default language for new users |
+//! | `AMAN_ONBOARDING_ENABLED` | `true` | Run first-contact onboarding |
+//! | `AMAN_ONBOARDING_WELCOME` | built-in | Greeting sent on first contact |
+//! | `AMAN_ONBOARDING_ASK_LANGUAGE` | `true` | Ask for the user's language |
+//! | `AMAN_ONBOARDING_LANGUAGE_PROMPT` | built-in | Language step prompt |
+//! | `AMAN_ONBOARDING_ASK_REGION` | `false` | Ask for the user's region |
+//! | `AMAN_ONBOARDING_REGION_PROMPT` | built-in | Region step prompt |
+//! | `AMAN_ONBOARDING_COMPLETE` | built-in | Sent when onboarding finishes |
 
 mod brain;
 mod config;
+mod onboarding;
 
 pub use brain::AgentBrain;
-pub use config::AgentBrainConfig;
+pub use config::{AgentBrainConfig, OnboardingConfig};
 
 pub fn version() -> &'static str {
     env!("CARGO_PKG_VERSION")
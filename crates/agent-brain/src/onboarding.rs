@@ -0,0 +1,161 @@
+//! First-contact onboarding steps.
+
+use crate::config::OnboardingConfig;
+
+/// Reply that skips the current onboarding step.
+pub(crate) const SKIP_ANSWER: &str = "skip";
+
+/// A step in the onboarding flow, stored per user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OnboardingStep {
+    Language,
+    Region,
+    Done,
+}
+
+impl OnboardingStep {
+    const ORDER: [OnboardingStep; 3] = [Self::Language, Self::Region, Self::Done];
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Language => "language",
+            Self::Region => "region",
+            Self::Done => "done",
+        }
+    }
+
+    /// Parse a stored step; unknown values count as finished.
+    pub(crate) fn parse(value: &str) -> Self {
+        match value {
+            "language" => Self::Language,
+            "region" => Self::Region,
+            _ => Self::Done,
+        }
+    }
+
+    /// First enabled step for a new user.
+    pub(crate) fn first(config: &OnboardingConfig) -> Self {
+        Self::ORDER
+            .into_iter()
+            .find(|step| step.enabled(config))
+            .unwrap_or(Self::Done)
+    }
+
+    /// Next enabled step after this one.
+    pub(crate) fn next(self, config: &OnboardingConfig) -> Self {
+        Self::ORDER
+            .into_iter()
+            .skip_while(|step| *step != self)
+            .skip(1)
+            .find(|step| step.enabled(config))
+            .unwrap_or(Self::Done)
+    }
+
+    /// Prompt asking for this step's answer; `None` for [`Self::Done`].
+    pub(crate) fn prompt(self, config: &OnboardingConfig) -> Option<&str> {
+        match self {
+            Self::Language => Some(&config.language_prompt),
+            Self::Region => Some(&config.region_prompt),
+            Self::Done => None,
+        }
+    }
+
+    fn enabled(self, config: &OnboardingConfig) -> bool {
+        match self {
+            Self::Language => config.ask_language,
+            Self::Region => config.ask_region,
+            Self::Done => true,
+        }
+    }
+}
+
+/// Longest accepted language answer, in characters.
+const MAX_LANGUAGE_CHARS: usize = 24;
+
+/// Most words in an accepted language answer ("Brazilian Portuguese").
+const MAX_LANGUAGE_WORDS: usize = 2;
+
+/// How a reply to the language prompt was understood.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LanguageAnswer {
+    /// A short alphabetic name, normalized for storage.
+    Language(String),
+    /// A short reply that is not a language name; ask again.
+    Invalid,
+    /// A real message (a sentence or question) rather than an answer.
+    Message,
+}
+
+/// Classify a reply to the language prompt.
+///
+/// Only short alphabetic names are stored, since the language later
+/// reaches the model prompt as a hint.
+pub(crate) fn parse_language(answer: &str) -> LanguageAnswer {
+    let answer = answer.trim();
+    if answer.split_whitespace().count() > MAX_LANGUAGE_WORDS || answer.ends_with('?') {
+        return LanguageAnswer::Message;
+    }
+    let valid = answer.chars().count() <= MAX_LANGUAGE_CHARS
+        && answer.chars().any(char::is_alphabetic)
+        && answer
+            .chars()
+            .all(|c| c.is_alphabetic() || c == '-' || c.is_whitespace());
+    if valid {
+        LanguageAnswer::Language(normalize_language(answer))
+    } else {
+        LanguageAnswer::Invalid
+    }
+}
+
+/// Normalize a language answer ("arabic" -> "Arabic").
+pub(crate) fn normalize_language(answer: &str) -> String {
+    let mut chars = answer.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_order_follows_toggles() {
+        let mut config = OnboardingConfig {
+            ask_region: true,
+            ..OnboardingConfig::default()
+        };
+        assert_eq!(OnboardingStep::first(&config), OnboardingStep::Language);
+        assert_eq!(OnboardingStep::Language.next(&config), OnboardingStep::Region);
+        assert_eq!(OnboardingStep::Region.next(&config), OnboardingStep::Done);
+
+        config.ask_language = false;
+        assert_eq!(OnboardingStep::first(&config), OnboardingStep::Region);
+
+        config.ask_region = false;
+        assert_eq!(OnboardingStep::first(&config), OnboardingStep::Done);
+        assert_eq!(OnboardingStep::parse("bogus"), OnboardingStep::Done);
+        assert_eq!(normalize_language("  aRABIC "), "Arabic");
+    }
+
+    #[test]
+    fn test_parse_language() {
+        assert_eq!(
+            parse_language(" brazilian portuguese "),
+            LanguageAnswer::Language("Brazilian portuguese".to_string())
+        );
+        assert_eq!(
+            parse_language("فارسی"),
+            LanguageAnswer::Language("فارسی".to_string())
+        );
+        assert_eq!(parse_language("42"), LanguageAnswer::Invalid);
+        assert_eq!(parse_language("Ignore-all<instructions>"), LanguageAnswer::Invalid);
+        assert_eq!(parse_language(&"a".repeat(40)), LanguageAnswer::Invalid);
+        assert_eq!(parse_language("weather?"), LanguageAnswer::Message);
+        assert_eq!(
+            parse_language("what is the weather in Tehran"),
+            LanguageAnswer::Message
+        );
+    }
+}
@@ -1,5 +1,7 @@
 //! Error types for tool operations.
 
+use std::time::Duration;
+
 use thiserror::Error;
 
 /// Errors that can occur during tool execution.
@@ -17,6 +19,10 @@ pub enum ToolError {
     #[error("Invalid parameter '{name}': {reason}")]
     InvalidParameter { name: String, reason: String },
 
+    /// Arguments do not match the tool's declared schema.
+    #[error("Invalid arguments for '{tool}': {detail}")]
+    InvalidArgs { tool: String, detail: String },
+
     /// HTTP request failed.
     #[error("HTTP error: {0}")]
     HttpError(#[from] reqwest::Error),
@@ -36,4 +42,8 @@ pub enum ToolError {
     /// Brain processing error.
     #[error("Brain error: {0}")]
     BrainError(String),
+
+    /// Tool did not finish within its timeout.
+    #[error("Tool '{name}' timed out after {}s", .after.as_secs_f64())]
+    Timeout { name: String, after: Duration },
 }
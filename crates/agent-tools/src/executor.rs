@@ -368,12 +368,15 @@ impl RegistryToolExecutor {
 
     fn format_result(&self, tool: &str, output: &ToolOutput) -> String {
         if self.policy.format_results_as_json {
-            serde_json::json!({
+            let mut result = serde_json::json!({
                 "tool": tool,
                 "success": output.success,
                 "content": output.content,
-            })
-            .to_string()
+            });
+            if let Some(data) = &output.data {
+                result["data"] = data.clone();
+            }
+            result.to_string()
         } else {
             output.content.clone()
         }
@@ -521,6 +524,32 @@ mod tests {
         assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn test_json_results_include_data() {
+        let mut registry = ToolRegistry::new();
+        registry.register(crate::tools::UnitConverter::new());
+        let executor = RegistryToolExecutor::with_policy(
+            registry,
+            ToolPolicy::default().with_json_results(true),
+        );
+
+        let mut args = HashMap::new();
+        args.insert("value".to_string(), serde_json::json!(1));
+        args.insert("from".to_string(), serde_json::json!("km"));
+        args.insert("to".to_string(), serde_json::json!("m"));
+        let request = ToolRequest {
+            id: "1".to_string(),
+            name: "unit_converter".to_string(),
+            arguments: args,
+            metadata: None,
+        };
+
+        let result = executor.execute(request).await;
+        let json: Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(json["content"], "1 km = 1000 m");
+        assert_eq!(json["data"]["result"], 1000.0);
+    }
+
     #[tokio::test]
     async fn test_cache_hits() {
         let counter = Arc::new(AtomicUsize::new(0));
@@ -0,0 +1,286 @@
+//! Map loosely-structured, free-text tool calls to structured arguments.
+//!
+//! The router normally emits `use_tool` actions with structured args, but a
+//! model sometimes just describes what it wants ("convert 10 km to miles").
+//! Each supported tool has a small parser that turns such text into the
+//! arguments its schema expects. Tools without a parser fall back to their
+//! schema: if it has exactly one required string argument, the whole text is
+//! passed as that argument.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::error::ToolError;
+use crate::tool::Tool;
+
+/// Words that introduce the target unit or currency.
+const CONNECTORS: &[&str] = &["to", "in", "into", "as"];
+
+/// Leading filler stripped before parsing.
+const FILLER_PREFIXES: &[&str] = &[
+    "please ",
+    "convert ",
+    "calculate ",
+    "compute ",
+    "evaluate ",
+    "what is ",
+    "what's ",
+    "whats ",
+    "how much is ",
+];
+
+/// Function and constant names the calculator understands.
+const MATH_WORDS: &[&str] = &[
+    "sin", "cos", "tan", "asin", "acos", "atan", "sqrt", "ln", "log", "exp", "abs", "floor",
+    "ceil", "round", "pi", "e",
+];
+
+/// Build arguments for `tool` from a free-text description.
+///
+/// Returns [`ToolError::InvalidArgs`] when the text cannot be interpreted.
+pub fn args_from_text(tool: &dyn Tool, text: &str) -> Result<HashMap<String, Value>, ToolError> {
+    let parsed = match tool.name() {
+        "calculator" => parse_calculation(text),
+        "unit_converter" => parse_unit_conversion(text),
+        "currency_converter" => parse_currency_conversion(text),
+        _ => tool
+            .args_schema()
+            .and_then(|schema| single_string_arg(&schema, text)),
+    };
+
+    parsed.ok_or_else(|| ToolError::InvalidArgs {
+            tool: tool.name().to_string(),
+            detail: format!("could not interpret \"{}\"", text.trim()),
+        })
+}
+
+/// Use the whole text for schemas with exactly one required string argument.
+fn single_string_arg(schema: &Value, text: &str) -> Option<HashMap<String, Value>> {
+    let required = schema.get("required")?.as_array()?;
+    let [name] = required.as_slice() else {
+        return None;
+    };
+    let name = name.as_str()?;
+    let kind = schema.get("properties")?.get(name)?.get("type")?.as_str()?;
+    let text = text.trim();
+    if kind != "string" || text.is_empty() {
+        return None;
+    }
+    Some(HashMap::from([(name.to_string(), json!(text))]))
+}
+
+/// Lowercase, trim trailing punctuation and strip leading filler words.
+fn normalize(text: &str) -> String {
+    let mut text = text
+        .trim()
+        .trim_end_matches(['?', '.', '!'])
+        .trim()
+        .to_lowercase();
+    while let Some(rest) = FILLER_PREFIXES
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix))
+    {
+        text = rest.trim_start().to_string();
+    }
+    text
+}
+
+fn parse_calculation(text: &str) -> Option<HashMap<String, Value>> {
+    let mut expression = format!(" {} ", normalize(text));
+    for (word, symbol) in [
+        (" multiplied by ", " * "),
+        (" divided by ", " / "),
+        (" to the power of ", " ^ "),
+        (" times ", " * "),
+        (" plus ", " + "),
+        (" minus ", " - "),
+        (" over ", " / "),
+        (" x ", " * "),
+    ] {
+        expression = expression.replace(word, symbol);
+    }
+    let expression = expression.trim();
+
+    // Only digits, operators and known function/constant names: anything
+    // else reads as prose rather than a calculation.
+    let is_math = expression
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || " .,+-*/^()%".contains(c));
+    let known_words = expression
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|word| !word.is_empty())
+        .all(|word| MATH_WORDS.contains(&word));
+    if !is_math || !known_words || !expression.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(HashMap::from([(
+        "expression".to_string(),
+        json!(expression.replace(',', "")),
+    )]))
+}
+
+/// A quantity with a source and target unit, e.g. "10 km to miles".
+struct Conversion {
+    value: f64,
+    from: String,
+    to: String,
+}
+
+/// Parse "<number><unit> <connector> <unit>", allowing a leading currency
+/// symbol and a unit attached to the number ("10km", "$50").
+fn parse_conversion(text: &str) -> Option<Conversion> {
+    let text = normalize(text);
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let connector = tokens
+        .iter()
+        .rposition(|token| CONNECTORS.contains(token))?;
+    let (quantity, target) = (&tokens[..connector], &tokens[connector + 1..]);
+    if quantity.is_empty() || target.is_empty() {
+        return None;
+    }
+
+    let quantity = quantity.join(" ");
+    let (symbol, rest) = match quantity.chars().next()? {
+        c @ ('$' | '€' | '£' | '¥') => (Some(c), &quantity[c.len_utf8()..]),
+        _ => (None, quantity.as_str()),
+    };
+    let number_len = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(rest.len());
+    let value: f64 = rest[..number_len].replace(',', "").parse().ok()?;
+    let unit = rest[number_len..].trim();
+    let from = match (symbol, unit.is_empty()) {
+        (Some(symbol), true) => symbol.to_string(),
+        (_, false) => unit.to_string(),
+        (None, true) => return None,
+    };
+
+    Some(Conversion {
+        value,
+        from,
+        to: target.join(" "),
+    })
+}
+
+fn parse_unit_conversion(text: &str) -> Option<HashMap<String, Value>> {
+    let conversion = parse_conversion(text)?;
+    Some(HashMap::from([
+        ("value".to_string(), json!(conversion.value)),
+        ("from".to_string(), json!(conversion.from)),
+        ("to".to_string(), json!(conversion.to)),
+    ]))
+}
+
+fn parse_currency_conversion(text: &str) -> Option<HashMap<String, Value>> {
+    let conversion = parse_conversion(text)?;
+    Some(HashMap::from([
+        ("amount".to_string(), json!(conversion.value)),
+        ("from".to_string(), json!(currency_code(&conversion.from)?)),
+        ("to".to_string(), json!(currency_code(&conversion.to)?)),
+    ]))
+}
+
+/// Map a currency symbol, name, or code to an ISO 4217 code.
+fn currency_code(input: &str) -> Option<String> {
+    let code = match input.trim() {
+        "$" | "dollar" | "dollars" | "us dollars" => "USD",
+        "€" | "euro" | "euros" => "EUR",
+        "£" | "pound" | "pounds" | "british pounds" => "GBP",
+        "¥" | "yen" | "japanese yen" => "JPY",
+        "yuan" | "renminbi" => "CNY",
+        "rupee" | "rupees" => "INR",
+        "franc" | "francs" | "swiss francs" => "CHF",
+        "canadian dollars" => "CAD",
+        "australian dollars" => "AUD",
+        "pesos" | "mexican pesos" => "MXN",
+        code if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) => {
+            return Some(code.to_uppercase())
+        }
+        _ => return None,
+    };
+    Some(code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::{Calculator, CurrencyConverter, Dictionary, UnitConverter};
+
+    fn args(tool: &dyn Tool, text: &str) -> Value {
+        json!(args_from_text(tool, text).unwrap())
+    }
+
+    #[test]
+    fn test_calculator_text() {
+        let calc = Calculator::new();
+        assert_eq!(args(&calc, "2 + 2 * 3"), json!({"expression": "2 + 2 * 3"}));
+        assert_eq!(
+            args(&calc, "What is 15 times 4?"),
+            json!({"expression": "15 * 4"})
+        );
+        assert_eq!(
+            args(&calc, "calculate sqrt(16) plus 1,000"),
+            json!({"expression": "sqrt(16) + 1000"})
+        );
+    }
+
+    #[test]
+    fn test_unit_converter_text() {
+        let converter = UnitConverter::new();
+        assert_eq!(
+            args(&converter, "convert 10 km to miles"),
+            json!({"value": 10.0, "from": "km", "to": "miles"})
+        );
+        assert_eq!(
+            args(&converter, "5.5kg in lb"),
+            json!({"value": 5.5, "from": "kg", "to": "lb"})
+        );
+        assert_eq!(
+            args(&converter, "What's 100 fahrenheit in celsius?"),
+            json!({"value": 100.0, "from": "fahrenheit", "to": "celsius"})
+        );
+    }
+
+    #[test]
+    fn test_currency_converter_text() {
+        let converter = CurrencyConverter::new();
+        assert_eq!(
+            args(&converter, "convert 100 usd to eur"),
+            json!({"amount": 100.0, "from": "USD", "to": "EUR"})
+        );
+        assert_eq!(
+            args(&converter, "how much is $1,250 in euros?"),
+            json!({"amount": 1250.0, "from": "USD", "to": "EUR"})
+        );
+        assert_eq!(
+            args(&converter, "50 pounds into yen"),
+            json!({"amount": 50.0, "from": "GBP", "to": "JPY"})
+        );
+    }
+
+    #[test]
+    fn test_uninterpretable_text() {
+        let converter = CurrencyConverter::new();
+        for text in ["convert money please", "100 to eur", "100 zorkmids to eur"] {
+            let err = args_from_text(&converter, text).unwrap_err();
+            assert!(matches!(err, ToolError::InvalidArgs { .. }), "{}", text);
+        }
+        assert!(args_from_text(&Calculator::new(), "tell me a joke").is_err());
+    }
+
+    #[test]
+    fn test_single_string_schema_fallback() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "word": { "type": "string" } },
+            "required": ["word"]
+        });
+        assert_eq!(
+            json!(single_string_arg(&schema, " serendipity ").unwrap()),
+            json!({"word": "serendipity"})
+        );
+        // Tools without a schema or parser cannot be mapped.
+        assert!(args_from_text(&Dictionary::new(), "serendipity").is_err());
+    }
+}
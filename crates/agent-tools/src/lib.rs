@@ -16,12 +16,15 @@
 //!
 //! ## Utility Tools
 //! - [`Calculator`] - Safe mathematical expression evaluation using `meval`.
-//! - [`Weather`] - Weather information via wttr.in (no API key needed).
+//! - [`Weather`] - Weather via wttr.in or Open-Meteo (no API key needed), metric or imperial.
 //! - [`WebFetch`] - Fetch URL content, convert HTML to text, optionally summarize.
 //! - [`Dictionary`] - Word definitions via Free Dictionary API.
 //! - [`WorldTime`] - Current time in any timezone via WorldTimeAPI.
 //! - [`UnitConverter`] - Convert between common units (length, weight, temperature, etc.).
 //! - [`RandomNumber`] - Generate random numbers, dice rolls, or coin flips.
+//! - [`WikiSummary`] - Wikipedia article summaries via the Wikipedia REST API.
+//! - [`HttpTool`] - Operator-defined tools that call an HTTP endpoint, loaded from
+//!   `AMAN_HTTP_TOOLS` / `AMAN_HTTP_TOOLS_FILE`.
 //!
 //! ## Financial Tools
 //! - [`BitcoinPrice`] - Bitcoin price via mempool.space (privacy-friendly).
@@ -51,37 +54,50 @@
 
 mod error;
 mod executor;
+mod intent;
+mod postprocess;
 mod registry;
+mod schema;
 mod tool;
 pub mod tools;
 
 pub use error::ToolError;
 pub use executor::{RateLimit, RegistryToolExecutor, ToolPolicy};
-pub use registry::ToolRegistry;
+pub use intent::args_from_text;
+pub use postprocess::{PiiRedactor, ResultPostProcessor, REDACT_TOOL_RESULTS_ENV};
+pub use registry::{ToolRegistry, DEFAULT_MAX_OUTPUT_CHARS, DEFAULT_TOOL_TIMEOUT};
 pub use tool::{Tool, ToolArgs, ToolOutput};
 pub use tools::{
     sanitize_system_prompt, BitcoinPrice, Calculator, CryptoPrice, CurrencyConverter, Dictionary,
-    RandomNumber, Sanitize, UnitConverter, Weather, WebFetch, WorldTime,
+    HttpTool, HttpToolConfig, RandomNumber, Sanitize, Translate, UnitConverter, Weather,
+    WeatherEndpoints, WeatherProvider, WeatherUnits, WebFetch, WikiSummary, WorldTime,
 };
 
 // Re-export async_trait for convenience
 pub use async_trait::async_trait;
 
+/// Timeout for tools that wait on a brain (summaries, sanitizing, translation).
+const BRAIN_TOOL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(90);
+
 /// Create a new registry with all built-in tools registered.
 ///
-/// Note: The `sanitize` tool requires a brain for PII detection.
+/// Note: The `sanitize` and `translate` tools require a brain.
 /// Call `registry.set_brain(brain)` to enable it.
+///
+/// Tools use [`DEFAULT_TOOL_TIMEOUT`], except brain-backed tools which get
+/// longer to respond.
 pub fn default_registry() -> ToolRegistry {
     let mut registry = ToolRegistry::new();
 
     // Utility tools
     registry.register(Calculator::new());
-    registry.register(Weather::new());
-    registry.register(WebFetch::new());
+    registry.register(Weather::from_env());
+    registry.register_with_timeout(WebFetch::new(), BRAIN_TOOL_TIMEOUT);
     registry.register(Dictionary::new());
     registry.register(WorldTime::new());
-    registry.register(UnitConverter::new());
+    registry.register(UnitConverter::from_env());
     registry.register(RandomNumber::new());
+    registry.register(WikiSummary::new());
 
     // Financial tools
     registry.register(BitcoinPrice::new());
@@ -89,7 +105,15 @@ pub fn default_registry() -> ToolRegistry {
     registry.register(CurrencyConverter::new());
 
     // AI-powered tools (require brain to be set)
-    registry.register(Sanitize::new());
+    registry.register_with_timeout(Sanitize::new(), BRAIN_TOOL_TIMEOUT);
+    registry.register_with_timeout(Translate::new(), BRAIN_TOOL_TIMEOUT);
+
+    // Operator-defined HTTP tools
+    registry.register_http_tools(HttpTool::from_env());
+
+    if PiiRedactor::enabled_from_env() {
+        registry.add_post_processor(PiiRedactor);
+    }
 
     registry
 }
@@ -0,0 +1,82 @@
+//! Hooks applied to every tool result after execution.
+
+use brain_core::redact_pii;
+use serde_json::Value;
+
+use crate::tool::ToolOutput;
+
+/// Environment variable that enables [`PiiRedactor`] in the default registry.
+pub const REDACT_TOOL_RESULTS_ENV: &str = "AMAN_TOOL_RESULTS_REDACT_PII";
+
+/// Transforms a tool's output before the registry returns it.
+///
+/// Post-processors run in registration order, each on the previous one's
+/// output, for successful and failed results alike. Plain closures
+/// `Fn(&str, ToolOutput) -> ToolOutput` work too.
+pub trait ResultPostProcessor: Send + Sync {
+    /// Process the output of tool `tool`.
+    fn process(&self, tool: &str, output: ToolOutput) -> ToolOutput;
+}
+
+impl<F> ResultPostProcessor for F
+where
+    F: Fn(&str, ToolOutput) -> ToolOutput + Send + Sync,
+{
+    fn process(&self, tool: &str, output: ToolOutput) -> ToolOutput {
+        self(tool, output)
+    }
+}
+
+/// Redacts emails and long digit runs from `content` and from every string
+/// in `data`, using the same rules as memory prompt redaction.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PiiRedactor;
+
+impl PiiRedactor {
+    /// Whether `AMAN_TOOL_RESULTS_REDACT_PII` asks for redaction.
+    pub fn enabled_from_env() -> bool {
+        std::env::var(REDACT_TOOL_RESULTS_ENV)
+            .map(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false)
+    }
+
+    fn redact_value(value: &mut Value) {
+        match value {
+            Value::String(text) => *text = redact_pii(text),
+            Value::Array(items) => items.iter_mut().for_each(Self::redact_value),
+            Value::Object(map) => map.values_mut().for_each(Self::redact_value),
+            _ => {}
+        }
+    }
+}
+
+impl ResultPostProcessor for PiiRedactor {
+    fn process(&self, _tool: &str, mut output: ToolOutput) -> ToolOutput {
+        output.content = redact_pii(&output.content);
+        if let Some(data) = output.data.as_mut() {
+            Self::redact_value(data);
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_pii_redactor_covers_content_and_data() {
+        let output = ToolOutput::success("Contact ana@example.org or 5551234567")
+            .with_data(json!({"contact": {"email": "ana@example.org", "phones": ["5551234567"]}}));
+
+        let redacted = PiiRedactor.process("lookup", output);
+        assert_eq!(redacted.content, "Contact [REDACTED_EMAIL] or [REDACTED_NUMBER]");
+        assert_eq!(
+            redacted.data,
+            Some(json!({
+                "contact": {"email": "[REDACTED_EMAIL]", "phones": ["[REDACTED_NUMBER]"]}
+            }))
+        );
+    }
+}
@@ -2,23 +2,46 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use brain_core::Brain;
 use serde_json::Value;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::error::ToolError;
+use crate::intent::args_from_text;
+use crate::postprocess::ResultPostProcessor;
+use crate::schema::validate_args;
 use crate::tool::{Tool, ToolArgs, ToolOutput};
+use crate::tools::HttpTool;
+
+/// Default time limit for a single tool execution.
+pub const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default cap on a tool's output `content`, in characters.
+pub const DEFAULT_MAX_OUTPUT_CHARS: usize = 8000;
 
 /// Registry for managing tools.
 ///
 /// The registry holds a collection of tools and can dispatch execution
-/// requests to the appropriate tool by name.
+/// requests to the appropriate tool by name. Every execution is bounded by
+/// a timeout and its output by a character limit: the tool's own override if
+/// set, otherwise the registry default.
 pub struct ToolRegistry {
     /// Registered tools by name.
     tools: HashMap<String, Arc<dyn Tool>>,
     /// Optional shared brain for tools that need AI processing.
     brain: Option<Arc<dyn Brain>>,
+    /// Per-tool timeout overrides.
+    timeouts: HashMap<String, Duration>,
+    /// Timeout for tools without an override.
+    default_timeout: Duration,
+    /// Per-tool output length overrides.
+    max_output_chars: HashMap<String, usize>,
+    /// Output length limit for tools without an override.
+    default_max_output_chars: usize,
+    /// Applied in order to every tool output.
+    post_processors: Vec<Arc<dyn ResultPostProcessor>>,
 }
 
 impl ToolRegistry {
@@ -27,14 +50,19 @@ impl ToolRegistry {
         Self {
             tools: HashMap::new(),
             brain: None,
+            timeouts: HashMap::new(),
+            default_timeout: DEFAULT_TOOL_TIMEOUT,
+            max_output_chars: HashMap::new(),
+            default_max_output_chars: DEFAULT_MAX_OUTPUT_CHARS,
+            post_processors: Vec::new(),
         }
     }
 
     /// Create a registry with a shared brain for AI-powered tools.
     pub fn with_brain(brain: Arc<dyn Brain>) -> Self {
         Self {
-            tools: HashMap::new(),
             brain: Some(brain),
+            ..Self::new()
         }
     }
 
@@ -52,6 +80,73 @@ impl ToolRegistry {
         self.tools.insert(name, Arc::new(tool));
     }
 
+    /// Register a tool with its own timeout.
+    pub fn register_with_timeout<T: Tool + 'static>(&mut self, tool: T, timeout: Duration) {
+        self.timeouts.insert(tool.name().to_string(), timeout);
+        self.register(tool);
+    }
+
+    /// Set the timeout for tools without an override.
+    pub fn set_default_timeout(&mut self, timeout: Duration) {
+        self.default_timeout = timeout;
+    }
+
+    /// Override the timeout for one tool by name.
+    pub fn set_timeout(&mut self, name: impl Into<String>, timeout: Duration) {
+        self.timeouts.insert(name.into(), timeout);
+    }
+
+    /// Effective timeout for a tool.
+    pub fn timeout_for(&self, name: &str) -> Duration {
+        self.timeouts
+            .get(name)
+            .copied()
+            .unwrap_or(self.default_timeout)
+    }
+
+    /// Set the output length limit for tools without an override.
+    pub fn set_default_max_output_chars(&mut self, max_chars: usize) {
+        self.default_max_output_chars = max_chars;
+    }
+
+    /// Override the output length limit for one tool by name.
+    pub fn set_max_output_chars(&mut self, name: impl Into<String>, max_chars: usize) {
+        self.max_output_chars.insert(name.into(), max_chars);
+    }
+
+    /// Effective output length limit for a tool.
+    pub fn max_output_chars_for(&self, name: &str) -> usize {
+        self.max_output_chars
+            .get(name)
+            .copied()
+            .unwrap_or(self.default_max_output_chars)
+    }
+
+    /// Add a post-processor that runs on every tool output, after any added earlier.
+    pub fn add_post_processor<P: ResultPostProcessor + 'static>(&mut self, processor: P) {
+        self.post_processors.push(Arc::new(processor));
+    }
+
+    /// Register config-defined HTTP tools, applying their timeout overrides.
+    ///
+    /// A tool whose name is already registered is skipped, so configuration
+    /// cannot shadow a built-in. Returns how many tools were registered.
+    pub fn register_http_tools(&mut self, tools: impl IntoIterator<Item = HttpTool>) -> usize {
+        let mut registered = 0;
+        for tool in tools {
+            if self.has_tool(tool.name()) {
+                warn!("Skipping HTTP tool '{}': name already registered", tool.name());
+                continue;
+            }
+            match tool.timeout() {
+                Some(timeout) => self.register_with_timeout(tool, timeout),
+                None => self.register(tool),
+            }
+            registered += 1;
+        }
+        registered
+    }
+
     /// Register a boxed tool.
     pub fn register_boxed(&mut self, tool: Arc<dyn Tool>) {
         let name = tool.name().to_string();
@@ -85,6 +180,13 @@ impl ToolRegistry {
     /// Execute a tool by name with the given parameters.
     ///
     /// The registry will automatically inject the shared brain if available.
+    /// The output passes through every post-processor, then its content is
+    /// clamped to [`max_output_chars_for`](Self::max_output_chars_for). Structured
+    /// `data` whose JSON exceeds the same limit is dropped, since it cannot be cut
+    /// without breaking it.
+    /// Returns [`ToolError::InvalidArgs`] if the tool declares a schema the
+    /// parameters do not satisfy, and [`ToolError::Timeout`] if the tool
+    /// exceeds its timeout.
     pub async fn execute(
         &self,
         name: &str,
@@ -95,6 +197,16 @@ impl ToolRegistry {
             .get(name)
             .ok_or_else(|| ToolError::NotFound(name.to_string()))?;
 
+        if let Some(schema) = tool.args_schema() {
+            validate_args(&schema, &params).map_err(|detail| {
+                debug!("Rejected args for tool '{}': {}", name, detail);
+                ToolError::InvalidArgs {
+                    tool: name.to_string(),
+                    detail,
+                }
+            })?;
+        }
+
         debug!("Executing tool '{}' with {} params", name, params.len());
 
         let args = if let Some(ref brain) = self.brain {
@@ -103,7 +215,17 @@ impl ToolRegistry {
             ToolArgs::new(params)
         };
 
-        let result = tool.execute(args).await?;
+        let timeout = self.timeout_for(name);
+        let result = match tokio::time::timeout(timeout, tool.execute(args)).await {
+            Ok(result) => result?,
+            Err(_) => {
+                warn!("Tool '{}' timed out after {:?}", name, timeout);
+                return Err(ToolError::Timeout {
+                    name: name.to_string(),
+                    after: timeout,
+                });
+            }
+        };
 
         debug!(
             "Tool '{}' completed: success={}, content_len={}",
@@ -112,7 +234,38 @@ impl ToolRegistry {
             result.content.len()
         );
 
-        Ok(result)
+        let mut output = self
+            .post_processors
+            .iter()
+            .fold(result, |output, processor| processor.process(name, output));
+        let max_chars = self.max_output_chars_for(name);
+        if let Some(clamped) = clamp_content(&output.content, max_chars) {
+            debug!("Tool '{}' output truncated to {} chars", name, max_chars);
+            output.content = clamped;
+        }
+        if output
+            .data
+            .as_ref()
+            .is_some_and(|data| data.to_string().chars().count() > max_chars)
+        {
+            debug!("Tool '{}' data dropped: over {} chars", name, max_chars);
+            output.data = None;
+        }
+        Ok(output)
+    }
+
+    /// Execute a tool from a free-text description of the call.
+    ///
+    /// The text is mapped to structured arguments with [`args_from_text`]
+    /// (e.g. "convert 10 km to miles" for the unit converter), then executed
+    /// as usual.
+    pub async fn execute_text(&self, name: &str, text: &str) -> Result<ToolOutput, ToolError> {
+        let tool = self
+            .tools
+            .get(name)
+            .ok_or_else(|| ToolError::NotFound(name.to_string()))?;
+        let params = args_from_text(tool.as_ref(), text)?;
+        self.execute(name, params).await
     }
 
     /// Execute a tool with JSON arguments string.
@@ -128,6 +281,17 @@ impl ToolRegistry {
     }
 }
 
+/// `content` cut to `max_chars` with a truncation note, or `None` if it fits.
+fn clamp_content(content: &str, max_chars: usize) -> Option<String> {
+    let total = content.chars().count();
+    if total <= max_chars {
+        return None;
+    }
+    let mut clamped: String = content.chars().take(max_chars).collect();
+    clamped.push_str(&format!("… [truncated: {} of {} chars shown]", max_chars, total));
+    Some(clamped)
+}
+
 impl Default for ToolRegistry {
     fn default() -> Self {
         Self::new()
@@ -157,6 +321,58 @@ mod tests {
         }
     }
 
+    /// Tool that declares an argument schema.
+    struct RepeatTool;
+
+    #[async_trait]
+    impl Tool for RepeatTool {
+        fn name(&self) -> &str {
+            "repeat"
+        }
+
+        fn description(&self) -> &str {
+            "Repeats text a number of times"
+        }
+
+        fn args_schema(&self) -> Option<Value> {
+            Some(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "text": { "type": "string" },
+                    "times": { "type": "integer", "minimum": 1 }
+                },
+                "required": ["text", "times"]
+            }))
+        }
+
+        async fn execute(&self, args: ToolArgs) -> Result<ToolOutput, ToolError> {
+            let text = args.get_string("text")?;
+            let times = args.get_number("times")? as usize;
+            let repeated = text.repeat(times);
+            Ok(ToolOutput::success(repeated.clone())
+                .with_data(serde_json::json!({ "repeated": repeated })))
+        }
+    }
+
+    /// Tool that sleeps before answering.
+    struct SlowTool;
+
+    #[async_trait]
+    impl Tool for SlowTool {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn description(&self) -> &str {
+            "Sleeps before answering"
+        }
+
+        async fn execute(&self, _args: ToolArgs) -> Result<ToolOutput, ToolError> {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(ToolOutput::success("done"))
+        }
+    }
+
     #[tokio::test]
     async fn test_registry_basic() {
         let mut registry = ToolRegistry::new();
@@ -193,10 +409,199 @@ mod tests {
         assert_eq!(result.content, "world");
     }
 
+    #[tokio::test]
+    async fn test_post_processors_apply_in_order() {
+        let mut registry = ToolRegistry::new();
+        registry.register(EchoTool);
+        registry.add_post_processor(|_tool: &str, mut output: ToolOutput| {
+            output.content = output.content.to_uppercase();
+            output
+        });
+        registry.add_post_processor(|tool: &str, mut output: ToolOutput| {
+            output.content = format!("{}: {}", tool, output.content);
+            output
+        });
+
+        let result = registry
+            .execute_json("echo", r#"{"message": "hello"}"#)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.content, "echo: HELLO");
+    }
+
+    #[tokio::test]
+    async fn test_output_clamped_at_limit() {
+        let mut registry = ToolRegistry::new();
+        registry.register(RepeatTool);
+        registry.register(EchoTool);
+        registry.set_default_max_output_chars(10);
+        registry.set_max_output_chars("echo", 4);
+        assert_eq!(registry.max_output_chars_for("repeat"), 10);
+
+        let result = registry
+            .execute_json("repeat", r#"{"text": "é", "times": 25}"#)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.content,
+            format!("{}… [truncated: 10 of 25 chars shown]", "é".repeat(10))
+        );
+
+        let result = registry
+            .execute_json("echo", r#"{"message": "hello"}"#)
+            .await
+            .unwrap();
+        assert_eq!(result.content, "hell… [truncated: 4 of 5 chars shown]");
+
+        let result = registry
+            .execute_json("repeat", r#"{"text": "ab", "times": 5}"#)
+            .await
+            .unwrap();
+        assert_eq!(result.content, "ababababab");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_data_dropped() {
+        let mut registry = ToolRegistry::new();
+        registry.register(RepeatTool);
+        registry.set_default_max_output_chars(40);
+
+        let result = registry
+            .execute_json("repeat", r#"{"text": "ab", "times": 5}"#)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.data,
+            Some(serde_json::json!({ "repeated": "ababababab" }))
+        );
+
+        // The content fits exactly, but its JSON form does not.
+        let result = registry
+            .execute_json("repeat", r#"{"text": "ab", "times": 20}"#)
+            .await
+            .unwrap();
+        assert_eq!(result.content, "ab".repeat(20));
+        assert!(result.data.is_none());
+    }
+
     #[tokio::test]
     async fn test_registry_not_found() {
         let registry = ToolRegistry::new();
         let result = registry.execute("nonexistent", HashMap::new()).await;
         assert!(matches!(result, Err(ToolError::NotFound(_))));
     }
+
+    #[tokio::test]
+    async fn test_default_timeout_fires() {
+        let mut registry = ToolRegistry::new();
+        registry.set_default_timeout(Duration::from_millis(50));
+        registry.register(SlowTool);
+
+        let result = registry.execute("slow", HashMap::new()).await;
+        match result {
+            Err(ToolError::Timeout { name, after }) => {
+                assert_eq!(name, "slow");
+                assert_eq!(after, Duration::from_millis(50));
+            }
+            other => panic!("expected timeout, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_per_tool_timeout_override() {
+        let mut registry = ToolRegistry::new();
+        registry.register_with_timeout(SlowTool, Duration::from_millis(20));
+        registry.register(EchoTool);
+
+        assert_eq!(registry.timeout_for("slow"), Duration::from_millis(20));
+        assert_eq!(registry.timeout_for("echo"), DEFAULT_TOOL_TIMEOUT);
+
+        let result = registry.execute("slow", HashMap::new()).await;
+        assert!(matches!(result, Err(ToolError::Timeout { .. })));
+
+        let result = registry
+            .execute_json("echo", r#"{"message": "fast"}"#)
+            .await
+            .unwrap();
+        assert_eq!(result.content, "fast");
+    }
+
+    #[tokio::test]
+    async fn test_schema_rejects_missing_args() {
+        let mut registry = ToolRegistry::new();
+        registry.register(RepeatTool);
+
+        let result = registry
+            .execute_json("repeat", r#"{"text": "ab"}"#)
+            .await;
+        match result {
+            Err(ToolError::InvalidArgs { tool, detail }) => {
+                assert_eq!(tool, "repeat");
+                assert_eq!(detail, "missing required argument 'times'");
+            }
+            other => panic!("expected InvalidArgs, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_schema_rejects_wrong_type() {
+        let mut registry = ToolRegistry::new();
+        registry.register(RepeatTool);
+
+        let err = registry
+            .execute_json("repeat", r#"{"text": "ab", "times": "two"}"#)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid arguments for 'repeat': argument 'times' must be of type integer, got string"
+        );
+
+        let err = registry
+            .execute_json("repeat", r#"{"text": 5, "times": 2}"#)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArgs { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_schema_accepts_valid_args() {
+        let mut registry = ToolRegistry::new();
+        registry.register(RepeatTool);
+
+        let result = registry
+            .execute_json("repeat", r#"{"text": "ab", "times": 3}"#)
+            .await
+            .unwrap();
+        assert_eq!(result.content, "ababab");
+    }
+
+    #[tokio::test]
+    async fn test_tool_without_schema_validates_itself() {
+        let mut registry = ToolRegistry::new();
+        registry.register(EchoTool);
+
+        let err = registry.execute_json("echo", "{}").await.unwrap_err();
+        assert!(matches!(err, ToolError::MissingParameter(name) if name == "message"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_text() {
+        let mut registry = ToolRegistry::new();
+        registry.register(crate::tools::UnitConverter::new());
+
+        let result = registry
+            .execute_text("unit_converter", "convert 10 km to miles")
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.content.contains("6.2137"), "{}", result.content);
+
+        let err = registry
+            .execute_text("unit_converter", "how far is it")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArgs { .. }));
+    }
 }
@@ -0,0 +1,171 @@
+//! Minimal JSON schema validation for tool arguments.
+//!
+//! Supports the subset of JSON Schema that tool argument schemas use:
+//! `type` (single or list), `properties`, `required`, `additionalProperties`
+//! (boolean), `enum`, `minimum`, `maximum` and array `items`. Unknown
+//! keywords are ignored.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+/// Validate tool parameters against an object schema.
+///
+/// Returns a human-readable description of the first violation found.
+pub(crate) fn validate_args(schema: &Value, params: &HashMap<String, Value>) -> Result<(), String> {
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for name in required.iter().filter_map(Value::as_str) {
+            if params.get(name).is_none_or(Value::is_null) {
+                return Err(format!("missing required argument '{}'", name));
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let mut names: Vec<_> = params.keys().collect();
+    names.sort_unstable();
+    for name in names {
+        let value = &params[name];
+        match properties.and_then(|props| props.get(name)) {
+            // Optional arguments may be passed as null.
+            Some(_) if value.is_null() => {}
+            Some(property) => validate_value(property, value, name)?,
+            None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                return Err(format!("unexpected argument '{}'", name));
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+fn validate_value(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| matches_type(t, value)) {
+            return Err(format!(
+                "argument '{}' must be of type {}, got {}",
+                path,
+                allowed.join(" or "),
+                type_name(value)
+            ));
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            let listed: Vec<String> = options.iter().map(Value::to_string).collect();
+            return Err(format!(
+                "argument '{}' must be one of {}",
+                path,
+                listed.join(", ")
+            ));
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+            if number < min {
+                return Err(format!("argument '{}' must be at least {}", path, min));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+            if number > max {
+                return Err(format!("argument '{}' must be at most {}", path, max));
+            }
+        }
+    }
+
+    if let (Some(items), Some(elements)) = (schema.get("items"), value.as_array()) {
+        for (index, element) in elements.iter().enumerate() {
+            validate_value(items, element, &format!("{}[{}]", path, index))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn params(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "count": { "type": "integer", "minimum": 1, "maximum": 10 },
+                "mode": { "type": "string", "enum": ["fast", "slow"] },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["count"]
+        })
+    }
+
+    #[test]
+    fn test_valid_args() {
+        assert!(validate_args(&schema(), &params(json!({"count": 3, "mode": "fast"}))).is_ok());
+        assert!(validate_args(&schema(), &params(json!({"count": 3, "extra": true}))).is_ok());
+    }
+
+    #[test]
+    fn test_rejections() {
+        let cases = [
+            (json!({}), "missing required argument 'count'"),
+            (json!({"count": null}), "missing required argument 'count'"),
+            (json!({"count": "3"}), "argument 'count' must be of type integer, got string"),
+            (json!({"count": 2.5}), "argument 'count' must be of type integer, got number"),
+            (json!({"count": 0}), "argument 'count' must be at least 1"),
+            (json!({"count": 1, "mode": "medium"}), "argument 'mode' must be one of \"fast\", \"slow\""),
+            (json!({"count": 1, "tags": ["a", 2]}), "argument 'tags[1]' must be of type string, got number"),
+        ];
+        for (args, expected) in cases {
+            assert_eq!(validate_args(&schema(), &params(args)).unwrap_err(), expected);
+        }
+    }
+
+    #[test]
+    fn test_additional_properties_false() {
+        let strict = json!({
+            "type": "object",
+            "properties": { "a": { "type": "string" } },
+            "additionalProperties": false
+        });
+        assert_eq!(
+            validate_args(&strict, &params(json!({"b": 1}))).unwrap_err(),
+            "unexpected argument 'b'"
+        );
+    }
+}
@@ -124,6 +124,10 @@ pub struct ToolOutput {
     pub content: String,
     /// Whether the execution was successful.
     pub success: bool,
+    /// Structured form of the result (prices, conversions) for callers that
+    /// render JSON instead of `content`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
 }
 
 impl ToolOutput {
@@ -132,6 +136,7 @@ impl ToolOutput {
         Self {
             content: content.into(),
             success: true,
+            data: None,
         }
     }
 
@@ -140,8 +145,15 @@ impl ToolOutput {
         Self {
             content: content.into(),
             success: false,
+            data: None,
         }
     }
+
+    /// Attach structured data; `content` stays the human-readable text.
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
 }
 
 /// Trait for tools that can be executed by the orchestrator.
@@ -157,6 +169,15 @@ pub trait Tool: Send + Sync {
     /// Human-readable description of what the tool does.
     fn description(&self) -> &str;
 
+    /// JSON schema describing the tool's arguments, if it declares one.
+    ///
+    /// Used when advertising the tool to models that support function calling.
+    /// When present, [`ToolRegistry::execute`](crate::ToolRegistry::execute)
+    /// validates arguments against it before dispatch.
+    fn args_schema(&self) -> Option<Value> {
+        None
+    }
+
     /// Execute the tool with the given arguments.
     async fn execute(&self, args: ToolArgs) -> Result<ToolOutput, ToolError>;
 }
@@ -2,6 +2,7 @@
 
 use async_trait::async_trait;
 use serde::Deserialize;
+use serde_json::json;
 use tracing::debug;
 
 use crate::error::ToolError;
@@ -45,6 +46,7 @@ struct MempoolPrices {
 /// ```
 pub struct BitcoinPrice {
     client: reqwest::Client,
+    base_url: String,
 }
 
 impl BitcoinPrice {
@@ -56,15 +58,22 @@ impl BitcoinPrice {
                 .timeout(std::time::Duration::from_secs(10))
                 .build()
                 .expect("Failed to create HTTP client"),
+            base_url: "https://mempool.space".to_string(),
         }
     }
 
+    /// Use a different API base URL (e.g. a mock server in tests).
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     /// Fetch Bitcoin prices from mempool.space.
     async fn fetch_prices(&self) -> Result<MempoolPrices, ToolError> {
-        let url = "https://mempool.space/api/v1/prices";
+        let url = format!("{}/api/v1/prices", self.base_url);
         debug!("Fetching Bitcoin price from: {}", url);
 
-        let response = self.client.get(url).send().await?;
+        let response = self.client.get(&url).send().await?;
 
         if !response.status().is_success() {
             return Err(ToolError::ExecutionFailed(format!(
@@ -127,11 +136,13 @@ impl Tool for BitcoinPrice {
                     format!("{}{:.2}", symbol, price)
                 };
 
-                Ok(ToolOutput::success(format!(
-                    "Bitcoin (BTC): {} {}",
-                    formatted,
-                    currency.to_uppercase()
-                )))
+                let currency = currency.to_uppercase();
+                Ok(ToolOutput::success(format!("Bitcoin (BTC): {} {}", formatted, currency))
+                    .with_data(json!({
+                        "asset": "BTC",
+                        "currency": currency,
+                        "price": price,
+                    })))
             }
             None => Err(ToolError::InvalidParameter {
                 name: "currency".to_string(),
@@ -147,6 +158,7 @@ impl Tool for BitcoinPrice {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::test_support::mock_http;
     use std::collections::HashMap;
 
     #[test]
@@ -167,6 +179,26 @@ mod tests {
         assert_eq!(BitcoinPrice::get_price(&prices, "INVALID"), None);
     }
 
+    #[tokio::test]
+    async fn test_price_has_content_and_data() {
+        let base_url = mock_http(vec![(
+            "/api/v1/prices",
+            200,
+            r#"{"time":1700000000,"USD":50000.5,"EUR":45000,"GBP":40000,"CAD":65000,"CHF":44000,"AUD":75000,"JPY":7500000}"#,
+        )])
+        .await;
+        let tool = BitcoinPrice::new().with_base_url(base_url);
+        let mut params = HashMap::new();
+        params.insert("currency".to_string(), serde_json::json!("eur"));
+
+        let result = tool.execute(ToolArgs::new(params)).await.unwrap();
+        assert_eq!(result.content, "Bitcoin (BTC): €45000.00 EUR");
+        assert_eq!(
+            result.data,
+            Some(serde_json::json!({"asset": "BTC", "currency": "EUR", "price": 45000.0}))
+        );
+    }
+
     #[tokio::test]
     #[ignore] // Requires network
     async fn test_fetch_bitcoin_price() {
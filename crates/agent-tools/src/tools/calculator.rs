@@ -1,6 +1,7 @@
 //! Calculator tool for safe math expression evaluation.
 
 use async_trait::async_trait;
+use serde_json::{json, Value};
 use tracing::debug;
 
 use crate::error::ToolError;
@@ -49,6 +50,19 @@ impl Tool for Calculator {
          powers (^), roots (sqrt), and constants (pi, e)."
     }
 
+    fn args_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "expression": {
+                    "type": "string",
+                    "description": "Mathematical expression, e.g. \"2 + 2 * 3\" or \"sqrt(16)\""
+                }
+            },
+            "required": ["expression"]
+        }))
+    }
+
     async fn execute(&self, args: ToolArgs) -> Result<ToolOutput, ToolError> {
         let expression = args.get_string("expression")?;
 
@@ -2,6 +2,7 @@
 
 use async_trait::async_trait;
 use serde::Deserialize;
+use serde_json::json;
 use std::collections::HashMap;
 use tracing::debug;
 
@@ -43,6 +44,7 @@ struct PriceData {
 /// - For full list: https://api.coingecko.com/api/v3/coins/list
 pub struct CryptoPrice {
     client: reqwest::Client,
+    base_url: String,
 }
 
 impl CryptoPrice {
@@ -54,13 +56,21 @@ impl CryptoPrice {
                 .timeout(std::time::Duration::from_secs(10))
                 .build()
                 .expect("Failed to create HTTP client"),
+            base_url: "https://api.coingecko.com".to_string(),
         }
     }
 
+    /// Use a different API base URL (e.g. a mock server in tests).
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     /// Fetch price data from CoinGecko.
     async fn fetch_price(&self, coin: &str) -> Result<PriceData, ToolError> {
         let url = format!(
-            "https://api.coingecko.com/api/v3/simple/price?ids={}&vs_currencies=usd,eur,gbp&include_24hr_change=true&include_market_cap=true",
+            "{}/api/v3/simple/price?ids={}&vs_currencies=usd,eur,gbp&include_24hr_change=true&include_market_cap=true",
+            self.base_url,
             coin.to_lowercase()
         );
 
@@ -165,13 +175,20 @@ impl Tool for CryptoPrice {
             response.push_str(&format!(" | Market Cap: {}", Self::format_market_cap(mcap)));
         }
 
-        Ok(ToolOutput::success(response))
+        Ok(ToolOutput::success(response).with_data(json!({
+            "coin": coin.to_lowercase(),
+            "currency": currency,
+            "price": price,
+            "usd_24h_change": data.usd_24h_change,
+            "usd_market_cap": data.usd_market_cap,
+        })))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::test_support::mock_http;
 
     #[test]
     fn test_format_market_cap() {
@@ -190,6 +207,35 @@ mod tests {
         assert_eq!(CryptoPrice::format_change(0.0), "↑0.00%");
     }
 
+    #[tokio::test]
+    async fn test_price_has_content_and_data() {
+        let base_url = mock_http(vec![(
+            "/api/v3/simple/price",
+            200,
+            r#"{"ethereum":{"usd":2500.5,"eur":2300,"gbp":2000,"usd_24h_change":-1.25,"usd_market_cap":300000000000}}"#,
+        )])
+        .await;
+        let tool = CryptoPrice::new().with_base_url(base_url);
+        let mut params = HashMap::new();
+        params.insert("coin".to_string(), json!("Ethereum"));
+
+        let result = tool.execute(ToolArgs::new(params)).await.unwrap();
+        assert_eq!(
+            result.content,
+            "ETHEREUM: $2500.50 USD (↓1.25%) | Market Cap: $300.00B"
+        );
+        assert_eq!(
+            result.data,
+            Some(json!({
+                "coin": "ethereum",
+                "currency": "USD",
+                "price": 2500.5,
+                "usd_24h_change": -1.25,
+                "usd_market_cap": 300000000000.0,
+            }))
+        );
+    }
+
     #[tokio::test]
     #[ignore] // Requires network
     async fn test_fetch_ethereum_price() {
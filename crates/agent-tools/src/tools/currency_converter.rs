@@ -1,6 +1,7 @@
 //! Currency converter tool using exchangerate.host API.
 
 use async_trait::async_trait;
+use serde_json::{json, Value};
 use serde::Deserialize;
 use tracing::debug;
 
@@ -43,6 +44,7 @@ struct ExchangeRateError {
 /// USD, EUR, GBP, JPY, CAD, AUD, CHF, CNY, INR, MXN, BRL, KRW, and many more.
 pub struct CurrencyConverter {
     client: reqwest::Client,
+    base_url: String,
 }
 
 impl CurrencyConverter {
@@ -54,13 +56,21 @@ impl CurrencyConverter {
                 .timeout(std::time::Duration::from_secs(10))
                 .build()
                 .expect("Failed to create HTTP client"),
+            base_url: "https://api.exchangerate.host".to_string(),
         }
     }
 
+    /// Use a different API base URL (e.g. a mock server in tests).
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     /// Fetch exchange rate and convert.
     async fn convert(&self, amount: f64, from: &str, to: &str) -> Result<f64, ToolError> {
         let url = format!(
-            "https://api.exchangerate.host/convert?from={}&to={}&amount={}",
+            "{}/convert?from={}&to={}&amount={}",
+            self.base_url,
             from.to_uppercase(),
             to.to_uppercase(),
             amount
@@ -132,6 +142,18 @@ impl Tool for CurrencyConverter {
          Supports USD, EUR, GBP, JPY, CAD, AUD, CHF, CNY, INR, and many more."
     }
 
+    fn args_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "amount": { "type": "number", "description": "Amount to convert" },
+                "from": { "type": "string", "description": "Source currency code, e.g. \"USD\"" },
+                "to": { "type": "string", "description": "Target currency code, e.g. \"EUR\"" }
+            },
+            "required": ["amount", "from", "to"]
+        }))
+    }
+
     async fn execute(&self, args: ToolArgs) -> Result<ToolOutput, ToolError> {
         let amount = args.get_f64("amount")?;
         let from = args.get_string("from")?.to_uppercase();
@@ -167,13 +189,20 @@ impl Tool for CurrencyConverter {
         Ok(ToolOutput::success(format!(
             "{}{} {} = {}{} {}",
             from_symbol, amount_formatted, from, to_symbol, result_formatted, to
-        )))
+        ))
+        .with_data(json!({
+            "amount": amount,
+            "from": from,
+            "to": to,
+            "result": result,
+        })))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::test_support::mock_http;
 
     #[test]
     fn test_get_symbol() {
@@ -184,6 +213,28 @@ mod tests {
         assert_eq!(CurrencyConverter::get_symbol("UNKNOWN"), "");
     }
 
+    #[tokio::test]
+    async fn test_conversion_has_content_and_data() {
+        let base_url = mock_http(vec![(
+            "/convert",
+            200,
+            r#"{"success":true,"result":92.5}"#,
+        )])
+        .await;
+        let tool = CurrencyConverter::new().with_base_url(base_url);
+        let mut params = std::collections::HashMap::new();
+        params.insert("amount".to_string(), json!(100));
+        params.insert("from".to_string(), json!("usd"));
+        params.insert("to".to_string(), json!("eur"));
+
+        let result = tool.execute(ToolArgs::new(params)).await.unwrap();
+        assert_eq!(result.content, "$100.00 USD = €92.50 EUR");
+        assert_eq!(
+            result.data,
+            Some(json!({"amount": 100.0, "from": "USD", "to": "EUR", "result": 92.5}))
+        );
+    }
+
     #[tokio::test]
     #[ignore] // Requires network
     async fn test_convert_usd_to_eur() {
@@ -7,6 +7,12 @@ use tracing::debug;
 use crate::error::ToolError;
 use crate::tool::{Tool, ToolArgs, ToolOutput};
 
+/// Embedded word list used for "did you mean" suggestions.
+const SUGGESTION_WORDS: &str = include_str!("dictionary_words.txt");
+
+/// Maximum number of suggestions returned for a misspelled word.
+const MAX_SUGGESTIONS: usize = 3;
+
 /// Response from Free Dictionary API.
 #[derive(Debug, Deserialize)]
 struct DictionaryEntry {
@@ -32,7 +38,8 @@ struct Definition {
 /// Dictionary tool using Free Dictionary API.
 ///
 /// Looks up word definitions, pronunciation, and examples.
-/// Free API, no key required.
+/// Free API, no key required. When a word is not found, close matches
+/// from a small embedded word list are offered as "did you mean" hints.
 ///
 /// # Parameters
 ///
@@ -47,6 +54,7 @@ struct Definition {
 /// ```
 pub struct Dictionary {
     client: reqwest::Client,
+    base_url: String,
 }
 
 impl Dictionary {
@@ -58,13 +66,23 @@ impl Dictionary {
                 .timeout(std::time::Duration::from_secs(10))
                 .build()
                 .expect("Failed to create HTTP client"),
+            base_url: "https://api.dictionaryapi.dev".to_string(),
         }
     }
 
+    /// Use a different API base URL (e.g. a mock server in tests).
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     /// Fetch definition from Free Dictionary API.
-    async fn lookup(&self, word: &str) -> Result<DictionaryEntry, ToolError> {
+    ///
+    /// Returns `Ok(None)` if the word is not in the dictionary.
+    async fn lookup(&self, word: &str) -> Result<Option<DictionaryEntry>, ToolError> {
         let url = format!(
-            "https://api.dictionaryapi.dev/api/v2/entries/en/{}",
+            "{}/api/v2/entries/en/{}",
+            self.base_url,
             urlencoding::encode(word)
         );
 
@@ -73,10 +91,7 @@ impl Dictionary {
         let response = self.client.get(&url).send().await?;
 
         if response.status() == reqwest::StatusCode::NOT_FOUND {
-            return Err(ToolError::ExecutionFailed(format!(
-                "Word '{}' not found in dictionary",
-                word
-            )));
+            return Ok(None);
         }
 
         if !response.status().is_success() {
@@ -88,7 +103,7 @@ impl Dictionary {
 
         let entries: Vec<DictionaryEntry> = response.json().await?;
 
-        entries.into_iter().next().ok_or_else(|| {
+        entries.into_iter().next().map(Some).ok_or_else(|| {
             ToolError::ExecutionFailed(format!("No definition found for '{}'", word))
         })
     }
@@ -138,6 +153,62 @@ impl Default for Dictionary {
     }
 }
 
+/// Suggest close matches for a misspelled word from the embedded word list.
+///
+/// Words within a small edit distance (1 for short words, 2 for words of 5+
+/// letters) are returned, closest first.
+fn suggest(word: &str) -> Vec<&'static str> {
+    let word = word.trim().to_lowercase();
+    let len = word.chars().count();
+    if len < 3 {
+        return Vec::new();
+    }
+    let max_distance = if len >= 5 { 2 } else { 1 };
+
+    let mut candidates: Vec<(usize, &'static str)> = SUGGESTION_WORDS
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|candidate| candidate.chars().count().abs_diff(len) <= max_distance)
+        .map(|candidate| (edit_distance(&word, candidate), candidate))
+        .filter(|(distance, _)| *distance > 0 && *distance <= max_distance)
+        .collect();
+    candidates.sort_by_key(|(distance, _)| *distance);
+    candidates.dedup_by_key(|(_, candidate)| *candidate);
+
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Edit distance between two strings, by characters.
+///
+/// Optimal string alignment: insertions, deletions, substitutions, and
+/// adjacent transpositions ("recieve" -> "receive") each cost 1.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
 // Simple URL encoding for the word
 mod urlencoding {
     pub fn encode(input: &str) -> String {
@@ -181,18 +252,89 @@ impl Tool for Dictionary {
 
         debug!("Looking up definition for: {}", word);
 
-        let entry = self.lookup(&word).await?;
-        let formatted = Self::format_entry(&entry);
+        if let Some(entry) = self.lookup(&word).await? {
+            return Ok(ToolOutput::success(Self::format_entry(&entry)));
+        }
+
+        let suggestions = suggest(&word);
+        if suggestions.is_empty() {
+            return Err(ToolError::ExecutionFailed(format!(
+                "Word '{}' not found in dictionary",
+                word
+            )));
+        }
 
-        Ok(ToolOutput::success(formatted))
+        Ok(ToolOutput::failure(format!(
+            "Word '{}' not found in dictionary. Did you mean {}?",
+            word,
+            suggestions.join(", ")
+        )))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::test_support::mock_http;
     use std::collections::HashMap;
 
+    fn make_args(word: &str) -> ToolArgs {
+        let mut params = HashMap::new();
+        params.insert("word".to_string(), serde_json::Value::String(word.to_string()));
+        ToolArgs::new(params)
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("recieve", "receive"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_suggest() {
+        assert_eq!(suggest("recieve").first(), Some(&"receive"));
+        assert_eq!(suggest("Seperate").first(), Some(&"separate"));
+        assert!(suggest("zzqxv").is_empty());
+        assert!(suggest("ab").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_exact_hit() {
+        let base = mock_http(vec![(
+            "/api/v2/entries/en/ephemeral",
+            200,
+            r#"[{"word":"ephemeral","phonetic":"/əˈfɛm(ə)rəl/","meanings":[{"partOfSpeech":"adjective","definitions":[{"definition":"Lasting for a very short time."}]}]}]"#,
+        )])
+        .await;
+        let tool = Dictionary::new().with_base_url(base);
+
+        let result = tool.execute(make_args("ephemeral")).await.unwrap();
+        assert!(result.success);
+        assert!(result.content.starts_with("**ephemeral**"));
+        assert!(result.content.contains("Lasting for a very short time."));
+        assert!(!result.content.contains("Did you mean"));
+    }
+
+    #[tokio::test]
+    async fn test_near_miss_suggests() {
+        let base = mock_http(vec![]).await;
+        let tool = Dictionary::new().with_base_url(base);
+
+        let result = tool.execute(make_args("recieve")).await.unwrap();
+        assert!(!result.success);
+        assert!(result.content.contains("Did you mean receive"));
+    }
+
+    #[tokio::test]
+    async fn test_nonsense_has_no_suggestion() {
+        let base = mock_http(vec![]).await;
+        let tool = Dictionary::new().with_base_url(base);
+
+        let result = tool.execute(make_args("qzxjvw")).await;
+        assert!(matches!(result, Err(ToolError::ExecutionFailed(ref msg)) if msg.contains("not found")));
+    }
+
     #[test]
     fn test_url_encoding() {
         assert_eq!(urlencoding::encode("hello"), "hello");
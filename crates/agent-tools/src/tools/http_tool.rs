@@ -0,0 +1,315 @@
+//! Config-defined tools that call an HTTP endpoint.
+
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::{debug, warn};
+use url::Url;
+
+use super::web_fetch::validate_url_ssrf;
+use crate::error::ToolError;
+use crate::tool::{Tool, ToolArgs, ToolOutput};
+
+/// Environment variable holding HTTP tool definitions as a JSON array.
+pub const HTTP_TOOLS_ENV: &str = "AMAN_HTTP_TOOLS";
+/// Environment variable holding a path to a JSON file of HTTP tool definitions.
+pub const HTTP_TOOLS_FILE_ENV: &str = "AMAN_HTTP_TOOLS_FILE";
+
+/// Response bodies are truncated to this many characters in `content`.
+const MAX_RESPONSE_CHARS: usize = 4000;
+
+/// An HTTP tool definition, e.g. loaded from JSON.
+///
+/// `url` is a template: each `{arg}` is replaced with the percent-encoded
+/// value of that argument, so arguments cannot add path segments or query
+/// parameters of their own.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct HttpToolConfig {
+    /// Tool name used for dispatch.
+    pub name: String,
+    /// Description shown to the router and models.
+    pub description: String,
+    /// JSON schema for the arguments, validated before every call.
+    #[serde(default)]
+    pub args_schema: Option<Value>,
+    /// URL template, e.g. `https://api.example.org/v1/lookup?q={query}`.
+    pub url: String,
+    /// Per-tool timeout override in seconds.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// A tool that fills a URL template from its arguments and GETs it.
+///
+/// Requests go through the same SSRF guard as [`WebFetch`](super::WebFetch)
+/// and do not follow redirects. JSON responses are also returned as
+/// [`ToolOutput::data`].
+pub struct HttpTool {
+    config: HttpToolConfig,
+    client: reqwest::Client,
+    allow_private_hosts: bool,
+}
+
+impl HttpTool {
+    /// Build a tool from its definition.
+    ///
+    /// Fails if the name or description is empty or the template is not an http(s) URL.
+    pub fn new(config: HttpToolConfig) -> Result<Self, ToolError> {
+        let invalid = |reason: &str| ToolError::InvalidParameter {
+            name: format!("http tool '{}'", config.name),
+            reason: reason.to_string(),
+        };
+        if config.name.trim().is_empty() {
+            return Err(invalid("name cannot be empty"));
+        }
+        if config.description.trim().is_empty() {
+            return Err(invalid("description cannot be empty"));
+        }
+        let probe = fill_template(&config.url, |_| Some("x".to_string()))
+            .ok_or_else(|| invalid("url has an unclosed '{' placeholder"))?;
+        match Url::parse(&probe) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => return Err(invalid("url must be an http(s) URL")),
+        }
+
+        let client = reqwest::Client::builder()
+            .user_agent("AmanBot/1.0")
+            .timeout(Duration::from_secs(10))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Ok(Self {
+            config,
+            client,
+            allow_private_hosts: false,
+        })
+    }
+
+    /// Allow endpoints on private or loopback addresses (e.g. a mock server in tests).
+    pub fn allow_private_hosts(mut self, allow: bool) -> Self {
+        self.allow_private_hosts = allow;
+        self
+    }
+
+    /// Timeout override from the definition, if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.config.timeout_secs.map(Duration::from_secs)
+    }
+
+    /// Build tools from a JSON array of [`HttpToolConfig`] definitions.
+    pub fn from_json(json: &str) -> Result<Vec<Self>, ToolError> {
+        let configs: Vec<HttpToolConfig> = serde_json::from_str(json)?;
+        configs.into_iter().map(Self::new).collect()
+    }
+
+    /// Build tools from `AMAN_HTTP_TOOLS`, or the file named by `AMAN_HTTP_TOOLS_FILE`.
+    ///
+    /// Invalid configuration is logged and ignored so built-in tools keep working.
+    pub fn from_env() -> Vec<Self> {
+        let json = match (
+            std::env::var(HTTP_TOOLS_ENV).ok(),
+            std::env::var(HTTP_TOOLS_FILE_ENV).ok(),
+        ) {
+            (Some(json), _) if !json.trim().is_empty() => json,
+            (_, Some(path)) if !path.trim().is_empty() => {
+                match std::fs::read_to_string(Path::new(path.trim())) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        warn!("Failed to read HTTP tools file {}: {}", path, e);
+                        return Vec::new();
+                    }
+                }
+            }
+            _ => return Vec::new(),
+        };
+
+        Self::from_json(&json).unwrap_or_else(|e| {
+            warn!("Ignoring HTTP tools: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn build_url(&self, args: &ToolArgs) -> Result<String, ToolError> {
+        let mut missing = None;
+        let url = fill_template(&self.config.url, |key| {
+            let value = match args.params.get(key) {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Null) | None => {
+                    missing.get_or_insert_with(|| key.to_string());
+                    return None;
+                }
+                Some(other) => other.to_string(),
+            };
+            Some(percent_encode(&value))
+        });
+        match (url, missing) {
+            (_, Some(key)) => Err(ToolError::MissingParameter(key)),
+            (Some(url), None) => Ok(url),
+            (None, None) => Err(ToolError::ExecutionFailed("Invalid URL template".to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for HttpTool {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn description(&self) -> &str {
+        &self.config.description
+    }
+
+    fn args_schema(&self) -> Option<Value> {
+        self.config.args_schema.clone()
+    }
+
+    async fn execute(&self, args: ToolArgs) -> Result<ToolOutput, ToolError> {
+        let url = self.build_url(&args)?;
+        if !self.allow_private_hosts {
+            validate_url_ssrf(&url).await?;
+        }
+
+        debug!("HTTP tool '{}' fetching {}", self.config.name, url);
+        let response = self.client.get(&url).send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Ok(ToolOutput::failure(format!(
+                "{} returned HTTP {}",
+                self.config.name,
+                status.as_u16()
+            )));
+        }
+
+        let content: String = body.chars().take(MAX_RESPONSE_CHARS).collect();
+        let output = ToolOutput::success(content);
+        Ok(match serde_json::from_str::<Value>(&body) {
+            Ok(data) => output.with_data(data),
+            Err(_) => output,
+        })
+    }
+}
+
+/// Replace each `{key}` in `template` with `value(key)`.
+///
+/// Returns `None` on an unclosed placeholder or when `value` returns `None`.
+fn fill_template(template: &str, mut value: impl FnMut(&str) -> Option<String>) -> Option<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find('}')? + start;
+        out.push_str(&value(rest[start + 1..end].trim())?);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Some(out)
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters.
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_support::mock_http;
+    use crate::ToolRegistry;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn config_json(base_url: &str) -> String {
+        json!([{
+            "name": "postcode_lookup",
+            "description": "Look up a postcode",
+            "args_schema": {
+                "type": "object",
+                "properties": { "code": { "type": "string" } },
+                "required": ["code"]
+            },
+            "url": format!("{}/postcodes/{{code}}?format=json", base_url),
+            "timeout_secs": 5
+        }])
+        .to_string()
+    }
+
+    #[test]
+    fn test_fill_template_encodes_args() {
+        let url = fill_template("https://x.test/a/{q}?n={n}", |key| {
+            Some(percent_encode(if key == "q" { "a b/../c?d=1" } else { "7" }))
+        });
+        assert_eq!(url.unwrap(), "https://x.test/a/a%20b%2F..%2Fc%3Fd%3D1?n=7");
+        assert!(fill_template("https://x.test/{q", |_| Some(String::new())).is_none());
+    }
+
+    #[test]
+    fn test_invalid_configs_rejected() {
+        let config = |name: &str, url: &str| HttpToolConfig {
+            name: name.to_string(),
+            description: "d".to_string(),
+            args_schema: None,
+            url: url.to_string(),
+            timeout_secs: None,
+        };
+        assert!(HttpTool::new(config("ok", "https://x.test/{q}")).is_ok());
+        assert!(HttpTool::new(config(" ", "https://x.test/")).is_err());
+        assert!(HttpTool::new(config("ftp", "ftp://x.test/{q}")).is_err());
+        assert!(HttpTool::new(config("open", "https://x.test/{q")).is_err());
+        assert!(HttpTool::from_json("{}").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_config_tool_runs_against_mock_server() {
+        let base_url = mock_http(vec![(
+            "/postcodes/SW1A%201AA",
+            200,
+            r#"{"postcode":"SW1A 1AA","region":"London"}"#,
+        )])
+        .await;
+        let tools = HttpTool::from_json(&config_json(&base_url)).unwrap();
+        let mut registry = ToolRegistry::new();
+        assert_eq!(
+            registry.register_http_tools(tools.into_iter().map(|t| t.allow_private_hosts(true))),
+            1
+        );
+        assert_eq!(registry.timeout_for("postcode_lookup"), Duration::from_secs(5));
+        let again = HttpTool::from_json(&config_json(&base_url)).unwrap();
+        assert_eq!(registry.register_http_tools(again), 0);
+
+        let mut params = HashMap::new();
+        params.insert("code".to_string(), json!("SW1A 1AA"));
+        let output = registry.execute("postcode_lookup", params).await.unwrap();
+        assert!(output.success);
+        assert!(output.content.contains("London"));
+        assert_eq!(output.data.unwrap()["region"], "London");
+
+        // The declared schema is enforced before any request is made
+        let err = registry.execute("postcode_lookup", HashMap::new()).await.unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArgs { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_private_hosts_blocked_by_default() {
+        let base_url = mock_http(vec![("/postcodes/", 200, "{}")]).await;
+        let tool = HttpTool::from_json(&config_json(&base_url)).unwrap().remove(0);
+        let mut params = HashMap::new();
+        params.insert("code".to_string(), json!("SW1A 1AA"));
+
+        let err = tool.execute(ToolArgs::new(params)).await.unwrap_err();
+        assert!(err.to_string().contains("private/internal"));
+    }
+}
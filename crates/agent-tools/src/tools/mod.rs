@@ -5,21 +5,30 @@ mod calculator;
 mod crypto_price;
 mod currency_converter;
 mod dictionary;
+mod http_tool;
 mod random_number;
 mod sanitize;
+mod translate;
 mod unit_converter;
 mod weather;
 mod web_fetch;
+mod wiki_summary;
 mod world_time;
 
+#[cfg(test)]
+pub(crate) mod test_support;
+
 pub use bitcoin_price::BitcoinPrice;
 pub use calculator::Calculator;
 pub use crypto_price::CryptoPrice;
 pub use currency_converter::CurrencyConverter;
 pub use dictionary::Dictionary;
+pub use http_tool::{HttpTool, HttpToolConfig, HTTP_TOOLS_ENV, HTTP_TOOLS_FILE_ENV};
 pub use random_number::RandomNumber;
 pub use sanitize::{sanitize_system_prompt, Sanitize};
+pub use translate::Translate;
 pub use unit_converter::UnitConverter;
-pub use weather::Weather;
+pub use weather::{Weather, WeatherEndpoints, WeatherProvider, WeatherUnits};
 pub use web_fetch::WebFetch;
+pub use wiki_summary::WikiSummary;
 pub use world_time::WorldTime;
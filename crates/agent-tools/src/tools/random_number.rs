@@ -1,7 +1,9 @@
 //! Random number generator tool.
 
 use async_trait::async_trait;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde_json::Value;
 use tracing::debug;
 
 use crate::error::ToolError;
@@ -18,6 +20,12 @@ use crate::tool::{Tool, ToolArgs, ToolOutput};
 /// - `max` (optional): Maximum value (inclusive). Defaults to 100.
 /// - `count` (optional): Number of random values to generate. Defaults to 1, max 100.
 /// - `float` (optional): If true, generate floating-point numbers. Defaults to false.
+/// - `dice` (optional): Dice notation such as `3d6+2`, `4d6kh3` (keep highest 3),
+///   `2d20kl1` (keep lowest 1), or `2d20 advantage`. When set, `min`/`max`/`count`
+///   are ignored and the individual rolls plus total are returned.
+/// - `seed` (optional): Non-negative integer seed. The same seed and arguments always
+///   produce the same values. Seeded draws are predictable by anyone who knows the
+///   seed and must not be used where cryptographic randomness is required.
 ///
 /// # Examples
 ///
@@ -26,6 +34,9 @@ use crate::tool::{Tool, ToolArgs, ToolOutput};
 /// {"min": 1, "max": 6}                  // Dice roll (1-6)
 /// {"min": 1, "max": 100, "count": 5}    // 5 random numbers 1-100
 /// {"min": 0.0, "max": 1.0, "float": true}  // Random float 0-1
+/// {"min": 1, "max": 6, "seed": 42}      // Reproducible dice roll
+/// {"dice": "3d6+2"}                     // Roll three d6 and add 2
+/// {"dice": "4d6kh3"}                    // Roll four d6, keep the highest three
 /// ```
 pub struct RandomNumber;
 
@@ -50,10 +61,27 @@ impl Tool for RandomNumber {
 
     fn description(&self) -> &str {
         "Generates random numbers. Parameters: min (default 1), max (default 100), \
-         count (default 1, max 100), float (default false for integers)."
+         count (default 1, max 100), float (default false for integers), \
+         dice (notation like 3d6+2, 4d6kh3, 2d20 advantage), \
+         seed (optional integer for reproducible, non-secure draws)."
     }
 
     async fn execute(&self, args: ToolArgs) -> Result<ToolOutput, ToolError> {
+        let seed = parse_seed(&args)?;
+
+        if let Some(notation) = args.get_string_opt("dice") {
+            let spec = DiceSpec::parse(&notation).map_err(|reason| ToolError::InvalidParameter {
+                name: "dice".to_string(),
+                reason,
+            })?;
+            debug!("Rolling dice: {}", spec);
+            let roll = match seed {
+                Some(seed) => spec.roll(&mut ChaCha20Rng::seed_from_u64(seed)),
+                None => spec.roll(&mut rand::thread_rng()),
+            };
+            return Ok(ToolOutput::success(roll.describe(&spec)));
+        }
+
         let min = args.get_number_opt("min")?.unwrap_or(1.0);
         let max = args.get_number_opt("max")?.unwrap_or(100.0);
         let count = args.get_number_opt("count")?.unwrap_or(1.0) as usize;
@@ -83,20 +111,10 @@ impl Tool for RandomNumber {
             });
         }
 
-        let mut rng = rand::thread_rng();
-        let numbers: Vec<String> = (0..count)
-            .map(|_| {
-                if use_float {
-                    let value: f64 = rng.gen_range(min..=max);
-                    format!("{:.4}", value).trim_end_matches('0').trim_end_matches('.').to_string()
-                } else {
-                    let min_int = min.floor() as i64;
-                    let max_int = max.floor() as i64;
-                    let value: i64 = rng.gen_range(min_int..=max_int);
-                    value.to_string()
-                }
-            })
-            .collect();
+        let numbers = match seed {
+            Some(seed) => generate(&mut ChaCha20Rng::seed_from_u64(seed), min, max, count, use_float),
+            None => generate(&mut rand::thread_rng(), min, max, count, use_float),
+        };
 
         let result = if count == 1 {
             numbers[0].clone()
@@ -125,11 +143,229 @@ impl Tool for RandomNumber {
     }
 }
 
+/// Maximum dice in a single roll.
+const MAX_DICE: u32 = 100;
+/// Maximum sides per die.
+const MAX_SIDES: u32 = 1000;
+/// Maximum absolute modifier.
+const MAX_MODIFIER: i64 = 10_000;
+
+/// Which dice count toward the total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keep {
+    Highest(u32),
+    Lowest(u32),
+}
+
+/// Parsed dice notation: `NdM`, optional `khK`/`klK`, optional `+K`/`-K`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiceSpec {
+    count: u32,
+    sides: u32,
+    keep: Option<Keep>,
+    modifier: i64,
+}
+
+/// Result of rolling a [`DiceSpec`].
+#[derive(Debug)]
+struct DiceRoll {
+    rolls: Vec<u32>,
+    kept: Vec<u32>,
+    total: i64,
+}
+
+impl DiceSpec {
+    /// Parse dice notation, e.g. `3d6+2`, `4d6kh3`, `d20`, `2d20 advantage`.
+    fn parse(input: &str) -> Result<Self, String> {
+        let normalized = input.trim().to_lowercase();
+        let normalized = normalized.strip_prefix("roll").unwrap_or(&normalized);
+
+        let mut words: Vec<&str> = normalized.split_whitespace().collect();
+        let mut implied_keep = None;
+        if let Some(last) = words.last() {
+            match *last {
+                "advantage" | "adv" => implied_keep = Some(Keep::Highest(1)),
+                "disadvantage" | "dis" => implied_keep = Some(Keep::Lowest(1)),
+                _ => {}
+            }
+            if implied_keep.is_some() {
+                words.pop();
+            }
+        }
+        let expr: String = words.concat();
+        let invalid = || {
+            format!(
+                "invalid dice notation '{}'; expected NdM with optional khK/klK and +K/-K (e.g. 3d6+2, 4d6kh3)",
+                input.trim()
+            )
+        };
+
+        let (count_part, rest) = expr.split_once('d').ok_or_else(invalid)?;
+        let count = if count_part.is_empty() {
+            1
+        } else {
+            parse_digits(count_part).ok_or_else(invalid)?
+        };
+
+        let sides_len = rest.chars().take_while(|ch| ch.is_ascii_digit()).count();
+        let sides = parse_digits(&rest[..sides_len]).ok_or_else(invalid)?;
+        let mut rest = &rest[sides_len..];
+
+        let mut keep = None;
+        for (prefix, highest) in [("kh", true), ("kl", false), ("k", true)] {
+            if let Some(after) = rest.strip_prefix(prefix) {
+                let len = after.chars().take_while(|ch| ch.is_ascii_digit()).count();
+                let n = parse_digits(&after[..len]).ok_or_else(invalid)?;
+                keep = Some(if highest { Keep::Highest(n) } else { Keep::Lowest(n) });
+                rest = &after[len..];
+                break;
+            }
+        }
+
+        let modifier = if rest.is_empty() {
+            0
+        } else {
+            let (sign, digits) = if let Some(digits) = rest.strip_prefix('+') {
+                (1, digits)
+            } else if let Some(digits) = rest.strip_prefix('-') {
+                (-1, digits)
+            } else {
+                return Err(invalid());
+            };
+            sign * i64::from(parse_digits(digits).ok_or_else(invalid)?)
+        };
+
+        if keep.is_some() && implied_keep.is_some() {
+            return Err(format!(
+                "'{}' combines keep notation with advantage/disadvantage",
+                input.trim()
+            ));
+        }
+        let keep = keep.or(implied_keep);
+
+        if count == 0 || count > MAX_DICE {
+            return Err(format!("dice count must be between 1 and {}", MAX_DICE));
+        }
+        if !(2..=MAX_SIDES).contains(&sides) {
+            return Err(format!("dice sides must be between 2 and {}", MAX_SIDES));
+        }
+        if let Some(Keep::Highest(n) | Keep::Lowest(n)) = keep {
+            if n == 0 || n > count {
+                return Err(format!("can only keep between 1 and {} dice", count));
+            }
+        }
+        if modifier.abs() > MAX_MODIFIER {
+            return Err(format!("modifier cannot exceed {}", MAX_MODIFIER));
+        }
+
+        Ok(Self {
+            count,
+            sides,
+            keep,
+            modifier,
+        })
+    }
+
+    fn roll<R: Rng>(&self, rng: &mut R) -> DiceRoll {
+        let rolls: Vec<u32> = (0..self.count)
+            .map(|_| rng.gen_range(1..=self.sides))
+            .collect();
+
+        let kept = match self.keep {
+            None => rolls.clone(),
+            Some(keep) => {
+                let mut sorted = rolls.clone();
+                sorted.sort_unstable_by(|a, b| b.cmp(a));
+                match keep {
+                    Keep::Highest(n) => sorted.into_iter().take(n as usize).collect(),
+                    Keep::Lowest(n) => sorted.into_iter().rev().take(n as usize).collect(),
+                }
+            }
+        };
+
+        let total = kept.iter().map(|&roll| i64::from(roll)).sum::<i64>() + self.modifier;
+        DiceRoll { rolls, kept, total }
+    }
+}
+
+impl std::fmt::Display for DiceSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)?;
+        match self.keep {
+            Some(Keep::Highest(n)) => write!(f, "kh{}", n)?,
+            Some(Keep::Lowest(n)) => write!(f, "kl{}", n)?,
+            None => {}
+        }
+        match self.modifier {
+            0 => Ok(()),
+            m if m > 0 => write!(f, "+{}", m),
+            m => write!(f, "{}", m),
+        }
+    }
+}
+
+impl DiceRoll {
+    fn describe(&self, spec: &DiceSpec) -> String {
+        let join = |values: &[u32]| {
+            values
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let mut output = format!("Dice roll {}: [{}]", spec, join(&self.rolls));
+        if spec.keep.is_some() {
+            output.push_str(&format!(" kept [{}]", join(&self.kept)));
+        }
+        match spec.modifier {
+            0 => {}
+            m if m > 0 => output.push_str(&format!(" +{}", m)),
+            m => output.push_str(&format!(" {}", m)),
+        }
+        output.push_str(&format!(" = {}", self.total));
+        output
+    }
+}
+
+fn parse_digits(value: &str) -> Option<u32> {
+    if value.is_empty() || !value.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// Read the optional `seed` argument as a non-negative integer.
+fn parse_seed(args: &ToolArgs) -> Result<Option<u64>, ToolError> {
+    match args.params.get("seed") {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or_else(|| ToolError::InvalidParameter {
+            name: "seed".to_string(),
+            reason: "expected a non-negative integer".to_string(),
+        }),
+    }
+}
+
+fn generate<R: Rng>(rng: &mut R, min: f64, max: f64, count: usize, use_float: bool) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            if use_float {
+                let value: f64 = rng.gen_range(min..=max);
+                format!("{:.4}", value).trim_end_matches('0').trim_end_matches('.').to_string()
+            } else {
+                let min_int = min.floor() as i64;
+                let max_int = max.floor() as i64;
+                let value: i64 = rng.gen_range(min_int..=max_int);
+                value.to_string()
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::collections::HashMap;
-    use serde_json::Value;
 
     fn make_args_empty() -> ToolArgs {
         ToolArgs::new(HashMap::new())
@@ -225,4 +461,106 @@ mod tests {
         let result = gen.execute(make_args_with_count(1.0, 100.0, 0)).await;
         assert!(matches!(result, Err(ToolError::InvalidParameter { .. })));
     }
+
+    fn make_seeded_args(seed: u64) -> ToolArgs {
+        let mut args = make_args_with_count(1.0, 1000.0, 10);
+        args.params.insert("seed".to_string(), Value::from(seed));
+        args
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_same_output() {
+        let gen = RandomNumber::new();
+        let first = gen.execute(make_seeded_args(42)).await.unwrap();
+        let second = gen.execute(make_seeded_args(42)).await.unwrap();
+        assert_eq!(first.content, second.content);
+    }
+
+    #[tokio::test]
+    async fn test_different_seed_different_output() {
+        let gen = RandomNumber::new();
+        let first = gen.execute(make_seeded_args(42)).await.unwrap();
+        let second = gen.execute(make_seeded_args(43)).await.unwrap();
+        assert_ne!(first.content, second.content);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_seed() {
+        let gen = RandomNumber::new();
+        let mut args = make_args_empty();
+        args.params.insert("seed".to_string(), Value::from(-1));
+        let result = gen.execute(args).await;
+        assert!(matches!(result, Err(ToolError::InvalidParameter { .. })));
+    }
+
+    fn make_dice_args(notation: &str) -> ToolArgs {
+        let mut params = HashMap::new();
+        params.insert("dice".to_string(), Value::from(notation));
+        params.insert("seed".to_string(), Value::from(7u64));
+        ToolArgs::new(params)
+    }
+
+    #[test]
+    fn test_parse_dice_notation() {
+        let spec = DiceSpec::parse("roll 3d6+2").unwrap();
+        assert_eq!((spec.count, spec.sides, spec.keep, spec.modifier), (3, 6, None, 2));
+
+        let spec = DiceSpec::parse("4d6kh3").unwrap();
+        assert_eq!(spec.keep, Some(Keep::Highest(3)));
+
+        let spec = DiceSpec::parse("2d20 advantage").unwrap();
+        assert_eq!(spec.keep, Some(Keep::Highest(1)));
+
+        let spec = DiceSpec::parse("d20-1").unwrap();
+        assert_eq!((spec.count, spec.modifier), (1, -1));
+    }
+
+    #[tokio::test]
+    async fn test_dice_with_modifier() {
+        let gen = RandomNumber::new();
+        let result = gen.execute(make_dice_args("3d6+2")).await.unwrap();
+        assert!(result.content.starts_with("Dice roll 3d6+2: ["));
+
+        let spec = DiceSpec::parse("3d6+2").unwrap();
+        let roll = spec.roll(&mut ChaCha20Rng::seed_from_u64(7));
+        assert_eq!(roll.rolls.len(), 3);
+        assert!(roll.rolls.iter().all(|r| (1..=6).contains(r)));
+        assert_eq!(roll.total, roll.rolls.iter().map(|&r| i64::from(r)).sum::<i64>() + 2);
+        assert_eq!(result.content, roll.describe(&spec));
+    }
+
+    #[tokio::test]
+    async fn test_dice_keep_highest() {
+        let spec = DiceSpec::parse("4d6kh3").unwrap();
+        for seed in 0..20 {
+            let roll = spec.roll(&mut ChaCha20Rng::seed_from_u64(seed));
+            let mut sorted = roll.rolls.clone();
+            sorted.sort_unstable();
+            let dropped = sorted[0];
+            assert_eq!(roll.rolls.len(), 4);
+            assert_eq!(roll.kept.len(), 3);
+            assert_eq!(roll.total, sorted.iter().map(|&r| i64::from(r)).sum::<i64>() - i64::from(dropped));
+        }
+
+        let gen = RandomNumber::new();
+        let result = gen.execute(make_dice_args("4d6kh3")).await.unwrap();
+        assert!(result.content.contains(" kept ["));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_dice_notation() {
+        let gen = RandomNumber::new();
+        let notations = [
+            "3x6", "3d", "4d6kh5", "0d6", "2d6+", "d20 advantage kh1",
+            // Multibyte trailing characters are rejected rather than panicking
+            "3d6é", "3d6+é",
+        ];
+        for notation in notations {
+            let result = gen.execute(make_dice_args(notation)).await;
+            assert!(
+                matches!(result, Err(ToolError::InvalidParameter { ref name, .. }) if name == "dice"),
+                "{notation} should be rejected"
+            );
+        }
+    }
 }
@@ -0,0 +1,35 @@
+//! Shared helpers for tool tests.
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Serve canned JSON responses keyed by request path prefix.
+///
+/// Routes are `(path_prefix, status, body)`; unmatched paths get a 404.
+/// Returns the base URL of the mock server.
+pub(crate) async fn mock_http(routes: Vec<(&'static str, u16, &'static str)>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else { break };
+            let mut buf = vec![0u8; 4096];
+            let n = stream.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+            let (status, body) = routes
+                .iter()
+                .find(|(prefix, _, _)| path.starts_with(prefix))
+                .map(|(_, status, body)| (*status, *body))
+                .unwrap_or((404, r#"{"type":"not_found"}"#));
+            let response = format!(
+                "HTTP/1.1 {} X\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    format!("http://{}", addr)
+}
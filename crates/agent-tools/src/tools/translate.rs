@@ -0,0 +1,211 @@
+//! Translate tool backed by the configured (privacy) brain.
+
+use async_trait::async_trait;
+use brain_core::InboundMessage;
+use tracing::{debug, info};
+
+use crate::error::ToolError;
+use crate::tool::{Tool, ToolArgs, ToolOutput};
+
+/// Maximum length of a language name argument.
+const MAX_LANGUAGE_CHARS: usize = 40;
+
+/// Translate tool that uses a Brain to translate text.
+///
+/// Like [`Sanitize`](crate::Sanitize), this tool requires a Brain to be
+/// provided in the ToolArgs (see `ToolRegistry::set_brain`). Wiring the
+/// registry to Maple keeps translation of sensitive content inside the TEE.
+///
+/// # Parameters
+///
+/// - `text` (required): The text to translate.
+/// - `to` (required): Target language (e.g. "Spanish", "fr").
+/// - `from` (optional): Source language. Auto-detected when omitted.
+///
+/// # Returns
+///
+/// Only the translated text, exactly as produced by the brain.
+///
+/// # Example
+///
+/// ```json
+/// {"text": "Where is the pharmacy?", "from": "English", "to": "Spanish"}
+/// ```
+///
+/// Returns: "¿Dónde está la farmacia?"
+pub struct Translate;
+
+impl Translate {
+    /// Create a new translate tool.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Build the prompt sent to the brain.
+    fn build_prompt(text: &str, from: Option<&str>, to: &str) -> String {
+        let direction = match from {
+            Some(from) => format!("from {} to {}", from, to),
+            None => format!("to {} (detect the source language)", to),
+        };
+        format!(
+            "Translate the following text {}. Output ONLY the translation, with no explanations, \
+             notes, quotes, or transliteration:\n\n{}",
+            direction, text
+        )
+    }
+}
+
+impl Default for Translate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn language_arg(args: &ToolArgs, key: &str) -> Result<Option<String>, ToolError> {
+    let Some(value) = args.get_string_opt(key) else {
+        return Ok(None);
+    };
+    let value = value.trim().to_string();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if value.chars().count() > MAX_LANGUAGE_CHARS || value.contains(['\n', '\r']) {
+        return Err(ToolError::InvalidParameter {
+            name: key.to_string(),
+            reason: "expected a language name or code".to_string(),
+        });
+    }
+    Ok(Some(value))
+}
+
+#[async_trait]
+impl Tool for Translate {
+    fn name(&self) -> &str {
+        "translate"
+    }
+
+    fn description(&self) -> &str {
+        "Translates text between languages using the privacy-preserving brain. \
+         Parameters: text, to (target language), from (optional source language)."
+    }
+
+    async fn execute(&self, args: ToolArgs) -> Result<ToolOutput, ToolError> {
+        let text = args.get_string("text")?;
+        if text.trim().is_empty() {
+            return Err(ToolError::InvalidParameter {
+                name: "text".to_string(),
+                reason: "Text cannot be empty".to_string(),
+            });
+        }
+        let to = language_arg(&args, "to")?.ok_or_else(|| ToolError::MissingParameter("to".to_string()))?;
+        let from = language_arg(&args, "from")?;
+
+        debug!("Translating text ({} chars) {:?} -> {}", text.len(), from, to);
+
+        let brain = args.brain.as_ref().ok_or_else(|| {
+            ToolError::ExecutionFailed("Translate tool requires a brain for translation".to_string())
+        })?;
+
+        let prompt = Self::build_prompt(&text, from.as_deref(), &to);
+
+        // Use a dedicated sender ID to avoid polluting real conversation history
+        let message = InboundMessage::direct("__translate_tool__", &prompt, 0);
+
+        let response = brain
+            .process(message)
+            .await
+            .map_err(|e| ToolError::BrainError(format!("Failed to process translation: {}", e)))?;
+
+        info!("Translated text: {} chars -> {} chars", text.len(), response.text.len());
+
+        Ok(ToolOutput::success(response.text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use brain_core::{Brain, BrainError, OutboundMessage};
+    use serde_json::Value;
+
+    /// Brain that records prompts and replies with a fixed text.
+    struct RecordingBrain {
+        reply: String,
+        prompts: Mutex<Vec<InboundMessage>>,
+    }
+
+    #[async_trait]
+    impl Brain for RecordingBrain {
+        async fn process(&self, message: InboundMessage) -> Result<OutboundMessage, BrainError> {
+            self.prompts.lock().unwrap().push(message.clone());
+            Ok(OutboundMessage::reply_to(&message, self.reply.clone()))
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+    }
+
+    fn make_args(params: &[(&str, &str)], brain: Option<Arc<dyn Brain>>) -> ToolArgs {
+        let params: HashMap<String, Value> = params
+            .iter()
+            .map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+            .collect();
+        match brain {
+            Some(brain) => ToolArgs::with_brain(params, brain),
+            None => ToolArgs::new(params),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prompt_and_verbatim_output() {
+        let brain = Arc::new(RecordingBrain {
+            reply: "¿Dónde está la farmacia?\n".to_string(),
+            prompts: Mutex::new(Vec::new()),
+        });
+        let args = make_args(
+            &[("text", "Where is the pharmacy?"), ("from", "English"), ("to", "Spanish")],
+            Some(brain.clone()),
+        );
+
+        let result = Translate::new().execute(args).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.content, "¿Dónde está la farmacia?\n");
+
+        let prompts = brain.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].sender, "__translate_tool__");
+        assert!(prompts[0].text.starts_with("Translate the following text from English to Spanish."));
+        assert!(prompts[0].text.contains("Output ONLY the translation"));
+        assert!(prompts[0].text.ends_with("\n\nWhere is the pharmacy?"));
+    }
+
+    #[tokio::test]
+    async fn test_prompt_without_source_language() {
+        let brain = Arc::new(RecordingBrain {
+            reply: "Bonjour".to_string(),
+            prompts: Mutex::new(Vec::new()),
+        });
+        let args = make_args(&[("text", "Hello"), ("to", "French")], Some(brain.clone()));
+
+        Translate::new().execute(args).await.unwrap();
+        let prompts = brain.prompts.lock().unwrap();
+        assert!(prompts[0].text.contains("to French (detect the source language)"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_target_language() {
+        let result = Translate::new().execute(make_args(&[("text", "Hello")], None)).await;
+        assert!(matches!(result, Err(ToolError::MissingParameter(ref name)) if name == "to"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_brain() {
+        let args = make_args(&[("text", "Hello"), ("to", "German")], None);
+        let result = Translate::new().execute(args).await;
+        assert!(matches!(result, Err(ToolError::ExecutionFailed(_))));
+    }
+}
@@ -1,7 +1,12 @@
 //! Unit converter tool for converting between common units.
 
+use std::collections::HashMap;
+use std::path::Path;
+
 use async_trait::async_trait;
-use tracing::debug;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{debug, warn};
 
 use crate::error::ToolError;
 use crate::tool::{Tool, ToolArgs, ToolOutput};
@@ -17,6 +22,9 @@ use crate::tool::{Tool, ToolArgs, ToolOutput};
 /// - Speed: km/h, mph, m/s, knots
 /// - Data: bytes, kilobytes, megabytes, gigabytes, terabytes
 ///
+/// Additional linear units can be supplied with [`UnitConverter::with_custom_units`]
+/// or via the environment (see [`UnitConverter::from_env`]).
+///
 /// # Parameters
 ///
 /// - `value` (required): The numeric value to convert.
@@ -30,12 +38,193 @@ use crate::tool::{Tool, ToolArgs, ToolOutput};
 /// {"value": 32, "from": "fahrenheit", "to": "celsius"}
 /// {"value": 5.5, "from": "kg", "to": "lb"}
 /// ```
-pub struct UnitConverter;
+pub struct UnitConverter {
+    /// Custom units keyed by lowercase name/alias.
+    custom: HashMap<String, CustomFactor>,
+}
+
+/// A custom unit definition, e.g. loaded from JSON.
+///
+/// `factor_to_base` is the multiplier to the category's base unit
+/// (meters, kilograms, liters, square meters, m/s, bytes). New categories
+/// may be introduced; the first unit defined with factor `1.0` acts as base.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct CustomUnit {
+    /// Unit name (matched case-insensitively).
+    pub name: String,
+    /// Alternative spellings or abbreviations.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Category, e.g. "volume".
+    pub category: String,
+    /// Factor to convert one unit into the category base unit.
+    pub factor_to_base: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CustomFactor {
+    to_base: f64,
+    category: String,
+}
+
+/// Environment variable holding custom units as a JSON array.
+pub const CUSTOM_UNITS_ENV: &str = "AMAN_CUSTOM_UNITS";
+/// Environment variable holding a path to a JSON file of custom units.
+pub const CUSTOM_UNITS_FILE_ENV: &str = "AMAN_CUSTOM_UNITS_FILE";
 
 impl UnitConverter {
-    /// Create a new unit converter tool.
+    /// Create a new unit converter tool with built-in units only.
     pub fn new() -> Self {
-        Self
+        Self {
+            custom: HashMap::new(),
+        }
+    }
+
+    /// Create a converter with additional units merged into the built-ins.
+    ///
+    /// Fails if a definition is invalid, redefines a built-in unit with a
+    /// different category or factor, or conflicts with another custom unit.
+    pub fn with_custom_units(units: Vec<CustomUnit>) -> Result<Self, ToolError> {
+        let mut custom: HashMap<String, CustomFactor> = HashMap::new();
+
+        for unit in units {
+            let category = unit.category.trim().to_lowercase();
+            let invalid = |reason: String| ToolError::InvalidParameter {
+                name: format!("unit '{}'", unit.name),
+                reason,
+            };
+            if category.is_empty() {
+                return Err(invalid("category cannot be empty".to_string()));
+            }
+            if category == "temperature" {
+                return Err(invalid("temperature units are not linear and cannot be customized".to_string()));
+            }
+            if !unit.factor_to_base.is_finite() || unit.factor_to_base <= 0.0 {
+                return Err(invalid("factor_to_base must be a positive number".to_string()));
+            }
+
+            let factor = CustomFactor {
+                to_base: unit.factor_to_base,
+                category,
+            };
+            for name in std::iter::once(&unit.name).chain(unit.aliases.iter()) {
+                let key = name.trim().to_lowercase();
+                if key.is_empty() {
+                    return Err(invalid("names and aliases cannot be empty".to_string()));
+                }
+                if let Some(builtin) = get_conversion(&key) {
+                    let same = builtin.category == factor.category
+                        && (builtin.to_base - factor.to_base).abs() <= f64::EPSILON * builtin.to_base.abs();
+                    if !same {
+                        return Err(invalid(format!(
+                            "'{}' conflicts with the built-in {} unit",
+                            key, builtin.category
+                        )));
+                    }
+                    continue;
+                }
+                match custom.get(&key) {
+                    Some(existing) if *existing != factor => {
+                        return Err(invalid(format!("'{}' is already defined differently", key)));
+                    }
+                    _ => {
+                        custom.insert(key, factor.clone());
+                    }
+                }
+            }
+        }
+
+        Ok(Self { custom })
+    }
+
+    /// Create a converter with custom units from the environment.
+    ///
+    /// Reads a JSON array of [`CustomUnit`] from `AMAN_CUSTOM_UNITS`, or from the
+    /// file named by `AMAN_CUSTOM_UNITS_FILE`. Invalid configuration is logged and
+    /// ignored so built-in units keep working.
+    pub fn from_env() -> Self {
+        let json = match (
+            std::env::var(CUSTOM_UNITS_ENV).ok(),
+            std::env::var(CUSTOM_UNITS_FILE_ENV).ok(),
+        ) {
+            (Some(json), _) if !json.trim().is_empty() => json,
+            (_, Some(path)) if !path.trim().is_empty() => match std::fs::read_to_string(Path::new(path.trim())) {
+                Ok(json) => json,
+                Err(e) => {
+                    warn!("Failed to read custom units file {}: {}", path, e);
+                    return Self::new();
+                }
+            },
+            _ => return Self::new(),
+        };
+
+        match Self::from_json(&json) {
+            Ok(converter) => converter,
+            Err(e) => {
+                warn!("Ignoring custom units: {}", e);
+                Self::new()
+            }
+        }
+    }
+
+    /// Create a converter from a JSON array of [`CustomUnit`] definitions.
+    pub fn from_json(json: &str) -> Result<Self, ToolError> {
+        let units: Vec<CustomUnit> = serde_json::from_str(json)?;
+        Self::with_custom_units(units)
+    }
+
+    /// Resolve a unit to its factor and category, custom units included.
+    fn resolve(&self, unit: &str) -> Option<(f64, String)> {
+        if let Some(builtin) = get_conversion(unit) {
+            return Some((builtin.to_base, builtin.category.to_string()));
+        }
+        self.custom
+            .get(&unit.trim().to_lowercase())
+            .map(|custom| (custom.to_base, custom.category.clone()))
+    }
+}
+
+impl UnitConverter {
+    /// The closest built-in unit to `unit` in the other measurement system.
+    ///
+    /// Returns None when `unit` already belongs to the requested system (or
+    /// has no metric/imperial split, like data sizes).
+    pub fn counterpart_unit(unit: &str, imperial: bool) -> Option<&'static str> {
+        let unit_lower = unit.trim().to_lowercase();
+        let target = match unit_lower.as_str() {
+            "km" | "kilometer" | "kilometers" => ("mi", false),
+            "m" | "meter" | "meters" => ("ft", false),
+            "cm" | "centimeter" | "centimeters" | "mm" | "millimeter" | "millimeters" => {
+                ("in", false)
+            }
+            "kg" | "kilogram" | "kilograms" | "t" | "ton" | "tons" | "tonne" | "tonnes" => {
+                ("lb", false)
+            }
+            "g" | "gram" | "grams" | "mg" | "milligram" | "milligrams" => ("oz", false),
+            "l" | "liter" | "liters" | "litre" | "litres" => ("gal", false),
+            "ml" | "milliliter" | "milliliters" | "millilitre" | "millilitres" => ("fl oz", false),
+            "m2" | "sqm" | "square meter" | "square meters" => ("ft2", false),
+            "km2" | "sqkm" | "square kilometer" | "square kilometers" | "ha" | "hectare"
+            | "hectares" => ("acre", false),
+            "km/h" | "kph" | "kmh" | "m/s" | "mps" => ("mph", false),
+            "c" | "celsius" | "k" | "kelvin" => ("f", false),
+            "mi" | "mile" | "miles" => ("km", true),
+            "ft" | "foot" | "feet" | "yd" | "yard" | "yards" => ("m", true),
+            "in" | "inch" | "inches" => ("cm", true),
+            "lb" | "lbs" | "pound" | "pounds" | "st" | "stone" | "stones" => ("kg", true),
+            "oz" | "ounce" | "ounces" => ("g", true),
+            "gal" | "gallon" | "gallons" | "qt" | "quart" | "quarts" | "pt" | "pint" | "pints"
+            | "cup" | "cups" => ("l", true),
+            "fl oz" | "fluid ounce" | "fluid ounces" | "floz" => ("ml", true),
+            "ft2" | "sqft" | "square foot" | "square feet" => ("m2", true),
+            "acre" | "acres" => ("ha", true),
+            "mph" | "ft/s" | "fps" => ("km/h", true),
+            "f" | "fahrenheit" => ("c", true),
+            _ => return None,
+        };
+
+        // `target.1` is true when the source unit is imperial.
+        (target.1 != imperial).then_some(target.0)
     }
 }
 
@@ -164,6 +353,18 @@ impl Tool for UnitConverter {
          area (sqft, acres), speed (km/h, mph), and data (KB, MB, GB)."
     }
 
+    fn args_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "value": { "type": "number", "description": "Value to convert" },
+                "from": { "type": "string", "description": "Source unit, e.g. \"km\"" },
+                "to": { "type": "string", "description": "Target unit, e.g. \"miles\"" }
+            },
+            "required": ["value", "from", "to"]
+        }))
+    }
+
     async fn execute(&self, args: ToolArgs) -> Result<ToolOutput, ToolError> {
         let value = args.get_number("value")?;
         let from = args.get_string("from")?;
@@ -172,34 +373,34 @@ impl Tool for UnitConverter {
         debug!("Converting {} {} to {}", value, from, to);
 
         // Get conversion factors
-        let from_conv = get_conversion(&from).ok_or_else(|| ToolError::InvalidParameter {
+        let (from_base, from_category) = self.resolve(&from).ok_or_else(|| ToolError::InvalidParameter {
             name: "from".to_string(),
             reason: format!("Unknown unit: {}. Supported units include: km, miles, kg, lb, celsius, fahrenheit, liters, gallons, etc.", from),
         })?;
 
-        let to_conv = get_conversion(&to).ok_or_else(|| ToolError::InvalidParameter {
+        let (to_base, to_category) = self.resolve(&to).ok_or_else(|| ToolError::InvalidParameter {
             name: "to".to_string(),
             reason: format!("Unknown unit: {}. Supported units include: km, miles, kg, lb, celsius, fahrenheit, liters, gallons, etc.", to),
         })?;
 
         // Check same category
-        if from_conv.category != to_conv.category {
+        if from_category != to_category {
             return Err(ToolError::InvalidParameter {
                 name: "to".to_string(),
                 reason: format!(
                     "Cannot convert {} ({}) to {} ({}). Units must be of the same type.",
-                    from, from_conv.category, to, to_conv.category
+                    from, from_category, to, to_category
                 ),
             });
         }
 
         // Temperature is special
-        let result = if from_conv.category == "temperature" {
+        let result = if from_category == "temperature" {
             convert_temperature(value, &from, &to)
         } else {
             // Convert: value * from_to_base / to_to_base
-            let base_value = value * from_conv.to_base;
-            base_value / to_conv.to_base
+            let base_value = value * from_base;
+            base_value / to_base
         };
 
         let formatted = format_number(result);
@@ -208,7 +409,14 @@ impl Tool for UnitConverter {
         Ok(ToolOutput::success(format!(
             "{} {} = {} {}",
             format_number(value), from, formatted, to
-        )))
+        ))
+        .with_data(json!({
+            "value": value,
+            "from": from,
+            "to": to,
+            "result": result,
+            "category": from_category,
+        })))
     }
 }
 
@@ -241,6 +449,23 @@ mod tests {
         assert!(result.content.contains("3.048"));
     }
 
+    #[tokio::test]
+    async fn test_conversion_has_content_and_data() {
+        let converter = UnitConverter::new();
+        let result = converter.execute(make_args(2.0, "kg", "g")).await.unwrap();
+        assert_eq!(result.content, "2 kg = 2000 g");
+        assert_eq!(
+            result.data,
+            Some(serde_json::json!({
+                "value": 2.0,
+                "from": "kg",
+                "to": "g",
+                "result": 2000.0,
+                "category": "weight",
+            }))
+        );
+    }
+
     #[tokio::test]
     async fn test_weight_conversion() {
         let converter = UnitConverter::new();
@@ -319,4 +544,66 @@ mod tests {
         let result = converter.execute(make_args(100.0, "KM", "Miles")).await.unwrap();
         assert!(result.success);
     }
+
+    fn custom(name: &str, aliases: &[&str], category: &str, factor: f64) -> CustomUnit {
+        CustomUnit {
+            name: name.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            category: category.to_string(),
+            factor_to_base: factor,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_unit_to_and_from_builtin() {
+        let converter = UnitConverter::from_json(
+            r#"[{"name": "arroba", "aliases": ["@"], "category": "volume", "factor_to_base": 15.62}]"#,
+        )
+        .unwrap();
+
+        let result = converter.execute(make_args(2.0, "arroba", "liters")).await.unwrap();
+        assert_eq!(result.content, "2 arroba = 31.24 liters");
+
+        let result = converter.execute(make_args(31.24, "l", "@")).await.unwrap();
+        assert_eq!(result.content, "31.24 l = 2 @");
+
+        // Built-ins still work unchanged
+        let result = converter.execute(make_args(100.0, "km", "miles")).await.unwrap();
+        assert!(result.content.contains("62.13"));
+    }
+
+    #[tokio::test]
+    async fn test_custom_unit_category_mismatch() {
+        let converter =
+            UnitConverter::with_custom_units(vec![custom("arroba", &[], "volume", 15.62)]).unwrap();
+        let result = converter.execute(make_args(1.0, "arroba", "kg")).await;
+        assert!(matches!(result, Err(ToolError::InvalidParameter { .. })));
+    }
+
+    #[test]
+    fn test_counterpart_unit() {
+        assert_eq!(UnitConverter::counterpart_unit("km", true), Some("mi"));
+        assert_eq!(UnitConverter::counterpart_unit("Celsius", true), Some("f"));
+        assert_eq!(UnitConverter::counterpart_unit("lbs", false), Some("kg"));
+        assert_eq!(UnitConverter::counterpart_unit("km", false), None);
+        assert_eq!(UnitConverter::counterpart_unit("miles", true), None);
+        assert_eq!(UnitConverter::counterpart_unit("gb", true), None);
+    }
+
+    #[test]
+    fn test_custom_unit_conflicts() {
+        // Redefining a built-in with a different meaning
+        assert!(UnitConverter::with_custom_units(vec![custom("mi", &[], "volume", 2.0)]).is_err());
+        // Conflicting custom definitions
+        assert!(UnitConverter::with_custom_units(vec![
+            custom("cuia", &[], "volume", 2.0),
+            custom("Cuia", &[], "volume", 3.0),
+        ])
+        .is_err());
+        // Temperature and invalid factors are rejected
+        assert!(UnitConverter::with_custom_units(vec![custom("rankine", &[], "temperature", 1.0)]).is_err());
+        assert!(UnitConverter::with_custom_units(vec![custom("zero", &[], "length", 0.0)]).is_err());
+        // Matching a built-in exactly is allowed
+        assert!(UnitConverter::with_custom_units(vec![custom("meter", &["metre"], "length", 1.0)]).is_ok());
+    }
 }
@@ -1,37 +1,362 @@
-//! Weather tool using wttr.in API.
+//! Weather tool using wttr.in or Open-Meteo.
+
+use std::fmt;
+use std::str::FromStr;
 
 use async_trait::async_trait;
+use serde::Deserialize;
 use tracing::{debug, warn};
 
 use crate::error::ToolError;
 use crate::tool::{Tool, ToolArgs, ToolOutput};
 
-/// Weather tool that fetches weather information from wttr.in.
+/// Weather data source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeatherProvider {
+    /// wttr.in (default).
+    Wttr,
+    /// Open-Meteo geocoding + forecast APIs.
+    OpenMeteo,
+}
+
+impl WeatherProvider {
+    fn other(self) -> Self {
+        match self {
+            Self::Wttr => Self::OpenMeteo,
+            Self::OpenMeteo => Self::Wttr,
+        }
+    }
+}
+
+impl fmt::Display for WeatherProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Wttr => write!(f, "wttr.in"),
+            Self::OpenMeteo => write!(f, "Open-Meteo"),
+        }
+    }
+}
+
+impl FromStr for WeatherProvider {
+    type Err = ToolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['-', '_', '.'], "").as_str() {
+            "wttr" | "wttrin" => Ok(Self::Wttr),
+            "openmeteo" => Ok(Self::OpenMeteo),
+            other => Err(ToolError::InvalidParameter {
+                name: "provider".to_string(),
+                reason: format!("unknown weather provider '{}'", other),
+            }),
+        }
+    }
+}
+
+/// Measurement system for temperatures and wind speeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeatherUnits {
+    /// Celsius and km/h (default).
+    #[default]
+    Metric,
+    /// Fahrenheit and mph.
+    Imperial,
+}
+
+impl FromStr for WeatherUnits {
+    type Err = ToolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "metric" | "c" | "celsius" => Ok(Self::Metric),
+            "imperial" | "f" | "fahrenheit" | "us" => Ok(Self::Imperial),
+            other => Err(ToolError::InvalidParameter {
+                name: "units".to_string(),
+                reason: format!("expected 'metric' or 'imperial', got '{}'", other),
+            }),
+        }
+    }
+}
+
+/// Base URLs for the weather APIs (overridable for tests).
+#[derive(Debug, Clone)]
+pub struct WeatherEndpoints {
+    /// wttr.in base URL.
+    pub wttr: String,
+    /// Open-Meteo geocoding API base URL.
+    pub geocoding: String,
+    /// Open-Meteo forecast API base URL.
+    pub forecast: String,
+}
+
+impl Default for WeatherEndpoints {
+    fn default() -> Self {
+        Self {
+            wttr: "https://wttr.in".to_string(),
+            geocoding: "https://geocoding-api.open-meteo.com".to_string(),
+            forecast: "https://api.open-meteo.com".to_string(),
+        }
+    }
+}
+
+impl WeatherEndpoints {
+    /// Serve every API from one base URL.
+    pub fn single(base_url: impl Into<String>) -> Self {
+        let base = base_url.into().trim_end_matches('/').to_string();
+        Self {
+            wttr: base.clone(),
+            geocoding: base.clone(),
+            forecast: base,
+        }
+    }
+}
+
+/// Current conditions normalized across providers, in metric units.
+#[derive(Debug, Clone, PartialEq)]
+struct Conditions {
+    temperature_c: f64,
+    condition: String,
+    wind_kmh: f64,
+    humidity: f64,
+}
+
+impl Conditions {
+    /// Format as "Condition, 21°C, wind 11 km/h, humidity 60%".
+    fn describe(&self, units: WeatherUnits) -> String {
+        let (temperature, temp_unit, wind, wind_unit) = match units {
+            WeatherUnits::Metric => (self.temperature_c, "°C", self.wind_kmh, "km/h"),
+            WeatherUnits::Imperial => (
+                self.temperature_c * 9.0 / 5.0 + 32.0,
+                "°F",
+                self.wind_kmh / 1.609_344,
+                "mph",
+            ),
+        };
+        format!(
+            "{}, {:.0}{}, wind {:.0} {}, humidity {:.0}%",
+            self.condition, temperature, temp_unit, wind, wind_unit, self.humidity
+        )
+    }
+}
+
+/// wttr.in `format=j1` response (only the fields we use).
+#[derive(Debug, Deserialize)]
+struct WttrResponse {
+    current_condition: Vec<WttrCurrent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WttrCurrent {
+    #[serde(rename = "temp_C")]
+    temp_c: String,
+    #[serde(rename = "windspeedKmph")]
+    windspeed_kmph: String,
+    humidity: String,
+    #[serde(rename = "weatherDesc", default)]
+    weather_desc: Vec<WttrValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WttrValue {
+    value: String,
+}
+
+/// Open-Meteo geocoding response.
+#[derive(Debug, Deserialize)]
+struct GeocodingResponse {
+    #[serde(default)]
+    results: Vec<GeocodingResult>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GeocodingResult {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    #[serde(default)]
+    admin1: Option<String>,
+    #[serde(default)]
+    country: Option<String>,
+    #[serde(default)]
+    population: Option<u64>,
+}
+
+impl GeocodingResult {
+    /// Human-readable label, e.g. "Springfield, Illinois, United States".
+    fn label(&self) -> String {
+        let mut parts = vec![self.name.as_str()];
+        for part in [&self.admin1, &self.country].into_iter().flatten() {
+            if !part.is_empty() && !parts.contains(&part.as_str()) {
+                parts.push(part);
+            }
+        }
+        parts.join(", ")
+    }
+}
+
+/// Maximum geocoding candidates requested and listed.
+const MAX_GEOCODING_RESULTS: usize = 5;
+
+/// A clear winner needs this many times the population of the runner-up.
+const DOMINANT_POPULATION_RATIO: u64 = 10;
+
+/// Outcome of resolving a place name.
+#[derive(Debug)]
+enum Resolution {
+    Place(GeocodingResult),
+    Ambiguous(Vec<GeocodingResult>),
+    NotFound,
+}
+
+/// Outcome of a provider lookup: conditions, or a name that needs narrowing.
+#[derive(Debug)]
+enum Lookup {
+    Conditions(Conditions),
+    Ambiguous(Vec<GeocodingResult>),
+}
+
+/// Choose a place from geocoding results, or report ambiguity.
+///
+/// "Strong" matches are results whose name equals the queried name. A
+/// qualifier after a comma ("Springfield, Illinois") narrows them by region
+/// or country. Several strong matches are ambiguous unless one dominates
+/// by population. When no name matches exactly (e.g. a query in another
+/// script), the API's top result is used.
+fn pick_place(query: &str, results: Vec<GeocodingResult>) -> Resolution {
+    let mut parts = query.split(',').map(|part| part.trim().to_lowercase());
+    let name = parts.next().unwrap_or_default();
+    let qualifiers: Vec<String> = parts.filter(|part| !part.is_empty()).collect();
+
+    let mut strong: Vec<GeocodingResult> = results
+        .iter()
+        .filter(|result| result.name.to_lowercase() == name)
+        .filter(|result| {
+            qualifiers.iter().all(|qualifier| {
+                [&result.admin1, &result.country]
+                    .into_iter()
+                    .flatten()
+                    .any(|region| region.to_lowercase().starts_with(qualifier.as_str()))
+            })
+        })
+        .cloned()
+        .collect();
+
+    if strong.is_empty() {
+        return match results.into_iter().next() {
+            Some(first) => Resolution::Place(first),
+            None => Resolution::NotFound,
+        };
+    }
+    if strong.len() == 1 {
+        return Resolution::Place(strong.remove(0));
+    }
+
+    strong.sort_by_key(|result| std::cmp::Reverse(result.population.unwrap_or(0)));
+    let top = strong[0].population.unwrap_or(0);
+    let runner_up = strong[1].population.unwrap_or(0);
+    if top > 0 && top >= runner_up.saturating_mul(DOMINANT_POPULATION_RATIO) {
+        return Resolution::Place(strong.remove(0));
+    }
+
+    strong.truncate(MAX_GEOCODING_RESULTS);
+    Resolution::Ambiguous(strong)
+}
+
+/// Format a short list of candidate places.
+fn disambiguation(query: &str, candidates: &[GeocodingResult]) -> String {
+    let mut output = format!("Multiple places match '{}':", query.trim());
+    for (i, candidate) in candidates.iter().enumerate() {
+        output.push_str(&format!("\n{}. {}", i + 1, candidate.label()));
+    }
+    if let Some(first) = candidates.first() {
+        let hint = first.admin1.as_ref().or(first.country.as_ref());
+        if let Some(hint) = hint {
+            output.push_str(&format!(
+                "\nPlease say which one, e.g. \"{}, {}\".",
+                first.name, hint
+            ));
+        }
+    }
+    output
+}
+
+/// Open-Meteo forecast response.
+#[derive(Debug, Deserialize)]
+struct ForecastResponse {
+    current: ForecastCurrent,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForecastCurrent {
+    temperature_2m: f64,
+    relative_humidity_2m: f64,
+    wind_speed_10m: f64,
+    weather_code: u16,
+}
+
+/// Describe a WMO weather interpretation code.
+fn wmo_condition(code: u16) -> &'static str {
+    match code {
+        0 => "Clear sky",
+        1 => "Mainly clear",
+        2 => "Partly cloudy",
+        3 => "Overcast",
+        45 | 48 => "Fog",
+        51 | 53 | 55 => "Drizzle",
+        56 | 57 => "Freezing drizzle",
+        61 | 63 | 65 => "Rain",
+        66 | 67 => "Freezing rain",
+        71 | 73 | 75 | 77 => "Snow",
+        80..=82 => "Rain showers",
+        85 | 86 => "Snow showers",
+        95 => "Thunderstorm",
+        96 | 99 => "Thunderstorm with hail",
+        _ => "Unknown conditions",
+    }
+}
+
+fn parse_number(value: &str, field: &str) -> Result<f64, ToolError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| ToolError::ExecutionFailed(format!("Invalid {} in weather response", field)))
+}
+
+/// Weather tool that fetches current conditions for a location.
 ///
-/// The wttr.in service is free and requires no API key. It provides
-/// weather information in various formats.
+/// Uses wttr.in by default, or Open-Meteo when configured. Both are free
+/// and require no API key. If the configured provider fails, the other one
+/// is tried before giving up.
+///
+/// With geocoding enabled (always on for Open-Meteo), the place name is
+/// first resolved through the Open-Meteo geocoding API. The resolved label
+/// is reported with the forecast, and ambiguous names like "Springfield"
+/// return a short list of candidates instead of a guess.
 ///
 /// # Parameters
 ///
 /// - `location` (required): City name, airport code, or coordinates.
+/// - `units` (optional): "metric" (default) or "imperial".
 /// - `format` (optional): Output format. Options:
-///   - "short" (default): One-line summary with temperature and conditions.
-///   - "full": Multi-line detailed forecast.
-///   - "json": Raw JSON data.
+///   - "short" (default): Condition, temperature, wind, and humidity.
+///   - "full": Multi-line detailed forecast (wttr.in only).
+///   - "json": Raw JSON data (wttr.in only).
 ///
 /// # Examples
 ///
 /// ```json
-/// {"location": "New York"}
+/// {"location": "New York", "units": "imperial"}
 /// {"location": "London", "format": "full"}
 /// {"location": "LAX", "format": "short"}
 /// ```
 pub struct Weather {
     client: reqwest::Client,
+    provider: WeatherProvider,
+    endpoints: WeatherEndpoints,
+    geocode: bool,
 }
 
 impl Weather {
-    /// Create a new weather tool.
+    /// Create a new weather tool using wttr.in.
     pub fn new() -> Self {
         Self {
             client: reqwest::Client::builder()
@@ -39,30 +364,59 @@ impl Weather {
                 .timeout(std::time::Duration::from_secs(10))
                 .build()
                 .expect("Failed to create HTTP client"),
+            provider: WeatherProvider::Wttr,
+            endpoints: WeatherEndpoints::default(),
+            geocode: false,
         }
     }
 
-    /// Fetch weather for a location with the given format.
-    async fn fetch_weather(&self, location: &str, format: &str) -> Result<String, ToolError> {
-        // URL-encode the location
-        let encoded_location = urlencoding::encode(location);
+    /// Create a weather tool configured from the environment.
+    ///
+    /// - `AMAN_WEATHER_PROVIDER`: `wttr` (default) or `open-meteo`
+    /// - `AMAN_WEATHER_GEOCODE`: resolve place names before fetching (default: false)
+    pub fn from_env() -> Self {
+        let provider = match std::env::var("AMAN_WEATHER_PROVIDER") {
+            Ok(value) if !value.trim().is_empty() => value.parse().unwrap_or_else(|e| {
+                warn!("Ignoring AMAN_WEATHER_PROVIDER: {}", e);
+                WeatherProvider::Wttr
+            }),
+            _ => WeatherProvider::Wttr,
+        };
+        let geocode = std::env::var("AMAN_WEATHER_GEOCODE")
+            .map(|value| matches!(value.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on"))
+            .unwrap_or(false);
+        Self::new().with_provider(provider).with_geocoding(geocode)
+    }
 
+    /// Set the primary provider.
+    pub fn with_provider(mut self, provider: WeatherProvider) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    /// Resolve place names via geocoding before fetching the forecast.
+    pub fn with_geocoding(mut self, enabled: bool) -> Self {
+        self.geocode = enabled;
+        self
+    }
+
+    /// Override API base URLs.
+    pub fn with_endpoints(mut self, endpoints: WeatherEndpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Fetch raw wttr.in output for the "full" and "json" formats.
+    async fn fetch_wttr_raw(&self, location: &str, format: &str) -> Result<String, ToolError> {
+        let encoded_location = urlencoding::encode(location);
         let url = match format {
-            "full" => format!("https://wttr.in/{}?T", encoded_location),
-            "json" => format!("https://wttr.in/{}?format=j1", encoded_location),
-            _ => {
-                // Short format: one-liner with emoji, condition, temp
-                format!("https://wttr.in/{}?format=%c+%C+%t", encoded_location)
-            }
+            "full" => format!("{}/{}?T", self.endpoints.wttr, encoded_location),
+            _ => format!("{}/{}?format=j1", self.endpoints.wttr, encoded_location),
         };
 
         debug!("Fetching weather from: {}", url);
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await?;
+        let response = self.client.get(&url).send().await?;
 
         if !response.status().is_success() {
             return Err(ToolError::ExecutionFailed(format!(
@@ -83,6 +437,135 @@ impl Weather {
 
         Ok(body.trim().to_string())
     }
+
+    /// Current conditions from wttr.in.
+    async fn fetch_wttr(&self, location: &str) -> Result<Conditions, ToolError> {
+        let body = self.fetch_wttr_raw(location, "json").await?;
+        let data: WttrResponse = serde_json::from_str(&body)?;
+        let current = data.current_condition.into_iter().next().ok_or_else(|| {
+            ToolError::ExecutionFailed(format!("No current conditions for {}", location))
+        })?;
+
+        Ok(Conditions {
+            temperature_c: parse_number(&current.temp_c, "temperature")?,
+            condition: current
+                .weather_desc
+                .first()
+                .map(|desc| desc.value.trim().to_string())
+                .unwrap_or_else(|| "Unknown conditions".to_string()),
+            wind_kmh: parse_number(&current.windspeed_kmph, "wind speed")?,
+            humidity: parse_number(&current.humidity, "humidity")?,
+        })
+    }
+
+    /// Resolve a place name with the Open-Meteo geocoding API.
+    async fn geocode(&self, location: &str) -> Result<Resolution, ToolError> {
+        // Search by the bare name; qualifiers are applied in `pick_place`.
+        let name = location.split(',').next().unwrap_or(location).trim();
+        let count = MAX_GEOCODING_RESULTS.to_string();
+        let url = format!("{}/v1/search", self.endpoints.geocoding);
+        debug!("Geocoding '{}' via {}", name, url);
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("name", name),
+                ("count", count.as_str()),
+                ("language", "en"),
+                ("format", "json"),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionFailed(format!(
+                "Geocoding API returned status {}",
+                response.status()
+            )));
+        }
+        let geocoding: GeocodingResponse = response.json().await?;
+        Ok(pick_place(location, geocoding.results))
+    }
+
+    /// Current conditions from Open-Meteo for a resolved place.
+    async fn fetch_open_meteo(&self, place: &GeocodingResult) -> Result<Conditions, ToolError> {
+        let url = format!("{}/v1/forecast", self.endpoints.forecast);
+        debug!("Fetching forecast for {},{}", place.latitude, place.longitude);
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("latitude", place.latitude.to_string()),
+                ("longitude", place.longitude.to_string()),
+                (
+                    "current",
+                    "temperature_2m,relative_humidity_2m,wind_speed_10m,weather_code".to_string(),
+                ),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionFailed(format!(
+                "Forecast API returned status {}",
+                response.status()
+            )));
+        }
+        let forecast: ForecastResponse = response.json().await?;
+
+        Ok(Conditions {
+            temperature_c: forecast.current.temperature_2m,
+            condition: wmo_condition(forecast.current.weather_code).to_string(),
+            wind_kmh: forecast.current.wind_speed_10m,
+            humidity: forecast.current.relative_humidity_2m,
+        })
+    }
+
+    async fn fetch_from(
+        &self,
+        provider: WeatherProvider,
+        location: &str,
+        place: Option<&GeocodingResult>,
+    ) -> Result<Lookup, ToolError> {
+        let conditions = match (provider, place) {
+            (WeatherProvider::Wttr, Some(place)) => {
+                self.fetch_wttr(&format!("{},{}", place.latitude, place.longitude))
+                    .await?
+            }
+            (WeatherProvider::Wttr, None) => self.fetch_wttr(location).await?,
+            (WeatherProvider::OpenMeteo, Some(place)) => self.fetch_open_meteo(place).await?,
+            (WeatherProvider::OpenMeteo, None) => match self.geocode(location).await? {
+                Resolution::Place(place) => self.fetch_open_meteo(&place).await?,
+                Resolution::Ambiguous(candidates) => return Ok(Lookup::Ambiguous(candidates)),
+                Resolution::NotFound => {
+                    return Err(ToolError::ExecutionFailed(format!(
+                        "Location not found: {}",
+                        location
+                    )))
+                }
+            },
+        };
+        Ok(Lookup::Conditions(conditions))
+    }
+
+    /// Fetch current conditions, falling back to the other provider on failure.
+    async fn fetch_conditions(
+        &self,
+        location: &str,
+        place: Option<&GeocodingResult>,
+    ) -> Result<Lookup, ToolError> {
+        match self.fetch_from(self.provider, location, place).await {
+            Ok(conditions) => Ok(conditions),
+            Err(primary_err) => {
+                let fallback = self.provider.other();
+                warn!(
+                    "Weather fetch from {} failed ({}), trying {}",
+                    self.provider, primary_err, fallback
+                );
+                self.fetch_from(fallback, location, place)
+                    .await
+                    .map_err(|_| primary_err)
+            }
+        }
+    }
 }
 
 impl Default for Weather {
@@ -98,27 +581,60 @@ impl Tool for Weather {
     }
 
     fn description(&self) -> &str {
-        "Fetches current weather for a location using wttr.in. \
-         Supports city names, airport codes, and coordinates."
+        "Fetches current weather for a location. \
+         Supports city names, airport codes, and coordinates. \
+         Parameters: location, units (optional: metric or imperial)."
     }
 
     async fn execute(&self, args: ToolArgs) -> Result<ToolOutput, ToolError> {
         let location = args.get_string("location")?;
         let format = args.get_string_opt("format").unwrap_or_else(|| "short".to_string());
+        let units = match args.get_string_opt("units") {
+            Some(units) if !units.trim().is_empty() => units.parse()?,
+            _ => WeatherUnits::default(),
+        };
 
-        debug!("Getting weather for '{}' (format: {})", location, format);
+        debug!("Getting weather for '{}' (format: {}, units: {:?})", location, format, units);
 
-        match self.fetch_weather(&location, &format).await {
-            Ok(weather) => {
-                if format == "short" {
-                    Ok(ToolOutput::success(format!(
-                        "Weather in {}: {}",
-                        location, weather
-                    )))
-                } else {
-                    Ok(ToolOutput::success(weather))
+        if format == "full" || format == "json" {
+            return match self.fetch_wttr_raw(&location, &format).await {
+                Ok(weather) => Ok(ToolOutput::success(weather)),
+                Err(e) => {
+                    warn!("Weather fetch failed: {}", e);
+                    Err(e)
+                }
+            };
+        }
+
+        let place = if self.geocode || self.provider == WeatherProvider::OpenMeteo {
+            match self.geocode(&location).await {
+                Ok(Resolution::Place(place)) => Some(place),
+                Ok(Resolution::Ambiguous(candidates)) => {
+                    return Ok(ToolOutput::success(disambiguation(&location, &candidates)));
+                }
+                Ok(Resolution::NotFound) => None,
+                Err(e) => {
+                    warn!("Geocoding failed, using the raw location: {}", e);
+                    None
                 }
             }
+        } else {
+            None
+        };
+        let label = place
+            .as_ref()
+            .map(GeocodingResult::label)
+            .unwrap_or_else(|| location.clone());
+
+        match self.fetch_conditions(&location, place.as_ref()).await {
+            Ok(Lookup::Conditions(conditions)) => Ok(ToolOutput::success(format!(
+                "Weather in {}: {}",
+                label,
+                conditions.describe(units)
+            ))),
+            Ok(Lookup::Ambiguous(candidates)) => {
+                Ok(ToolOutput::success(disambiguation(&location, &candidates)))
+            }
             Err(e) => {
                 warn!("Weather fetch failed: {}", e);
                 Err(e)
@@ -151,8 +667,15 @@ mod urlencoding {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
+    use crate::tools::test_support::mock_http;
     use serde_json::Value;
+    use std::collections::HashMap;
+
+    const WTTR_LONDON: &str = r#"{"current_condition":[{"temp_C":"21","temp_F":"70","windspeedKmph":"16","humidity":"60","weatherDesc":[{"value":"Partly cloudy"}]}]}"#;
+    const GEOCODE_LONDON: &str = r#"{"results":[{"name":"London","latitude":51.5,"longitude":-0.12,"admin1":"England","country":"United Kingdom","population":8961989},{"name":"London","latitude":42.98,"longitude":-81.23,"admin1":"Ontario","country":"Canada","population":346765}]}"#;
+    const GEOCODE_SPRINGFIELD: &str = r#"{"results":[{"name":"Springfield","latitude":37.21,"longitude":-93.29,"admin1":"Missouri","country":"United States","population":169176},{"name":"Springfield","latitude":42.1,"longitude":-72.59,"admin1":"Massachusetts","country":"United States","population":155929},{"name":"Springfield","latitude":39.8,"longitude":-89.64,"admin1":"Illinois","country":"United States","population":114230}]}"#;
+    const GEOCODE_TOKYO: &str = r#"{"results":[{"name":"Tokyo","latitude":35.69,"longitude":139.69,"admin1":"Tokyo","country":"Japan","population":8336599}]}"#;
+    const FORECAST_LONDON: &str = r#"{"current":{"temperature_2m":10.0,"relative_humidity_2m":81,"wind_speed_10m":24.0,"weather_code":61}}"#;
 
     fn make_args(location: &str) -> ToolArgs {
         let mut params = HashMap::new();
@@ -174,6 +697,176 @@ mod tests {
         assert_eq!(urlencoding::encode("São Paulo"), "S%C3%A3o+Paulo");
     }
 
+    fn with_units(mut args: ToolArgs, units: &str) -> ToolArgs {
+        args.params.insert("units".to_string(), Value::String(units.to_string()));
+        args
+    }
+
+    #[tokio::test]
+    async fn test_wttr_normalized_metric() {
+        let base = mock_http(vec![("/London", 200, WTTR_LONDON)]).await;
+        let weather = Weather::new().with_endpoints(WeatherEndpoints::single(base));
+
+        let result = weather.execute(make_args("London")).await.unwrap();
+        assert_eq!(
+            result.content,
+            "Weather in London: Partly cloudy, 21°C, wind 16 km/h, humidity 60%"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_meteo_normalized_imperial() {
+        let base = mock_http(vec![
+            ("/v1/search", 200, GEOCODE_LONDON),
+            ("/v1/forecast", 200, FORECAST_LONDON),
+        ])
+        .await;
+        let weather = Weather::new()
+            .with_provider(WeatherProvider::OpenMeteo)
+            .with_endpoints(WeatherEndpoints::single(base));
+
+        let result = weather
+            .execute(with_units(make_args("London"), "imperial"))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.content,
+            "Weather in London, England, United Kingdom: Rain, 50°F, wind 15 mph, humidity 81%"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_other_provider() {
+        // wttr.in is down (404 for every path); Open-Meteo answers.
+        let base = mock_http(vec![
+            ("/v1/search", 200, GEOCODE_LONDON),
+            ("/v1/forecast", 200, FORECAST_LONDON),
+        ])
+        .await;
+        let weather = Weather::new().with_endpoints(WeatherEndpoints::single(base));
+
+        let result = weather.execute(make_args("London")).await.unwrap();
+        assert!(result.content.contains("Rain, 10°C, wind 24 km/h"));
+
+        // And the other way around.
+        let base = mock_http(vec![("/London", 200, WTTR_LONDON)]).await;
+        let weather = Weather::new()
+            .with_provider(WeatherProvider::OpenMeteo)
+            .with_endpoints(WeatherEndpoints::single(base));
+
+        let result = weather
+            .execute(with_units(make_args("London"), "imperial"))
+            .await
+            .unwrap();
+        assert!(result.content.contains("Partly cloudy, 70°F, wind 10 mph"));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_reports_ambiguous_name() {
+        // wttr.in is down, so Open-Meteo geocodes the raw name on fallback.
+        let base = mock_http(vec![
+            ("/v1/search", 200, GEOCODE_SPRINGFIELD),
+            ("/v1/forecast", 200, FORECAST_LONDON),
+        ])
+        .await;
+        let weather = Weather::new().with_endpoints(WeatherEndpoints::single(base));
+
+        let result = weather.execute(make_args("Springfield")).await.unwrap();
+        assert!(result.success);
+        assert!(result.content.starts_with("Multiple places match 'Springfield':"));
+        assert!(result.content.contains("2. Springfield, Massachusetts, United States"));
+        assert!(!result.content.contains("°C"));
+    }
+
+    #[tokio::test]
+    async fn test_both_providers_fail() {
+        let base = mock_http(vec![]).await;
+        let weather = Weather::new().with_endpoints(WeatherEndpoints::single(base));
+
+        let result = weather.execute(make_args("Nowhere")).await;
+        assert!(matches!(result, Err(ToolError::ExecutionFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_geocoding_unique_city() {
+        let base = mock_http(vec![
+            ("/v1/search", 200, GEOCODE_LONDON),
+            ("/51.5%2C-0.12", 200, WTTR_LONDON),
+        ])
+        .await;
+        let weather = Weather::new()
+            .with_geocoding(true)
+            .with_endpoints(WeatherEndpoints::single(base));
+
+        let result = weather.execute(make_args("London")).await.unwrap();
+        assert!(result.success);
+        assert!(result
+            .content
+            .starts_with("Weather in London, England, United Kingdom: Partly cloudy"));
+    }
+
+    #[tokio::test]
+    async fn test_geocoding_ambiguous_name() {
+        let base = mock_http(vec![("/v1/search", 200, GEOCODE_SPRINGFIELD)]).await;
+        let weather = Weather::new()
+            .with_geocoding(true)
+            .with_endpoints(WeatherEndpoints::single(base));
+
+        let result = weather.execute(make_args("Springfield")).await.unwrap();
+        assert!(result.success);
+        assert!(result.content.starts_with("Multiple places match 'Springfield':"));
+        assert!(result.content.contains("1. Springfield, Missouri, United States"));
+        assert!(result.content.contains("3. Springfield, Illinois, United States"));
+        assert!(!result.content.contains("°C"));
+    }
+
+    #[tokio::test]
+    async fn test_geocoding_qualifier_resolves_ambiguity() {
+        let base = mock_http(vec![
+            ("/v1/search", 200, GEOCODE_SPRINGFIELD),
+            ("/v1/forecast", 200, FORECAST_LONDON),
+        ])
+        .await;
+        let weather = Weather::new()
+            .with_provider(WeatherProvider::OpenMeteo)
+            .with_endpoints(WeatherEndpoints::single(base));
+
+        let result = weather.execute(make_args("Springfield, Illinois")).await.unwrap();
+        assert!(result
+            .content
+            .starts_with("Weather in Springfield, Illinois, United States: Rain"));
+    }
+
+    #[tokio::test]
+    async fn test_geocoding_non_latin_name() {
+        let base = mock_http(vec![
+            ("/v1/search", 200, GEOCODE_TOKYO),
+            ("/v1/forecast", 200, FORECAST_LONDON),
+        ])
+        .await;
+        let weather = Weather::new()
+            .with_provider(WeatherProvider::OpenMeteo)
+            .with_endpoints(WeatherEndpoints::single(base));
+
+        let result = weather.execute(make_args("東京")).await.unwrap();
+        assert!(result.success);
+        assert!(result.content.starts_with("Weather in Tokyo, Japan: Rain, 10°C"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_units() {
+        let weather = Weather::new();
+        let result = weather.execute(with_units(make_args("London"), "kelvin")).await;
+        assert!(matches!(result, Err(ToolError::InvalidParameter { .. })));
+    }
+
+    #[test]
+    fn test_provider_parse() {
+        assert_eq!("open-meteo".parse::<WeatherProvider>().unwrap(), WeatherProvider::OpenMeteo);
+        assert_eq!("wttr.in".parse::<WeatherProvider>().unwrap(), WeatherProvider::Wttr);
+        assert!("darksky".parse::<WeatherProvider>().is_err());
+    }
+
     #[tokio::test]
     async fn test_missing_location() {
         let weather = Weather::new();
@@ -54,7 +54,7 @@ fn is_private_ip(ip: &IpAddr) -> bool {
 }
 
 /// Validate that a URL does not point to a private/internal address (SSRF protection).
-async fn validate_url_ssrf(url_str: &str) -> Result<(), ToolError> {
+pub(crate) async fn validate_url_ssrf(url_str: &str) -> Result<(), ToolError> {
     let url = Url::parse(url_str).map_err(|e| ToolError::InvalidParameter {
         name: "url".to_string(),
         reason: format!("Invalid URL: {}", e),
@@ -0,0 +1,325 @@
+//! Wikipedia summary tool using the Wikipedia REST API.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::debug;
+use url::Url;
+
+use crate::error::ToolError;
+use crate::tool::{Tool, ToolArgs, ToolOutput};
+
+/// Response from the REST `page/summary` endpoint.
+#[derive(Debug, Deserialize)]
+struct PageSummary {
+    #[serde(rename = "type", default)]
+    kind: String,
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    extract: String,
+    #[serde(default)]
+    content_urls: Option<ContentUrls>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentUrls {
+    desktop: Option<PageUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageUrl {
+    page: String,
+}
+
+/// Response from the REST `search/title` endpoint.
+#[derive(Debug, Deserialize)]
+struct TitleSearch {
+    #[serde(default)]
+    pages: Vec<SearchPage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchPage {
+    key: String,
+}
+
+/// Wikipedia summary tool.
+///
+/// Fetches the lead summary of a Wikipedia article. Faster and more private
+/// than a live web search for "who/what is X" questions. Free API, no key
+/// required. If the exact title is not found, the best title-search match is
+/// used instead.
+///
+/// # Parameters
+///
+/// - `query` (required): Article title or search text
+/// - `language` (optional): Wikipedia language code. Defaults to `en`.
+///
+/// # Examples
+///
+/// ```json
+/// {"query": "Ada Lovelace"}
+/// {"query": "Bitcoin", "language": "es"}
+/// ```
+pub struct WikiSummary {
+    client: reqwest::Client,
+    base_url: Option<String>,
+}
+
+impl WikiSummary {
+    /// Create a new Wikipedia summary tool.
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .user_agent("AmanBot/1.0")
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .expect("Failed to create HTTP client"),
+            base_url: None,
+        }
+    }
+
+    /// Use a fixed base URL instead of `https://{language}.wikipedia.org`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    fn endpoint(&self, language: &str, segments: &[&str]) -> Result<Url, ToolError> {
+        let base = match &self.base_url {
+            Some(base) => base.clone(),
+            None => format!("https://{}.wikipedia.org", language),
+        };
+        let mut url = Url::parse(&base)
+            .map_err(|e| ToolError::ExecutionFailed(format!("Invalid Wikipedia URL: {}", e)))?;
+        url.path_segments_mut()
+            .map_err(|_| ToolError::ExecutionFailed("Invalid Wikipedia URL".to_string()))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    /// Fetch a page summary, returning `None` if the title does not exist.
+    async fn fetch_summary(&self, language: &str, title: &str) -> Result<Option<PageSummary>, ToolError> {
+        let title = title.trim().replace(' ', "_");
+        let url = self.endpoint(language, &["api", "rest_v1", "page", "summary", &title])?;
+        debug!("Fetching Wikipedia summary: {}", url);
+
+        let response = self.client.get(url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionFailed(format!(
+                "Wikipedia API returned status {}",
+                response.status()
+            )));
+        }
+        Ok(Some(response.json().await?))
+    }
+
+    /// Find the best matching article key for free-text queries.
+    async fn search_title(&self, language: &str, query: &str) -> Result<Option<String>, ToolError> {
+        let mut url = self.endpoint(language, &["w", "rest.php", "v1", "search", "title"])?;
+        url.query_pairs_mut()
+            .append_pair("q", query)
+            .append_pair("limit", "1");
+        debug!("Searching Wikipedia titles: {}", url);
+
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+        let search: TitleSearch = response.json().await?;
+        Ok(search.pages.into_iter().next().map(|page| page.key))
+    }
+
+    fn format_summary(summary: &PageSummary) -> String {
+        let url = summary
+            .content_urls
+            .as_ref()
+            .and_then(|urls| urls.desktop.as_ref())
+            .map(|desktop| desktop.page.as_str());
+
+        if summary.kind == "disambiguation" {
+            let mut output = format!(
+                "**{}** may refer to several topics. Please ask about a more specific title.",
+                summary.title
+            );
+            if !summary.extract.trim().is_empty() {
+                output.push_str(&format!("\n\n{}", summary.extract.trim()));
+            }
+            if let Some(url) = url {
+                output.push_str(&format!("\n\nOptions: {}", url));
+            }
+            return output;
+        }
+
+        let mut output = format!("**{}**", summary.title);
+        if let Some(description) = summary.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            output.push_str(&format!(" — {}", description.trim()));
+        }
+        output.push_str(&format!("\n\n{}", summary.extract.trim()));
+        if let Some(url) = url {
+            output.push_str(&format!("\n\nSource: {}", url));
+        }
+        output
+    }
+}
+
+impl Default for WikiSummary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Accept plain language codes like `en`, `pt`, `zh-yue`.
+fn is_valid_language(language: &str) -> bool {
+    let mut parts = language.split('-');
+    let primary = parts.next().unwrap_or_default();
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|ch| ch.is_ascii_lowercase())
+        && parts.all(|part| !part.is_empty() && part.chars().all(|ch| ch.is_ascii_lowercase()))
+}
+
+#[async_trait]
+impl Tool for WikiSummary {
+    fn name(&self) -> &str {
+        "wiki_summary"
+    }
+
+    fn description(&self) -> &str {
+        "Looks up the Wikipedia summary for a topic. Parameters: query (title or search text), \
+         language (optional code, default en)."
+    }
+
+    async fn execute(&self, args: ToolArgs) -> Result<ToolOutput, ToolError> {
+        let query = args.get_string("query")?;
+        if query.trim().is_empty() {
+            return Err(ToolError::InvalidParameter {
+                name: "query".to_string(),
+                reason: "Query cannot be empty".to_string(),
+            });
+        }
+
+        let language = args
+            .get_string_opt("language")
+            .map(|lang| lang.trim().to_lowercase())
+            .filter(|lang| !lang.is_empty())
+            .unwrap_or_else(|| "en".to_string());
+        if !is_valid_language(&language) {
+            return Err(ToolError::InvalidParameter {
+                name: "language".to_string(),
+                reason: format!("'{}' is not a Wikipedia language code", language),
+            });
+        }
+
+        let mut summary = self.fetch_summary(&language, &query).await?;
+        if summary.is_none() {
+            if let Some(key) = self.search_title(&language, &query).await? {
+                summary = self.fetch_summary(&language, &key).await?;
+            }
+        }
+
+        match summary {
+            Some(summary) => Ok(ToolOutput::success(Self::format_summary(&summary))),
+            None => Ok(ToolOutput::failure(format!(
+                "No Wikipedia article found for '{}'",
+                query.trim()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::test_support::mock_http;
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    fn make_args(query: &str) -> ToolArgs {
+        let mut params = HashMap::new();
+        params.insert("query".to_string(), Value::String(query.to_string()));
+        ToolArgs::new(params)
+    }
+
+    #[tokio::test]
+    async fn test_standard_page() {
+        let base = mock_http(vec![(
+            "/api/rest_v1/page/summary/Ada_Lovelace",
+            200,
+            r#"{"type":"standard","title":"Ada Lovelace","description":"English mathematician","extract":"Augusta Ada King was an English mathematician.","content_urls":{"desktop":{"page":"https://en.wikipedia.org/wiki/Ada_Lovelace"}}}"#,
+        )])
+        .await;
+        let tool = WikiSummary::new().with_base_url(base);
+
+        let result = tool.execute(make_args("Ada Lovelace")).await.unwrap();
+        assert!(result.success);
+        assert!(result.content.starts_with("**Ada Lovelace** — English mathematician"));
+        assert!(result.content.contains("English mathematician."));
+        assert!(result.content.ends_with("Source: https://en.wikipedia.org/wiki/Ada_Lovelace"));
+    }
+
+    #[tokio::test]
+    async fn test_disambiguation_page() {
+        let base = mock_http(vec![(
+            "/api/rest_v1/page/summary/Mercury",
+            200,
+            r#"{"type":"disambiguation","title":"Mercury","extract":"Mercury commonly refers to:","content_urls":{"desktop":{"page":"https://en.wikipedia.org/wiki/Mercury"}}}"#,
+        )])
+        .await;
+        let tool = WikiSummary::new().with_base_url(base);
+
+        let result = tool.execute(make_args("Mercury")).await.unwrap();
+        assert!(result.success);
+        assert!(result.content.contains("may refer to several topics"));
+        assert!(result.content.contains("Options: https://en.wikipedia.org/wiki/Mercury"));
+    }
+
+    #[tokio::test]
+    async fn test_not_found_title() {
+        let base = mock_http(vec![("/w/rest.php/v1/search/title", 200, r#"{"pages":[]}"#)]).await;
+        let tool = WikiSummary::new().with_base_url(base);
+
+        let result = tool.execute(make_args("Qwxzyplk")).await.unwrap();
+        assert!(!result.success);
+        assert!(result.content.contains("No Wikipedia article found for 'Qwxzyplk'"));
+    }
+
+    #[tokio::test]
+    async fn test_search_fallback() {
+        let base = mock_http(vec![
+            ("/w/rest.php/v1/search/title", 200, r#"{"pages":[{"key":"Satoshi_Nakamoto"}]}"#),
+            (
+                "/api/rest_v1/page/summary/Satoshi_Nakamoto",
+                200,
+                r#"{"type":"standard","title":"Satoshi Nakamoto","extract":"Pseudonymous creator of Bitcoin."}"#,
+            ),
+        ])
+        .await;
+        let tool = WikiSummary::new().with_base_url(base);
+
+        let result = tool.execute(make_args("who created bitcoin")).await.unwrap();
+        assert!(result.success);
+        assert!(result.content.contains("Satoshi Nakamoto"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_language() {
+        let tool = WikiSummary::new();
+        let mut args = make_args("Bitcoin");
+        args.params.insert("language".to_string(), Value::String("evil.com/".to_string()));
+        let result = tool.execute(args).await;
+        assert!(matches!(result, Err(ToolError::InvalidParameter { .. })));
+    }
+
+    #[test]
+    fn test_language_codes() {
+        assert!(is_valid_language("en"));
+        assert!(is_valid_language("zh-yue"));
+        assert!(!is_valid_language("english"));
+        assert!(!is_valid_language("e"));
+    }
+}
@@ -0,0 +1,114 @@
+//! Bearer tokens with per-route scopes for Aman HTTP gateways.
+//!
+//! A gateway configures one token with every scope plus scoped tokens as
+//! `token:scope+scope` entries separated by commas, e.g.
+//! `reader:read,ops:read+write`. The API gateway and the Cloudflare worker
+//! share this crate; it has no dependencies and builds for `wasm32`.
+
+use std::collections::{BTreeSet, HashMap};
+
+/// What a token may do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Scope {
+    /// Chat completions, KB search and status.
+    Read,
+    /// KB sync, reindex, reloads and other admin routes.
+    Write,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 2] = [Scope::Read, Scope::Write];
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "read" => Some(Scope::Read),
+            "write" => Some(Scope::Write),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Write => "write",
+        }
+    }
+}
+
+/// Configured tokens and their scopes. Empty means auth is disabled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiTokens {
+    tokens: HashMap<String, BTreeSet<Scope>>,
+}
+
+impl ApiTokens {
+    /// `single` gets every scope; `scoped` lists `token:scope+scope` entries.
+    pub fn new(single: Option<&str>, scoped: Option<&str>) -> Result<Self, String> {
+        let mut tokens: HashMap<String, BTreeSet<Scope>> = HashMap::new();
+        if let Some(token) = single.map(str::trim).filter(|token| !token.is_empty()) {
+            tokens.insert(token.to_string(), Scope::ALL.into_iter().collect());
+        }
+
+        for entry in scoped.unwrap_or_default().split(',') {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            let Some((token, scopes)) = entry.rsplit_once(':') else {
+                return Err("token entry without scopes: expected token:scope+scope".to_string());
+            };
+            let token = token.trim();
+            if token.is_empty() {
+                return Err("token entry with an empty token".to_string());
+            }
+            let scopes = scopes
+                .split('+')
+                .map(|scope| {
+                    Scope::parse(scope).ok_or_else(|| format!("unknown scope '{}'", scope.trim()))
+                })
+                .collect::<Result<BTreeSet<_>, _>>()?;
+            tokens.entry(token.to_string()).or_default().extend(scopes);
+        }
+
+        Ok(Self { tokens })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Scopes granted to `token`, or `None` if it is not configured.
+    pub fn scopes(&self, token: &str) -> Option<&BTreeSet<Scope>> {
+        self.tokens.get(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_token_has_every_scope() {
+        let tokens = ApiTokens::new(Some("secret"), None).unwrap();
+        let scopes = tokens.scopes("secret").unwrap();
+        assert!(scopes.contains(&Scope::Read) && scopes.contains(&Scope::Write));
+        assert!(tokens.scopes("other").is_none());
+        assert!(ApiTokens::new(None, None).unwrap().is_empty());
+        assert!(ApiTokens::new(None, Some(" ")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scoped_tokens_parse() {
+        let tokens = ApiTokens::new(Some("admin"), Some("reader:read, ops:READ+write")).unwrap();
+        assert_eq!(
+            tokens.scopes("reader").unwrap().iter().copied().collect::<Vec<_>>(),
+            vec![Scope::Read]
+        );
+        assert_eq!(tokens.scopes("ops").unwrap().len(), 2);
+        assert_eq!(tokens.scopes("admin").unwrap().len(), 2);
+
+        assert!(ApiTokens::new(None, Some("reader")).is_err());
+        assert!(ApiTokens::new(None, Some(":read")).is_err());
+        assert!(ApiTokens::new(None, Some("reader:delete")).is_err());
+    }
+}
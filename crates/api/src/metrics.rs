@@ -0,0 +1,180 @@
+//! Minimal in-process metrics registry rendered in Prometheus text format.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds (seconds) for the request latency histogram.
+const LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0];
+
+#[derive(Default)]
+pub struct Metrics {
+    requests: Mutex<BTreeMap<&'static str, ModeStats>>,
+    upstream_errors: AtomicU64,
+    kb_lookups: AtomicU64,
+    kb_hits: AtomicU64,
+}
+
+#[derive(Default)]
+struct ModeStats {
+    count: u64,
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum_seconds: f64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a completed chat request for the given API mode.
+    pub fn record_request(&self, mode: &'static str, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let mut requests = self.requests.lock().unwrap_or_else(|err| err.into_inner());
+        let stats = requests.entry(mode).or_default();
+        stats.count += 1;
+        stats.sum_seconds += seconds;
+        for (bucket, bound) in stats.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+    }
+
+    /// Record a failed call to the upstream provider (OpenRouter).
+    pub fn record_upstream_error(&self) {
+        self.upstream_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a knowledge base lookup and whether it produced a hit.
+    pub fn record_kb_lookup(&self, hit: bool) {
+        self.kb_lookups.fetch_add(1, Ordering::Relaxed);
+        if hit {
+            self.kb_hits.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        let requests = self.requests.lock().unwrap_or_else(|err| err.into_inner());
+
+        out.push_str("# HELP aman_api_requests_total Chat completion requests by API mode.\n");
+        out.push_str("# TYPE aman_api_requests_total counter\n");
+        for (mode, stats) in requests.iter() {
+            let _ = writeln!(out, "aman_api_requests_total{{mode=\"{}\"}} {}", mode, stats.count);
+        }
+
+        out.push_str("# HELP aman_api_request_duration_seconds Chat completion latency by API mode.\n");
+        out.push_str("# TYPE aman_api_request_duration_seconds histogram\n");
+        for (mode, stats) in requests.iter() {
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(stats.buckets) {
+                let _ = writeln!(
+                    out,
+                    "aman_api_request_duration_seconds_bucket{{mode=\"{}\",le=\"{}\"}} {}",
+                    mode, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "aman_api_request_duration_seconds_bucket{{mode=\"{}\",le=\"+Inf\"}} {}",
+                mode, stats.count
+            );
+            let _ = writeln!(
+                out,
+                "aman_api_request_duration_seconds_sum{{mode=\"{}\"}} {}",
+                mode, stats.sum_seconds
+            );
+            let _ = writeln!(
+                out,
+                "aman_api_request_duration_seconds_count{{mode=\"{}\"}} {}",
+                mode, stats.count
+            );
+        }
+        drop(requests);
+
+        out.push_str("# HELP aman_api_upstream_errors_total Failed OpenRouter upstream calls.\n");
+        out.push_str("# TYPE aman_api_upstream_errors_total counter\n");
+        let _ = writeln!(
+            out,
+            "aman_api_upstream_errors_total {}",
+            self.upstream_errors.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP aman_api_kb_lookups_total Knowledge base lookups.\n");
+        out.push_str("# TYPE aman_api_kb_lookups_total counter\n");
+        let _ = writeln!(
+            out,
+            "aman_api_kb_lookups_total {}",
+            self.kb_lookups.load(Ordering::Relaxed)
+        );
+
+        out.push_str("# HELP aman_api_kb_hits_total Knowledge base lookups that returned a match.\n");
+        out.push_str("# TYPE aman_api_kb_hits_total counter\n");
+        let _ = writeln!(
+            out,
+            "aman_api_kb_hits_total {}",
+            self.kb_hits.load(Ordering::Relaxed)
+        );
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_well_formed(line: &str) -> bool {
+        let Some((series, value)) = line.rsplit_once(' ') else {
+            return false;
+        };
+        let name_end = series.find('{').unwrap_or(series.len());
+        let name = &series[..name_end];
+        let labels_ok = name_end == series.len() || series.ends_with('}');
+        !name.is_empty()
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && labels_ok
+            && value.parse::<f64>().is_ok()
+    }
+
+    #[test]
+    fn test_render_after_requests() {
+        let metrics = Metrics::new();
+        metrics.record_request("echo", Duration::from_millis(3));
+        metrics.record_request("echo", Duration::from_millis(300));
+        metrics.record_request("openrouter", Duration::from_secs(20));
+        metrics.record_upstream_error();
+        metrics.record_kb_lookup(true);
+        metrics.record_kb_lookup(false);
+
+        let output = metrics.render();
+        for line in output.lines() {
+            if line.starts_with('#') {
+                assert!(line.starts_with("# HELP ") || line.starts_with("# TYPE "), "{line}");
+            } else {
+                assert!(is_well_formed(line), "malformed metric line: {line}");
+            }
+        }
+
+        assert!(output.contains("aman_api_requests_total{mode=\"echo\"} 2"));
+        assert!(output.contains("aman_api_requests_total{mode=\"openrouter\"} 1"));
+        assert!(output.contains("aman_api_request_duration_seconds_bucket{mode=\"echo\",le=\"0.005\"} 1"));
+        assert!(output.contains("aman_api_request_duration_seconds_bucket{mode=\"echo\",le=\"0.5\"} 2"));
+        assert!(output.contains("aman_api_request_duration_seconds_bucket{mode=\"openrouter\",le=\"10\"} 0"));
+        assert!(output.contains("aman_api_request_duration_seconds_bucket{mode=\"openrouter\",le=\"+Inf\"} 1"));
+        assert!(output.contains("aman_api_upstream_errors_total 1"));
+        assert!(output.contains("aman_api_kb_lookups_total 2"));
+        assert!(output.contains("aman_api_kb_hits_total 1"));
+    }
+
+    #[test]
+    fn test_render_empty_registry() {
+        let output = Metrics::new().render();
+        assert!(output.contains("aman_api_upstream_errors_total 0"));
+        assert!(!output.contains("aman_api_requests_total{"));
+    }
+}
@@ -28,4 +28,21 @@ pub enum BrainError {
     /// Network or API error.
     #[error("network error: {0}")]
     Network(String),
+
+    /// The brain deliberately chose not to reply (e.g. a routing decision).
+    /// Not a failure: callers should send nothing.
+    #[error("message skipped: {0}")]
+    Skipped(String),
+
+    /// The message was accidental or meaningless and is silently ignored.
+    /// Not a failure: callers should send nothing.
+    #[error("message ignored")]
+    Ignored,
+}
+
+impl BrainError {
+    /// Whether this is a deliberate no-reply outcome rather than a failure.
+    pub fn is_no_reply(&self) -> bool {
+        matches!(self, Self::Skipped(_) | Self::Ignored)
+    }
 }
@@ -4,18 +4,25 @@
 //! automatic turn-based trimming and LRU eviction to prevent memory exhaustion.
 
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
 /// Default maximum number of senders to track before LRU eviction.
 const DEFAULT_MAX_SENDERS: usize = 10000;
 
+/// Maximum characters kept from a single tool result in history.
+const MAX_TOOL_RESULT_CHARS: usize = 2000;
+
 /// A single message in the conversation history.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HistoryMessage {
-    /// Role: "user" or "assistant"
+    /// Role: "user", "assistant", "system" or "tool"
     pub role: String,
     /// Message content
     pub content: String,
+    /// Tool that produced this message (tool role only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
 }
 
 impl HistoryMessage {
@@ -24,6 +31,7 @@ impl HistoryMessage {
         Self {
             role: "user".to_string(),
             content: content.into(),
+            tool_name: None,
         }
     }
 
@@ -32,6 +40,7 @@ impl HistoryMessage {
         Self {
             role: "system".to_string(),
             content: content.into(),
+            tool_name: None,
         }
     }
 
@@ -40,7 +49,72 @@ impl HistoryMessage {
         Self {
             role: "assistant".to_string(),
             content: content.into(),
+            tool_name: None,
+        }
+    }
+
+    /// Create a tool result message.
+    pub fn tool(tool_name: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            role: "tool".to_string(),
+            content: content.into(),
+            tool_name: Some(tool_name.into()),
+        }
+    }
+
+    /// Whether this message holds a tool result.
+    pub fn is_tool(&self) -> bool {
+        self.role == "tool"
+    }
+
+    /// Convert to a (role, content) pair for a chat completion prompt.
+    ///
+    /// Chat APIs only accept "tool" messages in reply to a tool call, so tool
+    /// results are passed as system context instead.
+    pub fn into_prompt_parts(self) -> (String, String) {
+        if !self.is_tool() {
+            return (self.role, self.content);
+        }
+        let tool = self.tool_name.as_deref().unwrap_or("tool");
+        (
+            "system".to_string(),
+            format!("Result from the {} tool:\n{}", tool, self.content),
+        )
+    }
+}
+
+/// Truncate a tool result to the history limit, on a char boundary.
+fn clip_tool_result(message: &HistoryMessage) -> HistoryMessage {
+    if message.content.chars().count() <= MAX_TOOL_RESULT_CHARS {
+        return message.clone();
+    }
+    let mut clipped: String = message.content.chars().take(MAX_TOOL_RESULT_CHARS).collect();
+    clipped.push_str("...");
+    HistoryMessage {
+        content: clipped,
+        ..message.clone()
+    }
+}
+
+/// Drop the oldest turns so at most `max_turns` user messages remain.
+///
+/// A turn is a user message plus everything after it up to the next one
+/// (tool results and the assistant reply).
+fn trim_to_turns(history: &mut Vec<HistoryMessage>, max_turns: usize) {
+    let user_positions: Vec<usize> = history
+        .iter()
+        .enumerate()
+        .filter(|(_, msg)| msg.role == "user")
+        .map(|(i, _)| i)
+        .collect();
+    if user_positions.len() <= max_turns {
+        return;
+    }
+    match user_positions.get(user_positions.len() - max_turns) {
+        Some(&cut) => {
+            history.drain(0..cut);
         }
+        None => history.clear(),
     }
 }
 
@@ -131,21 +205,37 @@ impl ConversationHistory {
     ///
     /// This also performs LRU eviction if the sender limit is exceeded.
     pub async fn add_exchange(&self, sender: &str, user_msg: &str, assistant_msg: &str) {
+        self.add_exchange_with_tools(sender, user_msg, &[], assistant_msg)
+            .await;
+    }
+
+    /// Add a user message, the tool results gathered for it, and the
+    /// assistant response to the history.
+    ///
+    /// Tool results are stored between the user message and the reply and
+    /// are trimmed together with their turn.
+    pub async fn add_exchange_with_tools(
+        &self,
+        sender: &str,
+        user_msg: &str,
+        tool_results: &[HistoryMessage],
+        assistant_msg: &str,
+    ) {
         let mut histories = self.histories.write().await;
 
         // Remove and re-insert to move to end (mark as recently used)
-        let history = histories.shift_remove(sender).unwrap_or_default();
-        let mut history = history;
+        let mut history = histories.shift_remove(sender).unwrap_or_default();
 
         history.push(HistoryMessage::user(user_msg));
+        history.extend(
+            tool_results
+                .iter()
+                .filter(|msg| msg.is_tool())
+                .map(clip_tool_result),
+        );
         history.push(HistoryMessage::assistant(assistant_msg));
 
-        // Trim to max turns (each turn is 2 messages)
-        let max_messages = self.max_turns * 2;
-        if history.len() > max_messages {
-            let to_remove = history.len() - max_messages;
-            history.drain(0..to_remove);
-        }
+        trim_to_turns(&mut history, self.max_turns);
 
         histories.insert(sender.to_string(), history);
 
@@ -156,6 +246,29 @@ impl ConversationHistory {
         }
     }
 
+    /// Remove the most recent user/assistant exchange for a sender,
+    /// including any tool results recorded with it.
+    ///
+    /// Returns false if the sender has no complete exchange to remove.
+    pub async fn remove_last_exchange(&self, sender: &str) -> bool {
+        let mut histories = self.histories.write().await;
+        let Some(history) = histories.get_mut(sender) else {
+            return false;
+        };
+        if history.last().map(|msg| msg.role.as_str()) != Some("assistant") {
+            return false;
+        }
+        let before_reply = &history[..history.len() - 1];
+        let Some(user_index) = before_reply.iter().rposition(|msg| !msg.is_tool()) else {
+            return false;
+        };
+        if history[user_index].role != "user" {
+            return false;
+        }
+        history.truncate(user_index);
+        true
+    }
+
     /// Clear history for a specific sender.
     pub async fn clear(&self, sender: &str) {
         let mut histories = self.histories.write().await;
@@ -214,6 +327,83 @@ mod tests {
         assert_eq!(messages[1].content, "Hi there!");
     }
 
+    #[tokio::test]
+    async fn test_remove_last_exchange() {
+        let history = ConversationHistory::new(5);
+
+        history.add_exchange("+1234", "First", "Response 1").await;
+        history.add_exchange("+1234", "Second", "Response 2").await;
+
+        assert!(history.remove_last_exchange("+1234").await);
+        let messages = history.get("+1234").await;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content, "First");
+        assert_eq!(messages[1].content, "Response 1");
+
+        assert!(history.remove_last_exchange("+1234").await);
+        assert!(!history.remove_last_exchange("+1234").await);
+        assert!(!history.remove_last_exchange("+5678").await);
+    }
+
+    #[tokio::test]
+    async fn test_tool_results_kept_with_their_turn() {
+        let history = ConversationHistory::new(2);
+
+        history.add_exchange("+1234", "First", "Response 1").await;
+        history
+            .add_exchange_with_tools(
+                "+1234",
+                "What's 2+2?",
+                &[HistoryMessage::tool("calculator", "4")],
+                "It's 4.",
+            )
+            .await;
+
+        let messages = history.get("+1234").await;
+        let roles: Vec<_> = messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant", "user", "tool", "assistant"]);
+        assert_eq!(messages[3].tool_name.as_deref(), Some("calculator"));
+
+        // A third turn trims the first, not the tool result
+        history.add_exchange("+1234", "Third", "Response 3").await;
+        let messages = history.get("+1234").await;
+        assert_eq!(messages.len(), 5);
+        assert_eq!(messages[0].content, "What's 2+2?");
+        assert!(messages[1].is_tool());
+
+        // Forgetting the last exchange leaves the tool turn intact
+        assert!(history.remove_last_exchange("+1234").await);
+        assert!(history.remove_last_exchange("+1234").await);
+        assert!(history.get("+1234").await.is_empty());
+    }
+
+    #[test]
+    fn test_tool_message_serde_round_trip() {
+        let message = HistoryMessage::tool("weather", "Sunny, 21°C");
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(
+            json,
+            r#"{"role":"tool","content":"Sunny, 21°C","tool_name":"weather"}"#
+        );
+        assert_eq!(serde_json::from_str::<HistoryMessage>(&json).unwrap(), message);
+
+        // Messages serialized before tool_name existed still load
+        let old: HistoryMessage =
+            serde_json::from_str(r#"{"role":"user","content":"hi"}"#).unwrap();
+        assert_eq!(old, HistoryMessage::user("hi"));
+        assert_eq!(serde_json::to_string(&old).unwrap(), r#"{"role":"user","content":"hi"}"#);
+    }
+
+    #[test]
+    fn test_tool_message_prompt_parts() {
+        let (role, content) = HistoryMessage::tool("calculator", "4").into_prompt_parts();
+        assert_eq!(role, "system");
+        assert_eq!(content, "Result from the calculator tool:\n4");
+
+        let (role, content) = HistoryMessage::user("hi").into_prompt_parts();
+        assert_eq!((role.as_str(), content.as_str()), ("user", "hi"));
+    }
+
     #[tokio::test]
     async fn test_history_trimming() {
         let history = ConversationHistory::new(2); // Keep only 2 turns
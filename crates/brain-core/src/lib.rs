@@ -39,14 +39,15 @@ mod trait_def;
 pub use error::BrainError;
 pub use history::{ConversationHistory, HistoryMessage};
 pub use memory::{
-    format_memory_prompt, MemoryClearEvent, MemoryError, MemoryPiiPolicy, MemoryPromptPolicy,
-    MemorySnapshot, MemoryStore, MemoryToolEntry,
+    format_memory_prompt, redact_pii, MemoryClearEvent, MemoryError, MemoryPiiPolicy,
+    MemoryPromptPolicy, MemoryPromptPreset, MemorySnapshot, MemoryStore, MemoryToolEntry,
+    MemoryTurn,
 };
 pub use message::{
-    InboundAttachment, InboundMessage, OutboundMessage, RoutingInfo, Sensitivity, TaskHint,
-    TextStyle,
+    InboundAttachment, InboundMessage, OutboundMessage, RoutingDebug, RoutingInfo, Sensitivity,
+    TaskHint, TextStyle,
 };
-pub use prompt::hash_prompt;
+pub use prompt::{hash_context, hash_prompt, PromptContext};
 pub use tools::{ToolExecutor, ToolRequest, ToolRequestMeta, ToolResult};
 pub use trait_def::Brain;
 
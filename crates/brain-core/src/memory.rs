@@ -1,5 +1,8 @@
 //! Durable memory contracts shared across brains and orchestrators.
 
+use std::fmt;
+use std::str::FromStr;
+
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -15,6 +18,9 @@ pub struct MemorySnapshot {
     pub tool_history: Vec<MemoryToolEntry>,
     /// Clear-context events (most recent first).
     pub clear_context_events: Vec<MemoryClearEvent>,
+    /// Recent user/assistant exchanges (most recent first).
+    #[serde(default)]
+    pub recent_turns: Vec<MemoryTurn>,
 }
 
 impl MemorySnapshot {
@@ -25,10 +31,24 @@ impl MemorySnapshot {
             .as_ref()
             .map(|text| text.trim().is_empty())
             .unwrap_or(true);
-        summary_empty && self.tool_history.is_empty() && self.clear_context_events.is_empty()
+        summary_empty
+            && self.tool_history.is_empty()
+            && self.clear_context_events.is_empty()
+            && self.recent_turns.is_empty()
     }
 }
 
+/// A recent user/assistant exchange included in a memory snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryTurn {
+    /// What the user said (possibly truncated).
+    pub user: String,
+    /// What the assistant replied (possibly truncated).
+    pub assistant: String,
+    /// Creation timestamp (provider-defined format).
+    pub created_at: Option<String>,
+}
+
 /// Tool history entry included in a memory snapshot.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryToolEntry {
@@ -84,6 +104,9 @@ pub struct MemoryPromptPolicy {
     pub include_clear_context: bool,
     /// PII handling policy.
     pub pii_policy: MemoryPiiPolicy,
+    /// Recent exchanges to include verbatim after the summary (0 disables).
+    #[serde(default)]
+    pub max_recent_turns: usize,
 }
 
 impl Default for MemoryPromptPolicy {
@@ -98,6 +121,68 @@ impl Default for MemoryPromptPolicy {
             include_tool_history: true,
             include_clear_context: true,
             pii_policy: MemoryPiiPolicy::Allow,
+            max_recent_turns: 0,
+        }
+    }
+}
+
+/// Named memory prompt presets controlling how much memory is injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryPromptPreset {
+    /// Summary only.
+    Minimal,
+    /// Summary plus the last 2 exchanges.
+    Standard,
+    /// Summary plus the last 6 exchanges.
+    Verbose,
+}
+
+impl MemoryPromptPreset {
+    /// Build the prompt policy for this preset.
+    ///
+    /// Tool history and clear-context sections are left out; the PII policy
+    /// is the default and can be overridden afterwards.
+    pub fn policy(self) -> MemoryPromptPolicy {
+        let (max_chars, max_recent_turns) = match self {
+            Self::Minimal => (1000, 0),
+            Self::Standard => (1800, 2),
+            Self::Verbose => (4000, 6),
+        };
+        MemoryPromptPolicy {
+            max_chars,
+            max_recent_turns,
+            include_tool_history: false,
+            include_clear_context: false,
+            ..MemoryPromptPolicy::default()
+        }
+    }
+
+    /// Preset name as used in configuration.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Minimal => "minimal",
+            Self::Standard => "standard",
+            Self::Verbose => "verbose",
+        }
+    }
+}
+
+impl fmt::Display for MemoryPromptPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MemoryPromptPreset {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "minimal" => Ok(Self::Minimal),
+            "standard" => Ok(Self::Standard),
+            "verbose" => Ok(Self::Verbose),
+            other => Err(format!("unknown memory prompt preset: {}", other)),
         }
     }
 }
@@ -148,6 +233,23 @@ pub fn format_memory_prompt(
         }
     }
 
+    if policy.max_recent_turns > 0 && !snapshot.recent_turns.is_empty() {
+        let mut section = String::new();
+        section.push_str("[RECENT TURNS]\n");
+        // Oldest first, so the exchange reads in order.
+        let turns: Vec<&MemoryTurn> = snapshot
+            .recent_turns
+            .iter()
+            .take(policy.max_recent_turns)
+            .collect();
+        for turn in turns.into_iter().rev() {
+            let user = apply_pii_policy(&turn.user, policy.pii_policy);
+            let assistant = apply_pii_policy(&turn.assistant, policy.pii_policy);
+            section.push_str(&format!("U: {}\nA: {}\n", user, assistant));
+        }
+        sections.push(section.trim_end().to_string());
+    }
+
     if policy.include_tool_history && policy.max_tool_entries > 0 && !snapshot.tool_history.is_empty()
     {
         let mut section = String::new();
@@ -233,7 +335,9 @@ fn apply_pii_policy(text: &str, policy: MemoryPiiPolicy) -> String {
     }
 }
 
-fn redact_pii(text: &str) -> String {
+/// Replace email-like tokens and runs of six or more digits (phone, account,
+/// and ID numbers) with placeholders.
+pub fn redact_pii(text: &str) -> String {
     let without_emails = redact_email_tokens(text);
     redact_digit_runs(&without_emails)
 }
@@ -318,3 +422,83 @@ fn truncate_text(text: &str, max_chars: usize) -> String {
     output.push_str(ellipsis);
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> MemorySnapshot {
+        MemorySnapshot {
+            summary: Some("User is planning a trip to Lisbon.".to_string()),
+            summary_updated_at: Some("2026-01-01 10:00:00".to_string()),
+            tool_history: vec![MemoryToolEntry {
+                tool: "weather".to_string(),
+                success: true,
+                content: "Sunny, 24C".to_string(),
+                created_at: None,
+            }],
+            clear_context_events: Vec::new(),
+            recent_turns: (1..=8)
+                .rev()
+                .map(|i| MemoryTurn {
+                    user: format!("question {}", i),
+                    assistant: format!("answer {}", i),
+                    created_at: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn render(preset: MemoryPromptPreset) -> String {
+        format_memory_prompt(&snapshot(), &preset.policy()).unwrap()
+    }
+
+    #[test]
+    fn test_minimal_preset_is_summary_only() {
+        let prompt = render(MemoryPromptPreset::Minimal);
+        assert!(prompt.contains("[SUMMARY]"));
+        assert!(prompt.contains("Lisbon"));
+        assert!(!prompt.contains("[RECENT TURNS]"));
+        assert!(!prompt.contains("[TOOLS]"));
+    }
+
+    #[test]
+    fn test_standard_preset_includes_last_two_turns() {
+        let prompt = render(MemoryPromptPreset::Standard);
+        assert!(prompt.contains("[SUMMARY]"));
+        assert_eq!(prompt.matches("U: ").count(), 2);
+        // Oldest of the two first.
+        let older = prompt.find("U: question 7").unwrap();
+        let newer = prompt.find("U: question 8").unwrap();
+        assert!(older < newer);
+        assert!(!prompt.contains("question 6"));
+    }
+
+    #[test]
+    fn test_verbose_preset_includes_last_six_turns() {
+        let prompt = render(MemoryPromptPreset::Verbose);
+        assert_eq!(prompt.matches("U: ").count(), 6);
+        assert_eq!(prompt.matches("A: ").count(), 6);
+        assert!(prompt.contains("question 3"));
+        assert!(!prompt.contains("question 2"));
+
+        let minimal = render(MemoryPromptPreset::Minimal).len();
+        let standard = render(MemoryPromptPreset::Standard).len();
+        assert!(minimal < standard && standard < prompt.len());
+    }
+
+    #[test]
+    fn test_default_policy_ignores_turns() {
+        let prompt = format_memory_prompt(&snapshot(), &MemoryPromptPolicy::default()).unwrap();
+        assert!(!prompt.contains("[RECENT TURNS]"));
+        assert!(prompt.contains("[TOOLS]"));
+    }
+
+    #[test]
+    fn test_preset_from_str() {
+        assert_eq!("Verbose".parse(), Ok(MemoryPromptPreset::Verbose));
+        assert_eq!(" minimal ".parse(), Ok(MemoryPromptPreset::Minimal));
+        assert!("chatty".parse::<MemoryPromptPreset>().is_err());
+        assert_eq!(MemoryPromptPreset::Standard.to_string(), "standard");
+    }
+}
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::history::HistoryMessage;
+
 /// An attachment included with a message.
 #[derive(Debug, Clone, Default)]
 pub struct InboundAttachment {
@@ -124,6 +126,78 @@ pub struct RoutingInfo {
     pub memory_source: Option<String>,
     /// Summary timestamp used for the memory snapshot, if available.
     pub memory_summary_updated_at: Option<String>,
+    /// Preferred reply language for the user, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Text to store in history when the message text was augmented with
+    /// gathered context.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_text: Option<String>,
+    /// Tool results gathered for this message, to keep in history.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_results: Vec<HistoryMessage>,
+    /// Whether the caller asked for routing details on the response.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub debug: bool,
+}
+
+/// Compact routing details for debugging model/brain choices.
+///
+/// Carries hashes and labels only, never prompt or memory text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingDebug {
+    /// Brain that produced the response (e.g., "maple", "grok").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brain: Option<String>,
+    /// Sensitivity used for the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensitivity: Option<Sensitivity>,
+    /// Task hint used for model selection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_hint: Option<TaskHint>,
+    /// Model selected for the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Router prompt fingerprint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub router_prompt_hash: Option<String>,
+    /// System prompt fingerprint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt_hash: Option<String>,
+    /// Memory prompt fingerprint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_prompt_hash: Option<String>,
+    /// Memory source identifier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_source: Option<String>,
+}
+
+impl RoutingInfo {
+    /// Compact debug view of this routing, for the given brain.
+    pub fn debug_summary(&self, brain: impl Into<String>) -> RoutingDebug {
+        RoutingDebug {
+            brain: Some(brain.into()),
+            sensitivity: self.sensitivity,
+            task_hint: self.task_hint,
+            model: self.model_override.clone(),
+            router_prompt_hash: self.router_prompt_hash.clone(),
+            system_prompt_hash: self.system_prompt_hash.clone(),
+            memory_prompt_hash: self.memory_prompt_hash.clone(),
+            memory_source: self.memory_source.clone(),
+        }
+    }
+
+    /// System instruction asking the brain to reply in the user's language.
+    pub fn language_hint(&self) -> Option<String> {
+        let language = self.language.as_deref()?.trim();
+        if language.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "The user's preferred language is {}. Reply in {} unless they write in another language.",
+            language, language
+        ))
+    }
 }
 
 /// An incoming message to be processed by the brain.
@@ -149,7 +223,7 @@ pub struct InboundMessage {
 }
 
 /// A text style range for message formatting.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TextStyle {
     /// Start position in the text (character index).
     pub start: u32,
@@ -171,7 +245,7 @@ impl TextStyle {
 }
 
 /// An outgoing message generated by the brain.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OutboundMessage {
     /// The recipient's identifier (phone number or group ID).
     pub recipient: String,
@@ -183,7 +257,12 @@ pub struct OutboundMessage {
     pub is_group: bool,
 
     /// Optional text styles for formatting.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub styles: Vec<TextStyle>,
+
+    /// Routing details, present only when debugging was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing_debug: Option<RoutingDebug>,
 }
 
 impl InboundMessage {
@@ -226,6 +305,24 @@ impl InboundMessage {
         self.attachments.iter().any(|a| a.is_image())
     }
 
+    /// Text to record in conversation history for this message.
+    ///
+    /// Falls back to the message text when no separate history text is set.
+    pub fn history_text(&self) -> &str {
+        self.routing
+            .as_ref()
+            .and_then(|routing| routing.history_text.as_deref())
+            .unwrap_or(&self.text)
+    }
+
+    /// Tool results to record in history alongside this message.
+    pub fn tool_results(&self) -> &[HistoryMessage] {
+        self.routing
+            .as_ref()
+            .map(|routing| routing.tool_results.as_slice())
+            .unwrap_or_default()
+    }
+
     /// Build a stable history key for this message.
     ///
     /// Group messages are prefixed to avoid collisions with direct senders.
@@ -246,6 +343,7 @@ impl OutboundMessage {
                 text: text.into(),
                 is_group: true,
                 styles: Vec::new(),
+                routing_debug: None,
             }
         } else {
             Self {
@@ -253,6 +351,7 @@ impl OutboundMessage {
                 text: text.into(),
                 is_group: false,
                 styles: Vec::new(),
+                routing_debug: None,
             }
         }
     }
@@ -264,6 +363,7 @@ impl OutboundMessage {
             text: text.into(),
             is_group: false,
             styles: Vec::new(),
+            routing_debug: None,
         }
     }
 
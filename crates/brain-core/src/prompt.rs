@@ -1,11 +1,93 @@
 //! Prompt helpers for hashing and tracking prompt versions.
 
+use std::collections::HashMap;
+
 use sha2::{Digest, Sha256};
 
+use crate::history::HistoryMessage;
+
 /// Compute a stable SHA-256 fingerprint for a prompt string.
 pub fn hash_prompt(prompt: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(prompt.as_bytes());
+    hex_digest(hasher)
+}
+
+/// Everything assembled into a single brain request.
+#[derive(Debug, Clone, Default)]
+pub struct PromptContext {
+    /// System prompt, if any.
+    pub system_prompt: Option<String>,
+    /// Memory prompt injected for the request, if any.
+    pub memory_prompt: Option<String>,
+    /// Knowledge base snippets, in prompt order.
+    pub knowledge: Vec<String>,
+    /// Conversation history, oldest first.
+    pub history: Vec<HistoryMessage>,
+    /// The user's message text.
+    pub user_text: String,
+    /// Extra request settings (e.g., model, temperature).
+    pub metadata: HashMap<String, String>,
+}
+
+/// Compute a stable SHA-256 fingerprint for a fully assembled request.
+///
+/// Fields are hashed in a fixed order with length prefixes, so adjacent
+/// fields cannot run together. Metadata is hashed in key order, making the
+/// result independent of map iteration order.
+pub fn hash_context(context: &PromptContext) -> String {
+    let mut hasher = Sha256::new();
+
+    update_optional(&mut hasher, b"system", context.system_prompt.as_deref());
+    update_optional(&mut hasher, b"memory", context.memory_prompt.as_deref());
+
+    update_field(&mut hasher, b"knowledge", &context.knowledge.len().to_string());
+    for snippet in &context.knowledge {
+        update_field(&mut hasher, b"snippet", snippet);
+    }
+
+    update_field(&mut hasher, b"history", &context.history.len().to_string());
+    for message in &context.history {
+        update_field(&mut hasher, b"role", &message.role);
+        update_optional(&mut hasher, b"tool", message.tool_name.as_deref());
+        update_field(&mut hasher, b"content", &message.content);
+    }
+
+    update_field(&mut hasher, b"user", &context.user_text);
+
+    let mut metadata: Vec<_> = context.metadata.iter().collect();
+    metadata.sort();
+    update_field(&mut hasher, b"metadata", &metadata.len().to_string());
+    for (key, value) in metadata {
+        update_field(&mut hasher, b"key", key);
+        update_field(&mut hasher, b"value", value);
+    }
+
+    hex_digest(hasher)
+}
+
+/// Hash a tagged, length-prefixed field.
+fn update_field(hasher: &mut Sha256, tag: &[u8], value: &str) {
+    hasher.update(tag);
+    hasher.update((value.len() as u64).to_be_bytes());
+    hasher.update(value.as_bytes());
+}
+
+/// Hash an optional field, distinguishing None from an empty string.
+fn update_optional(hasher: &mut Sha256, tag: &[u8], value: Option<&str>) {
+    match value {
+        Some(value) => {
+            hasher.update([1]);
+            update_field(hasher, tag, value);
+        }
+        None => {
+            hasher.update([0]);
+            hasher.update(tag);
+        }
+    }
+}
+
+fn hex_digest(hasher: Sha256) -> String {
     let digest = hasher.finalize();
     let mut hex = String::with_capacity(digest.len() * 2);
     for byte in digest {
@@ -16,7 +98,25 @@ pub fn hash_prompt(prompt: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::hash_prompt;
+    use super::*;
+
+    fn sample_context() -> PromptContext {
+        PromptContext {
+            system_prompt: Some("You are Aman.".to_string()),
+            memory_prompt: Some("[MEMORY] likes tea".to_string()),
+            knowledge: vec!["Aman runs on Signal.".to_string()],
+            history: vec![
+                HistoryMessage::user("hi"),
+                HistoryMessage::tool("calculator", "4"),
+                HistoryMessage::assistant("hello"),
+            ],
+            user_text: "what's new?".to_string(),
+            metadata: HashMap::from([
+                ("model".to_string(), "llama".to_string()),
+                ("temperature".to_string(), "0.7".to_string()),
+            ]),
+        }
+    }
 
     #[test]
     fn test_hash_prompt_stable() {
@@ -27,4 +127,57 @@ mod tests {
         assert_eq!(first, second);
         assert_ne!(first, different);
     }
+
+    #[test]
+    fn test_hash_context_stable() {
+        let first = hash_context(&sample_context());
+        assert_eq!(first, hash_context(&sample_context()));
+        assert_eq!(first.len(), 64);
+
+        // Metadata insertion order does not matter
+        let mut reordered = sample_context();
+        reordered.metadata = HashMap::new();
+        reordered.metadata.insert("temperature".to_string(), "0.7".to_string());
+        reordered.metadata.insert("model".to_string(), "llama".to_string());
+        assert_eq!(first, hash_context(&reordered));
+    }
+
+    #[test]
+    fn test_hash_context_sensitive_to_changes() {
+        let base = hash_context(&sample_context());
+        let changes: Vec<fn(&mut PromptContext)> = vec![
+            |c| c.system_prompt = None,
+            |c| c.system_prompt = Some(String::new()),
+            |c| c.memory_prompt = Some("[MEMORY] likes coffee".to_string()),
+            |c| c.knowledge.push("extra".to_string()),
+            |c| c.history[1] = HistoryMessage::tool("weather", "4"),
+            |c| c.history.reverse(),
+            |c| c.user_text.push('!'),
+            |c| {
+                c.metadata.insert("model".to_string(), "qwen".to_string());
+            },
+        ];
+
+        for change in changes {
+            let mut context = sample_context();
+            change(&mut context);
+            assert_ne!(base, hash_context(&context));
+        }
+    }
+
+    #[test]
+    fn test_hash_context_field_boundaries() {
+        // Moving text between fields changes the hash
+        let a = PromptContext {
+            system_prompt: Some("ab".to_string()),
+            user_text: "c".to_string(),
+            ..Default::default()
+        };
+        let b = PromptContext {
+            system_prompt: Some("a".to_string()),
+            user_text: "bc".to_string(),
+            ..Default::default()
+        };
+        assert_ne!(hash_context(&a), hash_context(&b));
+    }
 }
@@ -1,6 +1,7 @@
 //! The Brain trait definition.
 
 use async_trait::async_trait;
+use tokio::sync::mpsc;
 
 use crate::error::BrainError;
 use crate::message::{InboundMessage, OutboundMessage};
@@ -23,6 +24,23 @@ pub trait Brain: Send + Sync {
     /// processing failed.
     async fn process(&self, message: InboundMessage) -> Result<OutboundMessage, BrainError>;
 
+    /// Process a message, sending response text to `chunks` as it is produced.
+    ///
+    /// The returned `OutboundMessage` holds the complete response; the chunks
+    /// concatenate to its text. A closed receiver does not stop processing.
+    ///
+    /// Default implementation calls [`Brain::process`] and sends the whole
+    /// response as a single chunk.
+    async fn process_stream(
+        &self,
+        message: InboundMessage,
+        chunks: mpsc::Sender<String>,
+    ) -> Result<OutboundMessage, BrainError> {
+        let response = self.process(message).await?;
+        let _ = chunks.send(response.text.clone()).await;
+        Ok(response)
+    }
+
     /// Get a human-readable name for this brain implementation.
     fn name(&self) -> &str;
 
@@ -40,3 +58,33 @@ pub trait Brain: Send + Sync {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct UpperBrain;
+
+    #[async_trait]
+    impl Brain for UpperBrain {
+        async fn process(&self, message: InboundMessage) -> Result<OutboundMessage, BrainError> {
+            Ok(OutboundMessage::reply_to(&message, message.text.to_uppercase()))
+        }
+
+        fn name(&self) -> &str {
+            "UpperBrain"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_process_stream_sends_one_chunk() {
+        let (tx, mut rx) = mpsc::channel(8);
+        let message = InboundMessage::direct("+15551234567", "hello there", 1);
+
+        let response = UpperBrain.process_stream(message, tx).await.unwrap();
+
+        assert_eq!(response.text, "HELLO THERE");
+        assert_eq!(rx.recv().await.as_deref(), Some("HELLO THERE"));
+        assert_eq!(rx.recv().await, None);
+    }
+}
@@ -0,0 +1,11 @@
+-- Recent user/assistant exchanges for memory prompts.
+CREATE TABLE IF NOT EXISTS conversation_turns (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    history_key TEXT NOT NULL,
+    user_text TEXT NOT NULL,
+    assistant_text TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversation_turns_history_key ON conversation_turns(history_key);
+CREATE INDEX IF NOT EXISTS idx_conversation_turns_created_at ON conversation_turns(created_at);
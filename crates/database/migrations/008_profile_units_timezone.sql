@@ -0,0 +1,3 @@
+-- Preferred measurement system and timezone used as tool defaults
+ALTER TABLE user_profiles ADD COLUMN preferred_units TEXT;
+ALTER TABLE user_profiles ADD COLUMN timezone TEXT;
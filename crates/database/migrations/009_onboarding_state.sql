@@ -0,0 +1,10 @@
+-- Per-user onboarding progress for first-contact flows.
+-- step: current onboarding step (e.g., "language", "region", "done")
+-- region: region given during onboarding, if asked
+CREATE TABLE IF NOT EXISTS onboarding_state (
+    user_id TEXT PRIMARY KEY NOT NULL,
+    step TEXT NOT NULL,
+    region TEXT,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
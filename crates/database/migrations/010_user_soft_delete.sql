@@ -0,0 +1,3 @@
+-- Soft-delete marker for anonymized users.
+-- Rows are kept (name scrubbed) so aggregate counts survive; hard delete still removes them.
+ALTER TABLE users ADD COLUMN deleted_at TEXT;
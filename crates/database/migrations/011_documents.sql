@@ -0,0 +1,17 @@
+-- Local document store with FTS5 search, mirroring the gateway worker's
+-- `docs` / `chunks_fts` layout so operators can search the KB without D1.
+CREATE TABLE IF NOT EXISTS documents (
+    doc_id TEXT PRIMARY KEY NOT NULL,
+    title TEXT,
+    lang TEXT,
+    text TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Kept in sync by `documents::{insert, delete}`; only `text` is tokenized.
+CREATE VIRTUAL TABLE IF NOT EXISTS documents_fts USING fts5(
+    text,
+    doc_id UNINDEXED,
+    title UNINDEXED,
+    lang UNINDEXED
+);
@@ -0,0 +1,9 @@
+-- State needed to undo the latest exchange on "forget that".
+-- Each turn keeps the summary it replaced; NULL previous_message_count marks legacy rows.
+ALTER TABLE conversation_turns ADD COLUMN previous_summary TEXT;
+ALTER TABLE conversation_turns ADD COLUMN previous_message_count INTEGER;
+
+-- Tool rows link to the turn they fed. NULL means "pending the next turn";
+-- existing and rehydrated rows default to 0 (not attached to any turn).
+ALTER TABLE tool_history ADD COLUMN turn_id INTEGER DEFAULT 0;
+CREATE INDEX IF NOT EXISTS idx_tool_history_turn_id ON tool_history(turn_id);
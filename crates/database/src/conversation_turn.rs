@@ -0,0 +1,193 @@
+//! Recent conversation turn persistence.
+
+use std::time::Duration;
+
+use sqlx::SqlitePool;
+
+use crate::models::ConversationTurn;
+use crate::Result;
+
+/// Record a user/assistant exchange.
+///
+/// `previous` is the summary (and its message count) this exchange replaced,
+/// kept so [`take_latest_turn`] callers can restore it. Returns the new turn id.
+pub async fn insert_turn(
+    pool: &SqlitePool,
+    history_key: &str,
+    user_text: &str,
+    assistant_text: &str,
+    previous: Option<(&str, i64)>,
+) -> Result<i64> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO conversation_turns
+            (history_key, user_text, assistant_text, previous_summary, previous_message_count)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(history_key)
+    .bind(user_text)
+    .bind(assistant_text)
+    .bind(previous.map(|(summary, _)| summary))
+    .bind(previous.map_or(0, |(_, count)| count))
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Get the most recent turns for a history key, newest first.
+pub async fn list_recent_turns(
+    pool: &SqlitePool,
+    history_key: &str,
+    limit: i64,
+) -> Result<Vec<ConversationTurn>> {
+    let rows = sqlx::query_as::<_, ConversationTurn>(
+        r#"
+        SELECT id, history_key, user_text, assistant_text, previous_summary,
+               previous_message_count, created_at
+        FROM conversation_turns
+        WHERE history_key = ?
+        ORDER BY id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(history_key)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Delete and return the most recent turn for a history key.
+///
+/// Returns None if the key has no turns.
+pub async fn take_latest_turn(
+    pool: &SqlitePool,
+    history_key: &str,
+) -> Result<Option<ConversationTurn>> {
+    let row = sqlx::query_as::<_, ConversationTurn>(
+        r#"
+        DELETE FROM conversation_turns
+        WHERE id = (
+            SELECT MAX(id)
+            FROM conversation_turns
+            WHERE history_key = ?
+        )
+        RETURNING id, history_key, user_text, assistant_text, previous_summary,
+                  previous_message_count, created_at
+        "#,
+    )
+    .bind(history_key)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Delete all turns for a history key.
+pub async fn clear_turns(pool: &SqlitePool, history_key: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        DELETE FROM conversation_turns
+        WHERE history_key = ?
+        "#,
+    )
+    .bind(history_key)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Prune turns older than the specified TTL.
+pub async fn prune_older_than(pool: &SqlitePool, ttl: Duration) -> Result<u64> {
+    let modifier = format!("-{} seconds", ttl.as_secs());
+    let result = sqlx::query(
+        r#"
+        DELETE FROM conversation_turns
+        WHERE created_at < datetime('now', ?)
+        "#,
+    )
+    .bind(modifier)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Prune turns for a specific history key to a maximum row count.
+pub async fn prune_over_limit_for_key(
+    pool: &SqlitePool,
+    history_key: &str,
+    max_rows: usize,
+) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM conversation_turns
+        WHERE id IN (
+            SELECT id
+            FROM conversation_turns
+            WHERE history_key = ?
+            ORDER BY id DESC
+            LIMIT -1 OFFSET ?
+        )
+        "#,
+    )
+    .bind(history_key)
+    .bind(max_rows as i64)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    async fn test_db() -> Database {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_recent_turns_newest_first_and_pruned() {
+        let db = test_db().await;
+        for i in 1..=4 {
+            insert_turn(db.pool(), "alice", &format!("q{}", i), &format!("a{}", i), None)
+                .await
+                .unwrap();
+        }
+        insert_turn(db.pool(), "bob", "hi", "hello", None).await.unwrap();
+
+        let turns = list_recent_turns(db.pool(), "alice", 2).await.unwrap();
+        let texts: Vec<_> = turns.iter().map(|turn| turn.user_text.as_str()).collect();
+        assert_eq!(texts, vec!["q4", "q3"]);
+
+        let taken = take_latest_turn(db.pool(), "alice").await.unwrap().unwrap();
+        assert_eq!(taken.user_text, "q4");
+        assert_eq!(taken.previous_summary, None);
+        assert_eq!(taken.previous_message_count, Some(0));
+        let turns = list_recent_turns(db.pool(), "alice", 10).await.unwrap();
+        assert_eq!(turns[0].user_text, "q3");
+        insert_turn(db.pool(), "alice", "q4", "a4", Some(("U: q3", 3))).await.unwrap();
+        let taken = take_latest_turn(db.pool(), "alice").await.unwrap().unwrap();
+        assert_eq!(taken.previous_summary.as_deref(), Some("U: q3"));
+        assert_eq!(taken.previous_message_count, Some(3));
+        insert_turn(db.pool(), "alice", "q4", "a4", None).await.unwrap();
+
+        assert_eq!(prune_over_limit_for_key(db.pool(), "alice", 1).await.unwrap(), 3);
+        let turns = list_recent_turns(db.pool(), "alice", 10).await.unwrap();
+        assert_eq!(turns.len(), 1);
+        assert_eq!(turns[0].assistant_text, "a4");
+
+        clear_turns(db.pool(), "alice").await.unwrap();
+        assert!(take_latest_turn(db.pool(), "alice").await.unwrap().is_none());
+        assert!(list_recent_turns(db.pool(), "alice", 10).await.unwrap().is_empty());
+        assert_eq!(list_recent_turns(db.pool(), "bob", 10).await.unwrap().len(), 1);
+    }
+}
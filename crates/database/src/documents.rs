@@ -0,0 +1,250 @@
+//! Local document store with FTS5 search.
+//!
+//! Mirrors the gateway worker's `chunks_fts` layout (indexed `text`, unindexed
+//! `doc_id` / `title` / `lang`) and its OR-of-terms BM25 query, so the Rust side
+//! can search the same knowledge base without D1.
+
+use sqlx::SqlitePool;
+
+use crate::models::{Document, DocumentHit};
+use crate::{retry, Result};
+
+/// Create or replace a document and its search index entry.
+pub async fn insert(
+    pool: &SqlitePool,
+    doc_id: &str,
+    title: Option<&str>,
+    lang: Option<&str>,
+    text: &str,
+) -> Result<()> {
+    retry::on_busy(|| write_document(pool, doc_id, title, lang, text)).await?;
+    Ok(())
+}
+
+async fn write_document(
+    pool: &SqlitePool,
+    doc_id: &str,
+    title: Option<&str>,
+    lang: Option<&str>,
+    text: &str,
+) -> sqlx::Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO documents (doc_id, title, lang, text)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(doc_id) DO UPDATE SET
+            title = excluded.title,
+            lang = excluded.lang,
+            text = excluded.text,
+            updated_at = datetime('now')
+        "#,
+    )
+    .bind(doc_id)
+    .bind(title)
+    .bind(lang)
+    .bind(text)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM documents_fts WHERE doc_id = ?")
+        .bind(doc_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO documents_fts (text, doc_id, title, lang)
+        VALUES (?, ?, ?, ?)
+        "#,
+    )
+    .bind(text)
+    .bind(doc_id)
+    .bind(title)
+    .bind(lang)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+/// Get a document by ID.
+pub async fn get(pool: &SqlitePool, doc_id: &str) -> Result<Option<Document>> {
+    let record = sqlx::query_as::<_, Document>(
+        r#"
+        SELECT doc_id, title, lang, text, updated_at
+        FROM documents
+        WHERE doc_id = ?
+        "#,
+    )
+    .bind(doc_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record)
+}
+
+/// Search documents, best match first.
+///
+/// The query is split into alphanumeric terms which are OR-ed together, so
+/// punctuation and FTS5 operators in user input are never interpreted.
+pub async fn search(pool: &SqlitePool, query: &str, limit: usize) -> Result<Vec<DocumentHit>> {
+    let Some(match_query) = build_match_query(query) else {
+        return Ok(Vec::new());
+    };
+
+    let hits = sqlx::query_as::<_, DocumentHit>(
+        r#"
+        SELECT doc_id, title, lang, text, bm25(documents_fts) AS rank
+        FROM documents_fts
+        WHERE documents_fts MATCH ?
+        ORDER BY rank
+        LIMIT ?
+        "#,
+    )
+    .bind(match_query)
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(hits)
+}
+
+/// Delete a document and its index entry.
+///
+/// Returns true if a document was deleted, false if none existed.
+pub async fn delete(pool: &SqlitePool, doc_id: &str) -> Result<bool> {
+    let deleted = retry::on_busy(|| delete_document(pool, doc_id)).await?;
+    Ok(deleted)
+}
+
+async fn delete_document(pool: &SqlitePool, doc_id: &str) -> sqlx::Result<bool> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM documents_fts WHERE doc_id = ?")
+        .bind(doc_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let result = sqlx::query("DELETE FROM documents WHERE doc_id = ?")
+        .bind(doc_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Build an FTS5 OR-query of quoted, de-duplicated terms.
+fn build_match_query(query: &str) -> Option<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let term = format!("\"{}\"", word.to_lowercase());
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" OR "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Database;
+
+    async fn setup_db() -> Database {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[test]
+    fn test_build_match_query() {
+        assert_eq!(
+            build_match_query("VPN setup: vpn?").as_deref(),
+            Some("\"vpn\" OR \"setup\"")
+        );
+        assert_eq!(build_match_query("  -- ** "), None);
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_best_match_first() {
+        let db = setup_db().await;
+        insert(
+            db.pool(),
+            "vpn-guide",
+            Some("VPN guide"),
+            Some("en"),
+            "How to set up a VPN. A VPN hides your traffic; pick a VPN with no logs.",
+        )
+        .await
+        .unwrap();
+        insert(
+            db.pool(),
+            "travel",
+            Some("Travel tips"),
+            Some("en"),
+            "Pack light and use a VPN on hotel wifi.",
+        )
+        .await
+        .unwrap();
+        insert(db.pool(), "cooking", None, None, "Slow-cooked lentil soup.")
+            .await
+            .unwrap();
+
+        let hits = search(db.pool(), "vpn", 10).await.unwrap();
+        let ids: Vec<_> = hits.iter().map(|hit| hit.doc_id.as_str()).collect();
+        assert_eq!(ids, ["vpn-guide", "travel"]);
+        assert!(hits[0].rank < hits[1].rank);
+        assert_eq!(hits[0].title.as_deref(), Some("VPN guide"));
+
+        assert_eq!(search(db.pool(), "vpn", 1).await.unwrap().len(), 1);
+        assert!(search(db.pool(), "\"unbalanced OR", 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_insert_replaces_and_delete_removes() {
+        let db = setup_db().await;
+        insert(db.pool(), "doc", Some("Old"), None, "encryption basics")
+            .await
+            .unwrap();
+        insert(
+            db.pool(),
+            "doc",
+            Some("New"),
+            Some("en"),
+            "secure messaging",
+        )
+        .await
+        .unwrap();
+
+        assert!(search(db.pool(), "encryption", 10)
+            .await
+            .unwrap()
+            .is_empty());
+        let hits = search(db.pool(), "messaging", 10).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].title.as_deref(), Some("New"));
+        assert_eq!(
+            get(db.pool(), "doc").await.unwrap().unwrap().text,
+            "secure messaging"
+        );
+
+        assert!(delete(db.pool(), "doc").await.unwrap());
+        assert!(!delete(db.pool(), "doc").await.unwrap());
+        assert!(get(db.pool(), "doc").await.unwrap().is_none());
+        assert!(search(db.pool(), "messaging", 10).await.unwrap().is_empty());
+    }
+}
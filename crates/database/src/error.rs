@@ -17,9 +17,38 @@ pub enum DatabaseError {
     #[error("{entity} not found: {id}")]
     NotFound { entity: &'static str, id: String },
 
-    /// Record already exists
+    /// Record already exists (unique constraint violation)
     #[error("{entity} already exists: {id}")]
     AlreadyExists { entity: &'static str, id: String },
+
+    /// Record references a row that does not exist (foreign key violation)
+    #[error("{entity} references a missing record: {id}")]
+    ForeignKeyViolation { entity: &'static str, id: String },
+}
+
+impl DatabaseError {
+    /// Map an SQLx error from a write on `entity` to a structured variant.
+    ///
+    /// Unique violations become [`DatabaseError::AlreadyExists`] and foreign key
+    /// violations become [`DatabaseError::ForeignKeyViolation`]; anything else
+    /// is kept as [`DatabaseError::Sqlx`].
+    pub fn from_write(error: sqlx::Error, entity: &'static str, id: &str) -> Self {
+        if let sqlx::Error::Database(ref db_err) = error {
+            if db_err.is_unique_violation() {
+                return Self::AlreadyExists {
+                    entity,
+                    id: id.to_string(),
+                };
+            }
+            if db_err.is_foreign_key_violation() {
+                return Self::ForeignKeyViolation {
+                    entity,
+                    id: id.to_string(),
+                };
+            }
+        }
+        Self::Sqlx(error)
+    }
 }
 
 /// Result type for database operations.
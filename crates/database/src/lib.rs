@@ -30,23 +30,89 @@ pub mod error;
 pub mod models;
 pub mod preference;
 pub mod conversation_summary;
+pub mod conversation_turn;
+pub mod documents;
 pub mod tool_history;
 pub mod clear_context_event;
+pub mod onboarding;
+pub mod retry;
 pub mod user;
 pub mod user_profile;
 pub mod validation;
 
 pub use error::{DatabaseError, Result};
 pub use models::{
-    ClearContextEvent, ConversationSummary, Preference, ToolHistoryEntry,
-    User, UserProfile,
+    ClearContextEvent, ConversationSummary, ConversationTurn, Document, DocumentHit,
+    OnboardingState, Preference, ToolHistoryEntry, User, UserProfile,
 };
 pub use user_profile::ProfileField;
 pub use validation::ValidationError;
 
-use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
 use sqlx::SqlitePool;
 use std::str::FromStr;
+use std::time::Duration;
+
+/// Connection pool and SQLite tuning for [`Database::connect_with_config`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseConfig {
+    /// Maximum pooled connections.
+    pub max_connections: u32,
+    /// How long to wait for a free connection before failing.
+    pub acquire_timeout: Duration,
+    /// How long SQLite retries a locked database before returning "database is locked".
+    pub busy_timeout: Duration,
+    /// Use write-ahead logging so readers don't block the writer.
+    pub wal: bool,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: Database::DEFAULT_POOL_SIZE,
+            acquire_timeout: Duration::from_secs(30),
+            busy_timeout: Duration::from_secs(5),
+            wal: true,
+        }
+    }
+}
+
+impl DatabaseConfig {
+    /// Load pool settings from environment variables, falling back to defaults.
+    ///
+    /// - `SQLITE_MAX_CONNECTIONS` (default: 20)
+    /// - `SQLITE_ACQUIRE_TIMEOUT_SECS` (default: 30)
+    /// - `SQLITE_BUSY_TIMEOUT_MS` (default: 5000)
+    /// - `SQLITE_WAL` (default: true)
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let number = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+        Self {
+            max_connections: number("SQLITE_MAX_CONNECTIONS")
+                .and_then(|value| u32::try_from(value).ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(defaults.max_connections),
+            acquire_timeout: number("SQLITE_ACQUIRE_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.acquire_timeout),
+            busy_timeout: number("SQLITE_BUSY_TIMEOUT_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.busy_timeout),
+            wal: std::env::var("SQLITE_WAL")
+                .map(|value| {
+                    !matches!(
+                        value.trim().to_lowercase().as_str(),
+                        "false" | "0" | "no" | "off"
+                    )
+                })
+                .unwrap_or(defaults.wal),
+        }
+    }
+}
 
 /// Database connection wrapper.
 #[derive(Debug, Clone)]
@@ -77,25 +143,41 @@ impl Database {
     const DEFAULT_POOL_SIZE: u32 = 20;
 
     pub async fn connect(url: &str) -> Result<Self> {
-        Self::connect_with_pool_size(url, Self::DEFAULT_POOL_SIZE).await
+        Self::connect_with_config(url, &DatabaseConfig::default()).await
     }
 
     /// Connect to a SQLite database with a custom pool size.
     pub async fn connect_with_pool_size(url: &str, pool_size: u32) -> Result<Self> {
-        let options = SqliteConnectOptions::from_str(url)?
+        let config = DatabaseConfig {
+            max_connections: pool_size,
+            ..DatabaseConfig::default()
+        };
+        Self::connect_with_config(url, &config).await
+    }
+
+    /// Connect to a SQLite database with explicit pool and SQLite settings.
+    ///
+    /// WAL is ignored by SQLite for in-memory databases.
+    pub async fn connect_with_config(url: &str, config: &DatabaseConfig) -> Result<Self> {
+        let mut options = SqliteConnectOptions::from_str(url)?
             .create_if_missing(true)
-            .foreign_keys(true);
+            .foreign_keys(true)
+            .busy_timeout(config.busy_timeout);
+        if config.wal {
+            options = options.journal_mode(SqliteJournalMode::Wal);
+        }
 
         let pool = SqlitePoolOptions::new()
-            .max_connections(pool_size)
-            .acquire_timeout(std::time::Duration::from_secs(30))
+            .max_connections(config.max_connections)
+            .acquire_timeout(config.acquire_timeout)
             .connect_with(options)
             .await?;
 
         tracing::info!(
-            "Connected to database: {} (pool size: {})",
+            "Connected to database: {} (pool size: {}, wal: {})",
             url,
-            pool_size
+            config.max_connections,
+            config.wal
         );
 
         Ok(Self { pool })
@@ -146,6 +228,12 @@ mod tests {
         };
         user::create_user(db.pool(), &user).await.unwrap();
 
+        // Creating the same ID again is a conflict, not a generic failure
+        assert!(matches!(
+            user::create_user(db.pool(), &user).await,
+            Err(DatabaseError::AlreadyExists { entity: "User", .. })
+        ));
+
         // Read
         let fetched = user::get_user(db.pool(), &user.id).await.unwrap();
         assert_eq!(fetched.name, "Alice");
@@ -168,4 +256,139 @@ mod tests {
         let result = user::get_user(db.pool(), &user.id).await;
         assert!(matches!(result, Err(DatabaseError::NotFound { .. })));
     }
+
+    #[tokio::test]
+    async fn test_soft_and_hard_delete_user() {
+        let db = test_db().await;
+        for (id, name) in [("soft-1", "Carol"), ("hard-1", "Dave")] {
+            let user = User {
+                id: id.to_string(),
+                name: name.to_string(),
+                language: "Arabic".to_string(),
+            };
+            user::create_user(db.pool(), &user).await.unwrap();
+        }
+
+        let pool = db.pool();
+        for (field, value) in [
+            (user_profile::ProfileField::Email, "carol@example.com"),
+            (user_profile::ProfileField::Bolt12Offer, "lno1carol"),
+        ] {
+            user_profile::upsert_profile_field(pool, "soft-1", field, Some(value))
+                .await
+                .unwrap();
+        }
+        onboarding::set_step(pool, "soft-1", "done").await.unwrap();
+        onboarding::set_region(pool, "soft-1", "Tehran").await.unwrap();
+        assert!(onboarding::get_state(pool, "soft-1").await.unwrap().is_some());
+
+        // Soft delete keeps the row for counts but scrubs it and its dependents
+        user::anonymize_user(pool, "soft-1").await.unwrap();
+        assert!(matches!(
+            user::get_user(pool, "soft-1").await,
+            Err(DatabaseError::NotFound { .. })
+        ));
+        let names: Vec<_> = user::list_users(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|user| user.id)
+            .collect();
+        assert_eq!(names, vec!["hard-1".to_string()]);
+        let name: String = sqlx::query_scalar("SELECT name FROM users WHERE id = 'soft-1'")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        assert_eq!(name, user::ANONYMIZED_NAME);
+        assert!(user_profile::get_profile(pool, "soft-1").await.unwrap().is_none());
+        assert!(onboarding::get_state(pool, "soft-1").await.unwrap().is_none());
+        assert_eq!(
+            user::count_users_by_language(pool).await.unwrap(),
+            vec![("Arabic".to_string(), 2)]
+        );
+        assert!(user::is_anonymized(pool, "soft-1").await.unwrap());
+        assert!(!user::is_anonymized(pool, "hard-1").await.unwrap());
+        assert_eq!(user::count_users(pool).await.unwrap(), 2);
+
+        // Hard delete removes the row entirely
+        user::delete_user(db.pool(), "hard-1").await.unwrap();
+        assert!(matches!(
+            user::get_user(db.pool(), "hard-1").await,
+            Err(DatabaseError::NotFound { .. })
+        ));
+        assert_eq!(user::count_users(db.pool()).await.unwrap(), 1);
+
+        assert!(matches!(
+            user::anonymize_user(db.pool(), "missing").await,
+            Err(DatabaseError::NotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_connect_with_config_honors_pool_size() {
+        let config = DatabaseConfig {
+            max_connections: 3,
+            acquire_timeout: Duration::from_millis(200),
+            ..DatabaseConfig::default()
+        };
+        let db = Database::connect_with_config("sqlite::memory:", &config)
+            .await
+            .unwrap();
+        assert_eq!(db.pool().options().get_max_connections(), 3);
+
+        // A fourth concurrent connection times out instead of exceeding the cap
+        let mut held = Vec::new();
+        for _ in 0..3 {
+            held.push(db.pool().acquire().await.unwrap());
+        }
+        assert_eq!(db.pool().size(), 3);
+        assert!(db.pool().acquire().await.is_err());
+        drop(held);
+        assert!(db.pool().acquire().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_connect_with_config_enables_wal() {
+        let dir = std::env::temp_dir().join(format!("aman-db-wal-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let url = format!("sqlite:{}?mode=rwc", dir.join("wal.db").display());
+
+        let db = Database::connect_with_config(&url, &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(mode, "wal");
+        db.close().await;
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_insert_user_if_missing() {
+        let db = test_db().await;
+        let user = User {
+            id: "test-uuid-456".to_string(),
+            name: "Bob".to_string(),
+            language: "English".to_string(),
+        };
+
+        assert!(user::insert_user_if_missing(db.pool(), &user).await.unwrap());
+        let updated = User {
+            language: "Arabic".to_string(),
+            ..user.clone()
+        };
+        assert!(!user::insert_user_if_missing(db.pool(), &updated).await.unwrap());
+
+        assert_eq!(user::count_users(db.pool()).await.unwrap(), 1);
+        let fetched = user::get_user(db.pool(), &user.id).await.unwrap();
+        assert_eq!(fetched.language, "English");
+
+        // A returning anonymized user is created again.
+        user::anonymize_user(db.pool(), &user.id).await.unwrap();
+        assert!(user::insert_user_if_missing(db.pool(), &updated).await.unwrap());
+        let fetched = user::get_user(db.pool(), &user.id).await.unwrap();
+        assert_eq!(fetched, updated);
+    }
 }
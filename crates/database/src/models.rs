@@ -38,6 +38,25 @@ pub struct ConversationSummary {
     pub updated_at: String,
 }
 
+/// A recorded user/assistant exchange.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ConversationTurn {
+    /// Auto-incrementing ID.
+    pub id: i64,
+    /// History key for sender or group.
+    pub history_key: String,
+    /// User message text (possibly truncated).
+    pub user_text: String,
+    /// Assistant reply text (possibly truncated).
+    pub assistant_text: String,
+    /// Summary this exchange replaced, if there was one.
+    pub previous_summary: Option<String>,
+    /// Message count of the replaced summary; None for turns recorded before it was tracked.
+    pub previous_message_count: Option<i64>,
+    /// Creation timestamp.
+    pub created_at: String,
+}
+
 /// A tool execution record.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
 pub struct ToolHistoryEntry {
@@ -83,8 +102,55 @@ pub struct UserProfile {
     pub email: Option<String>,
     /// Lightning Bolt 12 offer for payments.
     pub bolt12_offer: Option<String>,
+    /// Preferred measurement system ("metric" or "imperial").
+    pub preferred_units: Option<String>,
+    /// Preferred timezone (e.g., "Europe/Paris").
+    pub timezone: Option<String>,
     /// When the profile was created.
     pub created_at: String,
     /// When the profile was last updated.
     pub updated_at: String,
 }
+
+/// A document in the local knowledge-base store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct Document {
+    /// Stable document ID (e.g. the Nostr `d` tag).
+    pub doc_id: String,
+    /// Display title, used in citations.
+    pub title: Option<String>,
+    /// Document language (BCP-47, e.g. "ar", "en").
+    pub lang: Option<String>,
+    /// Full document text.
+    pub text: String,
+    /// Last update timestamp.
+    pub updated_at: String,
+}
+
+/// A full-text search hit from [`crate::documents::search`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct DocumentHit {
+    /// Matching document ID.
+    pub doc_id: String,
+    /// Document title.
+    pub title: Option<String>,
+    /// Document language.
+    pub lang: Option<String>,
+    /// Full document text.
+    pub text: String,
+    /// BM25 rank; lower is more relevant.
+    pub rank: f64,
+}
+
+/// Onboarding progress for a user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct OnboardingState {
+    /// User ID.
+    pub user_id: String,
+    /// Current onboarding step.
+    pub step: String,
+    /// Region given during onboarding, if asked.
+    pub region: Option<String>,
+    /// Last update timestamp.
+    pub updated_at: String,
+}
@@ -0,0 +1,108 @@
+//! Onboarding progress for first-contact users.
+
+use sqlx::SqlitePool;
+
+use crate::models::OnboardingState;
+use crate::{DatabaseError, Result};
+
+/// Get a user's onboarding state, if onboarding was started.
+pub async fn get_state(pool: &SqlitePool, user_id: &str) -> Result<Option<OnboardingState>> {
+    let record = sqlx::query_as::<_, OnboardingState>(
+        r#"
+        SELECT user_id, step, region, updated_at
+        FROM onboarding_state
+        WHERE user_id = ?
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record)
+}
+
+/// Create or update a user's current onboarding step.
+///
+/// Returns [`DatabaseError::ForeignKeyViolation`] if the user does not exist.
+pub async fn set_step(pool: &SqlitePool, user_id: &str, step: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO onboarding_state (user_id, step)
+        VALUES (?, ?)
+        ON CONFLICT(user_id) DO UPDATE SET
+            step = excluded.step,
+            updated_at = datetime('now')
+        "#,
+    )
+    .bind(user_id)
+    .bind(step)
+    .execute(pool)
+    .await
+    .map_err(|e| DatabaseError::from_write(e, "OnboardingState", user_id))?;
+
+    Ok(())
+}
+
+/// Record the region a user gave during onboarding.
+pub async fn set_region(pool: &SqlitePool, user_id: &str, region: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE onboarding_state
+        SET region = ?, updated_at = datetime('now')
+        WHERE user_id = ?
+        "#,
+    )
+    .bind(region)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::User;
+    use crate::{user, Database};
+
+    async fn test_db() -> Database {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        db.migrate().await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_onboarding_state_roundtrip() {
+        let db = test_db().await;
+        let user = User {
+            id: "user-1".to_string(),
+            name: "user-1".to_string(),
+            language: "English".to_string(),
+        };
+        user::create_user(db.pool(), &user).await.unwrap();
+        assert!(get_state(db.pool(), &user.id).await.unwrap().is_none());
+
+        set_step(db.pool(), &user.id, "language").await.unwrap();
+        set_step(db.pool(), &user.id, "region").await.unwrap();
+        set_region(db.pool(), &user.id, "Iran").await.unwrap();
+
+        let state = get_state(db.pool(), &user.id).await.unwrap().unwrap();
+        assert_eq!(state.step, "region");
+        assert_eq!(state.region.as_deref(), Some("Iran"));
+
+        // Deleting the user removes their onboarding state
+        user::delete_user(db.pool(), &user.id).await.unwrap();
+        assert!(get_state(db.pool(), &user.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_set_step_for_missing_user_is_foreign_key_violation() {
+        let db = test_db().await;
+        let result = set_step(db.pool(), "ghost", "language").await;
+        assert!(matches!(
+            result,
+            Err(DatabaseError::ForeignKeyViolation { entity: "OnboardingState", ref id }) if id == "ghost"
+        ));
+    }
+}
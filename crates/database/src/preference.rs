@@ -3,7 +3,7 @@
 use sqlx::SqlitePool;
 
 use crate::models::Preference;
-use crate::Result;
+use crate::{retry, Result};
 
 /// Create or update a preference entry.
 pub async fn upsert_preference(
@@ -11,18 +11,20 @@ pub async fn upsert_preference(
     history_key: &str,
     preference: &str,
 ) -> Result<()> {
-    sqlx::query(
-        r#"
-        INSERT INTO preferences (history_key, preference)
-        VALUES (?, ?)
-        ON CONFLICT(history_key) DO UPDATE SET
-            preference = excluded.preference,
-            updated_at = datetime('now')
-        "#,
-    )
-    .bind(history_key)
-    .bind(preference)
-    .execute(pool)
+    retry::on_busy(|| {
+        sqlx::query(
+            r#"
+            INSERT INTO preferences (history_key, preference)
+            VALUES (?, ?)
+            ON CONFLICT(history_key) DO UPDATE SET
+                preference = excluded.preference,
+                updated_at = datetime('now')
+            "#,
+        )
+        .bind(history_key)
+        .bind(preference)
+        .execute(pool)
+    })
     .await?;
 
     Ok(())
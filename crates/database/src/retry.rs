@@ -0,0 +1,135 @@
+//! Retry helper for SQLite writes that hit `SQLITE_BUSY` / `SQLITE_LOCKED`.
+//!
+//! `busy_timeout` covers most contention, but SQLite returns busy immediately
+//! in some cases (e.g. a deferred transaction upgrading to a writer while
+//! another connection holds the write lock), so writes are retried here with a
+//! short exponential backoff.
+
+use std::future::Future;
+use std::time::Duration;
+
+/// Attempts made before the busy error is returned to the caller.
+pub const MAX_ATTEMPTS: u32 = 6;
+
+/// Delay before the first retry; doubled on each attempt.
+const BASE_DELAY: Duration = Duration::from_millis(10);
+
+/// Upper bound for a single backoff delay.
+const MAX_DELAY: Duration = Duration::from_millis(250);
+
+/// Primary SQLite result codes for a busy or locked database.
+const SQLITE_BUSY: i64 = 5;
+const SQLITE_LOCKED: i64 = 6;
+
+/// Whether an SQLx error is a transient "database is locked" error.
+pub fn is_busy_error(error: &sqlx::Error) -> bool {
+    let sqlx::Error::Database(db_err) = error else {
+        return false;
+    };
+    // SQLx reports the extended result code; the low byte is the primary code.
+    match db_err.code().and_then(|code| code.parse::<i64>().ok()) {
+        Some(code) => matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED),
+        None => db_err.message().contains("database is locked"),
+    }
+}
+
+/// Run a write, retrying with backoff while SQLite reports busy/locked.
+///
+/// `op` is called once per attempt, so it must rebuild the query each time.
+/// Any other error is returned immediately.
+pub async fn on_busy<T, F, Fut>(mut op: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut delay = BASE_DELAY;
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(e) if attempt < MAX_ATTEMPTS && is_busy_error(&e) => {
+                tracing::debug!("database busy (attempt {}), retrying in {:?}", attempt, delay);
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_DELAY);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::User;
+    use crate::{user, Database, DatabaseConfig};
+    use sqlx::Connection;
+
+    /// File-backed database whose pool fails fast on a held write lock.
+    async fn contended_db(name: &str) -> (Database, String, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("aman-db-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let url = format!("sqlite:{}?mode=rwc", dir.join("busy.db").display());
+        let config = DatabaseConfig {
+            busy_timeout: Duration::ZERO,
+            ..DatabaseConfig::default()
+        };
+        let db = Database::connect_with_config(&url, &config).await.unwrap();
+        db.migrate().await.unwrap();
+        (db, url, dir)
+    }
+
+    #[tokio::test]
+    async fn test_write_succeeds_after_lock_is_released() {
+        let (db, url, dir) = contended_db("retry").await;
+
+        // A second connection takes the write lock and releases it shortly after
+        let mut locker = sqlx::SqliteConnection::connect(&url).await.unwrap();
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut locker)
+            .await
+            .unwrap();
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            sqlx::query("COMMIT").execute(&mut locker).await.unwrap();
+            locker.close().await.unwrap();
+        });
+
+        let alice = User {
+            id: "alice".to_string(),
+            name: "Alice".to_string(),
+            language: "English".to_string(),
+        };
+        user::insert_user_if_missing(db.pool(), &alice).await.unwrap();
+        release.await.unwrap();
+
+        assert_eq!(user::get_user(db.pool(), "alice").await.unwrap(), alice);
+        db.close().await;
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_busy_error_surfaces_after_max_attempts() {
+        let (db, url, dir) = contended_db("busy").await;
+
+        let mut locker = sqlx::SqliteConnection::connect(&url).await.unwrap();
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut locker)
+            .await
+            .unwrap();
+
+        let mut attempts = 0;
+        let err = on_busy(|| {
+            attempts += 1;
+            sqlx::query("INSERT INTO users (id, name, language) VALUES ('bob', 'Bob', 'English')")
+                .execute(db.pool())
+        })
+        .await
+        .unwrap_err();
+        assert!(is_busy_error(&err));
+        assert_eq!(attempts, MAX_ATTEMPTS);
+
+        sqlx::query("ROLLBACK").execute(&mut locker).await.unwrap();
+        db.close().await;
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -19,8 +19,9 @@ pub async fn insert_tool_history(
 ) -> Result<()> {
     sqlx::query(
         r#"
-        INSERT INTO tool_history (history_key, tool_name, success, content, sender_id, group_id)
-        VALUES (?, ?, ?, ?, ?, ?)
+        INSERT INTO tool_history
+            (history_key, tool_name, success, content, sender_id, group_id, turn_id)
+        VALUES (?, ?, ?, ?, ?, ?, NULL)
         "#,
     )
     .bind(history_key)
@@ -35,6 +36,42 @@ pub async fn insert_tool_history(
     Ok(())
 }
 
+/// Attach tool entries still pending for a history key to the turn they fed.
+pub async fn attach_pending_to_turn(
+    pool: &SqlitePool,
+    history_key: &str,
+    turn_id: i64,
+) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        UPDATE tool_history
+        SET turn_id = ?
+        WHERE history_key = ? AND turn_id IS NULL
+        "#,
+    )
+    .bind(turn_id)
+    .bind(history_key)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Delete tool entries attached to a turn.
+pub async fn delete_for_turn(pool: &SqlitePool, turn_id: i64) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM tool_history
+        WHERE turn_id = ?
+        "#,
+    )
+    .bind(turn_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
 /// Get recent tool history entries for a history key.
 pub async fn list_tool_history(
     pool: &SqlitePool,
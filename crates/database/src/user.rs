@@ -4,42 +4,82 @@ use sqlx::SqlitePool;
 
 use crate::error::{DatabaseError, Result};
 use crate::models::User;
+use crate::retry;
+
+/// Name stored for anonymized users.
+pub const ANONYMIZED_NAME: &str = "[deleted]";
 
 /// Create a new user.
 pub async fn create_user(pool: &SqlitePool, user: &User) -> Result<()> {
-    sqlx::query(
-        r#"
-        INSERT INTO users (id, name, language)
-        VALUES (?, ?, ?)
-        "#,
-    )
-    .bind(&user.id)
-    .bind(&user.name)
-    .bind(&user.language)
-    .execute(pool)
+    retry::on_busy(|| {
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, name, language)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(&user.id)
+        .bind(&user.name)
+        .bind(&user.language)
+        .execute(pool)
+    })
     .await
-    .map_err(|e| {
-        if let sqlx::Error::Database(ref db_err) = e {
-            if db_err.is_unique_violation() {
-                return DatabaseError::AlreadyExists {
-                    entity: "User",
-                    id: user.id.clone(),
-                };
-            }
-        }
-        DatabaseError::Sqlx(e)
-    })?;
+    .map_err(|e| DatabaseError::from_write(e, "User", &user.id))?;
 
     Ok(())
 }
 
-/// Get a user by ID.
+/// Create a user unless a live one already exists; returns true when this call created it.
+///
+/// Unlike [`create_user`], concurrent calls for the same ID never fail on the
+/// primary key, and exactly one of them returns true. An anonymized user who
+/// returns is restored as a live user, which also counts as created.
+pub async fn insert_user_if_missing(pool: &SqlitePool, user: &User) -> Result<bool> {
+    let inserted = retry::on_busy(|| {
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, name, language)
+            VALUES (?, ?, ?)
+            ON CONFLICT(id) DO NOTHING
+            "#,
+        )
+        .bind(&user.id)
+        .bind(&user.name)
+        .bind(&user.language)
+        .execute(pool)
+    })
+    .await?
+    .rows_affected();
+    if inserted > 0 {
+        return Ok(true);
+    }
+
+    let revived = retry::on_busy(|| {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET name = ?, language = ?, deleted_at = NULL
+            WHERE id = ? AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(&user.name)
+        .bind(&user.language)
+        .bind(&user.id)
+        .execute(pool)
+    })
+    .await?
+    .rows_affected();
+
+    Ok(revived > 0)
+}
+
+/// Get a user by ID; anonymized users are not returned.
 pub async fn get_user(pool: &SqlitePool, id: &str) -> Result<User> {
     sqlx::query_as::<_, User>(
         r#"
         SELECT id, name, language
         FROM users
-        WHERE id = ?
+        WHERE id = ? AND deleted_at IS NULL
         "#,
     )
     .bind(id)
@@ -51,13 +91,13 @@ pub async fn get_user(pool: &SqlitePool, id: &str) -> Result<User> {
     })
 }
 
-/// Get a user by name.
+/// Get a user by name; anonymized users are not returned.
 pub async fn get_user_by_name(pool: &SqlitePool, name: &str) -> Result<User> {
     sqlx::query_as::<_, User>(
         r#"
         SELECT id, name, language
         FROM users
-        WHERE name = ?
+        WHERE name = ? AND deleted_at IS NULL
         "#,
     )
     .bind(name)
@@ -71,17 +111,19 @@ pub async fn get_user_by_name(pool: &SqlitePool, name: &str) -> Result<User> {
 
 /// Update an existing user.
 pub async fn update_user(pool: &SqlitePool, user: &User) -> Result<()> {
-    let result = sqlx::query(
-        r#"
-        UPDATE users
-        SET name = ?, language = ?
-        WHERE id = ?
-        "#,
-    )
-    .bind(&user.name)
-    .bind(&user.language)
-    .bind(&user.id)
-    .execute(pool)
+    let result = retry::on_busy(|| {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET name = ?, language = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&user.name)
+        .bind(&user.language)
+        .bind(&user.id)
+        .execute(pool)
+    })
     .await?;
 
     if result.rows_affected() == 0 {
@@ -94,16 +136,87 @@ pub async fn update_user(pool: &SqlitePool, user: &User) -> Result<()> {
     Ok(())
 }
 
-/// Delete a user by ID.
-pub async fn delete_user(pool: &SqlitePool, id: &str) -> Result<()> {
+/// Soft-delete a user: scrub the name, mark the row deleted, and remove
+/// the user's profile and onboarding rows, all in one transaction.
+///
+/// The `users` row (and its language) is kept so aggregate counts are
+/// preserved, but it is hidden from [`get_user`] and [`list_users`]; use
+/// [`delete_user`] for true erasure.
+pub async fn anonymize_user(pool: &SqlitePool, id: &str) -> Result<()> {
+    let anonymized = retry::on_busy(|| write_anonymize(pool, id)).await?;
+    if !anonymized {
+        return Err(DatabaseError::NotFound {
+            entity: "User",
+            id: id.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns false (and changes nothing) if the user does not exist.
+async fn write_anonymize(pool: &SqlitePool, id: &str) -> sqlx::Result<bool> {
+    let mut tx = pool.begin().await?;
+
     let result = sqlx::query(
         r#"
-        DELETE FROM users
+        UPDATE users
+        SET name = ?, deleted_at = COALESCE(deleted_at, datetime('now'))
         WHERE id = ?
         "#,
     )
+    .bind(ANONYMIZED_NAME)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    // Profiles hold the email and BOLT12 offer; onboarding holds the region
+    sqlx::query("DELETE FROM user_profiles WHERE sender_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM onboarding_state WHERE user_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Whether a user has been soft-deleted with [`anonymize_user`].
+pub async fn is_anonymized(pool: &SqlitePool, id: &str) -> Result<bool> {
+    let deleted_at = sqlx::query_scalar::<_, Option<String>>(
+        r#"
+        SELECT deleted_at FROM users WHERE id = ?
+        "#,
+    )
     .bind(id)
-    .execute(pool)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| DatabaseError::NotFound {
+        entity: "User",
+        id: id.to_string(),
+    })?;
+
+    Ok(deleted_at.is_some())
+}
+
+/// Hard-delete a user by ID, cascading to dependent rows.
+pub async fn delete_user(pool: &SqlitePool, id: &str) -> Result<()> {
+    let result = retry::on_busy(|| {
+        sqlx::query(
+            r#"
+            DELETE FROM users
+            WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .execute(pool)
+    })
     .await?;
 
     if result.rows_affected() == 0 {
@@ -116,12 +229,13 @@ pub async fn delete_user(pool: &SqlitePool, id: &str) -> Result<()> {
     Ok(())
 }
 
-/// List all users.
+/// List all users except anonymized ones.
 pub async fn list_users(pool: &SqlitePool) -> Result<Vec<User>> {
     let users = sqlx::query_as::<_, User>(
         r#"
         SELECT id, name, language
         FROM users
+        WHERE deleted_at IS NULL
         ORDER BY name
         "#,
     )
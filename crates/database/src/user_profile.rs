@@ -3,6 +3,7 @@
 use sqlx::SqlitePool;
 
 use crate::models::UserProfile;
+use crate::retry;
 use crate::Result;
 
 /// Profile field identifiers for updates.
@@ -14,6 +15,10 @@ pub enum ProfileField {
     Email,
     /// Bolt 12 offer for Lightning payments.
     Bolt12Offer,
+    /// Preferred measurement system.
+    PreferredUnits,
+    /// Preferred timezone.
+    Timezone,
 }
 
 impl ProfileField {
@@ -23,6 +28,8 @@ impl ProfileField {
             ProfileField::DefaultModel => "default_model",
             ProfileField::Email => "email",
             ProfileField::Bolt12Offer => "bolt12_offer",
+            ProfileField::PreferredUnits => "preferred_units",
+            ProfileField::Timezone => "timezone",
         }
     }
 
@@ -32,6 +39,8 @@ impl ProfileField {
             "default_model" | "model" => Some(ProfileField::DefaultModel),
             "email" | "e-mail" => Some(ProfileField::Email),
             "bolt12_offer" | "bolt12" | "lightning" => Some(ProfileField::Bolt12Offer),
+            "preferred_units" | "units" => Some(ProfileField::PreferredUnits),
+            "timezone" | "tz" | "time_zone" => Some(ProfileField::Timezone),
             _ => None,
         }
     }
@@ -42,6 +51,8 @@ impl ProfileField {
             ProfileField::DefaultModel => "default model",
             ProfileField::Email => "email",
             ProfileField::Bolt12Offer => "Bolt 12 offer",
+            ProfileField::PreferredUnits => "preferred units",
+            ProfileField::Timezone => "timezone",
         }
     }
 }
@@ -50,7 +61,8 @@ impl ProfileField {
 pub async fn get_profile(pool: &SqlitePool, sender_id: &str) -> Result<Option<UserProfile>> {
     let record = sqlx::query_as::<_, UserProfile>(
         r#"
-        SELECT sender_id, default_model, email, bolt12_offer, created_at, updated_at
+        SELECT sender_id, default_model, email, bolt12_offer, preferred_units, timezone,
+               created_at, updated_at
         FROM user_profiles
         WHERE sender_id = ?
         "#,
@@ -87,11 +99,13 @@ pub async fn upsert_profile_field(
         column = column
     );
 
-    sqlx::query(&query)
-        .bind(sender_id)
-        .bind(value)
-        .execute(pool)
-        .await?;
+    retry::on_busy(|| {
+        sqlx::query(&query)
+            .bind(sender_id)
+            .bind(value)
+            .execute(pool)
+    })
+    .await?;
 
     Ok(())
 }
@@ -179,6 +193,23 @@ mod tests {
         assert_eq!(profile.bolt12_offer, Some("lno1abc123".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_units_and_timezone_fields() {
+        let db = test_db().await;
+        let sender = "+1234567890";
+
+        upsert_profile_field(db.pool(), sender, ProfileField::PreferredUnits, Some("imperial"))
+            .await
+            .unwrap();
+        upsert_profile_field(db.pool(), sender, ProfileField::Timezone, Some("Europe/Paris"))
+            .await
+            .unwrap();
+
+        let profile = get_profile(db.pool(), sender).await.unwrap().unwrap();
+        assert_eq!(profile.preferred_units.as_deref(), Some("imperial"));
+        assert_eq!(profile.timezone.as_deref(), Some("Europe/Paris"));
+    }
+
     #[tokio::test]
     async fn test_clear_field() {
         let db = test_db().await;
@@ -229,6 +260,8 @@ mod tests {
         assert_eq!(ProfileField::from_str("e-mail"), Some(ProfileField::Email));
         assert_eq!(ProfileField::from_str("bolt12"), Some(ProfileField::Bolt12Offer));
         assert_eq!(ProfileField::from_str("lightning"), Some(ProfileField::Bolt12Offer));
+        assert_eq!(ProfileField::from_str("units"), Some(ProfileField::PreferredUnits));
+        assert_eq!(ProfileField::from_str("tz"), Some(ProfileField::Timezone));
         assert_eq!(ProfileField::from_str("invalid"), None);
     }
 }
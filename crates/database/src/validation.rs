@@ -9,6 +9,10 @@ pub enum ValidationError {
     InvalidEmail(String),
     /// Invalid Bolt 12 offer format.
     InvalidBolt12Offer(String),
+    /// Unknown measurement system.
+    InvalidUnits(String),
+    /// Invalid timezone identifier.
+    InvalidTimezone(String),
     /// Value too long.
     TooLong { field: String, max: usize, actual: usize },
     /// Empty value where one is required.
@@ -20,6 +24,8 @@ impl fmt::Display for ValidationError {
         match self {
             ValidationError::InvalidEmail(msg) => write!(f, "Invalid email: {}", msg),
             ValidationError::InvalidBolt12Offer(msg) => write!(f, "Invalid Bolt 12 offer: {}", msg),
+            ValidationError::InvalidUnits(msg) => write!(f, "Invalid units: {}", msg),
+            ValidationError::InvalidTimezone(msg) => write!(f, "Invalid timezone: {}", msg),
             ValidationError::TooLong { field, max, actual } => {
                 write!(f, "{} is too long ({} chars, max {})", field, actual, max)
             }
@@ -39,6 +45,12 @@ pub const MAX_BOLT12_LENGTH: usize = 1024;
 /// Maximum allowed length for model names.
 pub const MAX_MODEL_LENGTH: usize = 64;
 
+/// Maximum allowed length for timezone identifiers.
+pub const MAX_TIMEZONE_LENGTH: usize = 64;
+
+/// Measurement systems accepted for the preferred units field.
+pub const UNIT_SYSTEMS: &[&str] = &["metric", "imperial"];
+
 /// Validate an email address (basic RFC 5322 format check).
 ///
 /// This is a basic validation that checks:
@@ -177,6 +189,63 @@ pub fn validate_model_length(model: &str) -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Validate a preferred measurement system ("metric" or "imperial").
+pub fn validate_units(units: &str) -> Result<(), ValidationError> {
+    let units = units.trim().to_lowercase();
+
+    if units.is_empty() {
+        return Err(ValidationError::Empty("units".to_string()));
+    }
+
+    if !UNIT_SYSTEMS.contains(&units.as_str()) {
+        return Err(ValidationError::InvalidUnits(format!(
+            "'{}' (expected one of: {})",
+            units,
+            UNIT_SYSTEMS.join(", ")
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a timezone identifier.
+///
+/// Accepts IANA-style names such as "UTC" or "America/New_York". Whether the
+/// zone actually exists is left to the tools that consume it.
+pub fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    let timezone = timezone.trim();
+
+    if timezone.is_empty() {
+        return Err(ValidationError::Empty("timezone".to_string()));
+    }
+
+    if timezone.len() > MAX_TIMEZONE_LENGTH {
+        return Err(ValidationError::TooLong {
+            field: "timezone".to_string(),
+            max: MAX_TIMEZONE_LENGTH,
+            actual: timezone.len(),
+        });
+    }
+
+    if let Some(c) = timezone
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+')))
+    {
+        return Err(ValidationError::InvalidTimezone(format!(
+            "invalid character '{}'",
+            c
+        )));
+    }
+
+    if timezone.starts_with('/') || timezone.ends_with('/') || timezone.contains("//") {
+        return Err(ValidationError::InvalidTimezone(
+            "expected a name like 'Europe/Paris' or 'UTC'".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -311,6 +380,40 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_validate_units() {
+        assert!(validate_units("metric").is_ok());
+        assert!(validate_units(" Imperial ").is_ok());
+
+        assert!(matches!(validate_units(""), Err(ValidationError::Empty(_))));
+        assert!(matches!(
+            validate_units("furlongs"),
+            Err(ValidationError::InvalidUnits(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_timezone() {
+        assert!(validate_timezone("UTC").is_ok());
+        assert!(validate_timezone("Europe/Paris").is_ok());
+        assert!(validate_timezone("America/Argentina/Buenos_Aires").is_ok());
+        assert!(validate_timezone("Etc/GMT+5").is_ok());
+
+        assert!(matches!(validate_timezone(""), Err(ValidationError::Empty(_))));
+        assert!(matches!(
+            validate_timezone("Europe/Paris; DROP"),
+            Err(ValidationError::InvalidTimezone(_))
+        ));
+        assert!(matches!(
+            validate_timezone("/Paris"),
+            Err(ValidationError::InvalidTimezone(_))
+        ));
+        assert!(matches!(
+            validate_timezone(&"a".repeat(100)),
+            Err(ValidationError::TooLong { .. })
+        ));
+    }
+
     #[test]
     fn test_validation_error_display() {
         let err = ValidationError::InvalidEmail("test message".to_string());
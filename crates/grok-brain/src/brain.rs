@@ -81,25 +81,33 @@ impl GrokBrain {
         self.history.clear(sender).await;
     }
 
+    /// Forget the most recent exchange for a specific sender.
+    pub async fn forget_last_exchange(&self, sender: &str) -> bool {
+        self.history.remove_last_exchange(sender).await
+    }
+
     /// Clear all conversation histories.
     pub async fn clear_all_history(&self) {
         self.history.clear_all().await;
     }
 
+    /// Per-request system context: the memory prompt plus the user's
+    /// language hint, if either is present.
     fn memory_prompt_for_message(&self, message: &InboundMessage) -> Option<String> {
-        if self.config.memory_prompt_max_chars == 0 {
-            return None;
-        }
-        let prompt = message
-            .routing
-            .as_ref()
-            .and_then(|routing| routing.memory_prompt.as_deref())?;
-        let trimmed = truncate_text(prompt, self.config.memory_prompt_max_chars);
-        let trimmed = trimmed.trim().to_string();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed)
+        let routing = message.routing.as_ref()?;
+        let memory = routing
+            .memory_prompt
+            .as_deref()
+            .filter(|_| self.config.memory_prompt_max_chars > 0)
+            .map(|prompt| {
+                truncate_text(prompt, self.config.memory_prompt_max_chars)
+                    .trim()
+                    .to_string()
+            })
+            .filter(|prompt| !prompt.is_empty());
+        match (memory, routing.language_hint()) {
+            (Some(memory), Some(hint)) => Some(format!("{}\n\n{}", memory, hint)),
+            (memory, hint) => memory.or(hint),
         }
     }
 
@@ -112,13 +120,11 @@ impl GrokBrain {
             messages.push(ChatMessage::system(system_prompt.clone()));
         }
 
-        // Add conversation history
+        // Add conversation history (tool results become system context)
         let history = self.history.get(history_key).await;
         for msg in history {
-            messages.push(ChatMessage {
-                role: msg.role,
-                content: msg.content,
-            });
+            let (role, content) = msg.into_prompt_parts();
+            messages.push(ChatMessage { role, content });
         }
 
         // Add current user message
@@ -246,9 +252,15 @@ impl Brain for GrokBrain {
                 "I apologize, but I couldn't generate a response.".to_string()
             });
 
-        // Add to conversation history
+        // Add to conversation history, without the gathered context but with
+        // the tool results it came from
         self.history
-            .add_exchange(&history_key, user_text, &response_text)
+            .add_exchange_with_tools(
+                &history_key,
+                message.history_text(),
+                message.tool_results(),
+                &response_text,
+            )
             .await;
 
         // Log usage if available
@@ -380,4 +392,49 @@ mod tests {
         let selected = select_model_for_message(&config, &message);
         assert_eq!(selected, "grok-override");
     }
+
+    #[test]
+    fn test_memory_prompt_includes_language_hint() {
+        let config = GrokBrainConfig::builder().api_key("test-key").build();
+        let brain = GrokBrain::new(config).unwrap();
+        let mut message = InboundMessage::direct("+123", "hola", 0);
+        assert_eq!(brain.memory_prompt_for_message(&message), None);
+
+        message.routing = Some(brain_core::RoutingInfo {
+            language: Some("Spanish".to_string()),
+            ..Default::default()
+        });
+        let prompt = brain.memory_prompt_for_message(&message).unwrap();
+        assert!(prompt.contains("Reply in Spanish"));
+
+        message.routing.as_mut().unwrap().memory_prompt = Some("[MEMORY]".to_string());
+        let prompt = brain.memory_prompt_for_message(&message).unwrap();
+        assert!(prompt.starts_with("[MEMORY]"));
+        assert!(prompt.ends_with("unless they write in another language."));
+    }
+
+    #[tokio::test]
+    async fn test_build_messages_includes_tool_results() {
+        use brain_core::HistoryMessage;
+
+        let config = GrokBrainConfig::builder().api_key("test-key").build();
+        let brain = GrokBrain::new(config).unwrap();
+        brain
+            .history
+            .add_exchange_with_tools(
+                "+1234567890",
+                "Weather in Paris?",
+                &[HistoryMessage::tool("weather", "Paris: 18°C, cloudy")],
+                "It's 18°C and cloudy.",
+            )
+            .await;
+
+        let messages = brain.build_messages("+1234567890", "Will I need a jacket?").await;
+        let roles: Vec<_> = messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["user", "system", "assistant", "user"]);
+        assert_eq!(
+            messages[1].content,
+            "Result from the weather tool:\nParis: 18°C, cloudy"
+        );
+    }
 }
@@ -0,0 +1,253 @@
+//! Plain-text extraction for ingested documents.
+//!
+//! Text and markdown are read as UTF-8. PDF (`pdf` feature) and docx (`docx`
+//! feature) go through their own extractors so their optional deps stay out
+//! of default builds. Files with an unknown extension fall back to the raw
+//! text read.
+
+use std::fs;
+use std::path::Path;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ExtractError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("file is not valid UTF-8 text")]
+    NotUtf8,
+    #[error("{0} files are not supported in this build (enable the `{0}` feature)")]
+    Unsupported(&'static str),
+    #[error("failed to extract text from {format}: {reason}")]
+    Failed { format: &'static str, reason: String },
+    #[error("no text found in document")]
+    Empty,
+}
+
+/// Extracted text plus the MIME type of the source document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedText {
+    pub mime: &'static str,
+    pub text: String,
+}
+
+/// Turns one document format into plain text.
+pub trait TextExtractor: Send + Sync {
+    /// Lowercase file extensions handled, without the dot.
+    fn extensions(&self) -> &[&'static str];
+    /// MIME type recorded in the doc manifest.
+    fn mime(&self) -> &'static str;
+    fn extract(&self, bytes: &[u8]) -> Result<String, ExtractError>;
+}
+
+/// UTF-8 text, also the fallback for unknown extensions.
+pub struct PlainTextExtractor;
+
+impl TextExtractor for PlainTextExtractor {
+    fn extensions(&self) -> &[&'static str] {
+        &["txt", "text", "md", "markdown"]
+    }
+
+    fn mime(&self) -> &'static str {
+        "text/plain"
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<String, ExtractError> {
+        String::from_utf8(bytes.to_vec()).map_err(|_| ExtractError::NotUtf8)
+    }
+}
+
+#[cfg(feature = "pdf")]
+pub struct PdfExtractor;
+
+#[cfg(feature = "pdf")]
+impl TextExtractor for PdfExtractor {
+    fn extensions(&self) -> &[&'static str] {
+        &["pdf"]
+    }
+
+    fn mime(&self) -> &'static str {
+        "application/pdf"
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<String, ExtractError> {
+        pdf_extract::extract_text_from_mem(bytes).map_err(|err| ExtractError::Failed {
+            format: "pdf",
+            reason: err.to_string(),
+        })
+    }
+}
+
+/// Reads paragraph text from `word/document.xml`.
+#[cfg(feature = "docx")]
+pub struct DocxExtractor;
+
+#[cfg(feature = "docx")]
+impl DocxExtractor {
+    fn document_xml(bytes: &[u8]) -> Result<String, String> {
+        use std::io::{Cursor, Read};
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+        let mut entry = archive
+            .by_name("word/document.xml")
+            .map_err(|e| e.to_string())?;
+        let mut xml = String::new();
+        entry.read_to_string(&mut xml).map_err(|e| e.to_string())?;
+        Ok(xml)
+    }
+
+    fn paragraphs(xml: &str) -> Result<String, String> {
+        use quick_xml::events::Event;
+        use quick_xml::Reader;
+
+        let mut reader = Reader::from_str(xml);
+        let mut text = String::new();
+        let mut in_text = false;
+        loop {
+            match reader.read_event().map_err(|e| e.to_string())? {
+                Event::Start(tag) if tag.name().as_ref() == b"w:t" => in_text = true,
+                Event::End(tag) => match tag.name().as_ref() {
+                    b"w:t" => in_text = false,
+                    b"w:p" => text.push('\n'),
+                    _ => {}
+                },
+                Event::Empty(tag) => match tag.name().as_ref() {
+                    b"w:tab" => text.push('\t'),
+                    b"w:br" => text.push('\n'),
+                    _ => {}
+                },
+                Event::Text(chunk) if in_text => {
+                    text.push_str(&chunk.unescape().map_err(|e| e.to_string())?);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(feature = "docx")]
+impl TextExtractor for DocxExtractor {
+    fn extensions(&self) -> &[&'static str] {
+        &["docx"]
+    }
+
+    fn mime(&self) -> &'static str {
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<String, ExtractError> {
+        Self::document_xml(bytes)
+            .and_then(|xml| Self::paragraphs(&xml))
+            .map_err(|reason| ExtractError::Failed {
+                format: "docx",
+                reason,
+            })
+    }
+}
+
+/// Extractors chosen by file extension.
+pub struct Extractors {
+    extractors: Vec<Box<dyn TextExtractor>>,
+}
+
+impl Default for Extractors {
+    /// Plain text plus every format enabled at build time.
+    fn default() -> Self {
+        #[allow(unused_mut)]
+        let mut extractors = Self::new();
+        #[cfg(feature = "pdf")]
+        extractors.register(Box::new(PdfExtractor));
+        #[cfg(feature = "docx")]
+        extractors.register(Box::new(DocxExtractor));
+        extractors
+    }
+}
+
+impl Extractors {
+    /// Plain text only.
+    pub fn new() -> Self {
+        Self {
+            extractors: vec![Box::new(PlainTextExtractor)],
+        }
+    }
+
+    /// Add an extractor; later registrations win for the same extension.
+    pub fn register(&mut self, extractor: Box<dyn TextExtractor>) {
+        self.extractors.insert(0, extractor);
+    }
+
+    /// Extract text from `bytes`, choosing the extractor by extension.
+    pub fn extract(&self, extension: &str, bytes: &[u8]) -> Result<ExtractedText, ExtractError> {
+        let extension = extension.to_ascii_lowercase();
+        let extractor = match self.find(&extension) {
+            Some(extractor) => extractor,
+            None => match extension.as_str() {
+                "pdf" => return Err(ExtractError::Unsupported("pdf")),
+                "docx" => return Err(ExtractError::Unsupported("docx")),
+                _ => &PlainTextExtractor as &dyn TextExtractor,
+            },
+        };
+
+        let text = extractor.extract(bytes)?;
+        if text.trim().is_empty() {
+            return Err(ExtractError::Empty);
+        }
+        Ok(ExtractedText {
+            mime: extractor.mime(),
+            text,
+        })
+    }
+
+    /// Read and extract a file.
+    pub fn extract_file(&self, path: &Path) -> Result<ExtractedText, ExtractError> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.extract(&extension, &fs::read(path)?)
+    }
+
+    fn find(&self, extension: &str) -> Option<&dyn TextExtractor> {
+        self.extractors
+            .iter()
+            .find(|extractor| extractor.extensions().contains(&extension))
+            .map(|extractor| extractor.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_and_fallback() {
+        let extractors = Extractors::new();
+        let markdown = extractors.extract("MD", b"# Title\n\nBody").unwrap();
+        assert_eq!(markdown.mime, "text/plain");
+        assert_eq!(markdown.text, "# Title\n\nBody");
+        assert_eq!(extractors.extract("csv", b"a,b").unwrap().text, "a,b");
+
+        assert!(matches!(
+            extractors.extract("txt", &[0xff, 0xfe]),
+            Err(ExtractError::NotUtf8)
+        ));
+        assert!(matches!(
+            extractors.extract("txt", b"  \n"),
+            Err(ExtractError::Empty)
+        ));
+    }
+
+    #[test]
+    fn test_binary_formats_need_their_feature() {
+        let extractors = Extractors::new();
+        let err = extractors.extract("pdf", b"%PDF-1.4").unwrap_err();
+        assert!(matches!(err, ExtractError::Unsupported("pdf")));
+        assert!(err.to_string().contains("enable the `pdf` feature"));
+        assert!(matches!(
+            extractors.extract("docx", b"PK"),
+            Err(ExtractError::Unsupported("docx"))
+        ));
+    }
+}
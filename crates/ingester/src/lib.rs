@@ -0,0 +1,261 @@
+//! Chunking and publishing shared by the `ingester` binary and the
+//! orchestrator's Signal attachment ingestion.
+
+mod extract;
+
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use nostr_persistence::{
+    ChunkOffsets, ChunkRef, DocChunk, DocManifest, Error, NostrPublisher, PublishResult,
+};
+
+#[cfg(feature = "docx")]
+pub use extract::DocxExtractor;
+#[cfg(feature = "pdf")]
+pub use extract::PdfExtractor;
+pub use extract::{ExtractError, ExtractedText, Extractors, PlainTextExtractor, TextExtractor};
+
+/// Inline chunk text is capped at this many characters.
+pub const INLINE_TEXT_MAX_CHARS: usize = 2000;
+
+/// Default chunk size in characters.
+pub const DEFAULT_CHUNK_SIZE: usize = 800;
+
+/// Default chunk overlap in characters.
+pub const DEFAULT_CHUNK_OVERLAP: usize = 200;
+
+/// How to describe and split a document.
+#[derive(Debug, Clone)]
+pub struct DocumentOptions {
+    pub title: String,
+    pub lang: String,
+    pub mime: String,
+    pub source_type: String,
+    pub chunk_size: usize,
+    pub chunk_overlap: usize,
+    /// Embed chunk text in ChunkRef events (worker-friendly).
+    pub inline_text: bool,
+    /// Fixed doc id; `None` derives it from the content hash.
+    pub doc_id: Option<String>,
+}
+
+impl DocumentOptions {
+    /// Plain-text defaults for a document with the given title.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            lang: "en".to_string(),
+            mime: "text/plain".to_string(),
+            source_type: "file_ingest".to_string(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            chunk_overlap: DEFAULT_CHUNK_OVERLAP,
+            inline_text: false,
+            doc_id: None,
+        }
+    }
+}
+
+/// A document split into chunks, with the events that describe it.
+#[derive(Debug, Clone)]
+pub struct ChunkedDocument {
+    pub manifest: DocManifest,
+    /// One per chunk, in order.
+    pub chunk_refs: Vec<ChunkRef>,
+    /// `(start, end, text)` per chunk, offsets in characters.
+    pub chunks: Vec<(usize, usize, String)>,
+}
+
+/// Chunk `text` and build its manifest and chunk refs.
+///
+/// Unless `options.doc_id` is set, the doc id is derived from the content
+/// hash: re-ingesting the same text replaces the earlier events, but any edit
+/// produces a new doc. Use [`doc_id_for_path`] to keep one id across edits.
+pub fn chunk_document(text: &str, options: &DocumentOptions) -> ChunkedDocument {
+    let doc_hash = sha256_hex(text.as_bytes());
+    let doc_id = options
+        .doc_id
+        .clone()
+        .unwrap_or_else(|| format!("doc_{}", short_hash(&doc_hash)));
+    let chunks = chunk_text(text, options.chunk_size, options.chunk_overlap);
+
+    let mut doc_chunks = Vec::new();
+    let mut chunk_refs = Vec::new();
+    for (ord, (start, end, chunk)) in chunks.iter().enumerate() {
+        let chunk_id = format!("{}_chunk_{}", doc_id, ord);
+        let chunk_hash = format!("sha256:{}", sha256_hex(chunk.as_bytes()));
+        let offsets = ChunkOffsets {
+            start: *start as u64,
+            end: *end as u64,
+        };
+
+        doc_chunks.push(DocChunk {
+            chunk_id: chunk_id.clone(),
+            ord: ord as u32,
+            offsets: offsets.clone(),
+            chunk_hash: chunk_hash.clone(),
+            blob_ref: None,
+        });
+
+        let mut chunk_ref =
+            ChunkRef::new(chunk_id, doc_id.clone(), ord as u32, offsets, chunk_hash);
+        if options.inline_text {
+            let snippet = truncate_text(chunk, INLINE_TEXT_MAX_CHARS);
+            if !snippet.is_empty() {
+                chunk_ref.text = Some(snippet);
+            }
+        }
+        chunk_refs.push(chunk_ref);
+    }
+
+    let manifest = DocManifest::new(
+        doc_id,
+        options.title.clone(),
+        options.lang.clone(),
+        options.mime.clone(),
+        options.source_type.clone(),
+        format!("sha256:{}", doc_hash),
+        doc_chunks,
+    );
+
+    ChunkedDocument {
+        manifest,
+        chunk_refs,
+        chunks,
+    }
+}
+
+/// Publish the manifest, then each chunk ref, stopping at the first failure.
+pub async fn publish_document(
+    publisher: &dyn NostrPublisher,
+    manifest: &DocManifest,
+    chunk_refs: &[ChunkRef],
+) -> Result<Vec<PublishResult>, Error> {
+    let mut results = Vec::with_capacity(chunk_refs.len() + 1);
+    let result = publisher
+        .publish_doc_manifest(manifest.clone(), vec![])
+        .await?;
+    info!(event_id = %result.event_id, "Published doc manifest");
+    results.push(result);
+
+    for chunk_ref in chunk_refs {
+        let result = publisher
+            .publish_chunk_ref(chunk_ref.clone(), vec![])
+            .await?;
+        info!(event_id = %result.event_id, chunk_id = %chunk_ref.chunk_id, "Published chunk ref");
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// Doc id derived from a file path rather than its content, so edits
+/// republish the same doc (replacing its manifest and chunk refs).
+///
+/// Pass a canonical path so the id does not depend on the working directory.
+pub fn doc_id_for_path(path: &Path) -> String {
+    let hash = sha256_hex(path.to_string_lossy().as_bytes());
+    format!("doc_{}", short_hash(&hash))
+}
+
+pub fn chunk_text(
+    text: &str,
+    chunk_size: usize,
+    chunk_overlap: usize,
+) -> Vec<(usize, usize, String)> {
+    let size = chunk_size.max(1);
+    let overlap = chunk_overlap.min(size.saturating_sub(1));
+    let chars: Vec<char> = text.chars().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0usize;
+
+    while start < chars.len() {
+        let end = (start + size).min(chars.len());
+        let chunk: String = chars[start..end].iter().collect();
+        chunks.push((start, end, chunk));
+        if end == chars.len() {
+            break;
+        }
+        start = end.saturating_sub(overlap);
+    }
+
+    chunks
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    hex::encode(hasher.finalize())
+}
+
+fn short_hash(hash: &str) -> String {
+    hash.chars().take(12).collect()
+}
+
+fn truncate_text(input: &str, max_chars: usize) -> String {
+    input.chars().take(max_chars).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text_overlaps() {
+        let chunks = chunk_text("abcdefghij", 4, 1);
+        let spans: Vec<_> = chunks.iter().map(|(start, end, _)| (*start, *end)).collect();
+        assert_eq!(spans, vec![(0, 4), (3, 7), (6, 10)]);
+        assert_eq!(chunks[1].2, "defg");
+        assert!(chunk_text("", 4, 1).is_empty());
+    }
+
+    #[test]
+    fn test_chunk_document_builds_matching_events() {
+        let mut options = DocumentOptions::new("notes.md");
+        options.chunk_size = 4;
+        options.chunk_overlap = 0;
+        options.inline_text = true;
+
+        let document = chunk_document("abcdefghij", &options);
+        assert_eq!(document.chunks.len(), 3);
+        assert_eq!(document.chunk_refs.len(), 3);
+        assert_eq!(document.manifest.chunks.len(), 3);
+        assert!(document.manifest.doc_id.starts_with("doc_"));
+
+        for (chunk_ref, doc_chunk) in document.chunk_refs.iter().zip(&document.manifest.chunks) {
+            assert_eq!(chunk_ref.doc_id, document.manifest.doc_id);
+            assert_eq!(chunk_ref.chunk_id, doc_chunk.chunk_id);
+            assert_eq!(chunk_ref.chunk_hash, doc_chunk.chunk_hash);
+            assert!(chunk_ref.text_matches_hash());
+        }
+        assert_eq!(document.chunk_refs[2].text.as_deref(), Some("ij"));
+    }
+
+    #[test]
+    fn test_path_ids_are_stable_across_edits() {
+        let original = "Meeting notes, first draft.";
+        let edited = "Meeting notes, second draft.";
+
+        let by_content = DocumentOptions::new("notes.md");
+        assert_ne!(
+            chunk_document(original, &by_content).manifest.doc_id,
+            chunk_document(edited, &by_content).manifest.doc_id
+        );
+
+        let doc_id = doc_id_for_path(Path::new("/srv/kb/notes.md"));
+        assert_eq!(doc_id, doc_id_for_path(Path::new("/srv/kb/notes.md")));
+        assert_ne!(doc_id, doc_id_for_path(Path::new("/srv/kb/other.md")));
+
+        let by_path = DocumentOptions {
+            doc_id: Some(doc_id.clone()),
+            ..DocumentOptions::new("notes.md")
+        };
+        let first = chunk_document(original, &by_path);
+        let second = chunk_document(edited, &by_path);
+        assert_eq!(first.manifest.doc_id, doc_id);
+        assert_eq!(second.manifest.doc_id, doc_id);
+        assert_eq!(first.chunk_refs[0].chunk_id, second.chunk_refs[0].chunk_id);
+        assert_ne!(first.manifest.content_hash, second.manifest.content_hash);
+    }
+}
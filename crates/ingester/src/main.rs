@@ -1,26 +1,25 @@
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
-use clap::Parser;
-use sha2::{Digest, Sha256};
+use clap::{Parser, ValueEnum};
 use tracing::info;
 use uuid::Uuid;
 
+use ingester::{chunk_document, doc_id_for_path, ChunkedDocument, DocumentOptions, Extractors};
 use nostr_persistence::{
-    k_tag, unix_timestamp, ChunkOffsets, ChunkRef, DocChunk, DocManifest, IndexerConfig, NoopCodec,
-    NostrEvent, NostrIndexer, NostrIndexerImpl, NostrKinds, NostrPublisher, NostrPublisherImpl,
-    NostrTag, PublisherConfig, TAG_KIND_CHUNK_REF, TAG_KIND_DOC_MANIFEST,
+    k_tag, unix_timestamp, ChunkRef, CryptoError, DocManifest, IndexerConfig, NoopCodec,
+    NostrEvent, NostrIndexer, NostrIndexerImpl, NostrKinds, NostrPublisherImpl, NostrTag,
+    PayloadCodec, PublisherConfig, SecretBoxCodec, TAG_KIND_CHUNK_REF, TAG_KIND_DOC_MANIFEST,
 };
 
-const INLINE_TEXT_MAX_CHARS: usize = 2000;
-
 #[derive(Debug, Parser)]
 #[command(name = "ingester")]
 #[command(about = "Ingest a document, chunk it, and publish/index via Nostr")]
 struct Args {
-    /// Input document path (text/markdown)
+    /// Input document path (text/markdown; PDF and docx with the `pdf`/`docx` features)
     #[arg(long)]
     file: PathBuf,
 
@@ -32,6 +31,11 @@ struct Args {
     #[arg(long)]
     key: Option<String>,
 
+    /// Encrypt published payloads with secretbox-v1 (hex: or base64 key).
+    /// Falls back to NOSTR_SECRETBOX_KEY env.
+    #[arg(long)]
+    secretbox_key: Option<String>,
+
     /// Index directly into a local Nostr SQLite DB (no relay required)
     #[arg(long)]
     index_db: Option<PathBuf>,
@@ -52,6 +56,15 @@ struct Args {
     #[arg(long)]
     inline_text: bool,
 
+    /// Use this doc id instead of deriving one
+    #[arg(long, conflicts_with = "stable_id_from")]
+    doc_id: Option<String>,
+
+    /// Derive the doc id from the content hash (new doc on every edit) or the
+    /// file path (edits publish new versions of the same doc)
+    #[arg(long, value_enum, default_value_t = StableIdFrom::Content)]
+    stable_id_from: StableIdFrom,
+
     /// Document title (defaults to filename)
     #[arg(long)]
     title: Option<String>,
@@ -73,32 +86,42 @@ struct Args {
     timeout_secs: u64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum StableIdFrom {
+    Content,
+    Path,
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt::init();
     let args = Args::parse();
 
-    let text = fs::read_to_string(&args.file)?;
+    let extracted = Extractors::default()
+        .extract_file(&args.file)
+        .map_err(|err| format!("Could not read {}: {}", args.file.display(), err))?;
     let title = args
         .title
         .clone()
         .unwrap_or_else(|| args.file.file_name().unwrap().to_string_lossy().to_string());
-    let doc_hash = sha256_hex(text.as_bytes());
-    let doc_id = format!("doc_{}", short_hash(&doc_hash));
-
-    let chunks = chunk_text(&text, args.chunk_size, args.chunk_overlap);
-    let (doc_chunks, chunk_refs) =
-        write_chunks(&doc_id, &args.out_dir, &chunks, args.inline_text)?;
-
-    let mut manifest = DocManifest::new(
-        doc_id.clone(),
-        title,
-        args.lang.clone(),
-        "text/plain".to_string(),
-        args.source_type.clone(),
-        format!("sha256:{}", doc_hash),
-        doc_chunks,
-    );
+    let options = DocumentOptions {
+        mime: extracted.mime.to_string(),
+        lang: args.lang.clone(),
+        source_type: args.source_type.clone(),
+        chunk_size: args.chunk_size,
+        chunk_overlap: args.chunk_overlap,
+        inline_text: args.inline_text,
+        doc_id: doc_id_override(args.doc_id.clone(), args.stable_id_from, &args.file)?,
+        ..DocumentOptions::new(title)
+    };
+
+    let mut document = chunk_document(&extracted.text, &options);
+    write_chunks(&args.out_dir, &mut document)?;
+    let ChunkedDocument {
+        mut manifest,
+        chunk_refs,
+        ..
+    } = document;
     manifest.blob_ref = Some(args.file.canonicalize()?.display().to_string());
 
     if !args.relay.is_empty() {
@@ -107,27 +130,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .or_else(|| env::var("NOSTR_SECRET_KEY").ok())
             .ok_or("Missing NOSTR secret key (--key or NOSTR_SECRET_KEY)")?;
 
+        let codec = payload_codec(
+            args.secretbox_key
+                .or_else(|| env::var("NOSTR_SECRETBOX_KEY").ok()),
+        )?;
+        info!(
+            enc = codec.encoding_tag().unwrap_or("none"),
+            "Publishing to relays"
+        );
+
         let config = PublisherConfig {
             relays: args.relay.clone(),
             secret_key: key,
             min_acks: args.min_acks,
             timeout: Duration::from_secs(args.timeout_secs),
             kinds: NostrKinds::default(),
-            codec: std::sync::Arc::new(NoopCodec),
+            codec,
         };
-
-        let publisher = NostrPublisherImpl::new(config).await?;
-        let result = publisher
-            .publish_doc_manifest(manifest.clone(), vec![])
-            .await?;
-        info!(event_id = %result.event_id, "Published doc manifest");
-
-        for chunk_ref in &chunk_refs {
-            let result = publisher
-                .publish_chunk_ref(chunk_ref.clone(), vec![])
-                .await?;
-            info!(event_id = %result.event_id, chunk_id = %chunk_ref.chunk_id, "Published chunk ref");
-        }
+        publish_document(config, &manifest, &chunk_refs).await?;
     }
 
     if let Some(db_path) = args.index_db.as_ref() {
@@ -138,102 +158,59 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn sha256_hex(bytes: &[u8]) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(bytes);
-    hex::encode(hasher.finalize())
-}
-
-fn short_hash(hash: &str) -> String {
-    hash.chars().take(12).collect()
-}
-
-fn truncate_text(input: &str, max_chars: usize) -> String {
-    if max_chars == 0 {
-        return String::new();
+/// Doc id to use instead of the content hash, if any.
+fn doc_id_override(
+    doc_id: Option<String>,
+    stable_id_from: StableIdFrom,
+    file: &Path,
+) -> std::io::Result<Option<String>> {
+    if doc_id.is_some() {
+        return Ok(doc_id);
     }
-    let mut count = 0usize;
-    let mut out = String::new();
-    for ch in input.chars() {
-        if count >= max_chars {
-            break;
-        }
-        out.push(ch);
-        count += 1;
+    match stable_id_from {
+        StableIdFrom::Content => Ok(None),
+        StableIdFrom::Path => Ok(Some(doc_id_for_path(&file.canonicalize()?))),
     }
-    out
 }
 
-fn chunk_text(text: &str, chunk_size: usize, chunk_overlap: usize) -> Vec<(usize, usize, String)> {
-    let size = chunk_size.max(1);
-    let overlap = chunk_overlap.min(size.saturating_sub(1));
-    let chars: Vec<char> = text.chars().collect();
-    let mut chunks = Vec::new();
-    let mut start = 0usize;
-
-    while start < chars.len() {
-        let end = (start + size).min(chars.len());
-        let chunk: String = chars[start..end].iter().collect();
-        chunks.push((start, end, chunk));
-        if end == chars.len() {
-            break;
-        }
-        start = end.saturating_sub(overlap);
+/// Secretbox codec when a key is configured, so payloads match what the
+/// worker decrypts; plaintext otherwise.
+fn payload_codec(secretbox_key: Option<String>) -> Result<Arc<dyn PayloadCodec>, CryptoError> {
+    match secretbox_key.filter(|key| !key.trim().is_empty()) {
+        Some(key) => Ok(Arc::new(SecretBoxCodec::from_str(&key)?)),
+        None => Ok(Arc::new(NoopCodec)),
     }
+}
 
-    chunks
+async fn publish_document(
+    config: PublisherConfig,
+    manifest: &DocManifest,
+    chunk_refs: &[ChunkRef],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let publisher = NostrPublisherImpl::new(config).await?;
+    ingester::publish_document(&publisher, manifest, chunk_refs).await?;
+    Ok(())
 }
 
+/// Write each chunk to `<out_dir>/<doc_id>/chunk_<ord>.txt` and point the
+/// manifest and chunk refs at those files.
 fn write_chunks(
-    doc_id: &str,
     out_dir: &Path,
-    chunks: &[(usize, usize, String)],
-    inline_text: bool,
-) -> Result<(Vec<DocChunk>, Vec<ChunkRef>), Box<dyn std::error::Error>> {
-    let doc_dir = out_dir.join(doc_id);
+    document: &mut ChunkedDocument,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let doc_dir = out_dir.join(&document.manifest.doc_id);
     fs::create_dir_all(&doc_dir)?;
 
-    let mut doc_chunks = Vec::new();
-    let mut chunk_refs = Vec::new();
-
-    for (ord, (start, end, chunk)) in chunks.iter().enumerate() {
-        let chunk_id = format!("{}_chunk_{}", doc_id, ord);
-        let chunk_hash = sha256_hex(chunk.as_bytes());
+    for (ord, (_, _, chunk)) in document.chunks.iter().enumerate() {
         let chunk_path = doc_dir.join(format!("chunk_{}.txt", ord));
         fs::write(&chunk_path, chunk)?;
         let blob_ref = chunk_path.canonicalize()?.display().to_string();
 
-        let offsets = ChunkOffsets {
-            start: *start as u64,
-            end: *end as u64,
-        };
-
-        doc_chunks.push(DocChunk {
-            chunk_id: chunk_id.clone(),
-            ord: ord as u32,
-            offsets: offsets.clone(),
-            chunk_hash: format!("sha256:{}", chunk_hash),
-            blob_ref: Some(blob_ref.clone()),
-        });
-
-        let mut chunk_ref = ChunkRef::new(
-            chunk_id.clone(),
-            doc_id.to_string(),
-            ord as u32,
-            offsets,
-            format!("sha256:{}", chunk_hash),
-        );
-        chunk_ref.blob_ref = Some(blob_ref);
-        if inline_text {
-            let snippet = truncate_text(chunk, INLINE_TEXT_MAX_CHARS);
-            if !snippet.is_empty() {
-                chunk_ref.text = Some(snippet);
-            }
-        }
-        chunk_refs.push(chunk_ref);
+        document.manifest.chunks[ord].blob_ref = Some(blob_ref.clone());
+        document.chunk_refs[ord].blob_ref = Some(blob_ref);
     }
 
-    Ok((doc_chunks, chunk_refs))
+    Ok(())
 }
 
 async fn index_to_db(
@@ -249,7 +226,9 @@ async fn index_to_db(
         db_path: db_path.to_path_buf(),
         backfill_since: None,
         backfill_limit: None,
-        codec: std::sync::Arc::new(NoopCodec),
+        codec: Arc::new(NoopCodec),
+        decoders: Vec::new(),
+        verify_hashes: true,
     };
 
     let indexer = NostrIndexerImpl::new(config).await?;
@@ -286,3 +265,109 @@ async fn index_to_db(
 fn d_tag(value: &str) -> NostrTag {
     NostrTag::new("d", vec![value.to_string()])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use nostr_persistence::ChunkOffsets;
+    use nostr_relay_builder::MockRelay;
+    use nostr_sdk::prelude::{Client, Filter, Keys};
+
+    const SECRETBOX_KEY: &str =
+        "hex:1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[test]
+    fn test_payload_codec_selection() {
+        let codec = payload_codec(Some(SECRETBOX_KEY.to_string())).unwrap();
+        assert_eq!(codec.encoding_tag(), Some("secretbox-v1"));
+        assert_eq!(payload_codec(None).unwrap().encoding_tag(), None);
+        assert_eq!(
+            payload_codec(Some(" ".to_string())).unwrap().encoding_tag(),
+            None
+        );
+        assert!(payload_codec(Some("hex:00".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_doc_id_override() {
+        let file = std::env::temp_dir().join(format!("ingester-doc-id-{}.md", std::process::id()));
+        fs::write(&file, "first draft").unwrap();
+
+        let explicit = doc_id_override(Some("doc_handbook".to_string()), StableIdFrom::Path, &file);
+        assert_eq!(explicit.unwrap().as_deref(), Some("doc_handbook"));
+        assert_eq!(doc_id_override(None, StableIdFrom::Content, &file).unwrap(), None);
+
+        let by_path = doc_id_override(None, StableIdFrom::Path, &file).unwrap();
+        fs::write(&file, "second draft").unwrap();
+        assert_eq!(doc_id_override(None, StableIdFrom::Path, &file).unwrap(), by_path);
+        assert!(by_path.unwrap().starts_with("doc_"));
+        fs::remove_file(&file).ok();
+    }
+
+    #[tokio::test]
+    async fn test_publishes_encrypted_payloads() {
+        let relay = MockRelay::run().await.unwrap();
+        let url = relay.url().await.to_string();
+        let keys = Keys::generate();
+        let codec = payload_codec(Some(SECRETBOX_KEY.to_string())).unwrap();
+
+        let mut chunk_ref = ChunkRef::new(
+            "doc_enc_chunk_0",
+            "doc_enc",
+            0,
+            ChunkOffsets { start: 0, end: 18 },
+            "sha256:chunk",
+        );
+        chunk_ref.text = Some("secret chunk text".to_string());
+        let manifest = DocManifest::new(
+            "doc_enc",
+            "Encrypted doc",
+            "en",
+            "text/plain",
+            "file_ingest",
+            "sha256:doc",
+            vec![],
+        );
+        let config = PublisherConfig {
+            relays: vec![url.clone()],
+            secret_key: keys.secret_key().to_secret_hex(),
+            min_acks: 1,
+            timeout: Duration::from_secs(5),
+            kinds: NostrKinds::default(),
+            codec: codec.clone(),
+        };
+        publish_document(config, &manifest, &[chunk_ref])
+            .await
+            .unwrap();
+
+        let client = Client::default();
+        client.add_relay(&url).await.unwrap();
+        client.connect().await;
+        let events = client
+            .fetch_events(
+                Filter::new().author(keys.public_key()),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+
+        for event in events.iter() {
+            let enc = event.tags.iter().find_map(|tag| match tag.as_slice() {
+                [name, value, ..] if name == "enc" => Some(value.as_str()),
+                _ => None,
+            });
+            assert_eq!(enc, Some("secretbox-v1"));
+            assert!(!event.content.contains("doc_enc"));
+            assert!(!event.content.contains("secret chunk text"));
+
+            let ciphertext = base64::engine::general_purpose::STANDARD
+                .decode(&event.content)
+                .unwrap();
+            let payload: serde_json::Value =
+                serde_json::from_slice(&codec.decode(&ciphertext).unwrap()).unwrap();
+            assert_eq!(payload["doc_id"], "doc_enc");
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Extract the PDF and docx fixtures and chunk the result.
+//!
+//! Run with `cargo test -p ingester --features pdf,docx`.
+
+#![cfg(any(feature = "pdf", feature = "docx"))]
+
+use std::path::Path;
+
+use ingester::{chunk_document, DocumentOptions, Extractors};
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn small_chunks(title: &str, mime: &str) -> DocumentOptions {
+    DocumentOptions {
+        mime: mime.to_string(),
+        chunk_size: 16,
+        chunk_overlap: 4,
+        inline_text: true,
+        ..DocumentOptions::new(title)
+    }
+}
+
+#[cfg(feature = "pdf")]
+#[test]
+fn test_pdf_fixture_is_extracted_and_chunked() {
+    let extracted = Extractors::default()
+        .extract_file(&fixture("tiny.pdf"))
+        .unwrap();
+    assert_eq!(extracted.mime, "application/pdf");
+    assert!(extracted
+        .text
+        .contains("Aman tiny PDF fixture about community organizing."));
+
+    let document = chunk_document(&extracted.text, &small_chunks("tiny.pdf", extracted.mime));
+    assert!(document.chunk_refs.len() > 1);
+    assert_eq!(document.manifest.mime, "application/pdf");
+    assert!(document
+        .chunk_refs
+        .iter()
+        .any(|chunk| chunk.text.as_deref().unwrap_or_default().contains("PDF")));
+}
+
+#[cfg(feature = "docx")]
+#[test]
+fn test_docx_fixture_is_extracted_and_chunked() {
+    let extracted = Extractors::default()
+        .extract_file(&fixture("tiny.docx"))
+        .unwrap();
+    assert_eq!(
+        extracted.text,
+        "Aman tiny docx fixture.\nSecond paragraph & more text.\n"
+    );
+
+    let document = chunk_document(&extracted.text, &small_chunks("tiny.docx", extracted.mime));
+    assert_eq!(document.chunks.len(), document.chunk_refs.len());
+    assert!(document.chunk_refs.len() > 1);
+    assert_eq!(document.chunks[0].2, "Aman tiny docx f");
+}
+
+#[cfg(feature = "docx")]
+#[test]
+fn test_corrupt_docx_reports_extraction_error() {
+    let err = Extractors::default()
+        .extract("docx", b"not a zip archive")
+        .unwrap_err();
+    assert!(err.to_string().starts_with("failed to extract text from docx"));
+}
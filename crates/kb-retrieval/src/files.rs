@@ -0,0 +1,81 @@
+//! Load knowledge base documents from the filesystem.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::Document;
+
+/// Files larger than this are skipped.
+pub const MAX_FILE_BYTES: u64 = 512 * 1024;
+/// Characters kept per document.
+pub const MAX_DOCUMENT_CHARS: usize = 8000;
+
+/// Whether a path has a supported text extension (`txt`, `md`, `markdown`, `jsonl`).
+pub fn is_supported_path(path: &Path) -> bool {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => matches!(ext.to_ascii_lowercase().as_str(), "txt" | "md" | "markdown" | "jsonl"),
+        None => false,
+    }
+}
+
+/// Read a single document, truncated to [`MAX_DOCUMENT_CHARS`].
+///
+/// Returns `Ok(None)` for empty, oversized, or blank files.
+pub fn read_document(path: &Path, source: impl Into<String>) -> io::Result<Option<Document>> {
+    let metadata = fs::metadata(path)?;
+    if metadata.len() == 0 || metadata.len() > MAX_FILE_BYTES {
+        return Ok(None);
+    }
+
+    let text = fs::read_to_string(path)?;
+    let text: String = text.chars().take(MAX_DOCUMENT_CHARS).collect();
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(Document {
+        source: source.into(),
+        text,
+    }))
+}
+
+/// Load documents from a file or a directory tree.
+///
+/// Directories are walked recursively; unsupported or unreadable files are
+/// skipped. Each document's source is its path.
+pub fn load_path(path: &Path) -> io::Result<Vec<Document>> {
+    if path.is_file() {
+        return Ok(read_document(path, path.display().to_string())?
+            .into_iter()
+            .collect());
+    }
+
+    if !path.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Knowledge base path not found: {}", path.display()),
+        ));
+    }
+
+    let mut documents = Vec::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries: Vec<_> = fs::read_dir(&dir)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .collect();
+        entries.sort();
+        for path in entries {
+            if path.is_dir() {
+                pending.push(path);
+            } else if is_supported_path(&path) {
+                if let Ok(Some(document)) = read_document(&path, path.display().to_string()) {
+                    documents.push(document);
+                }
+            }
+        }
+    }
+
+    Ok(documents)
+}
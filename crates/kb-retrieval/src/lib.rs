@@ -0,0 +1,533 @@
+//! Shared keyword retrieval for Aman knowledge bases.
+//!
+//! The API gateway, the orchestrator, and the Cloudflare worker all ground
+//! responses in a small document corpus using keyword matching. This crate
+//! holds the pieces they share so ranking and snippets do not drift apart:
+//!
+//! - [`tokenize`] / [`is_stopword`] - Query keyword extraction
+//! - [`score`] - Keyword occurrence scoring
+//! - [`build_snippet`] / [`build_snippet_with`] - Snippet window around the
+//!   densest cluster of keyword hits, with optional [`Highlight`] markers
+//! - [`Retriever`] - Storage-agnostic search interface
+//! - [`MemoryIndex`] - In-memory [`Retriever`] over loaded documents
+//! - [`files`] - Loading documents from disk
+//! - [`sanitize`] - Prompt-injection defenses for snippets before injection
+//!
+//! Everything outside [`files`] is pure and builds for `wasm32`.
+
+pub mod files;
+pub mod sanitize;
+
+pub use sanitize::{contains_injection, neutralize_injection, UNTRUSTED_SOURCES_NOTICE};
+
+/// Maximum keywords extracted from a query.
+pub const MAX_QUERY_TOKENS: usize = 8;
+/// Minimum characters for a keyword to be kept.
+pub const MIN_TOKEN_CHARS: usize = 3;
+/// Characters of leading context kept before the first keyword hit.
+pub const SNIPPET_LEAD_CHARS: usize = 160;
+/// Total characters in a snippet.
+pub const SNIPPET_CHARS: usize = 400;
+
+/// A document available for retrieval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    /// Where the document came from (file path or `doc_id:chunk_id`).
+    pub source: String,
+    /// Document text.
+    pub text: String,
+}
+
+/// A ranked retrieval result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hit {
+    /// Source of the matching document.
+    pub source: String,
+    /// Snippet around the densest cluster of keyword hits.
+    pub snippet: String,
+    /// Keyword occurrence score (higher is better).
+    pub score: usize,
+}
+
+/// Storage-agnostic keyword search.
+pub trait Retriever {
+    /// Return up to `limit` hits for `query`, best first.
+    fn search(&self, query: &str, limit: usize) -> Vec<Hit>;
+}
+
+/// Whether a lowercase token is too common to be a useful keyword.
+pub fn is_stopword(token: &str) -> bool {
+    matches!(
+        token,
+        "a" | "an"
+            | "and"
+            | "are"
+            | "as"
+            | "at"
+            | "be"
+            | "been"
+            | "but"
+            | "by"
+            | "can"
+            | "could"
+            | "did"
+            | "do"
+            | "does"
+            | "for"
+            | "from"
+            | "had"
+            | "has"
+            | "have"
+            | "how"
+            | "if"
+            | "in"
+            | "is"
+            | "it"
+            | "its"
+            | "me"
+            | "of"
+            | "on"
+            | "or"
+            | "our"
+            | "please"
+            | "should"
+            | "tell"
+            | "that"
+            | "the"
+            | "their"
+            | "them"
+            | "then"
+            | "there"
+            | "these"
+            | "they"
+            | "this"
+            | "to"
+            | "was"
+            | "we"
+            | "were"
+            | "what"
+            | "when"
+            | "where"
+            | "which"
+            | "who"
+            | "why"
+            | "with"
+            | "would"
+            | "you"
+            | "your"
+            | "about"
+    )
+}
+
+/// Extract lowercase keywords from a query.
+///
+/// Splits on any non-alphanumeric character (Unicode-aware), drops tokens
+/// shorter than [`MIN_TOKEN_CHARS`] and stopwords, and keeps at most
+/// [`MAX_QUERY_TOKENS`].
+pub fn tokenize(query: &str) -> Vec<String> {
+    query
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| token.chars().count() >= MIN_TOKEN_CHARS && !is_stopword(token))
+        .take(MAX_QUERY_TOKENS)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Count keyword occurrences in already-lowercased text.
+pub fn score(text_lower: &str, tokens: &[String]) -> usize {
+    tokens
+        .iter()
+        .map(|token| text_lower.matches(token.as_str()).count())
+        .sum()
+}
+
+/// How matched keywords are marked in snippets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// `**match**`
+    Bold,
+    /// `[[match]]`
+    Brackets,
+}
+
+impl Highlight {
+    /// Parse `bold` or `brackets`; anything else (including `off`) disables
+    /// highlighting.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "bold" | "**" => Some(Self::Bold),
+            "brackets" | "[[" => Some(Self::Brackets),
+            _ => None,
+        }
+    }
+
+    /// Opening and closing markers.
+    pub fn markers(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Bold => ("**", "**"),
+            Self::Brackets => ("[[", "]]"),
+        }
+    }
+}
+
+/// Snippet window and highlighting options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnippetOptions {
+    /// Characters in a snippet, not counting highlight markers.
+    pub max_chars: usize,
+    /// Characters of context kept before the first hit in the window.
+    pub lead_chars: usize,
+    /// Marker style for matched keywords; `None` leaves text unmarked.
+    pub highlight: Option<Highlight>,
+}
+
+impl Default for SnippetOptions {
+    fn default() -> Self {
+        Self {
+            max_chars: SNIPPET_CHARS,
+            lead_chars: SNIPPET_LEAD_CHARS,
+            highlight: None,
+        }
+    }
+}
+
+/// Build a snippet with the default [`SnippetOptions`].
+pub fn build_snippet(text: &str, tokens: &[String]) -> String {
+    build_snippet_with(text, tokens, &SnippetOptions::default())
+}
+
+/// Build a snippet around the densest cluster of keyword hits.
+///
+/// The window starts `lead_chars` before the first hit of the cluster with
+/// the most hits (the earliest on ties). Falls back to the start of the text
+/// when no keyword matches.
+pub fn build_snippet_with(text: &str, tokens: &[String], options: &SnippetOptions) -> String {
+    let positions = match_positions(&text.to_lowercase(), tokens);
+    let span = options.max_chars.saturating_sub(options.lead_chars).max(1);
+    let snippet = match densest_cluster(&positions, span) {
+        Some(hit) => text
+            .chars()
+            .skip(hit.saturating_sub(options.lead_chars))
+            .take(options.max_chars)
+            .collect::<String>()
+            .trim()
+            .to_string(),
+        None => text.chars().take(options.max_chars).collect(),
+    };
+
+    match options.highlight {
+        Some(style) => highlight_matches(&snippet, tokens, style),
+        None => snippet,
+    }
+}
+
+/// Character positions of every keyword occurrence in lowercased text, sorted.
+fn match_positions(text_lower: &str, tokens: &[String]) -> Vec<usize> {
+    let mut positions: Vec<usize> = tokens
+        .iter()
+        .filter(|token| !token.is_empty())
+        .flat_map(|token| text_lower.match_indices(token.as_str()))
+        // Lowercasing can change byte lengths, so position by characters.
+        .map(|(idx, _)| text_lower[..idx].chars().count())
+        .collect();
+    positions.sort_unstable();
+    positions.dedup();
+    positions
+}
+
+/// First position of the `span`-character run holding the most positions.
+fn densest_cluster(positions: &[usize], span: usize) -> Option<usize> {
+    let mut best: Option<(usize, usize)> = None;
+    let mut end = 0;
+    for (start, &pos) in positions.iter().enumerate() {
+        while end < positions.len() && positions[end] < pos + span {
+            end += 1;
+        }
+        let count = end - start;
+        if best.is_none_or(|(best_count, _)| count > best_count) {
+            best = Some((count, pos));
+        }
+    }
+    best.map(|(_, pos)| pos)
+}
+
+/// Wrap every word containing a keyword in `style` markers.
+///
+/// Words are runs of alphanumeric characters compared in lowercase.
+pub fn highlight_matches(text: &str, tokens: &[String], style: Highlight) -> String {
+    highlight_matches_by(text, tokens, style, str::to_lowercase)
+}
+
+/// Like [`highlight_matches`], comparing words after `normalize`.
+///
+/// Lets callers that fold keywords (e.g. Arabic article stripping) mark the
+/// original words their normalized keywords came from.
+pub fn highlight_matches_by(
+    text: &str,
+    tokens: &[String],
+    style: Highlight,
+    normalize: impl Fn(&str) -> String,
+) -> String {
+    let (open, close) = style.markers();
+    let mut out = String::with_capacity(text.len());
+    let mut word = String::new();
+    let flush = |word: &mut String, out: &mut String| {
+        if word.is_empty() {
+            return;
+        }
+        let normalized = normalize(word);
+        if tokens
+            .iter()
+            .any(|token| !token.is_empty() && normalized.contains(token.as_str()))
+        {
+            out.push_str(open);
+            out.push_str(word);
+            out.push_str(close);
+        } else {
+            out.push_str(word);
+        }
+        word.clear();
+    };
+
+    for ch in text.chars() {
+        if ch.is_alphanumeric() {
+            word.push(ch);
+        } else {
+            flush(&mut word, &mut out);
+            out.push(ch);
+        }
+    }
+    flush(&mut word, &mut out);
+    out
+}
+
+/// In-memory keyword index over loaded documents.
+#[derive(Debug, Default)]
+pub struct MemoryIndex {
+    entries: Vec<IndexedDocument>,
+    snippet: SnippetOptions,
+}
+
+#[derive(Debug)]
+struct IndexedDocument {
+    document: Document,
+    text_lower: String,
+}
+
+impl MemoryIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the snippet window and highlighting used for hits.
+    pub fn with_snippet_options(mut self, options: SnippetOptions) -> Self {
+        self.snippet = options;
+        self
+    }
+
+    /// Add a document. Blank documents are ignored.
+    pub fn add(&mut self, document: Document) {
+        if document.text.trim().is_empty() {
+            return;
+        }
+        self.entries.push(IndexedDocument {
+            text_lower: document.text.to_lowercase(),
+            document,
+        });
+    }
+
+    /// Number of indexed documents.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the index has no documents.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl FromIterator<Document> for MemoryIndex {
+    fn from_iter<I: IntoIterator<Item = Document>>(iter: I) -> Self {
+        let mut index = Self::new();
+        for document in iter {
+            index.add(document);
+        }
+        index
+    }
+}
+
+impl Retriever for MemoryIndex {
+    fn search(&self, query: &str, limit: usize) -> Vec<Hit> {
+        let tokens = tokenize(query);
+        if tokens.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let mut scored: Vec<(usize, &IndexedDocument)> = self
+            .entries
+            .iter()
+            .map(|entry| (score(&entry.text_lower, &tokens), entry))
+            .filter(|(score, _)| *score > 0)
+            .collect();
+        // Stable sort keeps earlier documents first on ties.
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(score, entry)| Hit {
+                source: entry.document.source.clone(),
+                snippet: build_snippet_with(&entry.document.text, &tokens, &self.snippet),
+                score,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(source: &str, text: &str) -> Document {
+        Document {
+            source: source.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_tokenize_ascii() {
+        assert_eq!(
+            tokenize("Lightning invoices & BOLT12 offers"),
+            vec!["lightning", "invoices", "bolt12", "offers"]
+        );
+        assert_eq!(tokenize("How do I use the VPN?"), vec!["use", "vpn"]);
+        assert!(tokenize("a an to").is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_unicode() {
+        assert_eq!(tokenize("Привет, безопасность!"), vec!["привет", "безопасность"]);
+        assert_eq!(tokenize("Sécurité numérique"), vec!["sécurité", "numérique"]);
+    }
+
+    #[test]
+    fn test_tokenize_limit() {
+        let query = "alpha bravo charlie delta echo foxtrot golf hotel india juliet";
+        assert_eq!(tokenize(query).len(), MAX_QUERY_TOKENS);
+    }
+
+    #[test]
+    fn test_score_counts_occurrences() {
+        let tokens = tokenize("vpn kill switch");
+        assert_eq!(score("use a vpn. vpn kill switch on", &tokens), 4);
+        assert_eq!(score("nothing here", &tokens), 0);
+    }
+
+    #[test]
+    fn test_build_snippet_window() {
+        let text = format!("{}needle{}", "x".repeat(300), "y".repeat(600));
+        let snippet = build_snippet(&text, &["needle".to_string()]);
+        assert_eq!(snippet.chars().count(), SNIPPET_CHARS);
+        assert!(snippet.starts_with(&"x".repeat(SNIPPET_LEAD_CHARS)));
+        assert!(snippet[SNIPPET_LEAD_CHARS..].starts_with("needle"));
+    }
+
+    #[test]
+    fn test_build_snippet_no_hit_and_unicode() {
+        let snippet = build_snippet("  short text  ", &["missing".to_string()]);
+        assert_eq!(snippet, "  short text  ");
+
+        let text = format!("{}Безопасность важна", "ß".repeat(200));
+        let snippet = build_snippet(&text, &tokenize("безопасность"));
+        assert!(snippet.starts_with(&"ß".repeat(SNIPPET_LEAD_CHARS)));
+        assert!(snippet.ends_with("Безопасность важна"));
+    }
+
+    #[test]
+    fn test_snippet_centers_on_densest_cluster() {
+        // One early hit, then a tight cluster of three much later
+        let text = format!(
+            "vpn {} vpn kill switch keeps vpn traffic safe {}",
+            "x".repeat(600),
+            "y".repeat(600)
+        );
+        let tokens = tokenize("vpn kill switch");
+        let snippet = build_snippet(&text, &tokens);
+
+        assert_eq!(snippet.chars().count(), SNIPPET_CHARS);
+        assert!(snippet.starts_with(&format!("{} vpn", "x".repeat(SNIPPET_LEAD_CHARS - 1))));
+        assert!(snippet[SNIPPET_LEAD_CHARS..].starts_with("vpn kill switch keeps vpn"));
+    }
+
+    #[test]
+    fn test_snippet_highlight_markers() {
+        let text = "Use a VPN. A kill-switch stops VPNs leaking; kill it last.";
+        let tokens = tokenize("vpn kill switch");
+
+        let options = SnippetOptions {
+            highlight: Some(Highlight::Bold),
+            ..SnippetOptions::default()
+        };
+        assert_eq!(
+            build_snippet_with(text, &tokens, &options),
+            "Use a **VPN**. A **kill**-**switch** stops **VPNs** leaking; **kill** it last."
+        );
+
+        let options = SnippetOptions {
+            max_chars: 24,
+            lead_chars: 2,
+            highlight: Some(Highlight::Brackets),
+        };
+        assert_eq!(
+            build_snippet_with(text, &tokens, &options),
+            "a [[VPN]]. A [[kill]]-[[switch]] sto"
+        );
+
+        // Highlighting is off by default
+        assert_eq!(build_snippet(text, &tokens), text);
+    }
+
+    #[test]
+    fn test_highlight_parse_and_normalizer() {
+        assert_eq!(Highlight::parse(" Bold "), Some(Highlight::Bold));
+        assert_eq!(Highlight::parse("brackets"), Some(Highlight::Brackets));
+        assert_eq!(Highlight::parse("off"), None);
+
+        // Words are compared after the caller's normalization
+        let fold_accents = |word: &str| word.to_lowercase().replace('é', "e");
+        let tokens = ["cafe".to_string()];
+        let marked = highlight_matches_by("Café wifi, cafés", &tokens, Highlight::Bold, fold_accents);
+        assert_eq!(marked, "**Café** wifi, **cafés**");
+    }
+
+    #[test]
+    fn test_memory_index_ranking() {
+        let index: MemoryIndex = [
+            doc("a.md", "VPN basics."),
+            doc("b.md", "VPN kill switch keeps your VPN safe."),
+            doc("c.md", "   "),
+            doc("d.md", "Unrelated."),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(index.len(), 3);
+        let hits = index.search("vpn", 5);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].source, "b.md");
+        assert_eq!(hits[0].score, 2);
+        assert_eq!(hits[1].source, "a.md");
+        assert!(index.search("the", 5).is_empty());
+        assert_eq!(index.search("vpn", 1).len(), 1);
+
+        let index = index.with_snippet_options(SnippetOptions {
+            highlight: Some(Highlight::Bold),
+            ..SnippetOptions::default()
+        });
+        assert_eq!(index.search("vpn", 1)[0].snippet, "**VPN** kill switch keeps your **VPN** safe.");
+    }
+}
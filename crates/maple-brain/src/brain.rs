@@ -36,6 +36,11 @@ pub enum StatusUpdate {
         /// Name of the tool that completed.
         tool_name: String,
     },
+    /// The tool-calling cap was reached; answering with what is available.
+    ToolLimitReached {
+        /// The configured maximum number of tool iterations.
+        max_iterations: usize,
+    },
 }
 
 /// Type alias for the async status callback.
@@ -43,6 +48,25 @@ pub type StatusCallback = Box<
     dyn Fn(StatusUpdate) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync,
 >;
 
+/// Instruction added when the tool-calling cap is reached.
+const TOOL_LIMIT_PROMPT: &str = "Tool call limit reached. Do not request any more tools. \
+Answer now using only the information you already have, and briefly say that you \
+could not fully resolve the request.";
+
+/// Reply used when the model returns nothing after the tool-calling cap.
+const TOOL_LIMIT_FALLBACK: &str =
+    "I couldn't fully resolve this request with the tools available. Please try rephrasing or narrowing it down.";
+
+/// Source of chat completions (the OpenSecret API in production).
+#[async_trait]
+trait ChatCompleter: Send + Sync {
+    /// Complete a chat request, returning the text and any requested tool calls.
+    async fn complete(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> Result<(String, Option<Vec<ToolCall>>), BrainError>;
+}
+
 /// A brain implementation that uses OpenSecret SDK for AI processing.
 ///
 /// MapleBrain maintains per-sender conversation history and communicates
@@ -170,6 +194,11 @@ impl MapleBrain {
         self.history.clear(sender).await;
     }
 
+    /// Forget the most recent exchange for a specific sender.
+    pub async fn forget_last_exchange(&self, sender: &str) -> bool {
+        self.history.remove_last_exchange(sender).await
+    }
+
     /// Clear all conversation histories.
     pub async fn clear_all_history(&self) {
         self.history.clear_all().await;
@@ -221,20 +250,23 @@ impl MapleBrain {
         Some(summary)
     }
 
+    /// Per-request system context: the memory prompt plus the user's
+    /// language hint, if either is present.
     fn memory_prompt_for_message(&self, message: &InboundMessage) -> Option<String> {
-        if self.config.memory_prompt_max_chars == 0 {
-            return None;
-        }
-        let prompt = message
-            .routing
-            .as_ref()
-            .and_then(|routing| routing.memory_prompt.as_deref())?;
-        let trimmed = truncate_text(prompt, self.config.memory_prompt_max_chars);
-        let trimmed = trimmed.trim().to_string();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed)
+        let routing = message.routing.as_ref()?;
+        let memory = routing
+            .memory_prompt
+            .as_deref()
+            .filter(|_| self.config.memory_prompt_max_chars > 0)
+            .map(|prompt| {
+                truncate_text(prompt, self.config.memory_prompt_max_chars)
+                    .trim()
+                    .to_string()
+            })
+            .filter(|prompt| !prompt.is_empty());
+        match (memory, routing.language_hint()) {
+            (Some(memory), Some(hint)) => Some(format!("{}\n\n{}", memory, hint)),
+            (memory, hint) => memory.or(hint),
         }
     }
 
@@ -279,12 +311,13 @@ impl MapleBrain {
             });
         }
 
-        // Add conversation history
+        // Add conversation history (tool results become system context)
         let history = self.history.get(sender).await;
         for msg in history {
+            let (role, content) = msg.into_prompt_parts();
             messages.push(ChatMessage {
-                role: msg.role,
-                content: serde_json::Value::String(msg.content),
+                role,
+                content: serde_json::Value::String(content),
                 tool_calls: None,
             });
         }
@@ -512,7 +545,7 @@ impl MapleBrain {
         }
 
         // Choose model and build messages based on whether we have images
-        let (_, mut messages) = if has_images {
+        let (_, messages) = if has_images {
             // Use vision model for messages with images
             info!(
                 "Using vision model for message with {} image(s)",
@@ -557,75 +590,33 @@ impl MapleBrain {
         };
         let model = select_model_for_message(&self.config, &message);
 
-        // Get tools if we have an executor (not for vision)
-        let tools = if has_images { None } else { self.get_tools() };
+        // Get tools if we have an executor (not for vision, not when disabled)
+        let tools = if has_images || self.config.max_tool_iterations == 0 {
+            None
+        } else {
+            self.get_tools()
+        };
 
-        // Initial request
-        let mut request = ChatCompletionRequest {
-            model: model.clone(),
-            messages: messages.clone(),
+        let request = ChatCompletionRequest {
+            model,
+            messages,
             temperature: self.config.temperature,
             max_tokens: self.config.max_tokens.map(|t| t as i32),
             stream: Some(true),
             stream_options: None,
-            tools: tools.clone(),
+            tools,
             tool_choice: None,
         };
 
-        let mut response_text = String::new();
-        let mut rounds = 0;
-
-        // Tool call loop
-        loop {
-            rounds += 1;
-            if rounds > self.config.max_tool_rounds {
-                warn!(
-                    "Exceeded maximum tool call rounds ({})",
-                    self.config.max_tool_rounds
-                );
-                break;
-            }
-
-            let (text, tool_calls) = self.complete_chat(request.clone()).await?;
-
-            match tool_calls {
-                Some(calls) if !calls.is_empty() => {
-                    info!("Model requested {} tool call(s)", calls.len());
-
-                    // Add assistant message with tool calls to conversation
-                    messages.push(ChatMessage {
-                        role: "assistant".to_string(),
-                        content: serde_json::Value::Null,
-                        tool_calls: Some(calls.clone()),
-                    });
-
-                    // Execute tools with status callback
-                    let results = self
-                        .execute_tool_calls_with_status(&message, &calls, status_callback.as_ref())
-                        .await;
-                    messages.extend(results);
-
-                    // Update request for next round
-                    request = ChatCompletionRequest {
-                        model: model.clone(),
-                        messages: messages.clone(),
-                        temperature: self.config.temperature,
-                        max_tokens: self.config.max_tokens.map(|t| t as i32),
-                        stream: Some(true),
-                        stream_options: None,
-                        tools: tools.clone(),
-                        tool_choice: None,
-                    };
-
-                    // Continue loop to get model's response with tool results
-                }
-                _ => {
-                    // No tool calls - this is the final response
-                    response_text = text;
-                    break;
-                }
-            }
-        }
+        let (mut response_text, iterations) = run_tool_loop(
+            self,
+            self.tool_executor.as_deref(),
+            self.config.max_tool_iterations,
+            &message,
+            request,
+            status_callback.as_ref(),
+        )
+        .await?;
 
         if response_text.is_empty() {
             warn!("No response content from OpenSecret API");
@@ -633,111 +624,195 @@ impl MapleBrain {
         }
 
         info!(
-            "Generated response for {}: {} chars (tool rounds: {})",
+            "Generated response for {}: {} chars (tool iterations: {})",
             sender,
             response_text.len(),
-            rounds
+            iterations
         );
 
-        // Add to conversation history (for text messages only)
+        // Add to conversation history (for text messages only), without the
+        // gathered context but with the tool results it came from
         if !has_images {
             self.history
-                .add_exchange(&history_key, user_text, &response_text)
+                .add_exchange_with_tools(
+                    &history_key,
+                    message.history_text(),
+                    message.tool_results(),
+                    &response_text,
+                )
                 .await;
         }
 
         // Return the outbound message
         Ok(OutboundMessage::reply_to(&message, response_text))
     }
+}
 
-    /// Execute tool calls with optional status callback.
-    async fn execute_tool_calls_with_status(
+#[async_trait]
+impl ChatCompleter for MapleBrain {
+    async fn complete(
         &self,
-        message: &InboundMessage,
-        tool_calls: &[ToolCall],
-        status_callback: Option<&StatusCallback>,
-    ) -> Vec<ChatMessage> {
-        let executor = match &self.tool_executor {
-            Some(e) => e,
-            None => return vec![],
-        };
+        request: ChatCompletionRequest,
+    ) -> Result<(String, Option<Vec<ToolCall>>), BrainError> {
+        self.complete_chat(request).await
+    }
+}
 
-        let mut results = Vec::new();
-        let metadata = ToolRequestMeta {
-            sender: Some(message.sender.clone()),
-            group_id: message.group_id.clone(),
-            is_group: Some(message.group_id.is_some()),
+/// Run the tool-calling loop until the model answers without tool calls.
+///
+/// At most `max_iterations` rounds of tool calls are executed. If the model
+/// asks for more, it is told to stop and answer with what it has, and a
+/// [`StatusUpdate::ToolLimitReached`] is emitted. Returns the response text
+/// and the number of tool iterations run.
+async fn run_tool_loop(
+    completer: &dyn ChatCompleter,
+    executor: Option<&dyn ToolExecutor>,
+    max_iterations: usize,
+    message: &InboundMessage,
+    mut request: ChatCompletionRequest,
+    status_callback: Option<&StatusCallback>,
+) -> Result<(String, usize), BrainError> {
+    let mut iterations = 0;
+
+    loop {
+        let (text, tool_calls) = completer.complete(request.clone()).await?;
+
+        let calls = match tool_calls {
+            Some(calls) if !calls.is_empty() => calls,
+            // No tool calls - this is the final response
+            _ => return Ok((text, iterations)),
         };
 
-        for call in tool_calls {
-            let request = match ToolRequest::from_call_with_metadata(
-                call.id.clone(),
-                call.function.name.clone(),
-                &call.function.arguments,
-                metadata.clone(),
-            ) {
-                Ok(r) => r,
-                Err(e) => {
-                    warn!("Failed to parse tool arguments: {}", e);
-                    results.push(ChatMessage {
-                        role: "tool".to_string(),
-                        content: serde_json::Value::String(format!(
-                            "Error: Invalid arguments - {}",
-                            e
-                        )),
-                        tool_calls: None,
-                    });
-                    continue;
-                }
-            };
-
-            // Notify via callback that tool execution is starting
+        if iterations >= max_iterations {
+            warn!(
+                "Reached maximum tool iterations ({}), requesting best-effort answer",
+                max_iterations
+            );
             if let Some(callback) = status_callback {
-                let description = match request.name.as_str() {
-                    "realtime_search" => "Searching for current information...".to_string(),
-                    _ => format!("Executing {}...", request.name),
-                };
-                callback(StatusUpdate::ToolExecuting {
-                    tool_name: request.name.clone(),
-                    description,
-                })
-                .await;
+                callback(StatusUpdate::ToolLimitReached { max_iterations }).await;
             }
 
-            info!(
-                "Executing tool '{}' with sanitized query",
-                request.name
-            );
-            debug!("Tool request: {:?}", request);
+            request.tools = None;
+            request.tool_choice = None;
+            request.messages.push(ChatMessage {
+                role: "system".to_string(),
+                content: serde_json::Value::String(TOOL_LIMIT_PROMPT.to_string()),
+                tool_calls: None,
+            });
+            let (text, _) = completer.complete(request).await?;
+            let text = if text.trim().is_empty() {
+                TOOL_LIMIT_FALLBACK.to_string()
+            } else {
+                text
+            };
+            return Ok((text, iterations));
+        }
 
-            let result = executor.execute(request.clone()).await;
+        iterations += 1;
+        info!("Model requested {} tool call(s)", calls.len());
 
-            info!(
-                "Tool '{}' completed (success: {})",
-                call.function.name, result.success
-            );
+        // Add assistant message with tool calls to conversation
+        request.messages.push(ChatMessage {
+            role: "assistant".to_string(),
+            content: serde_json::Value::Null,
+            tool_calls: Some(calls.clone()),
+        });
 
-            // Notify via callback that tool execution completed
-            if let Some(callback) = status_callback {
-                callback(StatusUpdate::ToolComplete {
-                    tool_name: request.name.clone(),
-                })
-                .await;
+        // Execute tools with status callback, then continue to get the
+        // model's response with tool results
+        let results =
+            execute_tool_calls_with_status(executor, message, &calls, status_callback).await;
+        request.messages.extend(results);
+    }
+}
+
+/// Execute tool calls with optional status callback.
+async fn execute_tool_calls_with_status(
+    executor: Option<&dyn ToolExecutor>,
+    message: &InboundMessage,
+    tool_calls: &[ToolCall],
+    status_callback: Option<&StatusCallback>,
+) -> Vec<ChatMessage> {
+    let executor = match executor {
+        Some(e) => e,
+        None => return vec![],
+    };
+
+    let mut results = Vec::new();
+    let metadata = ToolRequestMeta {
+        sender: Some(message.sender.clone()),
+        group_id: message.group_id.clone(),
+        is_group: Some(message.group_id.is_some()),
+    };
+
+    for call in tool_calls {
+        let request = match ToolRequest::from_call_with_metadata(
+            call.id.clone(),
+            call.function.name.clone(),
+            &call.function.arguments,
+            metadata.clone(),
+        ) {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Failed to parse tool arguments: {}", e);
+                results.push(ChatMessage {
+                    role: "tool".to_string(),
+                    content: serde_json::Value::String(format!(
+                        "Error: Invalid arguments - {}",
+                        e
+                    )),
+                    tool_calls: None,
+                });
+                continue;
             }
+        };
 
-            // Add tool result as a message
-            results.push(ChatMessage {
-                role: "tool".to_string(),
-                content: serde_json::json!({
-                    "tool_call_id": result.tool_call_id,
-                    "result": result.content
-                }),
-                tool_calls: None,
-            });
+        // Notify via callback that tool execution is starting
+        if let Some(callback) = status_callback {
+            let description = match request.name.as_str() {
+                "realtime_search" => "Searching for current information...".to_string(),
+                _ => format!("Executing {}...", request.name),
+            };
+            callback(StatusUpdate::ToolExecuting {
+                tool_name: request.name.clone(),
+                description,
+            })
+            .await;
+        }
+
+        info!(
+            "Executing tool '{}' with sanitized query",
+            request.name
+        );
+        debug!("Tool request: {:?}", request);
+
+        let result = executor.execute(request.clone()).await;
+
+        info!(
+            "Tool '{}' completed (success: {})",
+            call.function.name, result.success
+        );
+
+        // Notify via callback that tool execution completed
+        if let Some(callback) = status_callback {
+            callback(StatusUpdate::ToolComplete {
+                tool_name: request.name.clone(),
+            })
+            .await;
         }
 
-        results
+        // Add tool result as a message
+        results.push(ChatMessage {
+            role: "tool".to_string(),
+            content: serde_json::json!({
+                "tool_call_id": result.tool_call_id,
+                "result": result.content
+            }),
+            tool_calls: None,
+        });
     }
+
+    results
 }
 
 fn select_model_for_message(config: &MapleBrainConfig, message: &InboundMessage) -> String {
@@ -802,7 +877,134 @@ impl Brain for MapleBrain {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use brain_core::{InboundAttachment, RoutingInfo};
+    use brain_core::{InboundAttachment, RoutingInfo, ToolResult};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Completer that requests a search on every call while tools are offered.
+    struct ToolHungryCompleter {
+        calls: AtomicUsize,
+        final_answer: &'static str,
+    }
+
+    #[async_trait]
+    impl ChatCompleter for ToolHungryCompleter {
+        async fn complete(
+            &self,
+            request: ChatCompletionRequest,
+        ) -> Result<(String, Option<Vec<ToolCall>>), BrainError> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            if request.tools.is_none() {
+                return Ok((self.final_answer.to_string(), None));
+            }
+            let call = ToolCall {
+                id: format!("call-{}", n),
+                tool_type: "function".to_string(),
+                function: opensecret::types::FunctionCall {
+                    name: "realtime_search".to_string(),
+                    arguments: r#"{"query": "more"}"#.to_string(),
+                },
+                index: Some(0),
+            };
+            Ok((String::new(), Some(vec![call])))
+        }
+    }
+
+    /// Executor whose results always leave the model wanting more.
+    struct CountingExecutor {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ToolExecutor for CountingExecutor {
+        async fn execute(&self, request: ToolRequest) -> ToolResult {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            ToolResult::success(request.id, "partial results, search again")
+        }
+
+        fn supported_tools(&self) -> Vec<&str> {
+            vec!["realtime_search"]
+        }
+    }
+
+    fn tool_request() -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: "test-model".to_string(),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: serde_json::Value::String("find everything".to_string()),
+                tool_calls: None,
+            }],
+            temperature: None,
+            max_tokens: None,
+            stream: Some(true),
+            stream_options: None,
+            tools: Some(vec![ToolDefinition::realtime_search().to_opensecret_tool()]),
+            tool_choice: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_loop_stops_at_cap() {
+        let completer = ToolHungryCompleter {
+            calls: AtomicUsize::new(0),
+            final_answer: "Best effort: I could not fully resolve this.",
+        };
+        let executor = CountingExecutor {
+            calls: AtomicUsize::new(0),
+        };
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let recorded = updates.clone();
+        let callback: StatusCallback = Box::new(move |update| {
+            recorded.lock().unwrap().push(update);
+            Box::pin(async {})
+        });
+        let message = InboundMessage::direct("+123", "find everything", 0);
+
+        let (text, iterations) = run_tool_loop(
+            &completer,
+            Some(&executor),
+            3,
+            &message,
+            tool_request(),
+            Some(&callback),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(iterations, 3);
+        assert_eq!(executor.calls.load(Ordering::SeqCst), 3);
+        // 3 tool rounds + the request that hit the cap + the best-effort answer
+        assert_eq!(completer.calls.load(Ordering::SeqCst), 5);
+        assert_eq!(text, "Best effort: I could not fully resolve this.");
+
+        let updates = updates.lock().unwrap();
+        assert!(matches!(
+            updates.last(),
+            Some(StatusUpdate::ToolLimitReached { max_iterations: 3 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tool_loop_zero_cap_answers_without_tools() {
+        let completer = ToolHungryCompleter {
+            calls: AtomicUsize::new(0),
+            final_answer: "",
+        };
+        let executor = CountingExecutor {
+            calls: AtomicUsize::new(0),
+        };
+        let message = InboundMessage::direct("+123", "find everything", 0);
+
+        let (text, iterations) =
+            run_tool_loop(&completer, Some(&executor), 0, &message, tool_request(), None)
+                .await
+                .unwrap();
+
+        assert_eq!(iterations, 0);
+        assert_eq!(executor.calls.load(Ordering::SeqCst), 0);
+        assert_eq!(text, TOOL_LIMIT_FALLBACK);
+    }
 
     #[test]
     fn test_select_model_for_message_default_text() {
@@ -7,8 +7,8 @@ use brain_core::BrainError;
 /// Default path for the system prompt file.
 pub const DEFAULT_PROMPT_FILE: &str = "SYSTEM_PROMPT.md";
 
-/// Default maximum number of tool call rounds.
-const DEFAULT_MAX_TOOL_ROUNDS: usize = 2;
+/// Default maximum number of tool-calling iterations per message.
+const DEFAULT_MAX_TOOL_ITERATIONS: usize = 2;
 
 /// Configuration for MapleBrain.
 #[derive(Debug, Clone)]
@@ -37,9 +37,12 @@ pub struct MapleBrainConfig {
     /// Maximum number of conversation turns to keep in history.
     pub max_history_turns: usize,
 
-    /// Maximum number of tool call rounds to prevent infinite loops.
+    /// Maximum number of tool-calling iterations per message.
+    ///
+    /// Each iteration executes the tool calls from one model reply. Once the
+    /// cap is hit the model must answer with what it has. 0 disables tools.
     /// Default: 2 (one search should usually be enough).
-    pub max_tool_rounds: usize,
+    pub max_tool_iterations: usize,
 
     /// Maximum characters for memory prompt injection (0 disables).
     pub memory_prompt_max_chars: usize,
@@ -56,7 +59,7 @@ impl Default for MapleBrainConfig {
             max_tokens: Some(1024),
             temperature: Some(0.7),
             max_history_turns: 10,
-            max_tool_rounds: DEFAULT_MAX_TOOL_ROUNDS,
+            max_tool_iterations: DEFAULT_MAX_TOOL_ITERATIONS,
             memory_prompt_max_chars: 1800,
         }
     }
@@ -77,7 +80,8 @@ impl MapleBrainConfig {
     /// - `MAPLE_MAX_TOKENS` - Max tokens (default: 1024)
     /// - `MAPLE_TEMPERATURE` - Temperature (default: 0.7)
     /// - `MAPLE_MAX_HISTORY_TURNS` - Max history turns (default: 10)
-    /// - `MAPLE_MAX_TOOL_ROUNDS` - Max tool call rounds (default: 2)
+    /// - `MAPLE_MAX_TOOL_ITERATIONS` - Max tool-calling iterations (default: 2;
+    ///   `MAPLE_MAX_TOOL_ROUNDS` is accepted as a legacy alias)
     /// - `MAPLE_MEMORY_PROMPT_MAX_CHARS` - Max memory prompt chars (default: 1800)
     /// - `MAPLE_MEMORY_PROMPT_MAX_TOKENS` - Max memory prompt tokens (approx, optional)
     ///
@@ -121,10 +125,11 @@ impl MapleBrainConfig {
             .and_then(|s| s.parse().ok())
             .unwrap_or(10);
 
-        let max_tool_rounds = env::var("MAPLE_MAX_TOOL_ROUNDS")
+        let max_tool_iterations = env::var("MAPLE_MAX_TOOL_ITERATIONS")
+            .or_else(|_| env::var("MAPLE_MAX_TOOL_ROUNDS"))
             .ok()
             .and_then(|s| s.parse().ok())
-            .unwrap_or(DEFAULT_MAX_TOOL_ROUNDS);
+            .unwrap_or(DEFAULT_MAX_TOOL_ITERATIONS);
 
         let memory_prompt_max_chars = env::var("MAPLE_MEMORY_PROMPT_MAX_CHARS")
             .ok()
@@ -146,7 +151,7 @@ impl MapleBrainConfig {
             max_tokens: max_tokens.or(Some(1024)),
             temperature: temperature.or(Some(0.7)),
             max_history_turns,
-            max_tool_rounds,
+            max_tool_iterations,
             memory_prompt_max_chars,
         })
     }
@@ -202,15 +207,21 @@ impl MapleBrainConfig {
         self
     }
 
-    /// Set the maximum number of tool call rounds.
+    /// Set the maximum number of tool-calling iterations.
     ///
     /// This limits how many times the model can request tool execution
     /// in a single message processing. Set to 0 to disable tools entirely.
-    pub fn with_max_tool_rounds(mut self, rounds: usize) -> Self {
-        self.max_tool_rounds = rounds;
+    pub fn with_max_tool_iterations(mut self, iterations: usize) -> Self {
+        self.max_tool_iterations = iterations;
         self
     }
 
+    /// Set the maximum number of tool-calling iterations.
+    #[deprecated(note = "renamed to `with_max_tool_iterations`")]
+    pub fn with_max_tool_rounds(self, rounds: usize) -> Self {
+        self.with_max_tool_iterations(rounds)
+    }
+
     /// Load system prompt from a file.
     ///
     /// Returns `Ok(self)` with the prompt loaded, or the original config if file doesn't exist.
@@ -24,7 +24,9 @@ mod tools;
 
 pub use brain::{MapleBrain, StatusCallback, StatusUpdate};
 pub use config::MapleBrainConfig;
-pub use tools::{ToolDefinition, ToolExecutor, ToolRequest, ToolResult};
+pub use tools::{
+    definitions_from_registry, ToolDefinition, ToolExecutor, ToolRequest, ToolResult,
+};
 
 // Re-export brain-core types for convenience
 pub use brain_core::{
@@ -3,6 +3,7 @@
 //! This module provides OpenSecret-specific tool definitions that MapleBrain
 //! can use. The core ToolExecutor trait is in brain-core.
 
+use agent_tools::{Tool, ToolRegistry};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -74,6 +75,26 @@ impl ToolDefinition {
         }
     }
 
+    /// Create a definition for an `agent-tools` tool.
+    ///
+    /// Tools without a declared argument schema are advertised as taking an
+    /// arbitrary object.
+    pub fn from_tool(tool: &dyn Tool) -> Self {
+        Self {
+            tool_type: "function".to_string(),
+            function: FunctionDefinition {
+                name: tool.name().to_string(),
+                description: Some(tool.description().to_string()),
+                parameters: tool.args_schema().unwrap_or_else(|| {
+                    serde_json::json!({
+                        "type": "object",
+                        "additionalProperties": true
+                    })
+                }),
+            },
+        }
+    }
+
     /// Convert to the OpenSecret SDK Tool type.
     pub fn to_opensecret_tool(&self) -> opensecret::types::Tool {
         opensecret::types::Tool {
@@ -87,9 +108,50 @@ impl ToolDefinition {
     }
 }
 
+/// Derive definitions for every tool in a registry, sorted by name.
+///
+/// Advertising exactly this set keeps the tools the model can call in sync
+/// with the tools the registry can execute.
+pub fn definitions_from_registry(registry: &ToolRegistry) -> Vec<ToolDefinition> {
+    let mut names = registry.list_tools();
+    names.sort_unstable();
+    names
+        .into_iter()
+        .filter_map(|name| registry.get(name))
+        .map(|tool| ToolDefinition::from_tool(tool.as_ref()))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use agent_tools::{default_registry, ToolArgs, ToolError, ToolOutput};
+    use async_trait::async_trait;
+
+    struct SchemaTool;
+
+    #[async_trait]
+    impl Tool for SchemaTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the given text"
+        }
+
+        fn args_schema(&self) -> Option<Value> {
+            Some(serde_json::json!({
+                "type": "object",
+                "properties": { "text": { "type": "string" } },
+                "required": ["text"]
+            }))
+        }
+
+        async fn execute(&self, args: ToolArgs) -> Result<ToolOutput, ToolError> {
+            Ok(ToolOutput::success(args.get_string("text")?))
+        }
+    }
 
     #[test]
     fn test_realtime_search_definition() {
@@ -103,4 +165,47 @@ mod tests {
         assert!(json.contains("realtime_search"));
         assert!(json.contains("privacy"));
     }
+
+    #[test]
+    fn test_definitions_cover_registered_tools() {
+        let registry = default_registry();
+        let definitions = definitions_from_registry(&registry);
+
+        let mut registered = registry.list_tools();
+        registered.sort_unstable();
+        let advertised: Vec<_> = definitions
+            .iter()
+            .map(|d| d.function.name.as_str())
+            .collect();
+        assert_eq!(advertised, registered);
+
+        for definition in &definitions {
+            assert_eq!(definition.tool_type, "function");
+            assert_eq!(definition.function.parameters["type"], "object");
+            let tool = registry.get(&definition.function.name).unwrap();
+            assert_eq!(
+                definition.function.description.as_deref(),
+                Some(tool.description())
+            );
+        }
+    }
+
+    #[test]
+    fn test_definition_uses_declared_schema() {
+        let mut registry = ToolRegistry::new();
+        registry.register(SchemaTool);
+
+        let definitions = definitions_from_registry(&registry);
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].function.name, "echo");
+        assert_eq!(definitions[0].function.parameters["required"][0], "text");
+
+        let tool = definitions[0].to_opensecret_tool();
+        assert_eq!(tool.function.name, "echo");
+    }
+
+    #[test]
+    fn test_empty_registry_has_no_definitions() {
+        assert!(definitions_from_registry(&ToolRegistry::new()).is_empty());
+    }
 }
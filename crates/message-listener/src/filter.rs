@@ -0,0 +1,147 @@
+//! Envelope filters applied before messages reach the brain.
+
+use std::fmt;
+
+use signal_daemon::Envelope;
+
+/// Decides whether an inbound envelope should be processed.
+///
+/// Any `Fn(&Envelope) -> bool` closure is a filter, so ad-hoc rules
+/// (subscriber allow-lists, blocked numbers) need no extra type:
+///
+/// ```
+/// use message_listener::ProcessorConfig;
+///
+/// let blocked = ["+15550000000".to_string()];
+/// let config = ProcessorConfig::default()
+///     .with_filter(move |envelope: &signal_daemon::Envelope| !blocked.contains(&envelope.source));
+/// ```
+pub trait MessageFilter: Send + Sync {
+    /// Return true to process the envelope, false to skip it.
+    fn allows(&self, envelope: &Envelope) -> bool;
+
+    /// Name used in skip reasons and logs.
+    fn name(&self) -> &str {
+        "custom filter"
+    }
+}
+
+impl<F> MessageFilter for F
+where
+    F: Fn(&Envelope) -> bool + Send + Sync,
+{
+    fn allows(&self, envelope: &Envelope) -> bool {
+        self(envelope)
+    }
+}
+
+impl fmt::Debug for dyn MessageFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MessageFilter({})", self.name())
+    }
+}
+
+/// Only process group messages that @mention the bot.
+///
+/// Direct messages always pass. A group message passes if one of its
+/// mentions matches the bot's number or UUID.
+#[derive(Debug, Clone)]
+pub struct RequireMentionInGroups {
+    bot_number: String,
+    bot_uuid: Option<String>,
+}
+
+impl RequireMentionInGroups {
+    /// Create a filter for the bot's phone number.
+    pub fn new(bot_number: impl Into<String>) -> Self {
+        Self {
+            bot_number: bot_number.into(),
+            bot_uuid: None,
+        }
+    }
+
+    /// Also match mentions by the bot's account UUID.
+    ///
+    /// Signal usually identifies mentions by UUID only, so set this when known.
+    pub fn with_uuid(mut self, bot_uuid: impl Into<String>) -> Self {
+        self.bot_uuid = Some(bot_uuid.into());
+        self
+    }
+}
+
+impl MessageFilter for RequireMentionInGroups {
+    fn allows(&self, envelope: &Envelope) -> bool {
+        let Some(data_message) = envelope.data_message.as_ref() else {
+            return true;
+        };
+        if data_message.group_info.is_none() {
+            return true;
+        }
+        data_message.mentions.iter().any(|mention| {
+            mention.number.as_deref() == Some(self.bot_number.as_str())
+                || (mention.uuid.is_some() && mention.uuid == self.bot_uuid)
+        })
+    }
+
+    fn name(&self) -> &str {
+        "require mention in groups"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use signal_daemon::{DataMessage, GroupInfo, Mention};
+
+    const BOT: &str = "+15551234567";
+    const BOT_UUID: &str = "bot-uuid";
+
+    fn envelope(group: bool, mentions: Vec<Mention>) -> Envelope {
+        Envelope {
+            source: "+15559876543".to_string(),
+            data_message: Some(DataMessage {
+                message: Some("\u{fffc} what's up".to_string()),
+                group_info: group.then(|| GroupInfo {
+                    group_id: "group-1".to_string(),
+                    ..Default::default()
+                }),
+                mentions,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn mention(number: Option<&str>, uuid: Option<&str>) -> Mention {
+        Mention {
+            start: 0,
+            length: 1,
+            number: number.map(str::to_string),
+            uuid: uuid.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_require_mention_in_groups() {
+        let filter = RequireMentionInGroups::new(BOT).with_uuid(BOT_UUID);
+
+        assert!(filter.allows(&envelope(false, vec![])));
+        assert!(!filter.allows(&envelope(true, vec![])));
+        assert!(!filter.allows(&envelope(true, vec![mention(Some("+15550001111"), Some("other"))])));
+        assert!(filter.allows(&envelope(true, vec![mention(Some(BOT), None)])));
+        assert!(filter.allows(&envelope(true, vec![mention(None, Some(BOT_UUID))])));
+    }
+
+    #[test]
+    fn test_uuid_mention_without_configured_uuid() {
+        let filter = RequireMentionInGroups::new(BOT);
+        assert!(!filter.allows(&envelope(true, vec![mention(None, Some(BOT_UUID))])));
+    }
+
+    #[test]
+    fn test_closure_filter() {
+        let filter = |envelope: &Envelope| envelope.source != "+15559876543";
+        assert!(!filter.allows(&envelope(false, vec![])));
+        assert_eq!(MessageFilter::name(&filter), "custom filter");
+    }
+}
@@ -50,14 +50,20 @@
 //! # }
 //! ```
 
+mod filter;
 mod processor;
+mod queue;
+#[cfg(test)]
+mod test_support;
 
 use signal_daemon::{DaemonConfig, DaemonError, MessageStream, SignalClient};
 use thiserror::Error;
 use tracing::info;
 
 // Re-export processor types
+pub use filter::{MessageFilter, RequireMentionInGroups};
 pub use processor::{MessageProcessor, ProcessorConfig, ProcessorError, ProcessResult};
+pub use queue::{OverflowPolicy, ProcessorMetrics, DEFAULT_BUSY_MESSAGE, DEFAULT_QUEUE_CAPACITY};
 
 // Re-export brain-core types for convenience
 pub use brain_core::{Brain, BrainError, InboundAttachment, InboundMessage, OutboundMessage};
@@ -1,18 +1,24 @@
 //! Message processor that connects signal-daemon to a Brain implementation.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
-use brain_core::{Brain, BrainError, TextStyle};
-use futures::StreamExt;
+use brain_core::{Brain, BrainError, OutboundMessage, TextStyle};
+use futures::{Stream, StreamExt};
 use mock_brain::EnvelopeExt;
 use signal_daemon::types::TextStyleParam;
 use signal_daemon::{DaemonError, Envelope, SignalClient};
 use thiserror::Error;
-use tokio::sync::Semaphore;
+use tokio::sync::oneshot::{self, error::TryRecvError};
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
 use tokio::time::timeout;
 use tracing::{debug, error, info, warn};
 
+use crate::filter::MessageFilter;
+use crate::queue::{OverflowPolicy, ProcessorMetrics, DEFAULT_BUSY_MESSAGE, DEFAULT_QUEUE_CAPACITY};
+
 /// Default timeout for brain processing (60 seconds).
 const DEFAULT_BRAIN_TIMEOUT: Duration = Duration::from_secs(60);
 
@@ -20,6 +26,49 @@ const DEFAULT_BRAIN_TIMEOUT: Duration = Duration::from_secs(60);
 /// This prevents resource exhaustion from message floods.
 const DEFAULT_MAX_CONCURRENT: usize = 10;
 
+/// Per-key FIFO queues, used to serialize processing per conversation.
+///
+/// A place in the queue is reserved synchronously, so messages keep the
+/// order they were admitted in even when their tasks start out of order.
+#[derive(Default)]
+struct ConversationQueues {
+    /// Completion signal of the last reserved turn for each key.
+    tails: std::sync::Mutex<HashMap<String, oneshot::Receiver<()>>>,
+}
+
+impl ConversationQueues {
+    /// Take the next turn for `key`.
+    fn reserve(&self, key: &str) -> ConversationTurn {
+        let (done, next) = oneshot::channel();
+        let mut tails = self.tails.lock().unwrap();
+        // Tails whose turn has finished are idle; drop them.
+        tails.retain(|_, tail| matches!(tail.try_recv(), Err(TryRecvError::Empty)));
+        let previous = tails.insert(key.to_string(), next);
+        ConversationTurn {
+            previous,
+            _done: done,
+        }
+    }
+}
+
+/// A reserved place in a conversation's queue.
+///
+/// Dropping it lets the next message in the conversation proceed.
+struct ConversationTurn {
+    previous: Option<oneshot::Receiver<()>>,
+    _done: oneshot::Sender<()>,
+}
+
+impl ConversationTurn {
+    /// Wait until every earlier message in the conversation has finished.
+    async fn wait(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            // The sender is never used; it closes when that turn is dropped.
+            let _ = previous.await;
+        }
+    }
+}
+
 /// Configuration for the message processor.
 #[derive(Debug, Clone)]
 pub struct ProcessorConfig {
@@ -48,6 +97,29 @@ pub struct ProcessorConfig {
     /// This prevents resource exhaustion from message floods.
     /// Default: 10.
     pub max_concurrent: usize,
+
+    /// Maximum number of messages waiting for a free worker. When full, the
+    /// `overflow` policy applies.
+    /// Default: 100.
+    pub queue_capacity: usize,
+
+    /// What to do with messages that arrive while the queue is full.
+    /// Default: [`OverflowPolicy::Wait`].
+    pub overflow: OverflowPolicy,
+
+    /// Reply sent to the sender of a shed message, so it is acknowledged
+    /// rather than silently dropped. `None` sends nothing.
+    pub busy_message: Option<String>,
+
+    /// Whether to process messages for the same conversation (history key)
+    /// one at a time, so rapid messages from one sender reach the brain and
+    /// are answered in order. Different conversations still run concurrently.
+    /// Default: true.
+    pub serialize_per_conversation: bool,
+
+    /// Optional predicate applied before dispatch to the brain. Envelopes it
+    /// rejects are skipped. Default: none (process everything).
+    pub filter: Option<Arc<dyn MessageFilter>>,
 }
 
 impl Default for ProcessorConfig {
@@ -60,6 +132,11 @@ impl Default for ProcessorConfig {
             brain_timeout: DEFAULT_BRAIN_TIMEOUT,
             process_attachment_only: true,
             max_concurrent: DEFAULT_MAX_CONCURRENT,
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            overflow: OverflowPolicy::Wait,
+            busy_message: Some(DEFAULT_BUSY_MESSAGE.to_string()),
+            serialize_per_conversation: true,
+            filter: None,
         }
     }
 }
@@ -72,6 +149,12 @@ impl ProcessorConfig {
             ..Default::default()
         }
     }
+
+    /// Only process envelopes accepted by `filter`.
+    pub fn with_filter(mut self, filter: impl MessageFilter + 'static) -> Self {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
 }
 
 /// Errors that can occur during message processing.
@@ -97,16 +180,27 @@ pub enum ProcessorError {
 /// Result of processing a single message.
 #[derive(Debug)]
 pub enum ProcessResult {
-    /// Message was processed and response sent.
-    Responded {
-        sender: String,
-        response: String,
-        timestamp: u64,
-    },
-    /// Message was skipped (e.g., from self, or not a text message).
-    Skipped { reason: String },
-    /// Error occurred during processing.
-    Error(ProcessorError),
+    /// Message was processed and this response was sent.
+    Responded(OutboundMessage),
+    /// Message was deliberately not answered, with the reason (e.g. from
+    /// self, filtered out, or skipped by the brain).
+    Skipped(String),
+    /// The brain judged the message accidental and silently ignored it.
+    Ignored,
+    /// Processing failed.
+    Failed(ProcessorError),
+}
+
+impl ProcessResult {
+    /// Short outcome label for logs and metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Responded(_) => "responded",
+            Self::Skipped(_) => "skipped",
+            Self::Ignored => "ignored",
+            Self::Failed(_) => "failed",
+        }
+    }
 }
 
 /// A message processor that receives Signal messages and processes them through a Brain.
@@ -116,17 +210,27 @@ pub struct MessageProcessor<B: Brain> {
     config: ProcessorConfig,
     /// Semaphore for limiting concurrent message processing.
     semaphore: Arc<Semaphore>,
+    /// Admission slots: workers plus queued messages.
+    queue_slots: Arc<Semaphore>,
+    /// Queue depth and throughput counters.
+    metrics: ProcessorMetrics,
+    /// Queues serializing processing per conversation.
+    conversations: ConversationQueues,
 }
 
 impl<B: Brain> MessageProcessor<B> {
     /// Create a new message processor.
     pub fn new(client: SignalClient, brain: B, config: ProcessorConfig) -> Self {
         let semaphore = Arc::new(Semaphore::new(config.max_concurrent));
+        let queue_slots = Arc::new(Semaphore::new(config.max_concurrent + config.queue_capacity));
         Self {
             client,
             brain,
             config,
             semaphore,
+            queue_slots,
+            metrics: ProcessorMetrics::default(),
+            conversations: ConversationQueues::default(),
         }
     }
 
@@ -145,6 +249,13 @@ impl<B: Brain> MessageProcessor<B> {
         &self.client
     }
 
+    /// Get a handle to the queue metrics.
+    ///
+    /// The handle stays live after the processor is moved into `run`.
+    pub fn metrics(&self) -> ProcessorMetrics {
+        self.metrics.clone()
+    }
+
     /// Check if we should process this envelope.
     fn should_process(&self, envelope: &Envelope) -> Result<(), String> {
         // Check if it's from ourselves
@@ -184,24 +295,51 @@ impl<B: Brain> MessageProcessor<B> {
             return Err("direct messages disabled".to_string());
         }
 
+        if let Some(ref filter) = self.config.filter {
+            if !filter.allows(envelope) {
+                return Err(format!("rejected by {}", filter.name()));
+            }
+        }
+
         Ok(())
     }
 
     /// Process a single envelope and return the result.
     pub async fn process_envelope(&self, envelope: &Envelope) -> ProcessResult {
+        let mut turn = self.reserve_turn(envelope);
+        if let Some(turn) = turn.as_mut() {
+            turn.wait().await;
+        }
+        self.process_envelope_locked(envelope).await
+    }
+
+    /// Reserve the envelope's place in its conversation's queue.
+    ///
+    /// Returns None when per-conversation serialization is disabled or the
+    /// envelope does not map to a conversation.
+    fn reserve_turn(&self, envelope: &Envelope) -> Option<ConversationTurn> {
+        if !self.config.serialize_per_conversation {
+            return None;
+        }
+        let inbound = envelope.to_inbound_message_with_config(self.client.config())?;
+        Some(self.conversations.reserve(&inbound.history_key()))
+    }
+
+    /// Process an envelope whose conversation turn, if any, has come.
+    ///
+    /// The turn is held until the reply is sent so replies stay in order.
+    async fn process_envelope_locked(&self, envelope: &Envelope) -> ProcessResult {
         // Check if we should process this message
         if let Err(reason) = self.should_process(envelope) {
             debug!("Skipping message: {}", reason);
-            return ProcessResult::Skipped { reason };
+            return ProcessResult::Skipped(reason);
         }
 
         // Convert to inbound message with full attachment paths
         let inbound = match envelope.to_inbound_message_with_config(self.client.config()) {
             Some(msg) => msg,
             None => {
-                return ProcessResult::Skipped {
-                    reason: "could not convert to inbound message".to_string(),
-                }
+                return ProcessResult::Skipped("could not convert to inbound message".to_string())
             }
         };
 
@@ -227,7 +365,11 @@ impl<B: Brain> MessageProcessor<B> {
         let response = match brain_result {
             Ok(Ok(resp)) => resp,
             Ok(Err(e)) => {
-                error!("Brain error for {}: {}", sender, e);
+                if e.is_no_reply() {
+                    info!("Not replying to {}: {}", sender, e);
+                } else {
+                    error!("Brain error for {}: {}", sender, e);
+                }
                 // Stop typing indicator on error
                 if self.config.send_typing_indicators {
                     let _ = if is_group {
@@ -240,7 +382,11 @@ impl<B: Brain> MessageProcessor<B> {
                         self.client.send_typing(&sender, false).await
                     };
                 }
-                return ProcessResult::Error(ProcessorError::Brain(e));
+                return match e {
+                    BrainError::Skipped(reason) => ProcessResult::Skipped(reason),
+                    BrainError::Ignored => ProcessResult::Ignored,
+                    e => ProcessResult::Failed(ProcessorError::Brain(e)),
+                };
             }
             Err(_elapsed) => {
                 error!(
@@ -259,7 +405,7 @@ impl<B: Brain> MessageProcessor<B> {
                         self.client.send_typing(&sender, false).await
                     };
                 }
-                return ProcessResult::Error(ProcessorError::Timeout(self.config.brain_timeout));
+                return ProcessResult::Failed(ProcessorError::Timeout(self.config.brain_timeout));
             }
         };
 
@@ -293,107 +439,57 @@ impl<B: Brain> MessageProcessor<B> {
                     "Sent response to {} (ts={}): {}",
                     response.recipient, result.timestamp, response.text
                 );
-                ProcessResult::Responded {
-                    sender,
-                    response: response.text,
-                    timestamp: result.timestamp,
-                }
+                ProcessResult::Responded(response)
             }
             Err(e) => {
                 error!("Failed to send response to {}: {}", response.recipient, e);
-                ProcessResult::Error(ProcessorError::Daemon(e))
+                ProcessResult::Failed(ProcessorError::Daemon(e))
             }
         }
     }
 
+    /// Send the configured busy reply for a message that was shed.
+    async fn acknowledge_shed(&self, envelope: &Envelope) {
+        let Some(busy) = self.config.busy_message.as_deref() else {
+            return;
+        };
+        let Some(inbound) = envelope.to_inbound_message() else {
+            return;
+        };
+        let result = match inbound.group_id {
+            Some(ref group_id) => self.client.send_to_group(group_id, busy).await,
+            None => self.client.send_text(&inbound.sender, busy).await,
+        };
+        if let Err(e) = result {
+            warn!("Failed to acknowledge shed message from {}: {}", inbound.sender, e);
+        }
+    }
+}
+
+impl<B: Brain + 'static> MessageProcessor<B> {
     /// Run the processor, handling messages until the stream ends or an error occurs.
     ///
     /// This method consumes self and runs indefinitely.
-    /// Messages are rate-limited by the configured max_concurrent setting.
+    /// Up to `max_concurrent` messages are processed at once; see
+    /// [`ProcessorConfig::overflow`] for what happens when the queue fills.
     pub async fn run(self) -> Result<(), ProcessorError> {
-        info!(
-            "Starting message processor with brain: {} (max concurrent: {})",
-            self.brain.name(),
-            self.config.max_concurrent
-        );
-
-        let mut stream = signal_daemon::subscribe(&self.client)?;
-
-        while let Some(result) = stream.next().await {
-            match result {
-                Ok(envelope) => {
-                    // Rate limiting: acquire permit before processing
-                    // This blocks if we're at capacity, providing backpressure
-                    let _permit = self.semaphore.acquire().await.map_err(|_| {
-                        ProcessorError::Daemon(DaemonError::Connection(
-                            "Semaphore closed unexpectedly".to_string(),
-                        ))
-                    })?;
-
-                    let result = self.process_envelope(&envelope).await;
-                    match result {
-                        ProcessResult::Responded { sender, response, .. } => {
-                            debug!("Responded to {}: {}", sender, response);
-                        }
-                        ProcessResult::Skipped { reason } => {
-                            debug!("Skipped: {}", reason);
-                        }
-                        ProcessResult::Error(e) => {
-                            // Log but continue processing
-                            warn!("Error processing message: {}", e);
-                        }
-                    }
-                    // Permit is automatically released when _permit goes out of scope
-                }
-                Err(e) => {
-                    error!("Stream error: {}", e);
-                    // Continue on stream errors - they might be recoverable
-                }
-            }
-        }
-
-        warn!("Message stream ended");
-        Err(ProcessorError::StreamEnded)
+        let stream = signal_daemon::subscribe(&self.client)?;
+        self.run_stream(stream, std::future::pending(), log_result)
+            .await
     }
 
     /// Run the processor with a callback for each processed message.
     ///
     /// The callback receives each ProcessResult, allowing for custom handling.
-    /// Messages are rate-limited by the configured max_concurrent setting.
-    pub async fn run_with_callback<F>(self, mut callback: F) -> Result<(), ProcessorError>
+    /// Results arrive in completion order, which may differ from arrival order
+    /// across conversations.
+    pub async fn run_with_callback<F>(self, callback: F) -> Result<(), ProcessorError>
     where
         F: FnMut(ProcessResult) + Send,
     {
-        info!(
-            "Starting message processor with brain: {} (max concurrent: {})",
-            self.brain.name(),
-            self.config.max_concurrent
-        );
-
-        let mut stream = signal_daemon::subscribe(&self.client)?;
-
-        while let Some(result) = stream.next().await {
-            match result {
-                Ok(envelope) => {
-                    // Rate limiting: acquire permit before processing
-                    let _permit = self.semaphore.acquire().await.map_err(|_| {
-                        ProcessorError::Daemon(DaemonError::Connection(
-                            "Semaphore closed unexpectedly".to_string(),
-                        ))
-                    })?;
-
-                    let result = self.process_envelope(&envelope).await;
-                    callback(result);
-                }
-                Err(e) => {
-                    error!("Stream error: {}", e);
-                    callback(ProcessResult::Error(ProcessorError::Daemon(e)));
-                }
-            }
-        }
-
-        warn!("Message stream ended");
-        Err(ProcessorError::StreamEnded)
+        let stream = signal_daemon::subscribe(&self.client)?;
+        self.run_stream(stream, std::future::pending(), callback)
+            .await
     }
 
     /// Run the processor with graceful shutdown support.
@@ -403,6 +499,9 @@ impl<B: Brain> MessageProcessor<B> {
     /// - The message stream ends
     /// - An unrecoverable error occurs
     ///
+    /// On shutdown, intake stops and messages already queued are finished
+    /// before the brain is shut down.
+    ///
     /// # Example
     ///
     /// ```ignore
@@ -423,70 +522,8 @@ impl<B: Brain> MessageProcessor<B> {
     where
         S: std::future::Future<Output = ()> + Send,
     {
-        info!(
-            "Starting message processor with brain: {} (graceful shutdown enabled, max concurrent: {})",
-            self.brain.name(),
-            self.config.max_concurrent
-        );
-
-        let mut stream = signal_daemon::subscribe(&self.client)?;
-
-        tokio::pin!(shutdown_signal);
-
-        loop {
-            tokio::select! {
-                biased;
-
-                // Check for shutdown signal first
-                () = &mut shutdown_signal => {
-                    info!("Shutdown signal received, stopping message processor");
-                    // Call brain's shutdown method if available
-                    if let Err(e) = self.brain.shutdown().await {
-                        warn!("Error during brain shutdown: {}", e);
-                    }
-                    return Ok(());
-                }
-
-                // Then handle messages
-                result = stream.next() => {
-                    match result {
-                        Some(Ok(envelope)) => {
-                            // Rate limiting: acquire permit before processing
-                            let _permit = match self.semaphore.acquire().await {
-                                Ok(permit) => permit,
-                                Err(_) => {
-                                    error!("Semaphore closed unexpectedly");
-                                    return Err(ProcessorError::Daemon(DaemonError::Connection(
-                                        "Semaphore closed unexpectedly".to_string(),
-                                    )));
-                                }
-                            };
-
-                            let result = self.process_envelope(&envelope).await;
-                            match result {
-                                ProcessResult::Responded { sender, response, .. } => {
-                                    debug!("Responded to {}: {}", sender, response);
-                                }
-                                ProcessResult::Skipped { reason } => {
-                                    debug!("Skipped: {}", reason);
-                                }
-                                ProcessResult::Error(e) => {
-                                    warn!("Error processing message: {}", e);
-                                }
-                            }
-                        }
-                        Some(Err(e)) => {
-                            error!("Stream error: {}", e);
-                            // Continue on stream errors - reconnection is handled automatically
-                        }
-                        None => {
-                            warn!("Message stream ended");
-                            return Err(ProcessorError::StreamEnded);
-                        }
-                    }
-                }
-            }
-        }
+        let stream = signal_daemon::subscribe(&self.client)?;
+        self.run_stream(stream, shutdown_signal, log_result).await
     }
 
     /// Run the processor until Ctrl+C is pressed.
@@ -519,6 +556,190 @@ impl<B: Brain> MessageProcessor<B> {
         };
         self.run_with_shutdown(shutdown).await
     }
+
+    /// Core processing loop shared by the `run*` methods.
+    ///
+    /// Envelopes are admitted into a bounded queue and processed by up to
+    /// `max_concurrent` tasks. Returns `Ok(())` when `shutdown` completes and
+    /// [`ProcessorError::StreamEnded`] when the stream ends; either way,
+    /// admitted messages are finished first.
+    pub(crate) async fn run_stream<St, S, F>(
+        self,
+        stream: St,
+        shutdown: S,
+        mut callback: F,
+    ) -> Result<(), ProcessorError>
+    where
+        St: Stream<Item = Result<Envelope, DaemonError>>,
+        S: std::future::Future<Output = ()>,
+        F: FnMut(ProcessResult),
+    {
+        info!(
+            "Starting message processor with brain: {} (max concurrent: {}, queue capacity: {}, overflow: {:?})",
+            self.brain.name(),
+            self.config.max_concurrent,
+            self.config.queue_capacity,
+            self.config.overflow
+        );
+
+        let this = Arc::new(self);
+        let (results_tx, mut results_rx) = mpsc::unbounded_channel();
+        let mut tasks = JoinSet::new();
+        tokio::pin!(stream);
+        tokio::pin!(shutdown);
+
+        let outcome = loop {
+            tokio::select! {
+                biased;
+
+                () = &mut shutdown => {
+                    info!("Shutdown signal received, stopping message processor");
+                    break Ok(());
+                }
+
+                Some(result) = results_rx.recv() => callback(result),
+
+                Some(joined) = tasks.join_next(), if !tasks.is_empty() => {
+                    if let Err(e) = joined {
+                        error!("Message processing task failed: {}", e);
+                    }
+                }
+
+                item = stream.next() => match item {
+                    Some(Ok(envelope)) => {
+                        if let Some(result) = this.admit(envelope, &mut tasks, &results_tx).await {
+                            callback(result);
+                        }
+                    }
+                    Some(Err(e)) => {
+                        error!("Stream error: {}", e);
+                        // Continue on stream errors - reconnection is handled automatically
+                        callback(ProcessResult::Failed(ProcessorError::Daemon(e)));
+                    }
+                    None => {
+                        warn!("Message stream ended");
+                        break Err(ProcessorError::StreamEnded);
+                    }
+                },
+            }
+        };
+
+        // Finish messages that were already admitted
+        if !tasks.is_empty() {
+            info!("Waiting for {} queued message(s) to finish", tasks.len());
+        }
+        while let Some(joined) = tasks.join_next().await {
+            if let Err(e) = joined {
+                error!("Message processing task failed: {}", e);
+            }
+        }
+        drop(results_tx);
+        while let Some(result) = results_rx.recv().await {
+            callback(result);
+        }
+
+        if outcome.is_ok() {
+            // Call brain's shutdown method if available
+            if let Err(e) = this.brain.shutdown().await {
+                warn!("Error during brain shutdown: {}", e);
+            }
+        }
+        outcome
+    }
+
+    /// Admit an envelope into the work queue.
+    ///
+    /// Returns a result immediately for envelopes that are skipped or shed;
+    /// otherwise the result is sent on `results` when processing finishes.
+    async fn admit(
+        self: &Arc<Self>,
+        envelope: Envelope,
+        tasks: &mut JoinSet<()>,
+        results: &mpsc::UnboundedSender<ProcessResult>,
+    ) -> Option<ProcessResult> {
+        // Cheap checks first, so receipts and typing events never take a slot
+        if let Err(reason) = self.should_process(&envelope) {
+            debug!("Skipping message: {}", reason);
+            return Some(ProcessResult::Skipped(reason));
+        }
+
+        let slot = match self.queue_slots.clone().try_acquire_owned() {
+            Ok(slot) => slot,
+            Err(_) if self.config.overflow == OverflowPolicy::Shed => {
+                warn!(
+                    "Processing queue full ({} queued, {} in flight); shedding message from {}",
+                    self.metrics.queue_depth(),
+                    self.metrics.in_flight(),
+                    envelope.source
+                );
+                self.metrics.record_shed();
+                self.acknowledge_shed(&envelope).await;
+                return Some(ProcessResult::Skipped("processing queue full".to_string()));
+            }
+            Err(_) => {
+                warn!(
+                    "Processing queue full ({} queued, {} in flight); pausing intake",
+                    self.metrics.queue_depth(),
+                    self.metrics.in_flight()
+                );
+                match self.queue_slots.clone().acquire_owned().await {
+                    Ok(slot) => slot,
+                    Err(_) => {
+                        return Some(ProcessResult::Failed(ProcessorError::Daemon(
+                            DaemonError::Connection("Semaphore closed unexpectedly".to_string()),
+                        )))
+                    }
+                }
+            }
+        };
+
+        self.metrics.enqueued();
+        // Reserve the conversation's place before spawning, so tasks that
+        // start out of order are still answered in arrival order
+        let mut turn = self.reserve_turn(&envelope);
+        let this = Arc::clone(self);
+        let results = results.clone();
+        tasks.spawn(async move {
+            let _slot = slot;
+            // Wait for the conversation before taking a permit, so messages queued
+            // behind a busy conversation never hold permits other senders need
+            if let Some(turn) = turn.as_mut() {
+                turn.wait().await;
+            }
+            let result = match this.semaphore.clone().acquire_owned().await {
+                Ok(_permit) => {
+                    this.metrics.started();
+                    let result = this.process_envelope_locked(&envelope).await;
+                    this.metrics.finished();
+                    result
+                }
+                Err(_) => ProcessResult::Failed(ProcessorError::Daemon(DaemonError::Connection(
+                    "Semaphore closed unexpectedly".to_string(),
+                ))),
+            };
+            let _ = results.send(result);
+        });
+        None
+    }
+}
+
+/// Default result handler: log and continue.
+fn log_result(result: ProcessResult) {
+    match result {
+        ProcessResult::Responded(response) => {
+            debug!("Responded to {}: {}", response.recipient, response.text);
+        }
+        ProcessResult::Skipped(reason) => {
+            debug!("Skipped: {}", reason);
+        }
+        ProcessResult::Ignored => {
+            debug!("Ignored accidental message");
+        }
+        ProcessResult::Failed(e) => {
+            // Log but continue processing
+            warn!("Error processing message: {}", e);
+        }
+    }
 }
 
 /// Convert brain-core TextStyle to signal-daemon TextStyleParam.
@@ -536,9 +757,10 @@ fn convert_styles(styles: &[TextStyle]) -> Vec<TextStyleParam> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use signal_daemon::{DataMessage, GroupInfo};
+    use crate::filter::RequireMentionInGroups;
+    use crate::test_support::{mock_client, CountingBrain};
+    use signal_daemon::{DataMessage, GroupInfo, Mention};
 
-    #[allow(dead_code)]
     fn make_test_envelope(sender: &str, text: &str) -> Envelope {
         Envelope {
             source: sender.to_string(),
@@ -552,7 +774,6 @@ mod tests {
         }
     }
 
-    #[allow(dead_code)]
     fn make_group_envelope(sender: &str, text: &str, group_id: &str) -> Envelope {
         Envelope {
             source: sender.to_string(),
@@ -593,4 +814,324 @@ mod tests {
         assert!(envelope.data_message.is_some());
         assert!(envelope.data_message.as_ref().unwrap().message.is_some());
     }
+
+    #[tokio::test]
+    async fn test_filter_applied_before_brain() {
+        let (client, sent) = mock_client().await;
+        let brain = CountingBrain::default();
+        let config = ProcessorConfig::with_bot_number("+15551234567")
+            .with_filter(|envelope: &Envelope| envelope.source != "+15550000000");
+        let processor = MessageProcessor::new(client, brain.clone(), config);
+
+        let blocked = processor
+            .process_envelope(&make_test_envelope("+15550000000", "hi"))
+            .await;
+        assert!(
+            matches!(&blocked, ProcessResult::Skipped(reason) if reason == "rejected by custom filter")
+        );
+
+        let allowed = processor
+            .process_envelope(&make_test_envelope("+15559876543", "hi"))
+            .await;
+        assert!(matches!(allowed, ProcessResult::Responded(_)));
+
+        assert_eq!(brain.processed(), vec!["+15559876543".to_string()]);
+        assert_eq!(sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_require_mention_filter() {
+        let (client, _sent) = mock_client().await;
+        let brain = CountingBrain::default();
+        let config = ProcessorConfig::with_bot_number("+15551234567")
+            .with_filter(RequireMentionInGroups::new("+15551234567"));
+        let processor = MessageProcessor::new(client, brain.clone(), config);
+
+        let unmentioned = make_group_envelope("+15559876543", "chatter", "group-1");
+        let mut mentioned = make_group_envelope("+15550001111", "\u{fffc} help", "group-1");
+        mentioned.data_message.as_mut().unwrap().mentions = vec![Mention {
+            start: 0,
+            length: 1,
+            number: Some("+15551234567".to_string()),
+            uuid: None,
+        }];
+        let direct = make_test_envelope("+15552223333", "hello");
+
+        for envelope in [&unmentioned, &mentioned, &direct] {
+            processor.process_envelope(envelope).await;
+        }
+
+        assert_eq!(
+            brain.processed(),
+            vec!["+15550001111".to_string(), "+15552223333".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_same_sender_processed_sequentially() {
+        let (client, _sent) = mock_client().await;
+        let brain = CountingBrain::with_delay(Duration::from_millis(50));
+        let processor = MessageProcessor::new(client, brain.clone(), ProcessorConfig::default());
+
+        let first = make_test_envelope("+15559876543", "first");
+        let second = make_test_envelope("+15559876543", "second");
+        let (a, b) = tokio::join!(
+            processor.process_envelope(&first),
+            processor.process_envelope(&second)
+        );
+
+        assert!(matches!(a, ProcessResult::Responded(_)));
+        assert!(matches!(b, ProcessResult::Responded(_)));
+        assert_eq!(brain.max_active(), 1);
+        assert_eq!(brain.texts(), vec!["first".to_string(), "second".to_string()]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_conversation_answered_in_arrival_order() {
+        let (client, _sent) = mock_client().await;
+        let brain = CountingBrain::with_delay(Duration::from_millis(1));
+        let processor = MessageProcessor::new(client, brain.clone(), ProcessorConfig::default());
+
+        let texts: Vec<String> = (0..50).map(|i| format!("message {}", i)).collect();
+        let envelopes = texts
+            .iter()
+            .map(|text| Ok(make_test_envelope("+15559876543", text)))
+            .collect::<Vec<_>>();
+        let _ = processor
+            .run_stream(futures::stream::iter(envelopes), std::future::pending(), |_| {})
+            .await;
+
+        assert_eq!(brain.texts(), texts);
+        assert_eq!(brain.max_active(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_different_senders_processed_concurrently() {
+        let (client, _sent) = mock_client().await;
+        let brain = CountingBrain::with_delay(Duration::from_millis(50));
+        let processor = MessageProcessor::new(client, brain.clone(), ProcessorConfig::default());
+
+        let alice = make_test_envelope("+15559876543", "hi");
+        let bob = make_test_envelope("+15550001111", "hi");
+        tokio::join!(
+            processor.process_envelope(&alice),
+            processor.process_envelope(&bob)
+        );
+
+        assert_eq!(brain.max_active(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_group_messages_share_a_conversation() {
+        let (client, _sent) = mock_client().await;
+        let brain = CountingBrain::with_delay(Duration::from_millis(50));
+        let processor = MessageProcessor::new(client, brain.clone(), ProcessorConfig::default());
+
+        let alice = make_group_envelope("+15559876543", "one", "group-1");
+        let bob = make_group_envelope("+15550001111", "two", "group-1");
+        tokio::join!(
+            processor.process_envelope(&alice),
+            processor.process_envelope(&bob)
+        );
+
+        assert_eq!(brain.max_active(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_per_conversation_serialization_can_be_disabled() {
+        let (client, _sent) = mock_client().await;
+        let brain = CountingBrain::with_delay(Duration::from_millis(50));
+        let config = ProcessorConfig {
+            serialize_per_conversation: false,
+            ..Default::default()
+        };
+        let processor = MessageProcessor::new(client, brain.clone(), config);
+
+        let first = make_test_envelope("+15559876543", "first");
+        let second = make_test_envelope("+15559876543", "second");
+        tokio::join!(
+            processor.process_envelope(&first),
+            processor.process_envelope(&second)
+        );
+
+        assert_eq!(brain.max_active(), 2);
+    }
+
+    fn flood(count: usize) -> impl Stream<Item = Result<Envelope, DaemonError>> {
+        futures::stream::iter(
+            (0..count).map(|i| Ok(make_test_envelope(&format!("+1555000{:04}", i), "hi"))),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_flood_is_bounded_by_max_concurrent() {
+        let (client, sent) = mock_client().await;
+        let brain = CountingBrain::with_delay(Duration::from_millis(20));
+        let config = ProcessorConfig {
+            max_concurrent: 3,
+            queue_capacity: 2,
+            ..Default::default()
+        };
+        let processor = MessageProcessor::new(client, brain.clone(), config);
+        let metrics = processor.metrics();
+
+        let mut responded = 0;
+        let result = processor
+            .run_stream(flood(30), std::future::pending(), |result| {
+                if matches!(result, ProcessResult::Responded(_)) {
+                    responded += 1;
+                }
+            })
+            .await;
+
+        assert!(matches!(result, Err(ProcessorError::StreamEnded)));
+        assert_eq!(responded, 30);
+        assert_eq!(brain.processed().len(), 30);
+        assert!(brain.max_active() <= 3, "max active {}", brain.max_active());
+        assert_eq!(sent.lock().unwrap().len(), 30);
+        assert_eq!(metrics.processed(), 30);
+        assert_eq!(metrics.shed(), 0);
+        assert_eq!(metrics.queue_depth(), 0);
+        assert_eq!(metrics.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_busy_conversation_does_not_block_others() {
+        let (client, _sent) = mock_client().await;
+        let brain = CountingBrain::with_delay(Duration::from_millis(50));
+        let config = ProcessorConfig {
+            max_concurrent: 2,
+            ..Default::default()
+        };
+        let processor = MessageProcessor::new(client, brain.clone(), config);
+
+        // Alice's backlog must wait on her conversation, not on processing permits
+        let alice = "+15559876543";
+        let bob = "+15550001111";
+        let envelopes = ["one", "two", "three", "four"]
+            .map(|text| make_test_envelope(alice, text))
+            .into_iter()
+            .chain([make_test_envelope(bob, "hi")])
+            .map(Ok);
+        let _ = processor
+            .run_stream(futures::stream::iter(envelopes), std::future::pending(), |_| {})
+            .await;
+
+        let processed = brain.processed();
+        assert_eq!(processed.len(), 5);
+        let bob_position = processed.iter().position(|sender| sender == bob).unwrap();
+        assert!(bob_position < 2, "bob finished at {} in {:?}", bob_position, processed);
+        assert_eq!(brain.max_active(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_shed_overflow_acknowledges_sender() {
+        let (client, sent) = mock_client().await;
+        let brain = CountingBrain::with_delay(Duration::from_millis(500));
+        let config = ProcessorConfig {
+            max_concurrent: 1,
+            queue_capacity: 1,
+            overflow: OverflowPolicy::Shed,
+            ..Default::default()
+        };
+        let processor = MessageProcessor::new(client, brain.clone(), config);
+        let metrics = processor.metrics();
+
+        let mut skipped = Vec::new();
+        let _ = processor
+            .run_stream(flood(10), std::future::pending(), |result| {
+                if let ProcessResult::Skipped(reason) = result {
+                    skipped.push(reason);
+                }
+            })
+            .await;
+
+        assert!(metrics.shed() > 0);
+        assert_eq!(metrics.processed() + metrics.shed(), 10);
+        assert_eq!(skipped.len() as u64, metrics.shed());
+        assert!(skipped.iter().all(|reason| reason == "processing queue full"));
+        assert_eq!(brain.max_active(), 1);
+
+        // Every message got either a reply or a busy acknowledgement.
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 10);
+        let busy = sent
+            .iter()
+            .filter(|body| body.contains("handling a lot of messages"))
+            .count();
+        assert_eq!(busy as u64, metrics.shed());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_finishes_queued_messages() {
+        let (client, _sent) = mock_client().await;
+        let brain = CountingBrain::with_delay(Duration::from_millis(50));
+        let processor = MessageProcessor::new(client, brain.clone(), ProcessorConfig::default());
+
+        let stream = flood(3).chain(futures::stream::pending());
+        let shutdown = tokio::time::sleep(Duration::from_millis(10));
+        let result = processor.run_stream(stream, shutdown, |_| {}).await;
+
+        assert!(result.is_ok());
+        assert_eq!(brain.processed().len(), 3);
+    }
+
+    /// Brain whose outcome is chosen by the message text.
+    struct OutcomeBrain;
+
+    #[brain_core::async_trait]
+    impl Brain for OutcomeBrain {
+        async fn process(
+            &self,
+            message: brain_core::InboundMessage,
+        ) -> Result<OutboundMessage, BrainError> {
+            match message.text.as_str() {
+                "skip" => Err(BrainError::Skipped("off topic".to_string())),
+                "?" => Err(BrainError::Ignored),
+                "fail" => Err(BrainError::ProcessingFailed("boom".to_string())),
+                text => Ok(OutboundMessage::reply_to(&message, format!("re: {}", text))),
+            }
+        }
+
+        fn name(&self) -> &str {
+            "outcome"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_result_variants() {
+        let (client, sent) = mock_client().await;
+        let config = ProcessorConfig::with_bot_number("+15551234567");
+        let processor = MessageProcessor::new(client, OutcomeBrain, config);
+        let sender = "+15559876543";
+
+        let responded = processor.process_envelope(&make_test_envelope(sender, "hello")).await;
+        assert!(
+            matches!(&responded, ProcessResult::Responded(reply) if reply.text == "re: hello" && reply.recipient == sender)
+        );
+
+        let skipped = processor.process_envelope(&make_test_envelope(sender, "skip")).await;
+        assert!(matches!(&skipped, ProcessResult::Skipped(reason) if reason == "off topic"));
+
+        let ignored = processor.process_envelope(&make_test_envelope(sender, "?")).await;
+        assert!(matches!(ignored, ProcessResult::Ignored));
+
+        let failed = processor.process_envelope(&make_test_envelope(sender, "fail")).await;
+        assert!(matches!(
+            failed,
+            ProcessResult::Failed(ProcessorError::Brain(BrainError::ProcessingFailed(_)))
+        ));
+
+        let from_self = processor
+            .process_envelope(&make_test_envelope("+15551234567", "hello"))
+            .await;
+        assert!(matches!(from_self, ProcessResult::Skipped(_)));
+
+        // Only the real response is sent; skips and ignores stay silent.
+        assert_eq!(sent.lock().unwrap().len(), 1);
+        assert_eq!(
+            [responded, skipped, ignored].map(|result| result.kind()),
+            ["responded", "skipped", "ignored"]
+        );
+    }
 }
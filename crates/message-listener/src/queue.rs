@@ -0,0 +1,78 @@
+//! Work-queue policy and metrics for the message processor.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Default number of messages that may wait for a free worker.
+pub const DEFAULT_QUEUE_CAPACITY: usize = 100;
+
+/// Default reply sent to senders whose message was shed.
+pub const DEFAULT_BUSY_MESSAGE: &str =
+    "I'm handling a lot of messages right now. Please try again in a minute.";
+
+/// What to do with a message when the processing queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Stop reading new messages until a queue slot frees up. Nothing is
+    /// dropped; signal-cli buffers the backlog.
+    #[default]
+    Wait,
+    /// Drop the message and, if a busy message is configured, tell the
+    /// sender to try again.
+    Shed,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    queued: AtomicUsize,
+    in_flight: AtomicUsize,
+    processed: AtomicU64,
+    shed: AtomicU64,
+}
+
+/// Live queue metrics for a [`MessageProcessor`](crate::MessageProcessor).
+///
+/// Cloning is cheap and clones observe the same counters, so a handle taken
+/// before `run` keeps reporting while the processor runs.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessorMetrics(Arc<Counters>);
+
+impl ProcessorMetrics {
+    /// Messages admitted and waiting for a worker.
+    pub fn queue_depth(&self) -> usize {
+        self.0.queued.load(Ordering::SeqCst)
+    }
+
+    /// Messages currently being processed.
+    pub fn in_flight(&self) -> usize {
+        self.0.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Messages that finished processing (successfully or not).
+    pub fn processed(&self) -> u64 {
+        self.0.processed.load(Ordering::SeqCst)
+    }
+
+    /// Messages dropped because the queue was full.
+    pub fn shed(&self) -> u64 {
+        self.0.shed.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn enqueued(&self) {
+        self.0.queued.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(crate) fn started(&self) {
+        self.0.queued.fetch_sub(1, Ordering::SeqCst);
+        self.0.in_flight.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(crate) fn finished(&self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.0.processed.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(crate) fn record_shed(&self) {
+        self.0.shed.fetch_add(1, Ordering::SeqCst);
+    }
+}
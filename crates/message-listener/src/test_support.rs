@@ -0,0 +1,134 @@
+//! Test helpers: a mock signal-cli daemon and a recording brain.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use brain_core::{async_trait, Brain, BrainError, InboundMessage, OutboundMessage};
+use signal_daemon::{DaemonConfig, SignalClient};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// JSON-RPC request bodies received by the mock daemon.
+pub(crate) type SentRequests = Arc<Mutex<Vec<String>>>;
+
+/// Start a mock daemon and return a client connected to it.
+///
+/// The health check always succeeds and every RPC call returns a send
+/// result; request bodies are recorded.
+pub(crate) async fn mock_client() -> (SignalClient, SentRequests) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let sent: SentRequests = Arc::default();
+    let recorded = sent.clone();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            tokio::spawn(handle(socket, recorded.clone()));
+        }
+    });
+
+    let client = SignalClient::connect(DaemonConfig::new(format!("http://{}", addr)))
+        .await
+        .unwrap();
+    (client, sent)
+}
+
+async fn handle(mut socket: TcpStream, sent: SentRequests) {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    let (head_len, body_len) = loop {
+        let n = socket.read(&mut buf).await.unwrap_or(0);
+        if n == 0 {
+            return;
+        }
+        request.extend_from_slice(&buf[..n]);
+        if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+            let head = String::from_utf8_lossy(&request[..pos]).to_lowercase();
+            let body_len = head
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .and_then(|len| len.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            break (pos + 4, body_len);
+        }
+    };
+    while request.len() < head_len + body_len {
+        let n = socket.read(&mut buf).await.unwrap_or(0);
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let body = if request.starts_with(b"POST") {
+        let mut sent = sent.lock().unwrap();
+        sent.push(String::from_utf8_lossy(&request[head_len..]).to_string());
+        format!(
+            r#"{{"jsonrpc":"2.0","result":{{"timestamp":{}}},"id":1}}"#,
+            sent.len()
+        )
+    } else {
+        String::new()
+    };
+    let response = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = socket.write_all(response.as_bytes()).await;
+}
+
+/// Brain that records which messages it processed, tracks how many calls
+/// overlap, and echoes the text.
+#[derive(Clone, Default)]
+pub(crate) struct CountingBrain {
+    processed: Arc<Mutex<Vec<String>>>,
+    texts: Arc<Mutex<Vec<String>>>,
+    delay: Duration,
+    active: Arc<AtomicUsize>,
+    max_active: Arc<AtomicUsize>,
+}
+
+impl CountingBrain {
+    /// Brain that takes `delay` to answer each message.
+    pub(crate) fn with_delay(delay: Duration) -> Self {
+        Self {
+            delay,
+            ..Default::default()
+        }
+    }
+
+    /// Senders of processed messages, in order.
+    pub(crate) fn processed(&self) -> Vec<String> {
+        self.processed.lock().unwrap().clone()
+    }
+
+    /// Texts of processed messages, in the order processing started.
+    pub(crate) fn texts(&self) -> Vec<String> {
+        self.texts.lock().unwrap().clone()
+    }
+
+    /// Highest number of overlapping `process` calls observed.
+    pub(crate) fn max_active(&self) -> usize {
+        self.max_active.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Brain for CountingBrain {
+    async fn process(&self, message: InboundMessage) -> Result<OutboundMessage, BrainError> {
+        let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_active.fetch_max(active, Ordering::SeqCst);
+        self.texts.lock().unwrap().push(message.text.clone());
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        self.processed.lock().unwrap().push(message.sender.clone());
+        self.active.fetch_sub(1, Ordering::SeqCst);
+        Ok(OutboundMessage::reply_to(&message, message.text.clone()))
+    }
+
+    fn name(&self) -> &str {
+        "counting"
+    }
+}
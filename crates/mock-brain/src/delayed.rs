@@ -3,6 +3,7 @@
 use std::time::Duration;
 
 use brain_core::{async_trait, Brain, BrainError, InboundMessage, OutboundMessage};
+use tokio::sync::mpsc;
 use tokio::time::sleep;
 
 /// A brain that wraps another brain and adds artificial delay.
@@ -37,6 +38,15 @@ impl<B: Brain> Brain for DelayedBrain<B> {
         self.inner.process(message).await
     }
 
+    async fn process_stream(
+        &self,
+        message: InboundMessage,
+        chunks: mpsc::Sender<String>,
+    ) -> Result<OutboundMessage, BrainError> {
+        sleep(self.delay).await;
+        self.inner.process_stream(message, chunks).await
+    }
+
     fn name(&self) -> &str {
         "DelayedBrain"
     }
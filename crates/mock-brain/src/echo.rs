@@ -1,6 +1,10 @@
 //! Echo brain implementation - echoes messages back.
 
+use std::time::Duration;
+
 use brain_core::{async_trait, Brain, BrainError, InboundMessage, OutboundMessage};
+use tokio::sync::mpsc;
+use tokio::time::sleep;
 
 /// A simple brain that echoes messages back to the sender.
 ///
@@ -9,6 +13,8 @@ use brain_core::{async_trait, Brain, BrainError, InboundMessage, OutboundMessage
 pub struct EchoBrain {
     /// Optional prefix to add before the echo.
     prefix: Option<String>,
+    /// Simulated delay per word, if streaming.
+    delay_per_token: Option<Duration>,
 }
 
 impl EchoBrain {
@@ -30,17 +36,79 @@ impl EchoBrain {
     pub fn with_prefix(prefix: impl Into<String>) -> Self {
         Self {
             prefix: Some(prefix.into()),
+            delay_per_token: None,
+        }
+    }
+
+    /// Create an EchoBrain that streams its echo word by word.
+    ///
+    /// Each word is delayed by `delay_per_token`, so a response of N words
+    /// takes about N × `delay_per_token`. Useful for load-testing streaming
+    /// paths and typing heartbeats without a real model.
+    pub fn streaming(delay_per_token: Duration) -> Self {
+        Self {
+            prefix: None,
+            delay_per_token: Some(delay_per_token),
+        }
+    }
+
+    fn response_text(&self, message: &InboundMessage) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{}{}", prefix, message.text),
+            None => message.text.clone(),
+        }
+    }
+}
+
+/// Split text into word chunks that concatenate back to the original.
+///
+/// Whitespace is attached to the end of the preceding word.
+fn word_chunks(text: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut seen_word = false;
+    let mut prev_whitespace = false;
+
+    for (i, c) in text.char_indices() {
+        let whitespace = c.is_whitespace();
+        if !whitespace && prev_whitespace && seen_word {
+            chunks.push(&text[start..i]);
+            start = i;
         }
+        seen_word |= !whitespace;
+        prev_whitespace = whitespace;
+    }
+
+    if start < text.len() {
+        chunks.push(&text[start..]);
     }
+    chunks
 }
 
 #[async_trait]
 impl Brain for EchoBrain {
     async fn process(&self, message: InboundMessage) -> Result<OutboundMessage, BrainError> {
-        let response_text = match &self.prefix {
-            Some(prefix) => format!("{}{}", prefix, message.text),
-            None => message.text.clone(),
-        };
+        let response_text = self.response_text(&message);
+
+        if let Some(delay) = self.delay_per_token {
+            sleep(delay * word_chunks(&response_text).len() as u32).await;
+        }
+
+        Ok(OutboundMessage::reply_to(&message, response_text))
+    }
+
+    async fn process_stream(
+        &self,
+        message: InboundMessage,
+        chunks: mpsc::Sender<String>,
+    ) -> Result<OutboundMessage, BrainError> {
+        let response_text = self.response_text(&message);
+        let delay = self.delay_per_token.unwrap_or_default();
+
+        for chunk in word_chunks(&response_text) {
+            sleep(delay).await;
+            let _ = chunks.send(chunk.to_string()).await;
+        }
 
         Ok(OutboundMessage::reply_to(&message, response_text))
     }
@@ -96,4 +164,49 @@ mod tests {
         let brain = EchoBrain::new();
         assert!(brain.is_ready().await);
     }
+
+    #[tokio::test]
+    async fn test_streaming_chunks_per_word() {
+        let brain = EchoBrain::streaming(Duration::ZERO);
+        let text = "the quick  brown fox\njumps";
+        let msg = InboundMessage::direct("+15551234567", text, 1234567890);
+        let (tx, mut rx) = mpsc::channel(16);
+
+        let response = brain.process_stream(msg, tx).await.unwrap();
+
+        let mut chunks = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+            chunks.push(chunk);
+        }
+        assert_eq!(chunks.len(), text.split_whitespace().count());
+        assert_eq!(chunks.concat(), text);
+        assert_eq!(response.text, text);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_total_delay() {
+        let brain = EchoBrain::streaming(Duration::from_millis(20));
+        let msg = InboundMessage::direct("+15551234567", "one two three four five", 1);
+        let (tx, mut rx) = mpsc::channel(16);
+
+        let start = std::time::Instant::now();
+        let handle = tokio::spawn(async move { brain.process_stream(msg, tx).await });
+        let mut count = 0;
+        while rx.recv().await.is_some() {
+            count += 1;
+        }
+        handle.await.unwrap().unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(count, 5);
+        assert!(elapsed >= Duration::from_millis(100));
+        assert!(elapsed < Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_word_chunks_edges() {
+        assert!(word_chunks("").is_empty());
+        assert_eq!(word_chunks("  lead"), vec!["  lead"]);
+        assert_eq!(word_chunks("a b "), vec!["a ", "b "]);
+    }
 }
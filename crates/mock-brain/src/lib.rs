@@ -4,6 +4,8 @@
 //! - `EchoBrain` - Echoes messages back
 //! - `PrefixBrain` - Adds prefix/suffix to messages
 //! - `DelayedBrain` - Wraps another brain with artificial delay
+//! - `RecordingBrain` / `ReplayBrain` - Record real responses to a file and
+//!   replay them for deterministic tests
 //!
 //! For production AI processing, use the `maple-brain` crate instead.
 //!
@@ -33,6 +35,7 @@
 mod echo;
 mod prefix;
 mod delayed;
+mod replay;
 
 // Optional signal-daemon integration
 #[cfg(feature = "signal-daemon")]
@@ -45,6 +48,7 @@ pub use brain_core::{async_trait, Brain, BrainError, InboundAttachment, InboundM
 pub use echo::EchoBrain;
 pub use prefix::PrefixBrain;
 pub use delayed::DelayedBrain;
+pub use replay::{load_recordings, message_hash, RecordingBrain, Recordings, ReplayBrain};
 
 // Re-export signal integration types at crate root when feature is enabled
 #[cfg(feature = "signal-daemon")]
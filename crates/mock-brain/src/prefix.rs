@@ -4,11 +4,13 @@ use brain_core::{async_trait, Brain, BrainError, InboundMessage, OutboundMessage
 
 /// A brain that transforms messages by adding prefix and/or suffix.
 ///
-/// Useful for testing message transformation without AI.
+/// Useful for testing message transformation without AI. A template can be
+/// used instead of a static prefix/suffix; see [`PrefixBrain::with_template`].
 #[derive(Debug, Clone)]
 pub struct PrefixBrain {
     prefix: String,
     suffix: String,
+    template: Option<String>,
 }
 
 impl PrefixBrain {
@@ -17,6 +19,27 @@ impl PrefixBrain {
         Self {
             prefix: prefix.into(),
             suffix: suffix.into(),
+            template: None,
+        }
+    }
+
+    /// Create a brain that renders each response from a template.
+    ///
+    /// Supported placeholders are `{sender}`, `{timestamp}` and `{text}`.
+    /// Unknown placeholders are left as-is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mock_brain::PrefixBrain;
+    ///
+    /// let brain = PrefixBrain::with_template("[{sender}] {text}");
+    /// // Will respond with "[+15551234567] <original message>"
+    /// ```
+    pub fn with_template(template: impl Into<String>) -> Self {
+        Self {
+            template: Some(template.into()),
+            ..Self::default()
         }
     }
 
@@ -37,10 +60,42 @@ impl Default for PrefixBrain {
     }
 }
 
+/// Interpolate known placeholders in a single pass.
+///
+/// Values are never re-scanned, so message text containing `{sender}` stays
+/// literal.
+fn render_template(template: &str, message: &InboundMessage) -> String {
+    let mut output = String::with_capacity(template.len() + message.text.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+
+        match &after[..end] {
+            "sender" => output.push_str(&message.sender),
+            "timestamp" => output.push_str(&message.timestamp.to_string()),
+            "text" => output.push_str(&message.text),
+            _ => output.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
 #[async_trait]
 impl Brain for PrefixBrain {
     async fn process(&self, message: InboundMessage) -> Result<OutboundMessage, BrainError> {
-        let response_text = format!("{}{}{}", self.prefix, message.text, self.suffix);
+        let response_text = match &self.template {
+            Some(template) => render_template(template, &message),
+            None => format!("{}{}{}", self.prefix, message.text, self.suffix),
+        };
         Ok(OutboundMessage::reply_to(&message, response_text))
     }
 
@@ -79,4 +134,39 @@ mod tests {
         let response = brain.process(msg).await.unwrap();
         assert_eq!(response.text, "[Bot] help");
     }
+
+    #[tokio::test]
+    async fn test_template_variables() {
+        let msg = InboundMessage::direct("+15551234567", "hi", 1234567890);
+
+        let cases = [
+            ("from {sender}", "from +15551234567"),
+            ("at {timestamp}", "at 1234567890"),
+            ("> {text}", "> hi"),
+            ("{sender}@{timestamp}: {text}", "+15551234567@1234567890: hi"),
+        ];
+        for (template, expected) in cases {
+            let brain = PrefixBrain::with_template(template);
+            let response = brain.process(msg.clone()).await.unwrap();
+            assert_eq!(response.text, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_template_unknown_placeholders_are_literal() {
+        let brain = PrefixBrain::with_template("{greeting} {text} {} {unclosed");
+        let msg = InboundMessage::direct("+15551234567", "{sender}", 1);
+
+        let response = brain.process(msg).await.unwrap();
+        assert_eq!(response.text, "{greeting} {sender} {} {unclosed");
+    }
+
+    #[tokio::test]
+    async fn test_plain_prefix_is_not_templated() {
+        let brain = PrefixBrain::new("{sender} ", "");
+        let msg = InboundMessage::direct("+15551234567", "test", 1);
+
+        let response = brain.process(msg).await.unwrap();
+        assert_eq!(response.text, "{sender} test");
+    }
 }
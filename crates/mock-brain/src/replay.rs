@@ -0,0 +1,235 @@
+//! Record/replay brains for golden-file testing.
+//!
+//! [`RecordingBrain`] wraps a real brain and writes every response to a JSON
+//! file keyed by [`message_hash`]. [`ReplayBrain`] reads such a file back and
+//! answers from it, so end-to-end tests run deterministically and offline.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use brain_core::{async_trait, Brain, BrainError, InboundMessage, OutboundMessage};
+use sha2::{Digest, Sha256};
+
+/// Recorded responses keyed by message hash.
+///
+/// A `BTreeMap` keeps golden files stable across runs.
+pub type Recordings = BTreeMap<String, OutboundMessage>;
+
+/// Hash the parts of a message that determine its response.
+///
+/// Covers the sender, group and text. Timestamps are left out so the same
+/// conversation replays regardless of when it is sent.
+pub fn message_hash(message: &InboundMessage) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(message.sender.as_bytes());
+    hasher.update([0]);
+    hasher.update(message.group_id.as_deref().unwrap_or("").as_bytes());
+    hasher.update([0]);
+    hasher.update(message.text.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Load recordings from a JSON file.
+pub fn load_recordings(path: impl AsRef<Path>) -> Result<Recordings, BrainError> {
+    let path = path.as_ref();
+    let json = fs::read_to_string(path).map_err(|e| {
+        BrainError::Configuration(format!("failed to read {}: {}", path.display(), e))
+    })?;
+    serde_json::from_str(&json).map_err(|e| {
+        BrainError::Configuration(format!("invalid recordings in {}: {}", path.display(), e))
+    })
+}
+
+/// A brain that answers from recorded responses.
+///
+/// Messages without a recording fail with `ProcessingFailed`, which makes a
+/// missing golden entry obvious instead of silently falling back.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayBrain {
+    recordings: Recordings,
+}
+
+impl ReplayBrain {
+    /// Create a replay brain from a map of message hash to response.
+    pub fn new(recordings: Recordings) -> Self {
+        Self { recordings }
+    }
+
+    /// Create a replay brain from a file written by [`RecordingBrain`].
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, BrainError> {
+        load_recordings(path).map(Self::new)
+    }
+
+    /// Add a canned response for a message.
+    pub fn with_response(mut self, message: &InboundMessage, response: OutboundMessage) -> Self {
+        self.recordings.insert(message_hash(message), response);
+        self
+    }
+
+    /// Number of recorded responses.
+    pub fn len(&self) -> usize {
+        self.recordings.len()
+    }
+
+    /// Whether there are no recorded responses.
+    pub fn is_empty(&self) -> bool {
+        self.recordings.is_empty()
+    }
+}
+
+#[async_trait]
+impl Brain for ReplayBrain {
+    async fn process(&self, message: InboundMessage) -> Result<OutboundMessage, BrainError> {
+        let hash = message_hash(&message);
+        self.recordings.get(&hash).cloned().ok_or_else(|| {
+            BrainError::ProcessingFailed(format!("no recorded response for message {}", hash))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "ReplayBrain"
+    }
+}
+
+/// A brain that wraps another brain and records its responses to a file.
+///
+/// Existing recordings in the file are kept, so a suite can be re-recorded
+/// incrementally. The file is rewritten after every response.
+pub struct RecordingBrain<B: Brain> {
+    inner: B,
+    path: PathBuf,
+    recordings: Mutex<Recordings>,
+}
+
+impl<B: Brain> RecordingBrain<B> {
+    /// Create a recording brain that writes to `path`.
+    pub fn new(inner: B, path: impl Into<PathBuf>) -> Result<Self, BrainError> {
+        let path = path.into();
+        let recordings = if path.exists() {
+            load_recordings(&path)?
+        } else {
+            Recordings::new()
+        };
+
+        Ok(Self {
+            inner,
+            path,
+            recordings: Mutex::new(recordings),
+        })
+    }
+
+    /// Snapshot of everything recorded so far.
+    pub fn recordings(&self) -> Recordings {
+        self.recordings.lock().unwrap().clone()
+    }
+
+    fn record(&self, hash: String, response: &OutboundMessage) -> Result<(), BrainError> {
+        let json = {
+            let mut recordings = self.recordings.lock().unwrap();
+            recordings.insert(hash, response.clone());
+            serde_json::to_string_pretty(&*recordings)
+                .map_err(|e| BrainError::ProcessingFailed(e.to_string()))?
+        };
+        fs::write(&self.path, json).map_err(|e| {
+            BrainError::ProcessingFailed(format!(
+                "failed to write {}: {}",
+                self.path.display(),
+                e
+            ))
+        })
+    }
+}
+
+#[async_trait]
+impl<B: Brain> Brain for RecordingBrain<B> {
+    async fn process(&self, message: InboundMessage) -> Result<OutboundMessage, BrainError> {
+        let hash = message_hash(&message);
+        let response = self.inner.process(message).await?;
+        self.record(hash, &response)?;
+        Ok(response)
+    }
+
+    fn name(&self) -> &str {
+        "RecordingBrain"
+    }
+
+    async fn is_ready(&self) -> bool {
+        self.inner.is_ready().await
+    }
+
+    async fn shutdown(&self) -> Result<(), BrainError> {
+        self.inner.shutdown().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PrefixBrain;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("mock_brain_{}_{}.json", name, std::process::id()))
+    }
+
+    fn conversation() -> Vec<InboundMessage> {
+        vec![
+            InboundMessage::direct("+15551234567", "Hello!", 1),
+            InboundMessage::direct("+15551234567", "What's the weather?", 2),
+            InboundMessage::group("+15551234567", "Hello!", 3, "group-1"),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_is_identical() {
+        let path = temp_path("record_replay");
+        let _ = fs::remove_file(&path);
+
+        let recorder = RecordingBrain::new(PrefixBrain::new("[", "]"), &path).unwrap();
+        let mut recorded = Vec::new();
+        for message in conversation() {
+            recorded.push(recorder.process(message).await.unwrap());
+        }
+        assert_eq!(recorder.recordings().len(), 3);
+
+        let replay = ReplayBrain::from_file(&path).unwrap();
+        assert_eq!(replay.len(), 3);
+        for (message, expected) in conversation().into_iter().zip(recorded) {
+            // Replay ignores timestamps
+            let message = InboundMessage {
+                timestamp: 99,
+                ..message
+            };
+            assert_eq!(replay.process(message).await.unwrap(), expected);
+        }
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_replay_unknown_message_fails() {
+        let known = InboundMessage::direct("+15551234567", "ping", 1);
+        let replay =
+            ReplayBrain::default().with_response(&known, OutboundMessage::reply_to(&known, "pong"));
+
+        assert_eq!(replay.process(known).await.unwrap().text, "pong");
+
+        let unknown = InboundMessage::direct("+15551234567", "something else", 1);
+        assert!(matches!(
+            replay.process(unknown).await,
+            Err(BrainError::ProcessingFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_message_hash_distinguishes_groups() {
+        let direct = InboundMessage::direct("+15551234567", "Hello!", 1);
+        let group = InboundMessage::group("+15551234567", "Hello!", 1, "group-1");
+        assert_ne!(message_hash(&direct), message_hash(&group));
+        assert_eq!(
+            message_hash(&direct),
+            message_hash(&InboundMessage::direct("+15551234567", "Hello!", 42))
+        );
+    }
+}
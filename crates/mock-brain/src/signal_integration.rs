@@ -337,6 +337,7 @@ mod tests {
             text: "Hello group".to_string(),
             is_group: true,
             styles: Vec::new(),
+            routing_debug: None,
         };
         assert!(group.recipient_number().is_none());
         assert_eq!(group.group_id(), Some("group123"));
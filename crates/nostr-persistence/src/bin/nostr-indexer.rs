@@ -6,8 +6,10 @@ use clap::Parser;
 use tracing::info;
 
 use nostr_persistence::{
-    IndexerConfig, NostrIndexer, NostrIndexerImpl, NostrKinds, NoopCodec, SecretBoxCodec,
+    IndexerConfig, Nip44Codec, NostrIndexer, NostrIndexerImpl, NostrKinds, NoopCodec,
+    PayloadCodec, SecretBoxCodec,
 };
+use nostr_sdk::prelude::{Keys, PublicKey};
 
 #[derive(Debug, Parser)]
 #[command(name = "nostr-indexer")]
@@ -27,6 +29,15 @@ struct Args {
     limit: Option<u64>,
     #[arg(long)]
     secretbox_key: Option<String>,
+    /// Our secret key for reading NIP-44 v2 payloads encrypted to us by each author
+    #[arg(long)]
+    nip44_key: Option<String>,
+    /// Index chunk refs even when their text does not match chunk_hash
+    #[arg(long)]
+    skip_hash_verification: bool,
+    /// Audit what the relays would deliver and print a JSON report; nothing is indexed
+    #[arg(long)]
+    verify: bool,
 }
 
 #[tokio::main]
@@ -43,18 +54,36 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Arc::new(NoopCodec)
     };
 
+    let mut decoders: Vec<Arc<dyn PayloadCodec>> = Vec::new();
+    if let Some(key) = args.nip44_key {
+        let keys = Keys::parse(&key)?;
+        let authors = args
+            .author
+            .iter()
+            .map(|author| PublicKey::parse(author))
+            .collect::<Result<Vec<_>, _>>()?;
+        decoders.push(Arc::new(Nip44Codec::with_peers(&keys, &authors)?));
+    }
+
     let config = IndexerConfig {
-        relays: args.relay,
-        authors: args.author,
+        relays: args.relay.clone(),
+        authors: args.author.clone(),
         timeout: Duration::from_secs(args.timeout_secs),
         kinds: NostrKinds::default(),
         db_path: args.db,
         backfill_since: args.since,
         backfill_limit: args.limit,
         codec,
+        decoders,
+        verify_hashes: !args.skip_hash_verification,
     };
 
     let indexer = NostrIndexerImpl::new(config).await?;
+    if args.verify {
+        let report = indexer.verify(&args.relay, &args.author).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
     info!(db = %indexer.db_path().display(), "Starting Nostr indexer");
 
     indexer.backfill().await?;
@@ -6,9 +6,10 @@ use clap::Parser;
 use tracing::info;
 
 use nostr_persistence::{
-    DocManifest, NostrKinds, NostrPublisher, NostrPublisherImpl, NoopCodec, PublisherConfig,
-    SecretBoxCodec,
+    DocManifest, Nip44Codec, NostrKinds, NostrPublisher, NostrPublisherImpl, NoopCodec,
+    PublisherConfig, SecretBoxCodec, VersionedPayload,
 };
+use nostr_sdk::prelude::{Keys, PublicKey};
 
 #[derive(Debug, Parser)]
 #[command(name = "nostr-publish-fixture")]
@@ -26,6 +27,9 @@ struct Args {
     timeout_secs: u64,
     #[arg(long)]
     secretbox_key: Option<String>,
+    /// Encrypt with NIP-44 v2 to this pubkey instead of secretbox
+    #[arg(long, conflicts_with = "secretbox_key")]
+    nip44_recipient: Option<String>,
 }
 
 #[tokio::main]
@@ -34,9 +38,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
     let bytes = std::fs::read(&args.doc)?;
-    let doc: DocManifest = serde_json::from_slice(&bytes)?;
+    let doc = DocManifest::from_versioned_json(&bytes)?;
 
-    let codec: Arc<dyn nostr_persistence::PayloadCodec> = if let Some(key) = args
+    let codec: Arc<dyn nostr_persistence::PayloadCodec> = if let Some(recipient) =
+        args.nip44_recipient
+    {
+        let keys = Keys::parse(&args.key)?;
+        Arc::new(Nip44Codec::new(&keys, &PublicKey::parse(&recipient)?)?)
+    } else if let Some(key) = args
         .secretbox_key
         .or_else(|| std::env::var("NOSTR_SECRETBOX_KEY").ok())
     {
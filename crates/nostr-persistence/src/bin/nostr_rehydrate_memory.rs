@@ -57,6 +57,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         backfill_since: args.since,
         backfill_limit: None,
         codec,
+        decoders: Vec::new(),
+        verify_hashes: true,
     };
 
     let indexer = NostrIndexerImpl::new(config).await?;
@@ -65,6 +65,11 @@ pub struct IndexerConfig {
     pub backfill_since: Option<u64>,
     pub backfill_limit: Option<u64>,
     pub codec: Arc<dyn PayloadCodec>,
+    /// Extra codecs accepted on read, chosen by the event's `enc` tag
+    /// (e.g. NIP-44 alongside secretbox).
+    pub decoders: Vec<Arc<dyn PayloadCodec>>,
+    /// Reject chunk refs whose inline text does not hash to `chunk_hash`.
+    pub verify_hashes: bool,
 }
 
 impl IndexerConfig {
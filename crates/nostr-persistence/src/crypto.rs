@@ -1,6 +1,8 @@
 use std::env;
 
 use base64::Engine;
+use nostr_sdk::nips::nip44::v2::{decrypt_to_bytes, encrypt_to_bytes, ConversationKey};
+use nostr_sdk::prelude::{Keys, PublicKey};
 use rand_core::{OsRng, RngCore};
 use thiserror::Error;
 use xsalsa20poly1305::aead::{Aead, KeyInit};
@@ -9,6 +11,7 @@ use xsalsa20poly1305::{Key, Nonce, XSalsa20Poly1305};
 use crate::events::enc_tag;
 
 const SECRETBOX_TAG: &str = "secretbox-v1";
+const NIP44_TAG: &str = "nip44-v2";
 const SECRETBOX_KEY_LEN: usize = 32;
 const SECRETBOX_NONCE_LEN: usize = 24;
 
@@ -16,6 +19,8 @@ const SECRETBOX_NONCE_LEN: usize = 24;
 pub enum CryptoError {
     #[error("missing secretbox key")]
     MissingKey,
+    #[error("missing nip44 peer pubkey")]
+    MissingPeer,
     #[error("invalid secretbox key length: {0}")]
     InvalidKeyLength(usize),
     #[error("invalid ciphertext length: {0}")]
@@ -26,6 +31,8 @@ pub enum CryptoError {
     Hex(#[from] hex::FromHexError),
     #[error("crypto failure")]
     Aead,
+    #[error("nip44 error: {0}")]
+    Nip44(#[from] nostr_sdk::nips::nip44::Error),
 }
 
 pub trait PayloadCodec: Send + Sync {
@@ -51,9 +58,14 @@ impl PayloadCodec for NoopCodec {
     }
 }
 
+/// Secretbox codec with key rotation support.
+///
+/// Keys are ordered current first, then previous keys. New payloads are
+/// always encrypted with the current key; decoding tries each key in order so
+/// events encrypted before a rotation stay readable.
 #[derive(Debug, Clone)]
 pub struct SecretBoxCodec {
-    key: [u8; SECRETBOX_KEY_LEN],
+    keys: Vec<[u8; SECRETBOX_KEY_LEN]>,
 }
 
 impl SecretBoxCodec {
@@ -62,9 +74,22 @@ impl SecretBoxCodec {
         Self::from_str(&value)
     }
 
+    /// Parse a single key or a comma-separated list (current key first).
     pub fn from_str(value: &str) -> Result<Self, CryptoError> {
-        let key_bytes = decode_key(value)?;
-        Ok(Self { key: key_bytes })
+        let keys = value
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(decode_key)
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            return Err(CryptoError::MissingKey);
+        }
+        Ok(Self { keys })
+    }
+
+    fn current_key(&self) -> &[u8; SECRETBOX_KEY_LEN] {
+        &self.keys[0]
     }
 
     pub fn enc_tag() -> crate::events::NostrTag {
@@ -78,7 +103,7 @@ impl SecretBoxCodec {
 
 impl PayloadCodec for SecretBoxCodec {
     fn encode(&self, input: &[u8]) -> Result<Vec<u8>, CryptoError> {
-        let cipher = XSalsa20Poly1305::new(Key::from_slice(&self.key));
+        let cipher = XSalsa20Poly1305::new(Key::from_slice(self.current_key()));
         let mut nonce_bytes = [0u8; SECRETBOX_NONCE_LEN];
         OsRng.fill_bytes(&mut nonce_bytes);
         let nonce = Nonce::from_slice(&nonce_bytes);
@@ -94,11 +119,15 @@ impl PayloadCodec for SecretBoxCodec {
             return Err(CryptoError::InvalidCiphertextLength(input.len()));
         }
         let (nonce_bytes, ciphertext) = input.split_at(SECRETBOX_NONCE_LEN);
-        let cipher = XSalsa20Poly1305::new(Key::from_slice(&self.key));
         let nonce = Nonce::from_slice(nonce_bytes);
-        cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|_| CryptoError::Aead)
+        self.keys
+            .iter()
+            .find_map(|key| {
+                XSalsa20Poly1305::new(Key::from_slice(key))
+                    .decrypt(nonce, ciphertext)
+                    .ok()
+            })
+            .ok_or(CryptoError::Aead)
     }
 
     fn encoding_tag(&self) -> Option<&'static str> {
@@ -106,6 +135,63 @@ impl PayloadCodec for SecretBoxCodec {
     }
 }
 
+/// NIP-44 v2 codec for interoperability with standard Nostr tooling.
+///
+/// Payloads are encrypted between our keys and a peer pubkey. The conversation
+/// key is symmetric, so the publisher (encrypting to the indexer's pubkey) and
+/// the indexer (decrypting with the author's pubkey) derive the same key.
+/// With several peers, encoding uses the first and decoding tries each.
+pub struct Nip44Codec {
+    conversation_keys: Vec<ConversationKey>,
+}
+
+impl Nip44Codec {
+    pub fn new(keys: &Keys, peer: &PublicKey) -> Result<Self, CryptoError> {
+        Self::with_peers(keys, std::slice::from_ref(peer))
+    }
+
+    pub fn with_peers(keys: &Keys, peers: &[PublicKey]) -> Result<Self, CryptoError> {
+        let conversation_keys = peers
+            .iter()
+            .map(|peer| ConversationKey::derive(keys.secret_key(), peer))
+            .collect::<Result<Vec<_>, _>>()?;
+        if conversation_keys.is_empty() {
+            return Err(CryptoError::MissingPeer);
+        }
+        Ok(Self { conversation_keys })
+    }
+
+    /// Codec for payloads only readable with our own keys.
+    pub fn to_self(keys: &Keys) -> Result<Self, CryptoError> {
+        Self::new(keys, &keys.public_key())
+    }
+
+    pub fn enc_tag() -> crate::events::NostrTag {
+        enc_tag(NIP44_TAG)
+    }
+
+    pub fn encoding_name() -> &'static str {
+        NIP44_TAG
+    }
+}
+
+impl PayloadCodec for Nip44Codec {
+    fn encode(&self, input: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        Ok(encrypt_to_bytes(&self.conversation_keys[0], input)?)
+    }
+
+    fn decode(&self, input: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        self.conversation_keys
+            .iter()
+            .find_map(|key| decrypt_to_bytes(key, input).ok())
+            .ok_or(CryptoError::Aead)
+    }
+
+    fn encoding_tag(&self) -> Option<&'static str> {
+        Some(NIP44_TAG)
+    }
+}
+
 fn decode_key(value: &str) -> Result<[u8; SECRETBOX_KEY_LEN], CryptoError> {
     let trimmed = value.trim();
     let bytes = if let Some(hex_value) = trimmed.strip_prefix("hex:") {
@@ -132,3 +218,92 @@ fn is_probably_hex(value: &str) -> bool {
 pub fn codec_tag(codec: &dyn PayloadCodec) -> Option<crate::events::NostrTag> {
     codec.encoding_tag().map(enc_tag)
 }
+
+/// Pick the codec whose encoding tag matches an event's `enc` tag.
+pub fn codec_for_tag<'a>(
+    codecs: impl IntoIterator<Item = &'a dyn PayloadCodec>,
+    enc: &str,
+) -> Option<&'a dyn PayloadCodec> {
+    codecs
+        .into_iter()
+        .find(|codec| codec.encoding_tag() == Some(enc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD_KEY: &str = "hex:0000000000000000000000000000000000000000000000000000000000000000";
+    const NEW_KEY: &str = "hex:1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[test]
+    fn decodes_payloads_from_previous_key() {
+        let old = SecretBoxCodec::from_str(OLD_KEY).unwrap();
+        let encrypted = old.encode(b"before rotation").unwrap();
+
+        let rotated = SecretBoxCodec::from_str(&format!("{NEW_KEY}, {OLD_KEY}")).unwrap();
+        assert_eq!(rotated.decode(&encrypted).unwrap(), b"before rotation");
+
+        let new_only = SecretBoxCodec::from_str(NEW_KEY).unwrap();
+        assert!(matches!(new_only.decode(&encrypted), Err(CryptoError::Aead)));
+    }
+
+    #[test]
+    fn encodes_with_current_key() {
+        let rotated = SecretBoxCodec::from_str(&format!("{NEW_KEY},{OLD_KEY}")).unwrap();
+        let encrypted = rotated.encode(b"after rotation").unwrap();
+
+        let new_only = SecretBoxCodec::from_str(NEW_KEY).unwrap();
+        assert_eq!(new_only.decode(&encrypted).unwrap(), b"after rotation");
+        let old_only = SecretBoxCodec::from_str(OLD_KEY).unwrap();
+        assert!(old_only.decode(&encrypted).is_err());
+    }
+
+    #[test]
+    fn nip44_roundtrip_between_peers() {
+        let alice = Keys::generate();
+        let bob = Keys::generate();
+        let sender = Nip44Codec::new(&alice, &bob.public_key()).unwrap();
+        let encrypted = sender.encode("مرحبا via nip44".as_bytes()).unwrap();
+
+        // Standard NIP-44 tooling reads the base64 form of the same bytes
+        let payload = base64::engine::general_purpose::STANDARD.encode(&encrypted);
+        let decrypted =
+            nostr_sdk::nips::nip44::decrypt(bob.secret_key(), &alice.public_key(), payload)
+                .unwrap();
+        assert_eq!(decrypted, "مرحبا via nip44");
+
+        let recipient = Nip44Codec::new(&bob, &alice.public_key()).unwrap();
+        assert_eq!(recipient.decode(&encrypted).unwrap(), "مرحبا via nip44".as_bytes());
+
+        let stranger = Nip44Codec::to_self(&Keys::generate()).unwrap();
+        assert!(stranger.decode(&encrypted).is_err());
+
+        // An indexer following several authors finds the matching conversation
+        let carol = Keys::generate();
+        let indexer =
+            Nip44Codec::with_peers(&bob, &[carol.public_key(), alice.public_key()]).unwrap();
+        assert_eq!(indexer.decode(&encrypted).unwrap(), "مرحبا via nip44".as_bytes());
+    }
+
+    #[test]
+    fn codec_for_tag_dispatches_on_enc() {
+        let secretbox = SecretBoxCodec::from_str(NEW_KEY).unwrap();
+        let nip44 = Nip44Codec::to_self(&Keys::generate()).unwrap();
+        let codecs: [&dyn PayloadCodec; 3] = [&NoopCodec, &secretbox, &nip44];
+
+        let chosen = codec_for_tag(codecs, "nip44-v2").unwrap();
+        assert_eq!(chosen.encoding_tag(), Some(Nip44Codec::encoding_name()));
+        let chosen = codec_for_tag(codecs, "secretbox-v1").unwrap();
+        assert_eq!(chosen.encoding_tag(), Some(SecretBoxCodec::encoding_name()));
+        assert!(codec_for_tag(codecs, "rot13").is_none());
+    }
+
+    #[test]
+    fn rejects_empty_key_list() {
+        assert!(matches!(
+            SecretBoxCodec::from_str(" , "),
+            Err(CryptoError::MissingKey)
+        ));
+    }
+}
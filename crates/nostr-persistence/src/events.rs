@@ -1,10 +1,18 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use nostr_sdk::prelude::*;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tracing::info;
 
 use crate::Error;
 
+/// Current KB payload schema.
+///
+/// - v0: payloads published before `schema_version` existed.
+/// - v1: initial versioned schema.
 pub const SCHEMA_VERSION: u32 = 1;
 
 pub const KIND_DOC_MANIFEST: u16 = 30090;
@@ -72,6 +80,54 @@ impl DocManifest {
     }
 }
 
+/// A KB payload that can be decoded from older schema versions.
+pub trait VersionedPayload: DeserializeOwned {
+    const NAME: &'static str;
+
+    /// Upgrade a raw payload from `version` to `version + 1`.
+    fn upgrade(_payload: &mut Map<String, Value>, _version: u32) {}
+
+    /// Deserialize a payload of any known schema version.
+    ///
+    /// Older payloads (or ones without `schema_version`, treated as v0) are
+    /// upgraded one version at a time and stamped with [`SCHEMA_VERSION`];
+    /// current and newer ones are parsed as-is.
+    fn from_versioned_json(bytes: &[u8]) -> Result<Self, Error> {
+        let mut value: Value = serde_json::from_slice(bytes)?;
+        if let Some(payload) = value.as_object_mut() {
+            let version = payload
+                .get("schema_version")
+                .and_then(Value::as_u64)
+                .map_or(0, |version| version as u32);
+            if version < SCHEMA_VERSION {
+                for step in version..SCHEMA_VERSION {
+                    Self::upgrade(payload, step);
+                }
+                payload.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+                info!(
+                    payload = Self::NAME,
+                    from = version,
+                    to = SCHEMA_VERSION,
+                    "Upgraded payload schema"
+                );
+            }
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+impl VersionedPayload for DocManifest {
+    const NAME: &'static str = "doc_manifest";
+}
+
+impl VersionedPayload for ChunkRef {
+    const NAME: &'static str = "chunk_ref";
+}
+
+impl VersionedPayload for AccessPolicy {
+    const NAME: &'static str = "access_policy";
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ChunkRef {
     pub schema_version: u32,
@@ -109,6 +165,23 @@ impl ChunkRef {
             text: None,
         }
     }
+
+    /// Whether inline `text` hashes to `chunk_hash`.
+    ///
+    /// Only `sha256:<hex>` (or bare 64-char hex) hashes are checked; chunks
+    /// without text or with another hash scheme pass.
+    pub fn text_matches_hash(&self) -> bool {
+        let Some(text) = self.text.as_deref() else {
+            return true;
+        };
+        let hash = self.chunk_hash.trim();
+        let expected = hash.strip_prefix("sha256:").unwrap_or(hash);
+        if expected.len() != 64 || !expected.chars().all(|c| c.is_ascii_hexdigit()) {
+            return true;
+        }
+        let actual = hex::encode(Sha256::digest(text.as_bytes()));
+        actual.eq_ignore_ascii_case(expected)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -261,6 +334,57 @@ mod tests {
         assert_eq!(chunk, parsed);
     }
 
+    #[test]
+    fn test_unversioned_doc_manifest_upgrades_to_current() {
+        let legacy = serde_json::json!({
+            "created_at": 10,
+            "updated_at": 20,
+            "doc_id": "doc-1",
+            "title": "Title",
+            "lang": "en",
+            "mime": "text/plain",
+            "source_type": "signal_paste",
+            "content_hash": "sha256:doc",
+            "chunks": [
+                {
+                    "chunk_id": "chunk-1",
+                    "ord": 0,
+                    "offsets": { "start": 0, "end": 120 },
+                    "chunk_hash": "sha256:abc",
+                    "blob_ref": null
+                }
+            ]
+        });
+
+        let doc = DocManifest::from_versioned_json(legacy.to_string().as_bytes()).unwrap();
+        assert_eq!(doc.schema_version, SCHEMA_VERSION);
+        assert_eq!(doc.blob_ref, None);
+        assert_eq!(doc.chunks[0].chunk_id, "chunk-1");
+        assert_eq!(doc.updated_at, 20);
+
+        // Current payloads are parsed unchanged
+        let current = serde_json::to_vec(&doc).unwrap();
+        assert_eq!(DocManifest::from_versioned_json(&current).unwrap(), doc);
+    }
+
+    #[test]
+    fn test_unversioned_chunk_ref_upgrades_to_current() {
+        let legacy = serde_json::json!({
+            "created_at": 10,
+            "updated_at": 10,
+            "chunk_id": "chunk-1",
+            "doc_id": "doc-1",
+            "ord": 0,
+            "offsets": { "start": 0, "end": 10 },
+            "chunk_hash": "sha256:chunk",
+            "blob_ref": null
+        });
+
+        let chunk = ChunkRef::from_versioned_json(legacy.to_string().as_bytes()).unwrap();
+        assert_eq!(chunk.schema_version, SCHEMA_VERSION);
+        assert_eq!(chunk.text, None);
+    }
+
     #[test]
     fn test_access_policy_roundtrip() {
         let policy = AccessPolicy::new("workspace-1", vec!["npub123".to_string()]);
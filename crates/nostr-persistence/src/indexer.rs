@@ -8,8 +8,10 @@ use rusqlite::{params, Connection};
 use tracing::{info, warn};
 
 use crate::config::IndexerConfig;
+use crate::crypto::codec_for_tag;
 use crate::events::{
-    tag_value, AccessPolicy, ChunkRef, DocManifest, NostrEvent, NostrTag, TAG_KIND_POLICY,
+    tag_value, AccessPolicy, ChunkRef, DocManifest, NostrEvent, NostrTag, VersionedPayload,
+    TAG_KIND_POLICY,
 };
 use crate::events::{TAG_KIND_CHUNK_REF, TAG_KIND_DOC_MANIFEST};
 use crate::memory::{
@@ -17,6 +19,7 @@ use crate::memory::{
     MEMORY_SCHEMA_VERSION, TAG_KIND_AMAN_CLEAR_CONTEXT, TAG_KIND_AMAN_PREFERENCE,
     TAG_KIND_AMAN_SUMMARY, TAG_KIND_AMAN_TOOL_HISTORY,
 };
+use crate::verify::{InvalidEvent, InvalidReason, VerifyReport};
 use crate::Error;
 
 #[async_trait]
@@ -24,6 +27,15 @@ pub trait NostrIndexer: Send + Sync {
     async fn backfill(&self) -> Result<(), Error>;
     async fn start(&self) -> Result<(), Error>;
     async fn handle_event(&self, event: NostrEvent) -> Result<(), Error>;
+    /// Fetch KB events from `relays` and validate them without writing to SQLite.
+    async fn verify(&self, relays: &[String], authors: &[String]) -> Result<VerifyReport, Error>;
+}
+
+/// Payload of an event that passed verification.
+enum Verified {
+    Manifest(DocManifest),
+    Chunk(ChunkRef),
+    Other,
 }
 
 #[derive(Clone)]
@@ -57,19 +69,22 @@ impl NostrIndexerImpl {
 
     fn decode_content(&self, event: &NostrEvent) -> Result<Vec<u8>, Error> {
         if let Some(enc) = tag_value(&event.tags, "enc") {
-            let expected = self
-                .config
-                .codec
-                .encoding_tag()
-                .unwrap_or("noop");
-            if expected != enc {
+            let codecs = std::iter::once(&self.config.codec)
+                .chain(&self.config.decoders)
+                .map(|codec| codec.as_ref());
+            let Some(codec) = codec_for_tag(codecs, enc) else {
+                let expected = self
+                    .config
+                    .codec
+                    .encoding_tag()
+                    .unwrap_or("noop");
                 return Err(Error::EncodingMismatch {
                     expected: expected.to_string(),
                     actual: enc.to_string(),
                 });
-            }
+            };
             let ciphertext = base64::engine::general_purpose::STANDARD.decode(&event.content)?;
-            Ok(self.config.codec.decode(&ciphertext)?)
+            Ok(codec.decode(&ciphertext)?)
         } else {
             Ok(event.content.as_bytes().to_vec())
         }
@@ -439,7 +454,7 @@ impl NostrIndexerImpl {
         Ok(())
     }
 
-    fn build_filter(&self) -> Result<Filter, Error> {
+    fn build_filter(&self, authors: Vec<PublicKey>) -> Filter {
         let mut filter = Filter::new().kinds([
             self.config.kinds.doc_manifest,
             self.config.kinds.chunk_ref,
@@ -451,8 +466,7 @@ impl NostrIndexerImpl {
             self.config.kinds.memory_subscription_state,
         ]);
 
-        if !self.config.authors.is_empty() {
-            let authors = self.config.author_keys()?;
+        if !authors.is_empty() {
             filter = filter.authors(authors);
         }
 
@@ -464,14 +478,102 @@ impl NostrIndexerImpl {
             filter = filter.limit(limit as usize);
         }
 
-        Ok(filter)
+        filter
+    }
+
+    /// Validate fetched events: signature, `d` tag, payload decoding, schema
+    /// and chunk hashes, then report manifest chunks without a valid chunk ref.
+    fn verify_events<'a>(&self, events: impl IntoIterator<Item = &'a Event>) -> VerifyReport {
+        let mut report = VerifyReport::default();
+        let mut manifests = Vec::new();
+        let mut chunks = Vec::new();
+
+        for event in events {
+            report.total += 1;
+            let parsed = NostrEvent::from_event(event);
+            let verified = match event.verify() {
+                Ok(()) => self.verify_payload(&parsed),
+                Err(_) => Err(InvalidReason::Signature),
+            };
+            match verified {
+                Ok(Verified::Manifest(doc)) => {
+                    report.manifests += 1;
+                    manifests.push(doc);
+                }
+                Ok(Verified::Chunk(chunk)) => {
+                    report.chunk_refs += 1;
+                    chunks.push(chunk);
+                }
+                Ok(Verified::Other) => {}
+                Err(reason) => {
+                    report.invalid.push(InvalidEvent {
+                        event_id: parsed.event_id,
+                        kind: parsed.kind,
+                        reason,
+                    });
+                    continue;
+                }
+            }
+            report.valid += 1;
+        }
+
+        report.find_missing_chunks(&manifests, &chunks);
+        report
+    }
+
+    fn verify_payload(&self, event: &NostrEvent) -> Result<Verified, InvalidReason> {
+        if tag_value(&event.tags, "d").is_none() {
+            return Err(InvalidReason::MissingDTag);
+        }
+        let payload = self
+            .decode_content(event)
+            .map_err(|err| InvalidReason::Decode(err.to_string()))?;
+        fn schema(err: impl std::fmt::Display) -> InvalidReason {
+            InvalidReason::Schema(err.to_string())
+        }
+
+        let kinds = &self.config.kinds;
+        match event.kind {
+            k if k == kinds.doc_manifest.as_u16() => {
+                let doc = DocManifest::from_versioned_json(&payload).map_err(schema)?;
+                Ok(Verified::Manifest(doc))
+            }
+            k if k == kinds.chunk_ref.as_u16() => {
+                let chunk = ChunkRef::from_versioned_json(&payload).map_err(schema)?;
+                if !chunk.text_matches_hash() {
+                    return Err(InvalidReason::HashMismatch);
+                }
+                Ok(Verified::Chunk(chunk))
+            }
+            k if k == kinds.access_policy.as_u16() => {
+                AccessPolicy::from_versioned_json(&payload).map_err(schema)?;
+                Ok(Verified::Other)
+            }
+            k if k == kinds.memory_preference.as_u16() => {
+                serde_json::from_slice::<AmanPreferenceEvent>(&payload).map_err(schema)?;
+                Ok(Verified::Other)
+            }
+            k if k == kinds.memory_summary.as_u16() => {
+                serde_json::from_slice::<AmanSummaryEvent>(&payload).map_err(schema)?;
+                Ok(Verified::Other)
+            }
+            k if k == kinds.memory_tool_history.as_u16() => {
+                serde_json::from_slice::<AmanToolHistoryEvent>(&payload).map_err(schema)?;
+                Ok(Verified::Other)
+            }
+            k if k == kinds.memory_clear_context.as_u16() => {
+                serde_json::from_slice::<AmanClearContextEvent>(&payload).map_err(schema)?;
+                Ok(Verified::Other)
+            }
+            _ => Ok(Verified::Other),
+        }
     }
 }
 
 #[async_trait]
 impl NostrIndexer for NostrIndexerImpl {
     async fn backfill(&self) -> Result<(), Error> {
-        let filter = self.build_filter()?;
+        let filter = self.build_filter(self.config.author_keys()?);
         let events = self
             .client
             .fetch_events(filter, self.config.timeout)
@@ -486,7 +588,7 @@ impl NostrIndexer for NostrIndexerImpl {
     }
 
     async fn start(&self) -> Result<(), Error> {
-        let mut filter = self.build_filter()?;
+        let mut filter = self.build_filter(self.config.author_keys()?);
         filter = filter.since(Timestamp::now());
         self.client.subscribe(filter, None).await?;
 
@@ -509,6 +611,33 @@ impl NostrIndexer for NostrIndexerImpl {
         Ok(())
     }
 
+    async fn verify(&self, relays: &[String], authors: &[String]) -> Result<VerifyReport, Error> {
+        let authors = authors
+            .iter()
+            .map(|value| PublicKey::parse(value).map_err(Error::from))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // A separate client so the audit never touches the indexer's relays
+        let client = Client::default();
+        for relay in relays {
+            client.add_relay(relay).await?;
+        }
+        client.connect().await;
+
+        let events = client
+            .fetch_events(self.build_filter(authors), self.config.timeout)
+            .await?;
+        let report = self.verify_events(events.iter());
+        info!(
+            total = report.total,
+            valid = report.valid,
+            invalid = report.invalid.len(),
+            missing_chunks = report.missing_chunks.len(),
+            "Verified relay events"
+        );
+        Ok(report)
+    }
+
     async fn handle_event(&self, event: NostrEvent) -> Result<(), Error> {
         let payload = self.decode_content(&event)?;
         let d_tag = tag_value(&event.tags, "d");
@@ -519,17 +648,25 @@ impl NostrIndexer for NostrIndexerImpl {
 
         match event.kind {
             k if k == self.config.kinds.doc_manifest.as_u16() => {
-                let doc: DocManifest = serde_json::from_slice(&payload)?;
+                let doc = DocManifest::from_versioned_json(&payload)?;
                 Self::warn_if_tag_mismatch(&event, "k", TAG_KIND_DOC_MANIFEST);
                 self.upsert_doc_manifest(&event, doc)?;
             }
             k if k == self.config.kinds.chunk_ref.as_u16() => {
-                let chunk: ChunkRef = serde_json::from_slice(&payload)?;
+                let chunk = ChunkRef::from_versioned_json(&payload)?;
                 Self::warn_if_tag_mismatch(&event, "k", TAG_KIND_CHUNK_REF);
+                if self.config.verify_hashes && !chunk.text_matches_hash() {
+                    warn!(
+                        event_id = %event.event_id,
+                        chunk_id = %chunk.chunk_id,
+                        "Rejecting chunk ref: text does not match chunk_hash"
+                    );
+                    return Ok(());
+                }
                 self.upsert_chunk_ref(&event, chunk)?;
             }
             k if k == self.config.kinds.access_policy.as_u16() => {
-                let policy: AccessPolicy = serde_json::from_slice(&payload)?;
+                let policy = AccessPolicy::from_versioned_json(&payload)?;
                 Self::warn_if_tag_mismatch(&event, "k", TAG_KIND_POLICY);
                 self.upsert_policy(&event, policy)?;
             }
@@ -667,7 +804,11 @@ mod tests {
     use crate::crypto::NoopCodec;
     use crate::events::{ChunkOffsets, DocChunk, DocManifest, NostrTag};
     use crate::memory::{AmanPreferenceEvent, TAG_KIND_AMAN_PREFERENCE};
+    use crate::config::PublisherConfig;
+    use crate::publish::{NostrPublisher, NostrPublisherImpl};
+    use nostr_relay_builder::MockRelay;
     use rusqlite::OptionalExtension;
+    use sha2::Digest;
 
     #[tokio::test]
     async fn test_replaceable_doc_manifest_upsert() {
@@ -683,6 +824,8 @@ mod tests {
             backfill_since: None,
             backfill_limit: None,
             codec: Arc::new(NoopCodec),
+            decoders: Vec::new(),
+            verify_hashes: true,
         };
 
         let indexer = NostrIndexerImpl::new(config).await.unwrap();
@@ -748,6 +891,200 @@ mod tests {
         let _ = std::fs::remove_file(&db_path);
     }
 
+    #[tokio::test]
+    async fn test_chunk_ref_hash_verification() {
+        let db_path = std::env::temp_dir().join("nostr_test_chunk_hash.db");
+        let _ = std::fs::remove_file(&db_path);
+
+        let config = IndexerConfig {
+            relays: vec![],
+            authors: vec![],
+            timeout: Duration::from_secs(1),
+            kinds: crate::NostrKinds::default(),
+            db_path: db_path.clone(),
+            backfill_since: None,
+            backfill_limit: None,
+            codec: Arc::new(NoopCodec),
+            decoders: Vec::new(),
+            verify_hashes: true,
+        };
+
+        let indexer = NostrIndexerImpl::new(config).await.unwrap();
+
+        let text = "How to set up a VPN";
+        let hash = format!("sha256:{}", hex::encode(sha2::Sha256::digest(text)));
+        let chunk_event = |chunk_id: &str, text: &str| {
+            let mut chunk = ChunkRef::new(
+                chunk_id,
+                "doc-1",
+                0,
+                ChunkOffsets { start: 0, end: 19 },
+                hash.clone(),
+            );
+            chunk.text = Some(text.to_string());
+            NostrEvent {
+                event_id: format!("event-{chunk_id}"),
+                kind: crate::KIND_CHUNK_REF,
+                pubkey: "pubkey".to_string(),
+                created_at: 10,
+                content: serde_json::to_string(&chunk).unwrap(),
+                tags: vec![
+                    NostrTag::new("d", vec![chunk_id.to_string()]),
+                    NostrTag::new("k", vec![TAG_KIND_CHUNK_REF.to_string()]),
+                ],
+                raw_json: "{}".to_string(),
+            }
+        };
+
+        indexer
+            .handle_event(chunk_event("chunk-good", text))
+            .await
+            .unwrap();
+        indexer
+            .handle_event(chunk_event("chunk-forged", "Send your password here"))
+            .await
+            .unwrap();
+
+        let conn = Connection::open(&db_path).unwrap();
+        let stored = |chunk_id: &str| -> Option<String> {
+            conn.query_row(
+                "SELECT text FROM chunks WHERE chunk_id = ?1",
+                params![chunk_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap()
+        };
+        assert_eq!(stored("chunk-good").as_deref(), Some(text));
+        assert_eq!(stored("chunk-forged"), None);
+    }
+
+    #[tokio::test]
+    async fn test_verify_reports_relay_kb_without_writing() {
+        let relay = MockRelay::run().await.unwrap();
+        let url = relay.url().await.to_string();
+        let keys = Keys::generate();
+
+        let db_path = std::env::temp_dir().join("nostr_test_verify.db");
+        let _ = std::fs::remove_file(&db_path);
+        let config = IndexerConfig {
+            relays: vec![],
+            authors: vec![],
+            timeout: Duration::from_secs(5),
+            kinds: crate::NostrKinds::default(),
+            db_path: db_path.clone(),
+            backfill_since: None,
+            backfill_limit: None,
+            codec: Arc::new(NoopCodec),
+            decoders: Vec::new(),
+            verify_hashes: true,
+        };
+        let indexer = NostrIndexerImpl::new(config).await.unwrap();
+
+        let publisher = NostrPublisherImpl::new(PublisherConfig {
+            relays: vec![url.clone()],
+            secret_key: keys.secret_key().to_secret_hex(),
+            min_acks: 1,
+            timeout: Duration::from_secs(5),
+            kinds: crate::NostrKinds::default(),
+            codec: Arc::new(NoopCodec),
+        })
+        .await
+        .unwrap();
+
+        // A manifest listing three chunks
+        let doc_chunk = |ord: u32| DocChunk {
+            chunk_id: format!("chunk-{ord}"),
+            ord,
+            offsets: ChunkOffsets { start: 0, end: 4 },
+            chunk_hash: "sha256:chunk".to_string(),
+            blob_ref: None,
+        };
+        let doc = DocManifest::new(
+            "doc-1",
+            "Title",
+            "en",
+            "text/plain",
+            "test",
+            "sha256:doc",
+            (0..3).map(doc_chunk).collect(),
+        );
+        publisher.publish_doc_manifest(doc, vec![]).await.unwrap();
+
+        // One valid chunk ref, one whose text does not match its hash
+        let chunk_ref = |ord: u32, text: &str| {
+            let hash = hex::encode(sha2::Sha256::digest("good"));
+            let mut chunk = ChunkRef::new(
+                format!("chunk-{ord}"),
+                "doc-1",
+                ord,
+                ChunkOffsets { start: 0, end: 4 },
+                format!("sha256:{hash}"),
+            );
+            chunk.text = Some(text.to_string());
+            chunk
+        };
+        publisher
+            .publish_chunk_ref(chunk_ref(0, "good"), vec![])
+            .await
+            .unwrap();
+        publisher
+            .publish_chunk_ref(chunk_ref(1, "forged"), vec![])
+            .await
+            .unwrap();
+
+        // A chunk ref whose content is not a ChunkRef
+        let client = Client::new(keys.clone());
+        client.add_relay(&url).await.unwrap();
+        client.connect().await;
+        let garbage = EventBuilder::new(Kind::Custom(crate::KIND_CHUNK_REF), "not json")
+            .tag(Tag::identifier("chunk-2"));
+        client.send_event_builder(garbage).await.unwrap();
+
+        let report = indexer
+            .verify(&[url], &[keys.public_key().to_string()])
+            .await
+            .unwrap();
+        assert_eq!(report.total, 4);
+        assert_eq!(report.valid, 2);
+        assert_eq!(report.manifests, 1);
+        assert_eq!(report.chunk_refs, 1);
+        assert_eq!(report.invalid.len(), 2);
+        assert!(report
+            .invalid
+            .iter()
+            .any(|invalid| invalid.reason == InvalidReason::HashMismatch));
+        assert!(report
+            .invalid
+            .iter()
+            .any(|invalid| matches!(invalid.reason, InvalidReason::Schema(_))));
+        let mut missing: Vec<_> = report
+            .missing_chunks
+            .iter()
+            .map(|missing| missing.chunk_id.as_str())
+            .collect();
+        missing.sort();
+        assert_eq!(missing, ["chunk-1", "chunk-2"]);
+
+        // Nothing was written to SQLite
+        let conn = Connection::open(&db_path).unwrap();
+        let stored: i64 = conn
+            .query_row("SELECT COUNT(*) FROM nostr_events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, 0);
+
+        // A tampered event fails signature verification
+        let event = EventBuilder::new(Kind::Custom(crate::KIND_CHUNK_REF), "{}")
+            .tag(Tag::identifier("chunk-3"))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let mut json: serde_json::Value = serde_json::from_str(&event.as_json()).unwrap();
+        json["content"] = "tampered".into();
+        let tampered = Event::from_json(json.to_string()).unwrap();
+        let report = indexer.verify_events([&tampered]);
+        assert_eq!(report.invalid[0].reason, InvalidReason::Signature);
+    }
+
     #[tokio::test]
     async fn test_memory_preference_lww_tiebreak() {
         let db_path = std::env::temp_dir().join("nostr_test_memory_pref.db");
@@ -762,6 +1099,8 @@ mod tests {
             backfill_since: None,
             backfill_limit: None,
             codec: Arc::new(NoopCodec),
+            decoders: Vec::new(),
+            verify_hashes: true,
         };
 
         let indexer = NostrIndexerImpl::new(config).await.unwrap();
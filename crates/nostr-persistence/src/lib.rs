@@ -69,6 +69,8 @@
 //!     backfill_since: None,
 //!     backfill_limit: Some(1000),
 //!     codec: Arc::new(NoopCodec),
+//!     decoders: vec![],
+//!     verify_hashes: true,
 //! };
 //!
 //! let indexer = NostrIndexerImpl::new(config).await?;
@@ -86,10 +88,16 @@
 //! let codec = SecretBoxCodec::from_env()?;
 //! ```
 //!
+//! For interoperability with standard Nostr tooling, [`Nip44Codec`] encrypts
+//! NIP-44 v2 payloads (`enc=nip44-v2`) between the publisher and a peer pubkey.
+//! The indexer picks a codec per event by its `enc` tag from `codec` and
+//! `decoders`.
+//!
 //! # Traits
 //!
 //! - [`NostrPublisher`] - Publish document manifests, chunk refs, and policies
-//! - [`NostrIndexer`] - Subscribe to relays and materialize events into SQLite
+//! - [`NostrIndexer`] - Subscribe to relays and materialize events into SQLite, or
+//!   audit a relay's KB without writing ([`NostrIndexer::verify`])
 
 mod config;
 mod crypto;
@@ -98,14 +106,17 @@ mod events;
 mod indexer;
 mod memory;
 mod publish;
+mod verify;
 
 pub use config::{IndexerConfig, NostrKinds, PublisherConfig};
-pub use crypto::{codec_tag, CryptoError, NoopCodec, PayloadCodec, SecretBoxCodec};
+pub use crypto::{
+    codec_for_tag, codec_tag, CryptoError, Nip44Codec, NoopCodec, PayloadCodec, SecretBoxCodec,
+};
 pub use error::Error;
 pub use events::{
     enc_tag, k_tag, tag_value, unix_timestamp, AccessPolicy, ChunkOffsets, ChunkRef, DocChunk,
-    DocManifest, NostrEvent, NostrTag, KIND_ACCESS_POLICY, KIND_CHUNK_REF, KIND_DOC_MANIFEST,
-    SCHEMA_VERSION, TAG_KIND_CHUNK_REF, TAG_KIND_DOC_MANIFEST, TAG_KIND_POLICY,
+    DocManifest, NostrEvent, NostrTag, VersionedPayload, KIND_ACCESS_POLICY, KIND_CHUNK_REF,
+    KIND_DOC_MANIFEST, SCHEMA_VERSION, TAG_KIND_CHUNK_REF, TAG_KIND_DOC_MANIFEST, TAG_KIND_POLICY,
 };
 pub use indexer::{NostrIndexer, NostrIndexerImpl};
 pub use memory::{
@@ -117,6 +128,7 @@ pub use memory::{
     TAG_KIND_AMAN_SUBSCRIPTION_STATE, TAG_KIND_AMAN_SUMMARY, TAG_KIND_AMAN_TOOL_HISTORY,
 };
 pub use publish::{NostrPublisher, NostrPublisherImpl, PublishResult};
+pub use verify::{InvalidEvent, InvalidReason, MissingChunk, VerifyReport};
 
 /// Crate version.
 pub fn version() -> &'static str {
@@ -156,6 +156,11 @@ fn project_summaries(
         let nostr_relay: Option<String> = row.get(6)?;
         let schema_version: i64 = row.get(7)?;
 
+        // An empty summary marks one that was cleared without a clear event
+        // (forgetting the only summarized exchange)
+        if summary.is_empty() {
+            continue;
+        }
         if let Some(clear_at) = latest_clear.get(&history_key) {
             if updated_at <= *clear_at {
                 continue;
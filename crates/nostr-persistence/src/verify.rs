@@ -0,0 +1,69 @@
+//! Dry-run audit of the KB events a relay would deliver.
+//!
+//! Produced by [`crate::NostrIndexer::verify`]; nothing is written to SQLite.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::events::{ChunkRef, DocManifest};
+
+/// Summary of a relay audit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VerifyReport {
+    /// Events fetched.
+    pub total: usize,
+    /// Events that passed every check.
+    pub valid: usize,
+    /// Valid doc manifests.
+    pub manifests: usize,
+    /// Valid chunk refs.
+    pub chunk_refs: usize,
+    pub invalid: Vec<InvalidEvent>,
+    /// Chunks listed in a valid manifest with no valid chunk ref.
+    pub missing_chunks: Vec<MissingChunk>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InvalidEvent {
+    pub event_id: String,
+    pub kind: u16,
+    pub reason: InvalidReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "detail", rename_all = "snake_case")]
+pub enum InvalidReason {
+    /// Event id or signature does not verify.
+    Signature,
+    MissingDTag,
+    /// Content could not be decoded (encoding tag, base64 or decryption).
+    Decode(String),
+    /// Payload does not match the kind's schema.
+    Schema(String),
+    /// Inline chunk text does not hash to `chunk_hash`.
+    HashMismatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingChunk {
+    pub doc_id: String,
+    pub chunk_id: String,
+}
+
+impl VerifyReport {
+    /// Record the manifest chunks that no valid chunk ref covers.
+    pub(crate) fn find_missing_chunks(&mut self, manifests: &[DocManifest], chunks: &[ChunkRef]) {
+        let present: HashSet<&str> = chunks.iter().map(|chunk| chunk.chunk_id.as_str()).collect();
+        for manifest in manifests {
+            for chunk in &manifest.chunks {
+                if !present.contains(chunk.chunk_id.as_str()) {
+                    self.missing_chunks.push(MissingChunk {
+                        doc_id: manifest.doc_id.clone(),
+                        chunk_id: chunk.chunk_id.clone(),
+                    });
+                }
+            }
+        }
+    }
+}
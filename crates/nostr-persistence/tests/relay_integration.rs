@@ -60,6 +60,8 @@ async fn publish_and_index_fixture() {
         backfill_since: None,
         backfill_limit: None,
         codec: Arc::new(NoopCodec),
+        decoders: Vec::new(),
+        verify_hashes: true,
     })
     .await
     .unwrap();
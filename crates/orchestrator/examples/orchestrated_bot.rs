@@ -382,6 +382,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                                 Err(OrchestratorError::Skipped(reason)) => {
                                     debug!("Message skipped by orchestrator: {}", reason);
                                 }
+                                Err(OrchestratorError::Ignored) => {
+                                    debug!("Message ignored by orchestrator");
+                                }
                                 Err(e) => {
                                     error!("Orchestrator error: {}", e);
 
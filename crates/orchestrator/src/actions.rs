@@ -90,6 +90,32 @@ impl UserPreference {
     }
 }
 
+/// Privacy flags the router may attach to a `search` or `use_tool` action.
+///
+/// They are flattened into the action's JSON and keep the message's
+/// sensitivity and PII known when the plan has no `respond` action.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivacyHints {
+    /// Sensitivity of the user's message, if the router judged it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensitivity: Option<Sensitivity>,
+    /// Whether the message contains personally identifiable information.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub has_pii: bool,
+    /// Types of PII detected (e.g., ["name", "ssn", "medical"]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pii_types: Vec<String>,
+}
+
+/// Order sensitivities from least to most private.
+pub(crate) fn sensitivity_rank(sensitivity: &Sensitivity) -> u8 {
+    match sensitivity {
+        Sensitivity::Insensitive => 0,
+        Sensitivity::Uncertain => 1,
+        Sensitivity::Sensitive => 2,
+    }
+}
+
 /// The routing plan from the first-pass analysis.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RoutingPlan {
@@ -210,6 +236,25 @@ impl RoutingPlan {
         self.actions.iter().any(|a| a.has_pii())
     }
 
+    /// All PII types flagged anywhere in the plan, without duplicates.
+    pub fn pii_types(&self) -> Vec<String> {
+        let mut types: Vec<String> = Vec::new();
+        for pii_type in self.actions.iter().filter_map(|a| a.pii_types()).flatten() {
+            if !types.contains(pii_type) {
+                types.push(pii_type.clone());
+            }
+        }
+        types
+    }
+
+    /// The most private sensitivity any action in the plan states, if any.
+    pub fn strongest_sensitivity(&self) -> Option<Sensitivity> {
+        self.actions
+            .iter()
+            .filter_map(|a| a.sensitivity())
+            .max_by_key(sensitivity_rank)
+    }
+
     /// Check if the plan contains a privacy_choice_response action.
     pub fn has_privacy_choice_response(&self) -> bool {
         self.actions
@@ -278,6 +323,9 @@ pub enum OrchestratorAction {
         /// Personal status message to show user (e.g., "Let me look that up for you...")
         #[serde(default, skip_serializing_if = "Option::is_none")]
         message: Option<String>,
+        /// Sensitivity and PII flags for the message, if the router set them.
+        #[serde(flatten)]
+        privacy: PrivacyHints,
     },
 
     /// Clear conversation context.
@@ -287,6 +335,13 @@ pub enum OrchestratorAction {
         message: Option<String>,
     },
 
+    /// Forget the most recent exchange (undo the last message).
+    ForgetLast {
+        /// Personal confirmation message (e.g., "Done, I've forgotten that.")
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+
     /// Show help information.
     Help,
 
@@ -364,6 +419,9 @@ pub enum OrchestratorAction {
         /// Optional status message to show user while tool runs.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         message: Option<String>,
+        /// Sensitivity and PII flags for the message, if the router set them.
+        #[serde(flatten)]
+        privacy: PrivacyHints,
     },
 
     /// Ask user how to handle detected PII before responding.
@@ -403,6 +461,9 @@ pub enum OrchestratorAction {
     /// View user's profile settings.
     ViewProfile,
 
+    /// Echo the user's stored Bolt 12 offer in full so they can verify it.
+    ViewOffer,
+
     /// Update a profile setting.
     UpdateProfile {
         /// Field to update: "default_model", "email", or "bolt12_offer".
@@ -436,6 +497,7 @@ impl OrchestratorAction {
         Self::Search {
             query: query.into(),
             message: None,
+            privacy: PrivacyHints::default(),
         }
     }
 
@@ -444,6 +506,7 @@ impl OrchestratorAction {
         Self::Search {
             query: query.into(),
             message: Some(message.into()),
+            privacy: PrivacyHints::default(),
         }
     }
 
@@ -459,6 +522,11 @@ impl OrchestratorAction {
         }
     }
 
+    /// Create a forget-last action.
+    pub fn forget_last() -> Self {
+        Self::ForgetLast { message: None }
+    }
+
     /// Create a respond action with sensitivity.
     pub fn respond(sensitivity: Sensitivity) -> Self {
         Self::Respond {
@@ -563,6 +631,7 @@ impl OrchestratorAction {
             name: name.into(),
             args,
             message: None,
+            privacy: PrivacyHints::default(),
         }
     }
 
@@ -576,6 +645,7 @@ impl OrchestratorAction {
             name: name.into(),
             args,
             message: Some(message.into()),
+            privacy: PrivacyHints::default(),
         }
     }
 
@@ -633,6 +703,11 @@ impl OrchestratorAction {
         Self::ViewProfile
     }
 
+    /// Create a view_offer action.
+    pub fn view_offer() -> Self {
+        Self::ViewOffer
+    }
+
     /// Create an update_profile action.
     pub fn update_profile(field: impl Into<String>, value: Option<String>) -> Self {
         Self::UpdateProfile {
@@ -670,6 +745,7 @@ impl OrchestratorAction {
         match self {
             Self::Search { query, .. } => format!("Search: {}", query),
             Self::ClearContext { .. } => "Clear conversation history".to_string(),
+            Self::ForgetLast { .. } => "Forget last exchange".to_string(),
             Self::Help => "Show help information".to_string(),
             Self::Support => "Show support information".to_string(),
             Self::Respond {
@@ -713,6 +789,7 @@ impl OrchestratorAction {
                 None => "Submit to inbox".to_string(),
             },
             Self::ViewProfile => "View profile settings".to_string(),
+            Self::ViewOffer => "View Bolt 12 offer".to_string(),
             Self::UpdateProfile { field, value } => match value {
                 Some(v) => format!("Update profile: {} = {}", field, v),
                 None => format!("Clear profile field: {}", field),
@@ -745,6 +822,7 @@ impl OrchestratorAction {
         match self {
             Self::Respond { has_pii, .. } => *has_pii,
             Self::AskPrivacyChoice { .. } => true, // Always has PII
+            Self::Search { privacy, .. } | Self::UseTool { privacy, .. } => privacy.has_pii,
             _ => false,
         }
     }
@@ -754,6 +832,20 @@ impl OrchestratorAction {
         match self {
             Self::Respond { pii_types, .. } => Some(pii_types),
             Self::AskPrivacyChoice { pii_types, .. } => Some(pii_types),
+            Self::Search { privacy, .. } | Self::UseTool { privacy, .. } => {
+                Some(&privacy.pii_types)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the sensitivity stated by this action, if any.
+    pub fn sensitivity(&self) -> Option<Sensitivity> {
+        match self {
+            Self::Respond { sensitivity, .. } | Self::AskPrivacyChoice { sensitivity, .. } => {
+                Some(*sensitivity)
+            }
+            Self::Search { privacy, .. } | Self::UseTool { privacy, .. } => privacy.sensitivity,
             _ => None,
         }
     }
@@ -858,7 +950,7 @@ mod tests {
         }"#;
 
         let plan: RoutingPlan = serde_json::from_str(json).unwrap();
-        if let OrchestratorAction::Search { query, message } = &plan.actions[0] {
+        if let OrchestratorAction::Search { query, message, .. } = &plan.actions[0] {
             assert_eq!(query, "weather NYC");
             assert_eq!(message.as_deref(), Some("Let me check the forecast..."));
         } else {
@@ -888,6 +980,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_forget_last() {
+        let json = r#"{"actions": [{"type": "forget_last"}]}"#;
+
+        let plan: RoutingPlan = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            plan.actions[0],
+            OrchestratorAction::ForgetLast { message: None }
+        ));
+        assert_eq!(plan.actions[0].description(), "Forget last exchange");
+    }
+
+    #[test]
+    fn test_parse_view_offer() {
+        let json = r#"{"actions": [{"type": "view_offer"}]}"#;
+
+        let plan: RoutingPlan = serde_json::from_str(json).unwrap();
+        assert!(matches!(plan.actions[0], OrchestratorAction::ViewOffer));
+    }
+
     #[test]
     fn test_parse_skip() {
         let json = r#"{"actions": [{"type": "skip", "reason": "not meant for bot"}]}"#;
@@ -1152,7 +1264,7 @@ mod tests {
         let plan: RoutingPlan = serde_json::from_str(json).unwrap();
         assert!(plan.has_use_tool());
 
-        if let OrchestratorAction::UseTool { name, args, message } = &plan.actions[0] {
+        if let OrchestratorAction::UseTool { name, args, message, .. } = &plan.actions[0] {
             assert_eq!(name, "calculator");
             assert_eq!(args.get("expression").unwrap().as_str(), Some("2+2"));
             assert!(message.is_none());
@@ -1161,12 +1273,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_gathering_actions_with_pii() {
+        let json = r#"{"actions": [
+            {"type": "search", "query": "ssn office", "has_pii": true, "pii_types": ["ssn"]},
+            {"type": "use_tool", "name": "weather", "sensitivity": "uncertain",
+             "pii_types": ["ssn", "address"]}
+        ]}"#;
+        let plan: RoutingPlan = serde_json::from_str(json).unwrap();
+
+        assert!(plan.has_pii());
+        assert_eq!(plan.pii_types(), vec!["ssn".to_string(), "address".to_string()]);
+        assert_eq!(plan.strongest_sensitivity(), Some(Sensitivity::Uncertain));
+        assert!(!plan.actions[1].has_pii());
+
+        // Flags round-trip flattened, and are omitted when unset
+        let value = serde_json::to_value(&plan.actions[0]).unwrap();
+        assert_eq!(value["has_pii"], true);
+        assert!(value.get("sensitivity").is_none());
+        let plain = serde_json::to_value(OrchestratorAction::search("weather")).unwrap();
+        assert_eq!(plain, serde_json::json!({"type": "search", "query": "weather"}));
+        assert_eq!(
+            RoutingPlan::respond_only().strongest_sensitivity(),
+            Some(Sensitivity::Insensitive)
+        );
+    }
+
     #[test]
     fn test_parse_use_tool_with_message() {
         let json = r#"{"actions": [{"type": "use_tool", "name": "weather", "args": {"location": "NYC"}, "message": "Checking weather..."}]}"#;
         let plan: RoutingPlan = serde_json::from_str(json).unwrap();
 
-        if let OrchestratorAction::UseTool { name, args, message } = &plan.actions[0] {
+        if let OrchestratorAction::UseTool { name, args, message, .. } = &plan.actions[0] {
             assert_eq!(name, "weather");
             assert_eq!(args.get("location").unwrap().as_str(), Some("NYC"));
             assert_eq!(message.as_deref(), Some("Checking weather..."));
@@ -1182,7 +1320,7 @@ mod tests {
 
         let action = OrchestratorAction::use_tool("web_fetch", args);
 
-        if let OrchestratorAction::UseTool { name, args, message } = action {
+        if let OrchestratorAction::UseTool { name, args, message, .. } = action {
             assert_eq!(name, "web_fetch");
             assert!(args.contains_key("url"));
             assert!(message.is_none());
@@ -0,0 +1,143 @@
+//! Receive-only donation invoices.
+//!
+//! The orchestrator only ever asks a wallet for invoices. [`InvoiceSource`]
+//! is the whole surface it sees, so there is no path from a chat message to
+//! paying or sending funds. [`OfferDecoder`] lets profile updates check that
+//! a user's Bolt 12 offer actually parses; decoding never moves funds either.
+
+use async_trait::async_trait;
+
+#[cfg(feature = "lightning")]
+use donation_wallet::DonationWallet;
+
+/// Description attached to donation invoices.
+pub const DONATION_DESCRIPTION: &str = "Aman Bot Donation";
+
+/// How long donation invoices stay valid, in seconds.
+pub const DONATION_EXPIRY_SECS: i64 = 3600;
+
+/// Something that can create Lightning invoices to receive donations.
+///
+/// Receive-only by design: do not add pay or send methods here.
+#[async_trait]
+pub trait InvoiceSource: Send + Sync {
+    /// Create an invoice and return its encoded (bolt11) string.
+    async fn create_invoice(
+        &self,
+        amount_msats: i64,
+        description: Option<String>,
+        expiry_secs: Option<i64>,
+    ) -> Result<String, String>;
+}
+
+#[cfg(feature = "lightning")]
+#[async_trait]
+impl InvoiceSource for DonationWallet {
+    async fn create_invoice(
+        &self,
+        amount_msats: i64,
+        description: Option<String>,
+        expiry_secs: Option<i64>,
+    ) -> Result<String, String> {
+        DonationWallet::create_invoice(self, amount_msats, description, expiry_secs)
+            .await
+            .map(|transaction| transaction.invoice)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Something that can decode Lightning invoices and offers.
+#[async_trait]
+pub trait OfferDecoder: Send + Sync {
+    /// Decode an encoded invoice or offer, returning the decoded details.
+    async fn decode(&self, encoded: &str) -> Result<String, String>;
+}
+
+#[cfg(feature = "lightning")]
+#[async_trait]
+impl OfferDecoder for DonationWallet {
+    async fn decode(&self, encoded: &str) -> Result<String, String> {
+        DonationWallet::decode(self, encoded)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Create a donation invoice for `amount_sats` (None lets the payer choose).
+pub async fn donation_invoice(
+    source: &dyn InvoiceSource,
+    amount_sats: Option<u64>,
+) -> Result<String, String> {
+    let amount_msats = amount_sats.map(|sats| (sats as i64) * 1000).unwrap_or(0);
+    let invoice = source
+        .create_invoice(
+            amount_msats,
+            Some(DONATION_DESCRIPTION.to_string()),
+            Some(DONATION_EXPIRY_SECS),
+        )
+        .await
+        .map_err(|e| format!("Failed to create Lightning invoice: {}", e))?;
+
+    if invoice.is_empty() {
+        return Err("Failed to generate Lightning invoice: empty invoice returned".to_string());
+    }
+    Ok(invoice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Wallet that records every call it receives.
+    #[derive(Default)]
+    struct MockWallet {
+        calls: Mutex<Vec<(String, i64)>>,
+        invoice: String,
+    }
+
+    #[async_trait]
+    impl InvoiceSource for MockWallet {
+        async fn create_invoice(
+            &self,
+            amount_msats: i64,
+            description: Option<String>,
+            expiry_secs: Option<i64>,
+        ) -> Result<String, String> {
+            assert_eq!(description.as_deref(), Some(DONATION_DESCRIPTION));
+            assert_eq!(expiry_secs, Some(DONATION_EXPIRY_SECS));
+            self.calls
+                .lock()
+                .unwrap()
+                .push(("create_invoice".to_string(), amount_msats));
+            Ok(self.invoice.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_donation_invoice_only_creates_invoices() {
+        let wallet = MockWallet {
+            invoice: "lnbc10u1mock".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(donation_invoice(&wallet, Some(1000)).await.unwrap(), "lnbc10u1mock");
+        assert_eq!(donation_invoice(&wallet, None).await.unwrap(), "lnbc10u1mock");
+
+        let calls = wallet.calls.lock().unwrap().clone();
+        assert_eq!(
+            calls,
+            vec![
+                ("create_invoice".to_string(), 1_000_000),
+                ("create_invoice".to_string(), 0),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_empty_invoice_is_an_error() {
+        let wallet = MockWallet::default();
+        let err = donation_invoice(&wallet, Some(1)).await.unwrap_err();
+        assert!(err.contains("empty invoice"));
+    }
+}
@@ -10,6 +10,10 @@ pub enum OrchestratorError {
     #[error("message skipped: {0}")]
     Skipped(String),
 
+    /// Message was accidental (e.g. a stray "?") and silently ignored.
+    #[error("message ignored")]
+    Ignored,
+
     /// Routing failed.
     #[error("routing failed: {0}")]
     RoutingFailed(String),
@@ -30,3 +34,34 @@ pub enum OrchestratorError {
     #[error("invalid routing plan: {0}")]
     InvalidPlan(String),
 }
+
+impl From<OrchestratorError> for BrainError {
+    /// Map orchestration outcomes onto brain errors, keeping skip and ignore
+    /// distinct from failures so message processors can tell them apart.
+    fn from(err: OrchestratorError) -> Self {
+        match err {
+            OrchestratorError::Skipped(reason) => BrainError::Skipped(reason),
+            OrchestratorError::Ignored => BrainError::Ignored,
+            OrchestratorError::Brain(e) => e,
+            other => BrainError::ProcessingFailed(other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_brain_error_keeps_no_reply_outcomes() {
+        let skipped: BrainError = OrchestratorError::Skipped("off topic".to_string()).into();
+        assert!(matches!(skipped, BrainError::Skipped(ref reason) if reason == "off topic"));
+
+        let ignored: BrainError = OrchestratorError::Ignored.into();
+        assert!(matches!(ignored, BrainError::Ignored));
+
+        let failed: BrainError = OrchestratorError::ToolFailed("boom".to_string()).into();
+        assert!(matches!(failed, BrainError::ProcessingFailed(_)));
+        assert!(!failed.is_no_reply());
+    }
+}
@@ -0,0 +1,498 @@
+//! Concurrent execution of independent data-gathering actions.
+//!
+//! Router plans often contain several `search` / `use_tool` actions before
+//! the final `respond`. Their arguments are fixed when the plan is made, so
+//! consecutive gathering actions never depend on each other's output and can
+//! run at the same time. Everything else (respond, clear_context,
+//! preferences, ...) stays sequential.
+
+use std::collections::HashMap;
+
+use agent_tools::ToolRegistry;
+use brain_core::{ToolExecutor, ToolRequest};
+use futures::future::join_all;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::actions::OrchestratorAction;
+use crate::context::Context;
+use crate::sender::MessageSender;
+
+/// Tool name recorded in history for realtime search.
+pub(crate) const SEARCH_TOOL_NAME: &str = "realtime_search";
+
+/// A data-gathering action borrowed from a routing plan.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum GatherAction<'a> {
+    /// Realtime search via the search executor.
+    Search {
+        query: &'a str,
+        status: Option<&'a str>,
+    },
+    /// A tool from the agent-tools registry.
+    Tool {
+        name: &'a str,
+        args: &'a HashMap<String, Value>,
+        status: Option<&'a str>,
+    },
+}
+
+impl<'a> GatherAction<'a> {
+    /// Borrow a plan action as a gathering action, if it is one.
+    pub(crate) fn from_action(action: &'a OrchestratorAction) -> Option<Self> {
+        match action {
+            OrchestratorAction::Search { query, message, .. } => Some(Self::Search {
+                query,
+                status: message.as_deref(),
+            }),
+            OrchestratorAction::UseTool {
+                name,
+                args,
+                message,
+                ..
+            } => Some(Self::Tool {
+                name,
+                args,
+                status: message.as_deref(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Status message to show the user before running this action.
+    pub(crate) fn status_message(&self) -> Option<String> {
+        match self {
+            Self::Search { query, status } => Some(
+                status
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("Searching: {}", query)),
+            ),
+            Self::Tool { status, .. } => status.map(str::to_string),
+        }
+    }
+}
+
+/// One step of a routing plan.
+#[derive(Debug)]
+pub(crate) enum PlanStep<'a> {
+    /// Consecutive gathering actions that can run concurrently.
+    Gather(Vec<GatherAction<'a>>),
+    /// Any other action, executed on its own.
+    Single(&'a OrchestratorAction),
+}
+
+/// Group a plan into steps, batching runs of consecutive gathering actions.
+pub(crate) fn plan_steps(actions: &[OrchestratorAction]) -> Vec<PlanStep<'_>> {
+    let mut steps = Vec::new();
+    for action in actions {
+        match (GatherAction::from_action(action), steps.last_mut()) {
+            (Some(gather), Some(PlanStep::Gather(batch))) => batch.push(gather),
+            (Some(gather), _) => steps.push(PlanStep::Gather(vec![gather])),
+            (None, _) => steps.push(PlanStep::Single(action)),
+        }
+    }
+    steps
+}
+
+/// Result of one gathering action.
+#[derive(Debug, Clone)]
+pub(crate) struct GatherOutcome {
+    /// Tool name for history (`realtime_search` for searches).
+    pub tool: String,
+    /// Search query, for search results.
+    pub query: Option<String>,
+    /// Whether the action succeeded.
+    pub success: bool,
+    /// Output (or error description).
+    pub content: String,
+}
+
+impl GatherOutcome {
+    /// Add this outcome to the response context.
+    pub(crate) fn apply(&self, context: &mut Context) {
+        match (&self.query, self.success) {
+            (Some(query), true) => context.add_search_result(query, &self.content),
+            (Some(query), false) => {
+                context.add_search_result(query, &format!("Search failed: {}", self.content))
+            }
+            (None, true) => context.add_tool_result(&self.tool, &self.content),
+            (None, false) => context.add_tool_result(&self.tool, &format!("Tool failed: {}", self.content)),
+        }
+    }
+}
+
+/// Send the status message for each action in the batch, in plan order.
+///
+/// Sending a message clears the typing indicator on Signal, so typing is
+/// restarted after each status message. With `suppress` set no status
+/// messages are sent; typing is refreshed once so it stays visible while
+/// the batch runs.
+pub(crate) async fn announce<S: MessageSender + ?Sized>(
+    sender: &S,
+    batch: &[GatherAction<'_>],
+    recipient: &str,
+    is_group: bool,
+    suppress: bool,
+) {
+    if suppress {
+        if let Err(e) = sender.set_typing(recipient, is_group, true).await {
+            warn!("Failed to refresh typing indicator: {}", e);
+        }
+        return;
+    }
+
+    for action in batch {
+        let Some(status) = action.status_message() else {
+            continue;
+        };
+        if let Err(e) = sender.send_message(recipient, &status, is_group).await {
+            warn!("Failed to send status notification: {}", e);
+        }
+
+        // Restart typing indicator after sending message
+        if let Err(e) = sender.set_typing(recipient, is_group, true).await {
+            warn!("Failed to restart typing indicator: {}", e);
+        }
+    }
+}
+
+/// Run a single gathering action.
+async fn run_action(
+    search: &dyn ToolExecutor,
+    tools: &ToolRegistry,
+    action: GatherAction<'_>,
+) -> GatherOutcome {
+    match action {
+        GatherAction::Search { query, .. } => {
+            info!("Executing search: {}", query);
+            let args_json = json!({ "query": query }).to_string();
+            let (success, content) = match ToolRequest::from_call(
+                "orchestrator-search".to_string(),
+                SEARCH_TOOL_NAME.to_string(),
+                &args_json,
+            ) {
+                Ok(request) => {
+                    let result = search.execute(request).await;
+                    if result.success {
+                        info!("Search completed successfully ({} chars)", result.content.len());
+                    } else {
+                        warn!("Search failed: {}", result.content);
+                    }
+                    (result.success, result.content)
+                }
+                Err(e) => (false, format!("Invalid search request: {}", e)),
+            };
+            GatherOutcome {
+                tool: SEARCH_TOOL_NAME.to_string(),
+                query: Some(query.to_string()),
+                success,
+                content,
+            }
+        }
+        GatherAction::Tool { name, args, .. } => {
+            info!("Executing tool '{}' with {} args", name, args.len());
+            let (success, content) = match tools.execute(name, args.clone()).await {
+                Ok(result) => {
+                    if result.success {
+                        info!("Tool '{}' completed successfully ({} chars)", name, result.content.len());
+                    } else {
+                        warn!("Tool '{}' returned failure: {}", name, result.content);
+                    }
+                    (result.success, result.content)
+                }
+                Err(e) => {
+                    warn!("Tool '{}' execution error: {}", name, e);
+                    (false, format!("Tool error: {}", e))
+                }
+            };
+            GatherOutcome {
+                tool: name.to_string(),
+                query: None,
+                success,
+                content,
+            }
+        }
+    }
+}
+
+/// Run gathering actions concurrently.
+///
+/// Outcomes are returned in the same order as `actions`, regardless of
+/// which finishes first, so context assembly is deterministic.
+pub(crate) async fn run_concurrently(
+    search: &dyn ToolExecutor,
+    tools: &ToolRegistry,
+    actions: &[GatherAction<'_>],
+) -> Vec<GatherOutcome> {
+    if actions.len() > 1 {
+        info!("Running {} gathering actions concurrently", actions.len());
+    }
+    join_all(actions.iter().map(|action| run_action(search, tools, *action))).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use agent_tools::{Tool, ToolArgs, ToolError, ToolOutput};
+    use async_trait::async_trait;
+    use brain_core::{InboundMessage, ToolResult};
+    use tokio::sync::Barrier;
+
+    use crate::actions::PrivacyHints;
+    use crate::formatting::format_with_footer;
+    use crate::preferences::AgentIndicator;
+    use crate::sender::{CapturingSender, SentCall};
+
+    /// Tool that waits until every tool sharing the barrier has started.
+    struct BarrierTool {
+        name: &'static str,
+        delay: Duration,
+        barrier: Arc<Barrier>,
+    }
+
+    #[async_trait]
+    impl Tool for BarrierTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "Waits for its peers"
+        }
+
+        async fn execute(&self, _args: ToolArgs) -> Result<ToolOutput, ToolError> {
+            self.barrier.wait().await;
+            tokio::time::sleep(self.delay).await;
+            Ok(ToolOutput::success(format!("{} output", self.name)))
+        }
+    }
+
+    struct StaticSearch;
+
+    #[async_trait]
+    impl ToolExecutor for StaticSearch {
+        async fn execute(&self, request: ToolRequest) -> ToolResult {
+            ToolResult::success(request.id, "search output")
+        }
+
+        fn supported_tools(&self) -> Vec<&str> {
+            vec![SEARCH_TOOL_NAME]
+        }
+    }
+
+    fn use_tool(name: &str) -> OrchestratorAction {
+        OrchestratorAction::UseTool {
+            name: name.to_string(),
+            args: HashMap::new(),
+            message: None,
+            privacy: PrivacyHints::default(),
+        }
+    }
+
+    fn search(query: &str) -> OrchestratorAction {
+        OrchestratorAction::Search {
+            query: query.to_string(),
+            message: None,
+            privacy: PrivacyHints::default(),
+        }
+    }
+
+    fn barrier_registry(parties: usize) -> ToolRegistry {
+        let barrier = Arc::new(Barrier::new(parties));
+        let mut registry = ToolRegistry::new();
+        // The first tool finishes last, so completion order differs from plan order.
+        registry.register(BarrierTool {
+            name: "slow",
+            delay: Duration::from_millis(50),
+            barrier: barrier.clone(),
+        });
+        registry.register(BarrierTool {
+            name: "fast",
+            delay: Duration::from_millis(0),
+            barrier,
+        });
+        registry
+    }
+
+    #[test]
+    fn test_plan_steps_batches_gathering_actions() {
+        let actions = vec![
+            search("btc news"),
+            use_tool("weather"),
+            OrchestratorAction::ClearContext { message: None },
+            use_tool("calculator"),
+            OrchestratorAction::Respond {
+                sensitivity: Default::default(),
+                task_hint: Default::default(),
+                has_pii: false,
+                pii_types: Vec::new(),
+            },
+        ];
+
+        let steps = plan_steps(&actions);
+        assert_eq!(steps.len(), 4);
+        assert!(matches!(&steps[0], PlanStep::Gather(batch) if batch.len() == 2));
+        assert!(matches!(steps[1], PlanStep::Single(OrchestratorAction::ClearContext { .. })));
+        assert!(matches!(&steps[2], PlanStep::Gather(batch) if batch.len() == 1));
+        assert!(matches!(steps[3], PlanStep::Single(OrchestratorAction::Respond { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_tools_dispatched_concurrently() {
+        let registry = barrier_registry(2);
+        let actions = [use_tool("slow"), use_tool("fast")];
+        let batch: Vec<_> = actions.iter().filter_map(GatherAction::from_action).collect();
+
+        // Sequential dispatch would deadlock on the barrier.
+        let outcomes = tokio::time::timeout(
+            Duration::from_secs(5),
+            run_concurrently(&StaticSearch, &registry, &batch),
+        )
+        .await
+        .expect("tools were not dispatched concurrently");
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes.iter().all(|outcome| outcome.success));
+    }
+
+    #[tokio::test]
+    async fn test_context_order_follows_plan() {
+        let registry = barrier_registry(2);
+        let actions = [use_tool("slow"), search("bitcoin"), use_tool("fast")];
+        let batch: Vec<_> = actions.iter().filter_map(GatherAction::from_action).collect();
+
+        let outcomes = run_concurrently(&StaticSearch, &registry, &batch).await;
+        let tools: Vec<_> = outcomes.iter().map(|outcome| outcome.tool.as_str()).collect();
+        assert_eq!(tools, vec!["slow", SEARCH_TOOL_NAME, "fast"]);
+
+        let mut context = Context::new();
+        for outcome in &outcomes {
+            outcome.apply(&mut context);
+        }
+        assert_eq!(context.tools_used(), vec!["search", "slow", "fast"]);
+
+        let augmented = context.augment_message(&InboundMessage::direct("+15550000000", "hi", 1));
+        let slow = augmented.text.find("slow output").unwrap();
+        let fast = augmented.text.find("fast output").unwrap();
+        assert!(slow < fast);
+    }
+
+    #[tokio::test]
+    async fn test_failed_tool_reported_in_context() {
+        let registry = ToolRegistry::new();
+        let actions = [use_tool("missing")];
+        let batch: Vec<_> = actions.iter().filter_map(GatherAction::from_action).collect();
+
+        let outcomes = run_concurrently(&StaticSearch, &registry, &batch).await;
+        assert!(!outcomes[0].success);
+        assert!(outcomes[0].content.starts_with("Tool error: Tool not found"));
+    }
+
+    #[tokio::test]
+    async fn test_search_announces_status_then_typing() {
+        let sender = CapturingSender::new();
+        let actions = [search("bitcoin price")];
+        let batch: Vec<_> = actions.iter().filter_map(GatherAction::from_action).collect();
+
+        announce(&sender, &batch, "+15550000000", false, false).await;
+
+        assert_eq!(
+            sender.calls(),
+            vec![
+                SentCall::Message {
+                    recipient: "+15550000000".to_string(),
+                    text: "Searching: bitcoin price".to_string(),
+                    is_group: false,
+                },
+                SentCall::Typing {
+                    recipient: "+15550000000".to_string(),
+                    is_group: false,
+                    started: true,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_announce_follows_plan_order() {
+        let sender = CapturingSender::new();
+        let actions = [
+            OrchestratorAction::Search {
+                query: "weather nyc".to_string(),
+                message: Some("Checking the forecast...".to_string()),
+                privacy: PrivacyHints::default(),
+            },
+            // Tools without a status message stay silent.
+            use_tool("calculator"),
+            OrchestratorAction::UseTool {
+                name: "bitcoin_price".to_string(),
+                args: HashMap::new(),
+                message: Some("Fetching price...".to_string()),
+                privacy: PrivacyHints::default(),
+            },
+        ];
+        let batch: Vec<_> = actions.iter().filter_map(GatherAction::from_action).collect();
+
+        announce(&sender, &batch, "group-1", true, false).await;
+
+        assert_eq!(
+            sender.messages(),
+            vec!["Checking the forecast...".to_string(), "Fetching price...".to_string()]
+        );
+        assert_eq!(sender.typing(), vec![true, true]);
+        assert!(sender.calls().iter().all(|call| matches!(
+            call,
+            SentCall::Message { recipient, is_group: true, .. }
+                | SentCall::Typing { recipient, is_group: true, .. } if recipient == "group-1"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_suppressed_status_keeps_typing_and_results() {
+        let sender = CapturingSender::new();
+        let registry = barrier_registry(1);
+        let actions = [
+            search("bitcoin price"),
+            OrchestratorAction::UseTool {
+                name: "fast".to_string(),
+                args: HashMap::new(),
+                message: Some("Running tool...".to_string()),
+                privacy: PrivacyHints::default(),
+            },
+        ];
+        let batch: Vec<_> = actions.iter().filter_map(GatherAction::from_action).collect();
+
+        announce(&sender, &batch, "+15550000000", false, true).await;
+        let outcomes = run_concurrently(&StaticSearch, &registry, &batch).await;
+
+        // No interim messages, but typing stays on.
+        assert!(sender.messages().is_empty());
+        assert_eq!(sender.typing(), vec![true]);
+
+        // Results still reach the context used for the final response.
+        let mut context = Context::new();
+        for outcome in &outcomes {
+            outcome.apply(&mut context);
+        }
+        let augmented = context.augment_message(&InboundMessage::direct("+15550000000", "hi", 1));
+        assert!(augmented.text.contains("search output"));
+        assert!(augmented.text.contains("fast output"));
+
+        // The final response is formatted as usual and credits both results.
+        let tools_used = context.tools_used();
+        let response = format_with_footer(
+            "Bitcoin is up today.",
+            AgentIndicator::Speed.label(),
+            None,
+            Some(&tools_used),
+        );
+        assert_eq!(
+            response.text,
+            "Bitcoin is up today.\n\n—\n⚡ Speed · Tools: search, fast"
+        );
+        assert!(sender.messages().is_empty());
+    }
+}
@@ -0,0 +1,370 @@
+//! Admin-only ingestion of Signal attachments into the knowledge base.
+//!
+//! When an allowlisted number sends a `.txt` or `.md` attachment, the file is
+//! chunked with the ingester's logic and published as DocManifest/ChunkRef
+//! events, so the indexer and worker pick it up like any other document.
+
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use brain_core::{InboundAttachment, InboundMessage};
+use ingester::{chunk_document, publish_document, DocumentOptions};
+use nostr_persistence::{
+    MemoryPublisherConfig, NoopCodec, NostrKinds, NostrPublisher, NostrPublisherImpl,
+    PayloadCodec, PublisherConfig,
+};
+use tracing::{info, warn};
+
+/// Comma-separated phone numbers allowed to add documents.
+pub const KB_ADMIN_NUMBERS_ENV: &str = "AMAN_KB_ADMIN_NUMBERS";
+
+/// Largest attachment accepted for ingestion.
+pub const KB_INGEST_MAX_BYTES: u64 = 1024 * 1024;
+
+const SOURCE_TYPE: &str = "signal_attachment";
+
+/// Publishes text attachments from admin numbers to the knowledge base.
+pub struct KbIngestor {
+    publisher: Arc<dyn NostrPublisher>,
+    admins: HashSet<String>,
+}
+
+impl KbIngestor {
+    pub fn new(
+        publisher: Arc<dyn NostrPublisher>,
+        admins: impl IntoIterator<Item = String>,
+    ) -> Self {
+        Self {
+            publisher,
+            admins: admins.into_iter().collect(),
+        }
+    }
+
+    /// Build from `AMAN_KB_ADMIN_NUMBERS` and the Nostr publisher settings
+    /// (`NOSTR_RELAYS`, `NOSTR_SECRET_KEY`, `NOSTR_SECRETBOX_KEY`).
+    ///
+    /// Returns `None` when no admins or relays are configured.
+    pub async fn from_env() -> Option<Self> {
+        let admins = parse_admin_numbers(&env::var(KB_ADMIN_NUMBERS_ENV).unwrap_or_default());
+        if admins.is_empty() {
+            return None;
+        }
+
+        let config = match MemoryPublisherConfig::from_env() {
+            Ok(Some(config)) => publisher_config(config),
+            Ok(None) => {
+                warn!(
+                    "{} is set but NOSTR_RELAYS is not; KB ingestion disabled",
+                    KB_ADMIN_NUMBERS_ENV
+                );
+                return None;
+            }
+            Err(err) => {
+                warn!("Failed to load Nostr config for KB ingestion: {}", err);
+                return None;
+            }
+        };
+
+        match NostrPublisherImpl::new(config).await {
+            Ok(publisher) => {
+                info!("KB ingestion enabled for {} admin number(s)", admins.len());
+                Some(Self::new(Arc::new(publisher), admins))
+            }
+            Err(err) => {
+                warn!("Failed to initialize Nostr KB publisher: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Whether `sender` may add documents.
+    pub fn is_admin(&self, sender: &str) -> bool {
+        self.admins.contains(sender)
+    }
+
+    /// Ingest the message's `.txt`/`.md` attachments when the sender is an
+    /// admin.
+    ///
+    /// Returns the confirmation to send back, or `None` when the message is
+    /// not an ingestion request and should be processed normally.
+    pub async fn handle(&self, message: &InboundMessage) -> Option<String> {
+        if !self.is_admin(&message.sender) {
+            return None;
+        }
+        let attachments: Vec<_> = message
+            .attachments
+            .iter()
+            .filter(|attachment| text_mime(attachment).is_some())
+            .collect();
+        if attachments.is_empty() {
+            return None;
+        }
+
+        let mut lines = Vec::with_capacity(attachments.len());
+        for attachment in attachments {
+            let name = attachment_name(attachment);
+            match self.ingest(attachment, &name).await {
+                Ok(line) => lines.push(line),
+                Err(err) => {
+                    warn!(sender = %message.sender, file = %name, "KB ingestion failed: {}", err);
+                    lines.push(format!("Could not add {} to the knowledge base: {}", name, err));
+                }
+            }
+        }
+        Some(lines.join("\n"))
+    }
+
+    async fn ingest(&self, attachment: &InboundAttachment, name: &str) -> Result<String, String> {
+        let path = attachment
+            .file_path
+            .as_deref()
+            .ok_or("the attachment was not downloaded")?;
+        let size = fs::metadata(path).map_err(|e| e.to_string())?.len();
+        if size > KB_INGEST_MAX_BYTES {
+            return Err(format!("the file is larger than {} KB", KB_INGEST_MAX_BYTES / 1024));
+        }
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        if text.trim().is_empty() {
+            return Err("the file is empty".to_string());
+        }
+
+        let options = DocumentOptions {
+            mime: text_mime(attachment).unwrap_or("text/plain").to_string(),
+            source_type: SOURCE_TYPE.to_string(),
+            // Relays only see the events, so the text has to travel inline
+            inline_text: true,
+            ..DocumentOptions::new(name)
+        };
+        let document = chunk_document(&text, &options);
+        publish_document(self.publisher.as_ref(), &document.manifest, &document.chunk_refs)
+            .await
+            .map_err(|e| format!("publishing failed ({})", e))?;
+
+        info!(
+            doc_id = %document.manifest.doc_id,
+            chunks = document.chunk_refs.len(),
+            "Ingested attachment into the knowledge base"
+        );
+        Ok(format!(
+            "Added {} to the knowledge base ({} chunks, {}).",
+            name,
+            document.chunk_refs.len(),
+            document.manifest.doc_id
+        ))
+    }
+}
+
+/// Parse a comma-separated list of admin numbers.
+pub fn parse_admin_numbers(value: &str) -> HashSet<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|number| !number.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// MIME type for supported attachments: by extension, falling back to the
+/// content type when Signal sent no filename.
+fn text_mime(attachment: &InboundAttachment) -> Option<&'static str> {
+    let extension = attachment
+        .filename
+        .as_deref()
+        .and_then(|name| Path::new(name).extension())
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("txt") => Some("text/plain"),
+        Some("md") => Some("text/markdown"),
+        Some(_) => None,
+        None => match attachment.content_type.as_str() {
+            "text/plain" => Some("text/plain"),
+            "text/markdown" => Some("text/markdown"),
+            _ => None,
+        },
+    }
+}
+
+fn attachment_name(attachment: &InboundAttachment) -> String {
+    attachment
+        .filename
+        .clone()
+        .or_else(|| {
+            attachment
+                .file_path
+                .as_deref()
+                .and_then(|path| Path::new(path).file_name())
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "attachment".to_string())
+}
+
+/// KB events use the same relays, key and secretbox codec as memory events.
+fn publisher_config(config: MemoryPublisherConfig) -> PublisherConfig {
+    let codec: Arc<dyn PayloadCodec> = match config.secretbox_key {
+        Some(codec) => Arc::new(codec),
+        None => Arc::new(NoopCodec),
+    };
+    PublisherConfig {
+        relays: config.relays,
+        secret_key: config.secret_key,
+        min_acks: config.min_acks,
+        timeout: config.timeout,
+        kinds: NostrKinds::default(),
+        codec,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use nostr_persistence::{AccessPolicy, ChunkRef, DocManifest, Error, NostrTag, PublishResult};
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    const ADMIN: &str = "+15550001111";
+
+    #[derive(Default)]
+    struct MockPublisher {
+        manifests: Mutex<Vec<DocManifest>>,
+        chunk_refs: Mutex<Vec<ChunkRef>>,
+        fail: bool,
+    }
+
+    impl MockPublisher {
+        fn result(&self, id: &str) -> Result<PublishResult, Error> {
+            if self.fail {
+                return Err(Error::MissingEnv("NOSTR_RELAYS"));
+            }
+            Ok(PublishResult {
+                event_id: id.to_string(),
+                success: 1,
+                failed: 0,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl NostrPublisher for MockPublisher {
+        async fn publish_doc_manifest(
+            &self,
+            doc: DocManifest,
+            _extra_tags: Vec<NostrTag>,
+        ) -> Result<PublishResult, Error> {
+            self.manifests.lock().unwrap().push(doc.clone());
+            self.result(&doc.doc_id)
+        }
+
+        async fn publish_chunk_ref(
+            &self,
+            chunk: ChunkRef,
+            _extra_tags: Vec<NostrTag>,
+        ) -> Result<PublishResult, Error> {
+            self.chunk_refs.lock().unwrap().push(chunk.clone());
+            self.result(&chunk.chunk_id)
+        }
+
+        async fn publish_policy(
+            &self,
+            policy: AccessPolicy,
+            _extra_tags: Vec<NostrTag>,
+        ) -> Result<PublishResult, Error> {
+            self.result(&policy.scope_id)
+        }
+    }
+
+    fn write_temp(name: &str, text: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("aman-kb-ingest-{}-{}", std::process::id(), name));
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    fn message_with(sender: &str, filename: &str, path: &Path) -> InboundMessage {
+        let mut message = InboundMessage::direct(sender, "", 1);
+        message.attachments.push(InboundAttachment {
+            content_type: "text/plain".to_string(),
+            filename: Some(filename.to_string()),
+            file_path: Some(path.display().to_string()),
+            ..Default::default()
+        });
+        message
+    }
+
+    #[test]
+    fn test_parse_admin_numbers() {
+        let admins = parse_admin_numbers(" +15550001111, ,+15550002222 ");
+        assert_eq!(admins.len(), 2);
+        assert!(admins.contains("+15550001111"));
+        assert!(parse_admin_numbers("").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_admin_text_attachment_is_chunked_and_published() {
+        let path = write_temp("notes.txt", &"a".repeat(2000));
+        let publisher = Arc::new(MockPublisher::default());
+        let ingestor = KbIngestor::new(publisher.clone(), [ADMIN.to_string()]);
+
+        let reply = ingestor
+            .handle(&message_with(ADMIN, "notes.txt", &path))
+            .await
+            .unwrap();
+        fs::remove_file(&path).ok();
+
+        let manifests = publisher.manifests.lock().unwrap();
+        let chunk_refs = publisher.chunk_refs.lock().unwrap();
+        assert_eq!(manifests.len(), 1);
+        assert_eq!(manifests[0].title, "notes.txt");
+        assert_eq!(manifests[0].source_type, SOURCE_TYPE);
+        // 800-char chunks with 200 overlap: 0..800, 600..1400, 1200..2000
+        assert_eq!(chunk_refs.len(), 3);
+        assert_eq!(manifests[0].chunks.len(), 3);
+        assert!(chunk_refs.iter().all(|chunk| chunk.doc_id == manifests[0].doc_id));
+        assert!(chunk_refs.iter().all(|chunk| chunk.text.is_some() && chunk.text_matches_hash()));
+        assert!(reply.contains("Added notes.txt"));
+        assert!(reply.contains("3 chunks"));
+    }
+
+    #[tokio::test]
+    async fn test_ignores_non_admins_and_unsupported_files() {
+        let path = write_temp("ignored.md", "# Notes\n\nSome text.");
+        let publisher = Arc::new(MockPublisher::default());
+        let ingestor = KbIngestor::new(publisher.clone(), [ADMIN.to_string()]);
+
+        let stranger = message_with("+15559999999", "ignored.md", &path);
+        assert!(ingestor.handle(&stranger).await.is_none());
+        let pdf = message_with(ADMIN, "ignored.pdf", &path);
+        assert!(ingestor.handle(&pdf).await.is_none());
+        assert!(ingestor
+            .handle(&InboundMessage::direct(ADMIN, "hello", 1))
+            .await
+            .is_none());
+        fs::remove_file(&path).ok();
+
+        assert!(publisher.manifests.lock().unwrap().is_empty());
+        assert!(publisher.chunk_refs.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reports_publish_failure() {
+        let path = write_temp("failing.md", "# Title\n\nBody text.");
+        let publisher = Arc::new(MockPublisher {
+            fail: true,
+            ..Default::default()
+        });
+        let ingestor = KbIngestor::new(publisher.clone(), [ADMIN.to_string()]);
+
+        let reply = ingestor
+            .handle(&message_with(ADMIN, "failing.md", &path))
+            .await
+            .unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(publisher.manifests.lock().unwrap().len(), 1);
+        assert_eq!(publisher.manifests.lock().unwrap()[0].mime, "text/markdown");
+        assert!(publisher.chunk_refs.lock().unwrap().is_empty());
+        assert!(reply.starts_with("Could not add failing.md"));
+    }
+}
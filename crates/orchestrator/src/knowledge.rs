@@ -0,0 +1,259 @@
+//! Knowledge base retrieval for grounding brain responses.
+//!
+//! The orchestrator can optionally hold a [`KnowledgeRetriever`]. Before a
+//! response is generated, the user's message is used as a keyword query and
+//! the best matching snippets are injected into the brain prompt.
+
+use std::env;
+use std::io;
+use std::path::Path;
+
+use kb_retrieval::{files, Document, MemoryIndex, Retriever};
+use tracing::{debug, warn};
+
+const DEFAULT_ADDRESS_MARKERS: &[&str] = &[
+    "street", "st.", "road", "rd.", "avenue", "ave", "blvd", "boulevard", "drive", "dr.", "lane",
+    "ln.", "address", "postal", "postcode", "zip",
+];
+
+/// A snippet of knowledge base content relevant to a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnowledgeSnippet {
+    /// Where the snippet came from (file path or document id).
+    pub source: String,
+    /// The snippet text.
+    pub text: String,
+}
+
+/// Source of knowledge base snippets.
+pub trait KnowledgeRetriever: Send + Sync {
+    /// Return up to `limit` snippets relevant to `query`, best first.
+    fn retrieve(&self, query: &str, limit: usize) -> Vec<KnowledgeSnippet>;
+}
+
+/// Settings controlling knowledge base injection.
+#[derive(Debug, Clone)]
+pub struct KnowledgeSettings {
+    /// Maximum snippets injected per response.
+    pub max_snippets: usize,
+    /// Skip retrieval when the query looks like it contains personal data.
+    pub suppress_sensitive: bool,
+    /// Digit count at which a query is treated as sensitive (0 disables).
+    pub digit_threshold: usize,
+    /// Lowercase substrings that mark a query as containing an address.
+    pub address_markers: Vec<String>,
+}
+
+impl Default for KnowledgeSettings {
+    fn default() -> Self {
+        Self {
+            max_snippets: 3,
+            suppress_sensitive: true,
+            digit_threshold: 6,
+            address_markers: DEFAULT_ADDRESS_MARKERS
+                .iter()
+                .map(|marker| marker.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl KnowledgeSettings {
+    /// Load settings from environment variables.
+    ///
+    /// - `AMAN_KB_MAX_SNIPPETS`: snippets per response (default: 3)
+    /// - `AMAN_KB_SENSITIVE_SUPPRESS`: skip sensitive-looking queries (default: true)
+    /// - `AMAN_KB_SENSITIVE_DIGIT_THRESHOLD`: digits that mark a query sensitive (default: 6)
+    pub fn from_env() -> Self {
+        let mut settings = Self::default();
+        if let Some(value) = env::var("AMAN_KB_MAX_SNIPPETS")
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+        {
+            settings.max_snippets = value;
+        }
+        if let Ok(value) = env::var("AMAN_KB_SENSITIVE_SUPPRESS") {
+            settings.suppress_sensitive =
+                !matches!(value.trim().to_lowercase().as_str(), "false" | "0" | "no" | "off");
+        }
+        if let Some(value) = env::var("AMAN_KB_SENSITIVE_DIGIT_THRESHOLD")
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+        {
+            settings.digit_threshold = value;
+        }
+        settings
+    }
+
+    /// Whether retrieval should be skipped for this query.
+    pub fn should_suppress(&self, query: &str) -> bool {
+        self.suppress_sensitive && self.looks_sensitive(query)
+    }
+
+    fn looks_sensitive(&self, query: &str) -> bool {
+        let lower = query.to_lowercase();
+        if lower.contains('@') && lower.contains('.') {
+            return true;
+        }
+
+        let digits = query.chars().filter(|ch| ch.is_ascii_digit()).count();
+        if self.digit_threshold > 0 && digits >= self.digit_threshold {
+            return true;
+        }
+
+        self.address_markers
+            .iter()
+            .any(|marker| lower.contains(marker.as_str()))
+    }
+}
+
+/// In-memory keyword knowledge base loaded from text files.
+#[derive(Debug, Default)]
+pub struct KnowledgeBase {
+    index: MemoryIndex,
+}
+
+impl KnowledgeBase {
+    /// Create an empty knowledge base.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a knowledge base from `AMAN_KB_PATH`, if set.
+    pub fn from_env() -> Option<Self> {
+        let path = env::var("AMAN_KB_PATH").ok()?;
+        if path.trim().is_empty() {
+            return None;
+        }
+        match Self::load(Path::new(path.trim())) {
+            Ok(kb) => Some(kb),
+            Err(e) => {
+                warn!("Failed to load knowledge base: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Load `.txt`, `.md`, and `.jsonl` files from a file or directory tree.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            index: files::load_path(path)?.into_iter().collect(),
+        })
+    }
+
+    /// Add an entry directly.
+    pub fn add(&mut self, source: impl Into<String>, text: impl Into<String>) {
+        self.index.add(Document {
+            source: source.into(),
+            text: text.into().chars().take(files::MAX_DOCUMENT_CHARS).collect(),
+        });
+    }
+
+    /// Number of loaded entries.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Whether the knowledge base has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+}
+
+impl KnowledgeRetriever for KnowledgeBase {
+    fn retrieve(&self, query: &str, limit: usize) -> Vec<KnowledgeSnippet> {
+        self.index
+            .search(query, limit)
+            .into_iter()
+            .map(|hit| KnowledgeSnippet {
+                source: hit.source,
+                text: hit.snippet,
+            })
+            .collect()
+    }
+}
+
+/// Retrieve snippets for a user message, honoring sensitive-query suppression.
+pub(crate) fn retrieve_for_message(
+    retriever: &dyn KnowledgeRetriever,
+    settings: &KnowledgeSettings,
+    text: &str,
+) -> Vec<KnowledgeSnippet> {
+    if settings.max_snippets == 0 || text.trim().is_empty() {
+        return Vec::new();
+    }
+    if settings.should_suppress(text) {
+        debug!("Skipping knowledge base lookup for sensitive-looking message");
+        return Vec::new();
+    }
+    retriever.retrieve(text, settings.max_snippets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Context;
+    use brain_core::InboundMessage;
+
+    fn seeded() -> KnowledgeBase {
+        let mut kb = KnowledgeBase::new();
+        kb.add("faq/vpn.md", "To stay safe online, use a trusted VPN and enable the kill switch.");
+        kb.add("faq/signal.md", "Signal disappearing messages can be enabled per conversation.");
+        kb
+    }
+
+    #[test]
+    fn test_retrieve_ranks_matching_entry() {
+        let kb = seeded();
+        let hits = kb.retrieve("How do I enable disappearing messages?", 3);
+        assert_eq!(hits[0].source, "faq/signal.md");
+        assert!(hits[0].text.contains("disappearing messages"));
+    }
+
+    #[test]
+    fn test_retrieve_no_match() {
+        let kb = seeded();
+        assert!(kb.retrieve("weather tomorrow", 3).is_empty());
+        assert!(kb.retrieve("a b", 3).is_empty());
+    }
+
+    #[test]
+    fn test_snippets_reach_augmented_message() {
+        let kb = seeded();
+        let settings = KnowledgeSettings::default();
+        let message = InboundMessage::direct("+15550000000", "Which VPN should I use?", 1);
+
+        let mut context = Context::new();
+        context.add_knowledge(retrieve_for_message(&kb, &settings, &message.text));
+        let augmented = context.augment_message(&message);
+
+        assert!(augmented.text.contains("[KNOWLEDGE BASE CONTEXT]"));
+        assert!(augmented.text.contains("faq/vpn.md"));
+        assert!(augmented.text.contains("trusted VPN"));
+        assert!(!augmented.text.contains("faq/signal.md"));
+    }
+
+    #[test]
+    fn test_sensitive_message_not_grounded() {
+        let kb = seeded();
+        let settings = KnowledgeSettings::default();
+        let text = "Which VPN works at 12 Main Street?";
+
+        assert!(retrieve_for_message(&kb, &settings, text).is_empty());
+    }
+
+    #[test]
+    fn test_suppress_sensitive_queries() {
+        let settings = KnowledgeSettings::default();
+        assert!(settings.should_suppress("my number is 555 123 4567"));
+        assert!(settings.should_suppress("email me at a@b.org"));
+        assert!(settings.should_suppress("I live on Main Street"));
+        assert!(!settings.should_suppress("how do I use a VPN?"));
+
+        let disabled = KnowledgeSettings {
+            suppress_sensitive: false,
+            ..KnowledgeSettings::default()
+        };
+        assert!(!disabled.should_suppress("my number is 555 123 4567"));
+    }
+}
@@ -23,8 +23,9 @@
 //! │         ↓                                                   │
 //! │  2. Route message (maple-brain, stateless)                  │
 //! │         ↓                                                   │
-//! │  3. Execute actions sequentially:                           │
+//! │  3. Execute actions in plan order:                          │
 //! │     • search → Call grok, send "Searching..." message       │
+//! │       (consecutive search/use_tool actions run concurrently)│
 //! │     • clear_context → Clear brain history, send confirm     │
 //! │     • respond → Pass to maple-brain for final response      │
 //! │         ↓                                                   │
@@ -73,8 +74,13 @@
 
 mod actions;
 mod context;
+mod donation;
 mod error;
 mod formatting;
+mod gather;
+#[cfg(feature = "nostr")]
+mod kb_ingest;
+mod knowledge;
 mod model_selection;
 mod memory;
 mod nostr;
@@ -83,22 +89,42 @@ mod preferences;
 mod profile;
 mod router;
 mod sender;
+mod tool_brain;
 
 // Public exports
-pub use actions::{OrchestratorAction, RoutingPlan, UserPreference};
-pub use context::Context;
+pub use actions::{OrchestratorAction, PrivacyHints, RoutingPlan, UserPreference};
+pub use context::{
+    BrainResultSummarizer, Context, ResultSummarizer, DEFAULT_CONTEXT_MAX_CHARS,
+    RESULTS_SUMMARY_SYSTEM_PROMPT,
+};
+pub use donation::{
+    donation_invoice, InvoiceSource, OfferDecoder, DONATION_DESCRIPTION, DONATION_EXPIRY_SECS,
+};
 pub use error::OrchestratorError;
 pub use formatting::{parse_markdown, format_with_footer, FormattedMessage, StyleType};
+#[cfg(feature = "nostr")]
+pub use kb_ingest::{
+    parse_admin_numbers, KbIngestor, KB_ADMIN_NUMBERS_ENV, KB_INGEST_MAX_BYTES,
+};
+pub use knowledge::{KnowledgeBase, KnowledgeRetriever, KnowledgeSettings, KnowledgeSnippet};
 pub use model_selection::{GrokModels, MapleModels, ModelSelector};
-pub use memory::{MemorySettings, MemoryStore, RetentionPolicy, SummaryPolicy};
+pub use memory::{MemorySettings, MemoryStore, RetentionPolicy, SummaryPolicy, SUMMARY_SYSTEM_PROMPT};
 pub use orchestrator::{Orchestrator, HELP_TEXT};
 pub use preferences::{AgentIndicator, PreferenceStore};
-pub use profile::{ProfileError, ProfileStore};
-pub use router::{load_router_prompt, Router, DEFAULT_ROUTER_PROMPT_FILE, DEFAULT_ROUTER_SYSTEM_PROMPT};
-pub use sender::{LoggingSender, MessageSender, NoOpSender};
+pub use profile::{ProfileError, ProfileStore, ToolDefaults};
+pub use router::{
+    load_router_prompt, Router, SensitivityOverrides, DEFAULT_ROUTER_PROMPT_FILE,
+    DEFAULT_ROUTER_SYSTEM_PROMPT, FAIL_CLOSED_ENV, SENSITIVITY_KEYWORDS_ENV,
+};
+pub use sender::{
+    CapturingSender, HttpSender, LoggingSender, MessageSender, NoOpSender, SentCall,
+    DEFAULT_WEBHOOK_TIMEOUT,
+};
 
 // Re-export commonly used types from dependencies
-pub use brain_core::{InboundMessage, OutboundMessage, RoutingInfo, Sensitivity, TaskHint};
+pub use brain_core::{
+    InboundMessage, OutboundMessage, RoutingDebug, RoutingInfo, Sensitivity, TaskHint,
+};
 pub use grok_brain::GrokToolExecutor;
 pub use maple_brain::{MapleBrain, MapleBrainConfig};
 pub use agent_tools::{Tool, ToolError, ToolRegistry};
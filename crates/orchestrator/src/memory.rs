@@ -2,17 +2,20 @@
 
 use std::collections::HashMap;
 use std::env;
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use brain_core::{
-    MemoryClearEvent, MemoryError, MemoryPiiPolicy, MemoryPromptPolicy, MemorySnapshot,
-    MemoryToolEntry,
+    Brain, InboundMessage, MemoryClearEvent, MemoryError, MemoryPiiPolicy, MemoryPromptPolicy,
+    MemoryPromptPreset, MemorySnapshot, MemoryToolEntry, MemoryTurn, RoutingInfo,
 };
 use crate::nostr::MemoryPublisher;
 use aman_database::{
-    clear_context_event, conversation_summary, tool_history, ConversationSummary, Database,
+    clear_context_event, conversation_summary, conversation_turn, tool_history,
+    ConversationSummary, Database,
 };
+use chrono::{NaiveDateTime, Utc};
 use serde::Deserialize;
 use tokio::time;
 use tracing::warn;
@@ -20,6 +23,17 @@ use tracing::warn;
 #[cfg(feature = "nostr")]
 use nostr_persistence::AmanToolHistoryEvent;
 
+/// Sender id used for summarization requests, kept apart from real users.
+const SUMMARIZER_SENDER: &str = "memory-summarizer";
+
+/// Format of SQLite `datetime('now')` timestamps.
+const SQLITE_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// System prompt for a dedicated summarization brain.
+pub const SUMMARY_SYSTEM_PROMPT: &str = "You maintain short running summaries of conversations. \
+Keep names, preferences, decisions and open questions; drop pleasantries. \
+Reply with the summary only.";
+
 /// Summary formatting policy.
 #[derive(Debug, Clone)]
 pub struct SummaryPolicy {
@@ -48,6 +62,8 @@ pub struct RetentionPolicy {
     pub max_tool_history_total: Option<usize>,
     pub max_tool_history_per_key: Option<usize>,
     pub max_clear_context_events: Option<usize>,
+    /// Recent turns kept per history key (turns expire with `summary_ttl`).
+    pub max_turns_per_key: Option<usize>,
 }
 
 impl Default for RetentionPolicy {
@@ -60,6 +76,7 @@ impl Default for RetentionPolicy {
             max_tool_history_total: Some(10_000),
             max_tool_history_per_key: Some(200),
             max_clear_context_events: Some(5_000),
+            max_turns_per_key: Some(20),
         }
     }
 }
@@ -67,6 +84,8 @@ impl Default for RetentionPolicy {
 /// Per-history overrides for memory prompt formatting.
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct MemoryPromptOverrides {
+    /// Start from a preset; the other fields then adjust it.
+    pub preset: Option<MemoryPromptPreset>,
     pub max_chars: Option<usize>,
     pub max_summary_chars: Option<usize>,
     pub max_tool_entries: Option<usize>,
@@ -76,10 +95,16 @@ pub struct MemoryPromptOverrides {
     pub include_tool_history: Option<bool>,
     pub include_clear_context: Option<bool>,
     pub pii_policy: Option<MemoryPiiPolicy>,
+    pub max_recent_turns: Option<usize>,
 }
 
 impl MemoryPromptOverrides {
     fn apply_to(&self, policy: &mut MemoryPromptPolicy) {
+        if let Some(preset) = self.preset {
+            let pii_policy = policy.pii_policy;
+            *policy = preset.policy();
+            policy.pii_policy = pii_policy;
+        }
         if let Some(value) = self.max_chars {
             policy.max_chars = value;
         }
@@ -107,6 +132,9 @@ impl MemoryPromptOverrides {
         if let Some(value) = self.pii_policy {
             policy.pii_policy = value;
         }
+        if let Some(value) = self.max_recent_turns {
+            policy.max_recent_turns = value;
+        }
     }
 }
 
@@ -119,6 +147,14 @@ pub struct MemorySettings {
     pub prompt_policy: MemoryPromptPolicy,
     pub prompt_overrides: HashMap<String, MemoryPromptOverrides>,
     pub compaction_interval: Option<Duration>,
+    /// Model used to write conversation summaries. When set (and a
+    /// summarizer brain is attached), summaries are generated by this model
+    /// instead of the response brains; otherwise they are built from the
+    /// latest exchanges.
+    pub summary_model: Option<String>,
+    /// Summaries last updated longer ago than this are left out of the
+    /// memory prompt. They stay stored until the retention TTL prunes them.
+    pub summary_max_age: Option<Duration>,
 }
 
 impl Default for MemorySettings {
@@ -130,6 +166,8 @@ impl Default for MemorySettings {
             prompt_policy: MemoryPromptPolicy::default(),
             prompt_overrides: HashMap::new(),
             compaction_interval: None,
+            summary_model: None,
+            summary_max_age: Some(Duration::from_secs(14 * 24 * 60 * 60)),
         }
     }
 }
@@ -152,6 +190,15 @@ impl MemorySettings {
             settings.tool_output_max_chars = value;
         }
 
+        if let Ok(value) = env::var("AMAN_MEMORY_PROMPT_PRESET") {
+            match value.parse::<MemoryPromptPreset>() {
+                Ok(preset) => settings.prompt_policy = preset.policy(),
+                Err(err) => warn!("{}", err),
+            }
+        }
+        if let Some(value) = env_usize("AMAN_MEMORY_PROMPT_MAX_RECENT_TURNS") {
+            settings.prompt_policy.max_recent_turns = value;
+        }
         if let Some(value) = env_usize("AMAN_MEMORY_PROMPT_MAX_CHARS") {
             settings.prompt_policy.max_chars = value;
         } else if let Some(value) = env_usize("AMAN_MEMORY_PROMPT_MAX_TOKENS") {
@@ -190,6 +237,17 @@ impl MemorySettings {
             }
         }
 
+        if let Ok(model) = env::var("AMAN_MEMORY_SUMMARY_MODEL") {
+            let model = model.trim();
+            if !model.is_empty() {
+                settings.summary_model = Some(model.to_string());
+            }
+        }
+
+        if let Some(days) = env_u64("AMAN_MEMORY_SUMMARY_MAX_AGE_DAYS") {
+            settings.summary_max_age = days_to_duration(days);
+        }
+
         if let Some(seconds) = env_u64("AMAN_MEMORY_COMPACT_INTERVAL_SECS") {
             settings.compaction_interval = seconds_to_duration(seconds);
         }
@@ -216,6 +274,9 @@ impl MemorySettings {
         if let Some(value) = env_usize("AMAN_MEMORY_MAX_CLEAR_EVENTS") {
             settings.retention.max_clear_context_events = cap_from_env(value);
         }
+        if let Some(value) = env_usize("AMAN_MEMORY_MAX_TURNS_PER_KEY") {
+            settings.retention.max_turns_per_key = cap_from_env(value);
+        }
 
         settings
     }
@@ -236,6 +297,7 @@ pub struct MemoryStore {
     settings: MemorySettings,
     #[cfg_attr(not(feature = "nostr"), allow(dead_code))]
     publisher: Option<MemoryPublisher>,
+    summarizer: Option<Arc<dyn Brain>>,
 }
 
 impl MemoryStore {
@@ -248,9 +310,19 @@ impl MemoryStore {
             database,
             settings,
             publisher,
+            summarizer: None,
         }
     }
 
+    /// Generate summaries with `brain`, using `settings.summary_model`.
+    ///
+    /// The brain should be stateless (no history); each request carries the
+    /// previous summary and the new exchange.
+    pub fn with_summarizer(mut self, brain: Arc<dyn Brain>) -> Self {
+        self.summarizer = Some(brain);
+        self
+    }
+
     pub fn settings(&self) -> &MemorySettings {
         &self.settings
     }
@@ -309,6 +381,7 @@ impl MemoryStore {
 
         let (summary, summary_updated_at) = match (summary_row.as_ref(), latest_clear_at.as_ref()) {
             (Some(row), Some(clear_at)) if row.updated_at <= *clear_at => (None, None),
+            (Some(row), _) if self.is_stale(&row.updated_at) => (None, None),
             (Some(row), _) => (Some(row.summary.clone()), Some(row.updated_at.clone())),
             (None, _) => (None, None),
         };
@@ -350,14 +423,52 @@ impl MemoryStore {
             Vec::new()
         };
 
+        let mut recent_turns = Vec::new();
+        if policy.max_recent_turns > 0 {
+            let turns = conversation_turn::list_recent_turns(
+                self.database.pool(),
+                history_key,
+                policy.max_recent_turns as i64,
+            )
+            .await?;
+            recent_turns = turns
+                .into_iter()
+                .filter(|turn| {
+                    latest_clear_at
+                        .as_ref()
+                        .map(|clear_at| turn.created_at > *clear_at)
+                        .unwrap_or(true)
+                })
+                .map(|turn| MemoryTurn {
+                    user: turn.user_text,
+                    assistant: turn.assistant_text,
+                    created_at: Some(turn.created_at),
+                })
+                .collect();
+        }
+
         Ok(MemorySnapshot {
             summary,
             summary_updated_at,
             tool_history,
             clear_context_events,
+            recent_turns,
         })
     }
 
+    /// Whether a summary updated at `updated_at` is past `summary_max_age`.
+    fn is_stale(&self, updated_at: &str) -> bool {
+        let Some(max_age) = self.settings.summary_max_age else {
+            return false;
+        };
+        let Ok(updated_at) = NaiveDateTime::parse_from_str(updated_at, SQLITE_DATETIME_FORMAT)
+        else {
+            return false;
+        };
+        let age = Utc::now().naive_utc().signed_duration_since(updated_at);
+        age.to_std().is_ok_and(|age| age > max_age)
+    }
+
     pub async fn record_exchange(
         &self,
         history_key: &str,
@@ -365,8 +476,14 @@ impl MemoryStore {
         assistant_text: &str,
     ) -> aman_database::Result<()> {
         let existing = conversation_summary::get_summary(self.database.pool(), history_key).await?;
-        let (summary, message_count) =
+        let (mut summary, message_count) =
             self.build_summary(existing.as_ref(), user_text, assistant_text);
+        if let Some(generated) = self
+            .generate_summary(existing.as_ref(), user_text, assistant_text)
+            .await
+        {
+            summary = generated;
+        }
 
         conversation_summary::upsert_summary(
             self.database.pool(),
@@ -376,15 +493,21 @@ impl MemoryStore {
         )
         .await?;
 
+        let max_entry_chars = self.settings.summary.max_entry_chars;
+        let turn_id = conversation_turn::insert_turn(
+            self.database.pool(),
+            history_key,
+            &truncate_text(&collapse_lines(user_text), max_entry_chars),
+            &truncate_text(&collapse_lines(assistant_text), max_entry_chars),
+            existing
+                .as_ref()
+                .map(|row| (row.summary.as_str(), row.message_count)),
+        )
+        .await?;
+        tool_history::attach_pending_to_turn(self.database.pool(), history_key, turn_id).await?;
+
         #[cfg(feature = "nostr")]
-        if let Some(publisher) = &self.publisher {
-            if let Err(err) = publisher
-                .publish_summary(history_key, &summary, message_count)
-                .await
-            {
-                warn!("Failed to publish summary to Nostr: {}", err);
-            }
-        }
+        self.publish_summary(history_key, &summary, message_count).await;
 
         self.prune(history_key).await?;
         Ok(())
@@ -396,6 +519,7 @@ impl MemoryStore {
         sender_id: Option<&str>,
     ) -> aman_database::Result<()> {
         conversation_summary::clear_summary(self.database.pool(), history_key).await?;
+        conversation_turn::clear_turns(self.database.pool(), history_key).await?;
         clear_context_event::insert_event(self.database.pool(), history_key, sender_id).await?;
 
         #[cfg(feature = "nostr")]
@@ -411,6 +535,52 @@ impl MemoryStore {
         Ok(())
     }
 
+    /// Forget the most recent exchange for a history key.
+    ///
+    /// Deletes the latest stored turn and the tool results recorded for it,
+    /// then restores the summary that turn replaced (or clears it if there
+    /// was none), so neither the forgotten text nor older context folded into
+    /// the summary is lost. Either way the result is published to Nostr.
+    /// Turns stored before the prior summary was tracked leave the summary
+    /// untouched. Returns false if there was no turn to forget.
+    pub async fn forget_last_exchange(&self, history_key: &str) -> aman_database::Result<bool> {
+        let pool = self.database.pool();
+        let Some(turn) = conversation_turn::take_latest_turn(pool, history_key).await? else {
+            return Ok(false);
+        };
+        tool_history::delete_for_turn(pool, turn.id).await?;
+
+        let Some(message_count) = turn.previous_message_count else {
+            return Ok(true);
+        };
+        let Some(summary) = turn.previous_summary else {
+            conversation_summary::clear_summary(pool, history_key).await?;
+            // An empty summary replaces the published one; rehydration skips it
+            #[cfg(feature = "nostr")]
+            self.publish_summary(history_key, "", 0).await;
+            return Ok(true);
+        };
+        conversation_summary::upsert_summary(pool, history_key, &summary, message_count).await?;
+
+        #[cfg(feature = "nostr")]
+        self.publish_summary(history_key, &summary, message_count).await;
+
+        Ok(true)
+    }
+
+    /// Publish a conversation summary to Nostr, logging failures.
+    #[cfg(feature = "nostr")]
+    async fn publish_summary(&self, history_key: &str, summary: &str, message_count: i64) {
+        if let Some(publisher) = &self.publisher {
+            if let Err(err) = publisher
+                .publish_summary(history_key, summary, message_count)
+                .await
+            {
+                warn!("Failed to publish summary to Nostr: {}", err);
+            }
+        }
+    }
+
     pub async fn record_tool(
         &self,
         history_key: &str,
@@ -475,6 +645,14 @@ impl MemoryStore {
                 tool_history::prune_over_limit_for_key(self.database.pool(), history_key, max_rows)
                     .await?;
         }
+        if let Some(max_rows) = self.settings.retention.max_turns_per_key {
+            let _ = conversation_turn::prune_over_limit_for_key(
+                self.database.pool(),
+                history_key,
+                max_rows,
+            )
+            .await?;
+        }
 
         Ok(())
     }
@@ -482,6 +660,7 @@ impl MemoryStore {
     async fn prune_all(&self) -> aman_database::Result<()> {
         if let Some(ttl) = self.settings.retention.summary_ttl {
             let _ = conversation_summary::prune_older_than(self.database.pool(), ttl).await?;
+            let _ = conversation_turn::prune_older_than(self.database.pool(), ttl).await?;
         }
         if let Some(max_rows) = self.settings.retention.max_summaries {
             let _ = conversation_summary::prune_over_limit(self.database.pool(), max_rows).await?;
@@ -504,6 +683,59 @@ impl MemoryStore {
         Ok(())
     }
 
+    /// Ask the summary model for an updated summary.
+    ///
+    /// Returns `None` (keep the extractive summary) when no summary model or
+    /// summarizer is configured, or when the model fails.
+    async fn generate_summary(
+        &self,
+        existing: Option<&ConversationSummary>,
+        user_text: &str,
+        assistant_text: &str,
+    ) -> Option<String> {
+        let model = self.settings.summary_model.as_ref()?;
+        let summarizer = self.summarizer.as_ref()?;
+
+        let max_chars = self.settings.summary.max_summary_chars;
+        let mut prompt = String::from("Update the conversation summary with the new exchange.\n\n");
+        if let Some(row) = existing {
+            prompt.push_str(&format!("Current summary:\n{}\n\n", row.summary));
+        }
+        prompt.push_str(&format!(
+            "New exchange:\nUser: {}\nAssistant: {}\n\n",
+            collapse_lines(user_text),
+            collapse_lines(assistant_text)
+        ));
+        if max_chars > 0 {
+            prompt.push_str(&format!("Keep it under {} characters.", max_chars));
+        }
+
+        let mut request = InboundMessage::direct(SUMMARIZER_SENDER, prompt, 0);
+        request.routing = Some(RoutingInfo {
+            model_override: Some(model.clone()),
+            ..Default::default()
+        });
+
+        match summarizer.process(request).await {
+            Ok(response) if !response.text.trim().is_empty() => {
+                let summary = response.text.trim();
+                Some(if max_chars > 0 {
+                    truncate_text(summary, max_chars)
+                } else {
+                    summary.to_string()
+                })
+            }
+            Ok(_) => {
+                warn!("Summary model {} returned an empty summary", model);
+                None
+            }
+            Err(err) => {
+                warn!("Summary model {} failed: {}", model, err);
+                None
+            }
+        }
+    }
+
     fn build_summary(
         &self,
         existing: Option<&ConversationSummary>,
@@ -526,6 +758,11 @@ impl MemoryStore {
         lines.push(user_line);
         lines.push(assistant_line);
 
+        (self.fit_summary(lines), message_count + 1)
+    }
+
+    /// Trim summary lines to the configured entry and character limits.
+    fn fit_summary(&self, mut lines: Vec<String>) -> String {
         let max_entries = self.settings.summary.max_entries;
         if max_entries > 0 {
             let max_lines = max_entries.saturating_mul(2);
@@ -548,7 +785,7 @@ impl MemoryStore {
             }
         }
 
-        (summary, message_count + 1)
+        summary
     }
 }
 
@@ -646,3 +883,231 @@ fn truncate_text(text: &str, max_chars: usize) -> String {
     output.push_str(ellipsis);
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use brain_core::{BrainError, OutboundMessage};
+
+    /// Brain that records requests and answers with a fixed summary.
+    #[derive(Default)]
+    struct RecordingBrain {
+        requests: Mutex<Vec<InboundMessage>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl Brain for RecordingBrain {
+        async fn process(&self, message: InboundMessage) -> Result<OutboundMessage, BrainError> {
+            self.requests.lock().unwrap().push(message.clone());
+            if self.fail {
+                return Err(BrainError::Network("unavailable".to_string()));
+            }
+            Ok(OutboundMessage::reply_to(&message, "User likes green tea."))
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+    }
+
+    async fn store(summary_model: Option<&str>, summarizer: Option<Arc<RecordingBrain>>) -> MemoryStore {
+        let database = Database::connect_with_pool_size("sqlite::memory:", 1)
+            .await
+            .unwrap();
+        database.migrate().await.unwrap();
+        let settings = MemorySettings {
+            summary_model: summary_model.map(str::to_string),
+            ..Default::default()
+        };
+        let store = MemoryStore::new(database, settings, None);
+        match summarizer {
+            Some(brain) => store.with_summarizer(brain),
+            None => store,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_summary_uses_configured_summary_model() {
+        let brain = Arc::new(RecordingBrain::default());
+        let store = store(Some("cheap-model"), Some(brain.clone())).await;
+
+        store
+            .record_exchange("+15550000000", "I love green tea", "Noted!")
+            .await
+            .unwrap();
+
+        let requests = brain.requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        let model = requests[0]
+            .routing
+            .as_ref()
+            .and_then(|routing| routing.model_override.as_deref());
+        assert_eq!(model, Some("cheap-model"));
+        assert_eq!(requests[0].sender, SUMMARIZER_SENDER);
+        assert!(requests[0].text.contains("User: I love green tea"));
+
+        assert_eq!(
+            store.get_summary("+15550000000").await.as_deref(),
+            Some("User likes green tea.")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_summary_without_model_is_extractive() {
+        let brain = Arc::new(RecordingBrain::default());
+        let store = store(None, Some(brain.clone())).await;
+
+        store
+            .record_exchange("+15550000000", "I love green tea", "Noted!")
+            .await
+            .unwrap();
+
+        assert!(brain.requests.lock().unwrap().is_empty());
+        let summary = store.get_summary("+15550000000").await.unwrap();
+        assert!(summary.contains("I love green tea"));
+    }
+
+    #[tokio::test]
+    async fn test_summary_model_failure_falls_back() {
+        let brain = Arc::new(RecordingBrain {
+            fail: true,
+            ..Default::default()
+        });
+        let store = store(Some("cheap-model"), Some(brain.clone())).await;
+
+        store
+            .record_exchange("+15550000000", "I love green tea", "Noted!")
+            .await
+            .unwrap();
+
+        assert_eq!(brain.requests.lock().unwrap().len(), 1);
+        let summary = store.get_summary("+15550000000").await.unwrap();
+        assert!(summary.contains("I love green tea"));
+    }
+
+    #[test]
+    fn test_prompt_preset_override_per_history_key() {
+        let mut settings = MemorySettings::default();
+        settings.prompt_policy.pii_policy = MemoryPiiPolicy::Skip;
+        settings.prompt_overrides = serde_json::from_str(
+            r#"{"group:abc": {"preset": "minimal"}, "+15550000000": {"preset": "verbose", "max_recent_turns": 3}}"#,
+        )
+        .unwrap();
+
+        let minimal = settings.prompt_policy_for("group:abc");
+        assert_eq!(minimal.max_chars, MemoryPromptPreset::Minimal.policy().max_chars);
+        assert_eq!(minimal.max_recent_turns, 0);
+        assert_eq!(minimal.pii_policy, MemoryPiiPolicy::Skip);
+
+        let verbose = settings.prompt_policy_for("+15550000000");
+        assert_eq!(verbose.max_chars, MemoryPromptPreset::Verbose.policy().max_chars);
+        assert_eq!(verbose.max_recent_turns, 3);
+
+        let default = settings.prompt_policy_for("+15551111111");
+        assert_eq!(default.max_chars, settings.prompt_policy.max_chars);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_includes_recent_turns() {
+        let store = store(None, None).await;
+        let key = "+15550000000";
+        for (user, assistant) in [("hi", "hello"), ("tea?", "green"), ("bye", "see you")] {
+            store.record_exchange(key, user, assistant).await.unwrap();
+        }
+
+        let policy = MemoryPromptPreset::Standard.policy();
+        let snapshot = store.snapshot_with_policy(key, &policy).await.unwrap();
+        let users: Vec<&str> = snapshot.recent_turns.iter().map(|t| t.user.as_str()).collect();
+        assert_eq!(users, vec!["bye", "tea?"]);
+
+        let minimal = MemoryPromptPreset::Minimal.policy();
+        let snapshot = store.snapshot_with_policy(key, &minimal).await.unwrap();
+        assert!(snapshot.recent_turns.is_empty());
+
+        store.clear_context(key, None).await.unwrap();
+        let snapshot = store.snapshot_with_policy(key, &policy).await.unwrap();
+        assert!(snapshot.recent_turns.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_expired_summary_not_injected() {
+        let store = store(None, None).await;
+        store.record_exchange("fresh", "I love green tea", "Noted!").await.unwrap();
+        store.record_exchange("stale", "I love black coffee", "Noted!").await.unwrap();
+        sqlx::query(
+            "UPDATE conversation_summaries SET updated_at = datetime('now', '-30 days') WHERE history_key = 'stale'",
+        )
+        .execute(store.database.pool())
+        .await
+        .unwrap();
+
+        let policy = MemoryPromptPolicy::default();
+        let fresh = store.snapshot_with_policy("fresh", &policy).await.unwrap();
+        assert!(fresh.summary.unwrap().contains("green tea"));
+
+        let stale = store.snapshot_with_policy("stale", &policy).await.unwrap();
+        assert!(stale.summary.is_none());
+        assert!(stale.summary_updated_at.is_none());
+        // Still stored for export.
+        assert!(store.get_summary("stale").await.unwrap().contains("black coffee"));
+    }
+
+    #[tokio::test]
+    async fn test_forget_last_exchange() {
+        let store = store(None, None).await;
+        let key = "+15550000000";
+        let pool = store.database.pool();
+        store
+            .record_tool(key, "weather", true, "sunny", None, None)
+            .await
+            .unwrap();
+        store.record_exchange(key, "I love green tea", "Noted!").await.unwrap();
+        store
+            .record_tool(key, "web_fetch", true, "PIN reset page", None, None)
+            .await
+            .unwrap();
+        store.record_exchange(key, "My PIN is 1234", "Got it.").await.unwrap();
+
+        assert!(store.forget_last_exchange(key).await.unwrap());
+
+        let summary = store.get_summary(key).await.unwrap();
+        assert!(summary.contains("I love green tea"));
+        assert!(!summary.contains("1234"));
+        let tools = tool_history::list_tool_history(pool, key, 10).await.unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t.tool_name.as_str()).collect();
+        assert_eq!(names, vec!["weather"]);
+        let policy = MemoryPromptPreset::Verbose.policy();
+        let snapshot = store.snapshot_with_policy(key, &policy).await.unwrap();
+        let users: Vec<&str> = snapshot.recent_turns.iter().map(|t| t.user.as_str()).collect();
+        assert_eq!(users, vec!["I love green tea"]);
+
+        assert!(store.forget_last_exchange(key).await.unwrap());
+        assert!(store.get_summary(key).await.is_none());
+        assert!(!store.forget_last_exchange(key).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_forget_last_exchange_restores_prior_summary() {
+        let store = store(None, None).await;
+        let key = "+15550000000";
+        let pool = store.database.pool();
+        // More exchanges than the retained turns, so the summary cannot be rebuilt from them.
+        for i in 1..=30 {
+            store
+                .record_exchange(key, &format!("question {}", i), &format!("answer {}", i))
+                .await
+                .unwrap();
+        }
+        let before = conversation_summary::get_summary(pool, key).await.unwrap().unwrap();
+
+        store.record_exchange(key, "My PIN is 1234", "Got it.").await.unwrap();
+        assert!(store.forget_last_exchange(key).await.unwrap());
+
+        let after = conversation_summary::get_summary(pool, key).await.unwrap().unwrap();
+        assert_eq!(after.summary, before.summary);
+        assert_eq!(after.message_count, before.message_count);
+    }
+}
@@ -58,6 +58,16 @@ impl MapleModels {
             .map(|(_, canonical)| *canonical)
     }
 
+    /// Resolve an alias or canonical name to the canonical model name.
+    pub fn canonical_model(input: &str) -> Option<&'static str> {
+        Self::normalize_model(input).or_else(|| {
+            Self::available_models()
+                .iter()
+                .find(|(_, canonical)| canonical.eq_ignore_ascii_case(input))
+                .map(|(_, canonical)| *canonical)
+        })
+    }
+
     /// Get the list of available model aliases for display.
     pub fn model_aliases() -> Vec<&'static str> {
         Self::available_models()
@@ -137,6 +147,19 @@ impl Default for GrokModels {
 }
 
 impl GrokModels {
+    /// Known Grok model names.
+    pub fn available_models() -> &'static [&'static str] {
+        &["grok-4-1-fast", "grok-4-1", "grok-3", "grok-3-mini", "grok-4"]
+    }
+
+    /// Resolve a model name to its canonical Grok model name.
+    pub fn canonical_model(input: &str) -> Option<&'static str> {
+        Self::available_models()
+            .iter()
+            .find(|model| model.eq_ignore_ascii_case(input))
+            .copied()
+    }
+
     /// Select the best model for a given task hint.
     ///
     /// Note: Vision tasks are not supported by Grok. If passed Vision,
@@ -222,6 +245,33 @@ impl ModelSelector {
         self.grok.select(task_hint)
     }
 
+    /// Select a model, letting a user's profile default act as a soft override.
+    ///
+    /// The profile model replaces the pick for general queries only, and only
+    /// when it belongs to the provider chosen for this request: a Grok model
+    /// never pulls a private request off Maple, and task-specific models
+    /// (coding, vision, ...) still win.
+    pub fn select_with_profile(
+        &self,
+        use_grok: bool,
+        task_hint: TaskHint,
+        profile_model: Option<&str>,
+    ) -> String {
+        let generic = matches!(task_hint, TaskHint::General | TaskHint::AboutBot);
+        let profile_model = profile_model.filter(|_| generic).and_then(|model| {
+            if use_grok {
+                GrokModels::canonical_model(model)
+            } else {
+                MapleModels::canonical_model(model)
+            }
+        });
+        match profile_model {
+            Some(model) => model.to_string(),
+            None if use_grok => self.select_grok(task_hint).to_string(),
+            None => self.select_maple(task_hint).to_string(),
+        }
+    }
+
     /// Get the vision model for Maple.
     pub fn maple_vision(&self) -> &str {
         &self.maple.vision
@@ -288,6 +338,26 @@ mod tests {
         assert_eq!(selector.maple_vision(), "qwen3-vl-30b");
     }
 
+    #[test]
+    fn test_select_with_profile() {
+        let selector = ModelSelector::new();
+
+        // Profile model replaces the general pick on its own provider.
+        assert_eq!(selector.select_with_profile(false, TaskHint::General, Some("qwen")), "qwen2-5-72b");
+        assert_eq!(selector.select_with_profile(true, TaskHint::General, Some("Grok-4")), "grok-4");
+        assert_eq!(
+            selector.select_with_profile(false, TaskHint::General, Some("gpt-oss-120b")),
+            "gpt-oss-120b"
+        );
+
+        // Task-specific hints, provider mismatches and unknown models keep the default.
+        assert_eq!(selector.select_with_profile(false, TaskHint::Coding, Some("qwen")), "deepseek-r1-0528");
+        assert_eq!(selector.select_with_profile(false, TaskHint::General, Some("grok-4")), "llama-3.3-70b");
+        assert_eq!(selector.select_with_profile(true, TaskHint::General, Some("llama")), "grok-4-1-fast");
+        assert_eq!(selector.select_with_profile(false, TaskHint::General, Some("nope")), "llama-3.3-70b");
+        assert_eq!(selector.select_with_profile(false, TaskHint::General, None), "llama-3.3-70b");
+    }
+
     #[test]
     fn test_normalize_model() {
         // Valid aliases
@@ -3,35 +3,41 @@
 use std::collections::HashMap;
 use std::env;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use brain_core::{
-    format_memory_prompt, hash_prompt, Brain, InboundMessage, OutboundMessage, ToolExecutor,
-    ToolRequest,
-};
-use aman_database::Database;
+use brain_core::{format_memory_prompt, hash_prompt, Brain, InboundMessage, OutboundMessage};
+use aman_database::{Database, DatabaseConfig};
 use grok_brain::{GrokBrain, GrokBrainConfig, GrokToolExecutor};
 use maple_brain::{MapleBrain, MapleBrainConfig};
 use chrono::Utc;
-use serde_json::{json, Value};
 use agent_tools::ToolRegistry;
 use tracing::{debug, info, trace, warn};
 
 #[cfg(feature = "lightning")]
 use donation_wallet::{DonationWallet, DonationWalletConfig};
 
-use brain_core::{Sensitivity, TaskHint};
+use brain_core::{RoutingDebug, Sensitivity, TaskHint};
 use crate::actions::{OrchestratorAction, PrivacyChoice, RoutingPlan, UserPreference};
-use crate::context::Context;
+use crate::context::{
+    BrainResultSummarizer, Context, ResultSummarizer, DEFAULT_CONTEXT_MAX_CHARS,
+    RESULTS_SUMMARY_SYSTEM_PROMPT,
+};
+#[cfg(feature = "lightning")]
+use crate::donation::{donation_invoice, InvoiceSource};
 use crate::error::OrchestratorError;
 use crate::formatting::format_with_footer;
-use crate::memory::{MemorySettings, MemoryStore};
+use crate::gather::{announce, plan_steps, run_concurrently, GatherAction, PlanStep};
+#[cfg(feature = "nostr")]
+use crate::kb_ingest::KbIngestor;
+use crate::knowledge::{retrieve_for_message, KnowledgeBase, KnowledgeRetriever, KnowledgeSettings};
+use crate::memory::{MemorySettings, MemoryStore, SUMMARY_SYSTEM_PROMPT};
 use crate::model_selection::ModelSelector;
 use crate::nostr::memory_publisher_from_env;
 use crate::preferences::{AgentIndicator, PreferenceStore};
 use crate::profile::ProfileStore;
 use crate::router::Router;
 use crate::sender::MessageSender;
+use crate::tool_brain::ToolBrain;
 
 /// Help text shown when user asks for help.
 pub const HELP_TEXT: &str = r#"I'm an AI assistant with two modes:
@@ -47,9 +53,11 @@ Commands:
 • "maple: <query>" - One-time direct query to Maple
 • "<model>: <query>" - One-time query to specific model
 • "forget our chat" - Clear conversation history
+• "forget that" - Forget just the last message and reply
 
 Profile Settings:
 • "show my settings" - View your profile
+• "show my bolt12 offer" - Echo your saved offer to verify it
 • "set my email to X" - Update your email
 • "set my default model to X" - Set preferred AI model
 • "set my bolt12 to lno1..." - Set Lightning payment offer
@@ -64,6 +72,12 @@ I automatically detect sensitive topics (health, finances, personal) and route t
 
 Just send me a message and I'll do my best to help!"#;
 
+/// Reply after the last exchange was forgotten.
+const FORGET_LAST_CONFIRMATION: &str = "Done, I've forgotten your last message and my reply.";
+
+/// Reply when there is no exchange left to forget.
+const FORGET_LAST_NOTHING: &str = "There's nothing recent for me to forget.";
+
 /// Default path for the support prompt file.
 pub const DEFAULT_SUPPORT_PROMPT_FILE: &str = "SUPPORT_PROMPT.md";
 
@@ -99,6 +113,111 @@ fn load_support_text() -> String {
     DEFAULT_SUPPORT_TEXT.to_string()
 }
 
+/// Whether `AMAN_SUPPRESS_STATUS_MESSAGES` asks for interim status messages
+/// to be skipped.
+fn load_suppress_status_from_env() -> bool {
+    env::var("AMAN_SUPPRESS_STATUS_MESSAGES")
+        .map(|value| matches!(value.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Whether `AMAN_DEBUG_ROUTING` asks for routing details on every response.
+fn load_debug_routing_from_env() -> bool {
+    env::var("AMAN_DEBUG_ROUTING")
+        .map(|value| matches!(value.trim().to_lowercase().as_str(), "true" | "1" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Sensitivity and task hint for a response the plan did not ask for.
+///
+/// Images and PII flagged on any action route privately. Otherwise the
+/// strongest sensitivity the plan states is used, failing closed when it
+/// states none. The privacy choice prompt is skipped here so gathered
+/// results are not thrown away.
+fn fallback_routing(
+    plan: &RoutingPlan,
+    has_images: bool,
+    fail_closed: bool,
+) -> (Sensitivity, TaskHint) {
+    if has_images {
+        return (Sensitivity::Sensitive, TaskHint::Vision);
+    }
+    if plan.has_pii() {
+        info!("Plan flagged PII ({}); responding privately", plan.pii_types().join(", "));
+        return (Sensitivity::Sensitive, TaskHint::default());
+    }
+    (
+        resolve_sensitivity(plan.strongest_sensitivity(), fail_closed),
+        TaskHint::default(),
+    )
+}
+
+/// Budget for search and tool results from `AMAN_CONTEXT_MAX_CHARS`.
+fn load_context_max_chars_from_env() -> usize {
+    env::var("AMAN_CONTEXT_MAX_CHARS")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(DEFAULT_CONTEXT_MAX_CHARS)
+}
+
+/// Turns remembered per history key for [`ResponderLog`].
+const MAX_LOGGED_TURNS: usize = 64;
+
+/// Brain that answered a turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Responder {
+    Maple,
+    Grok,
+}
+
+/// Which brain answered each recent turn, per history key.
+///
+/// Maple and Grok keep separate histories, so forgetting the last exchange
+/// must undo it in the brain that actually answered.
+#[derive(Debug, Default)]
+struct ResponderLog {
+    turns: Mutex<HashMap<String, Vec<Responder>>>,
+}
+
+impl ResponderLog {
+    fn record(&self, history_key: &str, responder: Responder) {
+        let mut turns = self.turns.lock().unwrap();
+        let log = turns.entry(history_key.to_string()).or_default();
+        log.push(responder);
+        if log.len() > MAX_LOGGED_TURNS {
+            log.remove(0);
+        }
+    }
+
+    /// Remove and return the brain that answered the latest turn.
+    fn take_last(&self, history_key: &str) -> Option<Responder> {
+        self.turns.lock().unwrap().get_mut(history_key)?.pop()
+    }
+
+    fn clear(&self, history_key: &str) {
+        self.turns.lock().unwrap().remove(history_key);
+    }
+}
+
+/// Sensitivity to route with, failing closed when it is undetermined.
+///
+/// With `fail_closed`, uncertain or missing sensitivity is treated as
+/// sensitive so the message stays on Maple.
+fn resolve_sensitivity(sensitivity: Option<Sensitivity>, fail_closed: bool) -> Sensitivity {
+    match sensitivity {
+        Some(Sensitivity::Uncertain) | None if fail_closed => Sensitivity::Sensitive,
+        Some(sensitivity) => sensitivity,
+        None => Sensitivity::default(),
+    }
+}
+
+/// Routing details for a response, when debugging is on for every
+/// response or was requested for this message.
+fn routing_debug_for(always: bool, message: &InboundMessage, brain: &str) -> Option<RoutingDebug> {
+    let routing = message.routing.as_ref()?;
+    (always || routing.debug).then(|| routing.debug_summary(brain))
+}
+
 /// Load text from a file path.
 ///
 /// Returns `Some(content)` if the file exists and is readable, `None` otherwise.
@@ -124,6 +243,8 @@ struct MemoryContext {
     prompt: Option<String>,
     summary_updated_at: Option<String>,
     source: Option<String>,
+    /// Preferred reply language from the sender's user record.
+    language: Option<String>,
 }
 
 /// Email client wrapper that holds both the client and dropbox address.
@@ -170,9 +291,26 @@ pub struct Orchestrator<S: MessageSender> {
     profile: ProfileStore,
     /// Support text for donation/support inquiries.
     support_text: String,
+    /// Optional knowledge base used to ground responses.
+    knowledge: Option<Arc<dyn KnowledgeRetriever>>,
+    /// Settings for knowledge base injection.
+    knowledge_settings: KnowledgeSettings,
+    /// Skip interim "Searching: ..." and tool status messages.
+    suppress_status_messages: bool,
+    /// Attach routing details to every response (`AMAN_DEBUG_ROUTING`).
+    debug_routing: bool,
+    /// Character budget for search and tool results in the brain prompt.
+    context_max_chars: usize,
+    /// Which brain answered recent turns, for forget-last.
+    responders: ResponderLog,
+    /// Optional summarizer for results that exceed the context budget.
+    result_summarizer: Option<Arc<dyn ResultSummarizer>>,
     /// Optional donation wallet for Lightning payments.
     #[cfg(feature = "lightning")]
-    donation_wallet: Option<Arc<DonationWallet>>,
+    donation_wallet: Option<Arc<dyn InvoiceSource>>,
+    /// Optional admin-only ingestion of text attachments into the KB.
+    #[cfg(feature = "nostr")]
+    kb_ingestor: Option<KbIngestor>,
 }
 
 impl<S: MessageSender> Orchestrator<S> {
@@ -186,7 +324,7 @@ impl<S: MessageSender> Orchestrator<S> {
     ) -> Self {
         let maple_brain = Arc::new(maple_brain);
         let mut tool_registry = agent_tools::default_registry();
-        let brain: Arc<dyn Brain> = maple_brain.clone();
+        let brain: Arc<dyn Brain> = Arc::new(ToolBrain::new(maple_brain.clone()));
         tool_registry.set_brain(brain);
 
         Self {
@@ -202,8 +340,17 @@ impl<S: MessageSender> Orchestrator<S> {
             email_client: None,
             profile: ProfileStore::new(),
             support_text: load_support_text(),
+            knowledge: None,
+            knowledge_settings: KnowledgeSettings::default(),
+            suppress_status_messages: false,
+            debug_routing: false,
+            context_max_chars: DEFAULT_CONTEXT_MAX_CHARS,
+            result_summarizer: None,
+            responders: ResponderLog::default(),
             #[cfg(feature = "lightning")]
             donation_wallet: None,
+            #[cfg(feature = "nostr")]
+            kb_ingestor: None,
         }
     }
 
@@ -218,7 +365,7 @@ impl<S: MessageSender> Orchestrator<S> {
     ) -> Self {
         let maple_brain = Arc::new(maple_brain);
         let mut tool_registry = tool_registry;
-        let brain: Arc<dyn Brain> = maple_brain.clone();
+        let brain: Arc<dyn Brain> = Arc::new(ToolBrain::new(maple_brain.clone()));
         tool_registry.set_brain(brain);
 
         Self {
@@ -234,8 +381,17 @@ impl<S: MessageSender> Orchestrator<S> {
             email_client: None,
             profile: ProfileStore::new(),
             support_text: load_support_text(),
+            knowledge: None,
+            knowledge_settings: KnowledgeSettings::default(),
+            suppress_status_messages: false,
+            debug_routing: false,
+            context_max_chars: DEFAULT_CONTEXT_MAX_CHARS,
+            result_summarizer: None,
+            responders: ResponderLog::default(),
             #[cfg(feature = "lightning")]
             donation_wallet: None,
+            #[cfg(feature = "nostr")]
+            kb_ingestor: None,
         }
     }
 
@@ -261,7 +417,7 @@ impl<S: MessageSender> Orchestrator<S> {
         );
 
         // Create Maple brain with shared tool support
-        let maple_brain = MapleBrain::with_shared_tools(maple_config, search.clone())
+        let maple_brain = MapleBrain::with_shared_tools(maple_config.clone(), search.clone())
             .await
             .map_err(|e| OrchestratorError::RoutingFailed(format!("Maple init error: {}", e)))?;
 
@@ -272,18 +428,19 @@ impl<S: MessageSender> Orchestrator<S> {
         // Create model selector from environment
         let model_selector = ModelSelector::from_env();
 
-        let (preferences, memory, profile) = Self::load_persistence_from_env().await?;
+        let (preferences, memory, profile) = Self::load_persistence_from_env(&maple_config).await?;
+        let result_summarizer = load_result_summarizer_from_env(&maple_config).await;
 
         // Try to initialize email client from environment
         let email_client = Self::load_email_client_from_env();
 
         // Try to initialize donation wallet from environment
         #[cfg(feature = "lightning")]
-        let donation_wallet = Self::load_donation_wallet_from_env().await;
+        let (donation_wallet, profile) = Self::attach_donation_wallet(profile).await;
 
         let maple_brain = Arc::new(maple_brain);
         let mut tool_registry = agent_tools::default_registry();
-        let brain: Arc<dyn Brain> = maple_brain.clone();
+        let brain: Arc<dyn Brain> = Arc::new(ToolBrain::new(maple_brain.clone()));
         tool_registry.set_brain(brain);
 
         Ok(Self {
@@ -299,8 +456,17 @@ impl<S: MessageSender> Orchestrator<S> {
             email_client,
             profile,
             support_text: load_support_text(),
+            knowledge: load_knowledge_from_env(),
+            knowledge_settings: KnowledgeSettings::from_env(),
+            suppress_status_messages: load_suppress_status_from_env(),
+            debug_routing: load_debug_routing_from_env(),
+            context_max_chars: load_context_max_chars_from_env(),
+            result_summarizer,
+            responders: ResponderLog::default(),
             #[cfg(feature = "lightning")]
             donation_wallet,
+            #[cfg(feature = "nostr")]
+            kb_ingestor: KbIngestor::from_env().await,
         })
     }
 
@@ -312,7 +478,7 @@ impl<S: MessageSender> Orchestrator<S> {
         search: Arc<GrokToolExecutor>,
         sender: S,
     ) -> Result<Self, OrchestratorError> {
-        let maple_brain = MapleBrain::with_shared_tools(maple_config, search.clone())
+        let maple_brain = MapleBrain::with_shared_tools(maple_config.clone(), search.clone())
             .await
             .map_err(|e| OrchestratorError::RoutingFailed(format!("Maple init error: {}", e)))?;
 
@@ -321,17 +487,18 @@ impl<S: MessageSender> Orchestrator<S> {
 
         let maple_brain = Arc::new(maple_brain);
         let mut tool_registry = agent_tools::default_registry();
-        let brain: Arc<dyn Brain> = maple_brain.clone();
+        let brain: Arc<dyn Brain> = Arc::new(ToolBrain::new(maple_brain.clone()));
         tool_registry.set_brain(brain);
 
-        let (preferences, memory, profile) = Self::load_persistence_from_env().await?;
+        let (preferences, memory, profile) = Self::load_persistence_from_env(&maple_config).await?;
+        let result_summarizer = load_result_summarizer_from_env(&maple_config).await;
 
         // Try to initialize email client from environment
         let email_client = Self::load_email_client_from_env();
 
         // Try to initialize donation wallet from environment
         #[cfg(feature = "lightning")]
-        let donation_wallet = Self::load_donation_wallet_from_env().await;
+        let (donation_wallet, profile) = Self::attach_donation_wallet(profile).await;
 
         Ok(Self {
             router,
@@ -346,8 +513,17 @@ impl<S: MessageSender> Orchestrator<S> {
             email_client,
             profile,
             support_text: load_support_text(),
+            knowledge: load_knowledge_from_env(),
+            knowledge_settings: KnowledgeSettings::from_env(),
+            suppress_status_messages: load_suppress_status_from_env(),
+            debug_routing: load_debug_routing_from_env(),
+            context_max_chars: load_context_max_chars_from_env(),
+            result_summarizer,
+            responders: ResponderLog::default(),
             #[cfg(feature = "lightning")]
             donation_wallet,
+            #[cfg(feature = "nostr")]
+            kb_ingestor: KbIngestor::from_env().await,
         })
     }
 
@@ -388,6 +564,7 @@ impl<S: MessageSender> Orchestrator<S> {
         };
         routing.system_prompt_hash = system_prompt_hash.map(|hash| hash.to_string());
         if let Some(memory) = memory_context {
+            routing.language = memory.language.clone();
             if let Some(prompt) = memory.prompt.as_ref() {
                 routing.memory_prompt = Some(prompt.clone());
                 routing.memory_prompt_hash = Some(hash_prompt(prompt));
@@ -398,6 +575,10 @@ impl<S: MessageSender> Orchestrator<S> {
         message.routing = Some(routing);
     }
 
+    fn routing_debug(&self, message: &InboundMessage, brain: &str) -> Option<RoutingDebug> {
+        routing_debug_for(self.debug_routing, message, brain)
+    }
+
     async fn load_memory_context(&self, history_key: &str) -> MemoryContext {
         let Some(memory) = &self.memory else {
             return MemoryContext::default();
@@ -410,6 +591,7 @@ impl<S: MessageSender> Orchestrator<S> {
                 summary_updated_at: snapshot.summary_updated_at.clone(),
                 prompt: format_memory_prompt(&snapshot, &policy),
                 source: Some("sqlite".to_string()),
+                language: None,
             },
             Err(err) => {
                 warn!("Failed to load memory snapshot: {}", err);
@@ -450,13 +632,22 @@ impl<S: MessageSender> Orchestrator<S> {
             "INBOUND_MESSAGE"
         );
 
+        // Admin text attachments go to the knowledge base instead of the router
+        #[cfg(feature = "nostr")]
+        if let Some(ingestor) = &self.kb_ingestor {
+            if let Some(confirmation) = ingestor.handle(&message).await {
+                return Ok(OutboundMessage::reply_to(&message, confirmation));
+            }
+        }
+
         // 1. Start typing indicator
         if let Err(e) = self.sender.set_typing(recipient, is_group, true).await {
             warn!("Failed to start typing indicator: {}", e);
         }
 
         // 2. Get conversation context (local operation, fast)
-        let memory_context = self.load_memory_context(&history_key).await;
+        let mut memory_context = self.load_memory_context(&history_key).await;
+        memory_context.language = self.profile.language(&message.sender).await;
         let maple_context = self.maple_brain.get_context_summary(&history_key).await;
         let mut routing_context = memory_context.summary.clone();
         if routing_context.is_none() {
@@ -498,11 +689,12 @@ impl<S: MessageSender> Orchestrator<S> {
         }
 
         // 4. Execute actions, building context
-        let memory_context_ref = if memory_context.prompt.is_some() {
-            Some(&memory_context)
-        } else {
-            None
-        };
+        let memory_context_ref =
+            if memory_context.prompt.is_some() || memory_context.language.is_some() {
+                Some(&memory_context)
+            } else {
+                None
+            };
         let result = self
             .execute_plan(
                 &message,
@@ -532,30 +724,47 @@ impl<S: MessageSender> Orchestrator<S> {
         history_key: &str,
         memory_context: Option<&MemoryContext>,
     ) -> Result<OutboundMessage, OrchestratorError> {
-        let mut context = Context::new();
+        let mut context = Context::with_budget(self.context_max_chars);
+
+        // Fill in tool arguments the user left out from their profile
+        let mut actions = plan.actions.clone();
+        if actions
+            .iter()
+            .any(|action| matches!(action, OrchestratorAction::UseTool { .. }))
+        {
+            self.profile
+                .tool_defaults(&message.sender)
+                .await
+                .apply_to_actions(&mut actions);
+        }
+
+        for step in plan_steps(&actions) {
+            let action = match step {
+                PlanStep::Gather(batch) => {
+                    self.execute_gather(message, history_key, &batch, &mut context, recipient, is_group)
+                        .await;
+                    continue;
+                }
+                PlanStep::Single(action) => action,
+            };
 
-        for action in &plan.actions {
             match action {
-                OrchestratorAction::Search {
-                    query,
-                    message: status_msg,
-                } => {
-                    self.execute_search(
-                        message,
-                        history_key,
-                        query,
-                        status_msg.as_deref(),
-                        &mut context,
-                        recipient,
-                        is_group,
-                    )
-                    .await?;
+                OrchestratorAction::Search { .. } | OrchestratorAction::UseTool { .. } => {
+                    let batch: Vec<_> = GatherAction::from_action(action).into_iter().collect();
+                    self.execute_gather(message, history_key, &batch, &mut context, recipient, is_group)
+                        .await;
                 }
 
                 OrchestratorAction::ClearContext { .. } => {
                     self.execute_clear_context(history_key, &message.sender).await?;
                 }
 
+                OrchestratorAction::ForgetLast { message: confirmation } => {
+                    return self
+                        .execute_forget_last(message, history_key, confirmation.as_deref())
+                        .await;
+                }
+
                 OrchestratorAction::Help => {
                     return Ok(OutboundMessage::reply_to(message, HELP_TEXT));
                 }
@@ -640,25 +849,7 @@ impl<S: MessageSender> Orchestrator<S> {
 
                 OrchestratorAction::Ignore => {
                     info!("Ignoring accidental message");
-                    return Err(OrchestratorError::Skipped("accidental message".to_string()));
-                }
-
-                OrchestratorAction::UseTool {
-                    name,
-                    args,
-                    message: status_msg,
-                } => {
-                    self.execute_use_tool(
-                        message,
-                        history_key,
-                        name,
-                        args,
-                        status_msg.as_deref(),
-                        &mut context,
-                        recipient,
-                        is_group,
-                    )
-                    .await?;
+                    return Err(OrchestratorError::Ignored);
                 }
 
                 OrchestratorAction::AskPrivacyChoice {
@@ -694,6 +885,10 @@ impl<S: MessageSender> Orchestrator<S> {
                     return self.execute_view_profile(message).await;
                 }
 
+                OrchestratorAction::ViewOffer => {
+                    return self.execute_view_offer(message).await;
+                }
+
                 OrchestratorAction::UpdateProfile { field, value } => {
                     return self
                         .execute_update_profile(message, field, value.as_deref())
@@ -716,18 +911,11 @@ impl<S: MessageSender> Orchestrator<S> {
             }
         }
 
-        // If no Respond action in plan, generate one with default sensitivity and task hint
+        // If no Respond action in plan, generate one anyway, keeping the
+        // sensitivity and PII flags the rest of the plan carried
         info!("No response action in plan, generating response anyway");
-        let fallback_task_hint = if message.has_images() {
-            TaskHint::Vision
-        } else {
-            TaskHint::default()
-        };
-        let fallback_sensitivity = if message.has_images() {
-            Sensitivity::Sensitive
-        } else {
-            Sensitivity::default()
-        };
+        let (fallback_sensitivity, fallback_task_hint) =
+            fallback_routing(plan, message.has_images(), self.router.fail_closed());
 
         self.execute_respond(
             message,
@@ -740,69 +928,45 @@ impl<S: MessageSender> Orchestrator<S> {
         .await
     }
 
-    /// Execute a search action.
-    async fn execute_search(
+    /// Execute a batch of independent search/tool actions.
+    ///
+    /// Status messages (unless suppressed) are sent in plan order, then the actions run
+    /// concurrently and their results are added to the context in plan order.
+    async fn execute_gather(
         &self,
         message: &InboundMessage,
         history_key: &str,
-        query: &str,
-        status_message: Option<&str>,
+        batch: &[GatherAction<'_>],
         context: &mut Context,
         recipient: &str,
         is_group: bool,
-    ) -> Result<(), OrchestratorError> {
-        info!("Executing search: {}", query);
-
-        // Notify user that we're searching
-        let search_msg = status_message
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| format!("Searching: {}", query));
-
-        if let Err(e) = self
-            .sender
-            .send_message(recipient, &search_msg, is_group)
-            .await
-        {
-            warn!("Failed to send search notification: {}", e);
-        }
-
-        // Restart typing indicator after sending message
-        if let Err(e) = self.sender.set_typing(recipient, is_group, true).await {
-            warn!("Failed to restart typing indicator: {}", e);
-        }
-
-        // Execute the search
-        let args_json = json!({ "query": query }).to_string();
-        let request = ToolRequest::from_call(
-            "orchestrator-search".to_string(),
-            "realtime_search".to_string(),
-            &args_json,
+    ) {
+        announce(
+            &self.sender,
+            batch,
+            recipient,
+            is_group,
+            self.suppress_status_messages,
         )
-        .map_err(|e| OrchestratorError::ToolFailed(format!("Invalid search request: {}", e)))?;
+        .await;
 
-        let result = self.search.execute(request).await;
+        let outcomes = run_concurrently(self.search.as_ref(), &self.tool_registry, batch).await;
 
-        if result.success {
-            info!(
-                "Search completed successfully ({} chars)",
-                result.content.len()
-            );
-            context.add_search_result(query, &result.content);
-        } else {
-            warn!("Search failed: {}", result.content);
-            context.add_search_result(query, &format!("Search failed: {}", result.content));
+        for outcome in &outcomes {
+            outcome.apply(context);
+            self.record_tool_history(
+                history_key,
+                &outcome.tool,
+                outcome.success,
+                &outcome.content,
+                message,
+            )
+            .await;
         }
 
-        self.record_tool_history(
-            history_key,
-            "realtime_search",
-            result.success,
-            &result.content,
-            message,
-        )
-        .await;
-
-        Ok(())
+        if let Some(summarizer) = &self.result_summarizer {
+            context.summarize_if_over_budget(summarizer.as_ref()).await;
+        }
     }
 
     /// Execute a clear context action (silent - no user notification).
@@ -814,6 +978,7 @@ impl<S: MessageSender> Orchestrator<S> {
         info!("Clearing conversation history for {}", history_key);
         self.maple_brain.clear_history(history_key).await;
         self.grok_brain.clear_history(history_key).await;
+        self.responders.clear(history_key);
 
         if let Some(memory) = &self.memory {
             if let Err(err) = memory.clear_context(history_key, Some(sender_id)).await {
@@ -823,61 +988,34 @@ impl<S: MessageSender> Orchestrator<S> {
         Ok(())
     }
 
-    /// Execute a use_tool action.
-    async fn execute_use_tool(
+    /// Execute a forget-last action - drop the latest exchange from its brain and memory.
+    async fn execute_forget_last(
         &self,
         message: &InboundMessage,
         history_key: &str,
-        name: &str,
-        args: &HashMap<String, Value>,
-        status_message: Option<&str>,
-        context: &mut Context,
-        recipient: &str,
-        is_group: bool,
-    ) -> Result<(), OrchestratorError> {
-        info!("Executing tool '{}' with {} args", name, args.len());
-
-        // Send status message if provided
-        if let Some(msg) = status_message {
-            if let Err(e) = self.sender.send_message(recipient, msg, is_group).await {
-                warn!("Failed to send tool status notification: {}", e);
-            }
+        confirmation: Option<&str>,
+    ) -> Result<OutboundMessage, OrchestratorError> {
+        info!("Forgetting last exchange for {}", history_key);
+        // Only the brain that answered holds the exchange in its history
+        let mut forgotten = match self.responders.take_last(history_key) {
+            Some(Responder::Maple) => self.maple_brain.forget_last_exchange(history_key).await,
+            Some(Responder::Grok) => self.grok_brain.forget_last_exchange(history_key).await,
+            None => false,
+        };
 
-            // Restart typing indicator after sending message
-            if let Err(e) = self.sender.set_typing(recipient, is_group, true).await {
-                warn!("Failed to restart typing indicator: {}", e);
+        if let Some(memory) = &self.memory {
+            match memory.forget_last_exchange(history_key).await {
+                Ok(removed) => forgotten |= removed,
+                Err(err) => warn!("Failed to forget last exchange in memory: {}", err),
             }
         }
 
-        // Execute the tool
-        let (tool_success, tool_content) = match self.tool_registry.execute(name, args.clone()).await {
-            Ok(result) => {
-                let content = result.content;
-                if result.success {
-                    info!(
-                        "Tool '{}' completed successfully ({} chars)",
-                        name,
-                        content.len()
-                    );
-                    context.add_tool_result(name, &content);
-                } else {
-                    warn!("Tool '{}' returned failure: {}", name, content);
-                    context.add_tool_result(name, &format!("Tool failed: {}", content));
-                }
-                (result.success, content)
-            }
-            Err(e) => {
-                warn!("Tool '{}' execution error: {}", name, e);
-                let content = format!("Tool error: {}", e);
-                context.add_tool_result(name, &content);
-                (false, content)
-            }
+        let text = if forgotten {
+            confirmation.unwrap_or(FORGET_LAST_CONFIRMATION)
+        } else {
+            FORGET_LAST_NOTHING
         };
-
-        self.record_tool_history(history_key, name, tool_success, &tool_content, message)
-            .await;
-
-        Ok(())
+        Ok(OutboundMessage::reply_to(message, text))
     }
 
     /// Execute a respond action - generate the final response using sensitivity-based routing.
@@ -890,6 +1028,9 @@ impl<S: MessageSender> Orchestrator<S> {
         history_key: &str,
         memory_context: Option<&MemoryContext>,
     ) -> Result<OutboundMessage, OrchestratorError> {
+        // Uncertain sensitivity fails closed to Maple unless disabled
+        let sensitivity = resolve_sensitivity(Some(sensitivity), self.router.fail_closed());
+
         // Vision tasks MUST use Maple - Grok has no vision support
         let effective_task_hint = Self::resolve_task_hint(message, task_hint);
         let force_maple = effective_task_hint == TaskHint::Vision;
@@ -910,18 +1051,31 @@ impl<S: MessageSender> Orchestrator<S> {
             AgentIndicator::Privacy
         };
 
-        // Select the best model based on task hint
-        let selected_model = if use_grok {
-            self.model_selector.select_grok(effective_task_hint)
-        } else {
-            self.model_selector.select_maple(effective_task_hint)
-        };
+        // Select the best model based on task hint, with the sender's
+        // profile default as a soft override for general queries
+        let profile_model = self.profile.default_model(&message.sender).await;
+        let selected_model = self.model_selector.select_with_profile(
+            use_grok,
+            effective_task_hint,
+            profile_model.as_deref(),
+        );
+        let selected_model = selected_model.as_str();
 
         info!(
             "Generating response with {:?} (sensitivity: {:?}, task_hint: {:?}, model: {}, use_grok: {}, force_maple: {})",
             indicator, sensitivity, effective_task_hint, selected_model, use_grok, force_maple
         );
 
+        // Ground the response in knowledge base snippets when configured
+        let mut context = context.clone();
+        if let Some(knowledge) = &self.knowledge {
+            let snippets = retrieve_for_message(knowledge.as_ref(), &self.knowledge_settings, &message.text);
+            if !snippets.is_empty() {
+                debug!("Injecting {} knowledge base snippet(s)", snippets.len());
+                context.add_knowledge(snippets);
+            }
+        }
+
         // Augment message with search context if any
         let mut augmented = context.augment_message(message);
         self.attach_routing_info(
@@ -957,11 +1111,17 @@ impl<S: MessageSender> Orchestrator<S> {
         // Process through the appropriate brain
         // Note: Currently using the default model configured in the brain.
         // TODO: Add per-request model override support to brains for dynamic model selection.
+        let routing_debug = self.routing_debug(&augmented, if use_grok { "grok" } else { "maple" });
         let mut response = if use_grok {
             self.grok_brain.process(augmented).await?
         } else {
             self.maple_brain.process(augmented).await?
         };
+        self.responders.record(
+            history_key,
+            if use_grok { Responder::Grok } else { Responder::Maple },
+        );
+        response.routing_debug = routing_debug;
         let summary_text = response.text.clone();
 
         // Log the response from the brain
@@ -1053,7 +1213,11 @@ impl<S: MessageSender> Orchestrator<S> {
         // Process through Grok
         // Note: Currently using the default model configured in the brain.
         // TODO: Add per-request model override support for dynamic model selection.
+        let routing_debug = self.routing_debug(&augmented, "grok");
         let mut response = self.grok_brain.process(augmented).await?;
+        let history_key = Self::history_key(message);
+        self.responders.record(&history_key, Responder::Grok);
+        response.routing_debug = routing_debug;
         let summary_text = response.text.clone();
 
         // Add speed indicator
@@ -1062,7 +1226,6 @@ impl<S: MessageSender> Orchestrator<S> {
             response.text = format!("{}{}", indicator.prefix(), response.text);
         }
 
-        let history_key = Self::history_key(message);
         self.record_exchange(&history_key, query, &summary_text).await;
 
         info!("Direct Grok response: {} chars", response.text.len());
@@ -1107,7 +1270,10 @@ impl<S: MessageSender> Orchestrator<S> {
         // Process through Maple
         // Note: Currently using the default model configured in the brain.
         // TODO: Add per-request model override support for dynamic model selection.
-        let response = self.maple_brain.process(augmented).await?;
+        let routing_debug = self.routing_debug(&augmented, "maple");
+        let mut response = self.maple_brain.process(augmented).await?;
+        self.responders.record(history_key, Responder::Maple);
+        response.routing_debug = routing_debug;
 
         self.record_exchange(history_key, query, &response.text).await;
 
@@ -1168,7 +1334,10 @@ impl<S: MessageSender> Orchestrator<S> {
         // Process through Maple
         // Note: Currently using the default model configured in the brain.
         // TODO: Add per-request model override support for dynamic model selection.
-        let response = self.maple_brain.process(augmented).await?;
+        let routing_debug = self.routing_debug(&augmented, "maple");
+        let mut response = self.maple_brain.process(augmented).await?;
+        self.responders.record(history_key, Responder::Maple);
+        response.routing_debug = routing_debug;
 
         self.record_exchange(history_key, query, &response.text).await;
 
@@ -1423,6 +1592,19 @@ impl<S: MessageSender> Orchestrator<S> {
         Ok(OutboundMessage::reply_to(message, response_text))
     }
 
+    /// Execute a view_offer action - echo the user's full Bolt 12 offer.
+    async fn execute_view_offer(
+        &self,
+        message: &InboundMessage,
+    ) -> Result<OutboundMessage, OrchestratorError> {
+        info!("Viewing Bolt 12 offer for {}", message.sender);
+
+        let profile = self.profile.get(&message.sender).await;
+        let response_text = ProfileStore::format_offer(profile.as_ref());
+
+        Ok(OutboundMessage::reply_to(message, response_text))
+    }
+
     /// Execute an update_profile action - update a profile field.
     async fn execute_update_profile(
         &self,
@@ -1539,28 +1721,14 @@ impl<S: MessageSender> Orchestrator<S> {
 
         info!("Generating Lightning invoice (amount: {:?} sats)", amount_sats);
 
-        // Convert sats to msats (1 sat = 1000 msats)
-        let amount_msats = amount_sats.map(|sats| (sats as i64) * 1000).unwrap_or(0);
-        let description = Some("Aman Bot Donation".to_string());
-        let expiry_secs = Some(3600); // 1 hour
-
-        // Create the invoice
-        let transaction = match wallet.create_invoice(amount_msats, description, expiry_secs).await {
-            Ok(tx) => tx,
-            Err(e) => {
-                let error_msg = format!("Failed to create Lightning invoice: {}", e);
+        let invoice = match donation_invoice(wallet.as_ref(), amount_sats).await {
+            Ok(invoice) => invoice,
+            Err(error_msg) => {
                 warn!("{}", error_msg);
                 return Ok(OutboundMessage::reply_to(message, error_msg));
             }
         };
-
-        // Extract invoice string
-        let invoice = &transaction.invoice;
-        if invoice.is_empty() {
-            let error_msg = "Failed to generate Lightning invoice: empty invoice returned";
-            warn!("{}", error_msg);
-            return Ok(OutboundMessage::reply_to(message, error_msg));
-        }
+        let invoice = &invoice;
 
         // Generate QR code
         let qr_path = match Self::generate_qr_code(invoice) {
@@ -1744,6 +1912,7 @@ impl<S: MessageSender> Orchestrator<S> {
     }
 
     async fn load_persistence_from_env(
+        maple_config: &MapleBrainConfig,
     ) -> Result<(PreferenceStore, Option<MemoryStore>, ProfileStore), OrchestratorError> {
         let sqlite_path = match env::var("SQLITE_PATH") {
             Ok(path) => path,
@@ -1751,7 +1920,7 @@ impl<S: MessageSender> Orchestrator<S> {
         };
 
         let sqlite_url = sqlite_url_from_path(&sqlite_path);
-        let database = Database::connect(&sqlite_url)
+        let database = Database::connect_with_config(&sqlite_url, &DatabaseConfig::from_env())
             .await
             .map_err(|e| OrchestratorError::ToolFailed(format!("Database error: {}", e)))?;
         database
@@ -1762,12 +1931,37 @@ impl<S: MessageSender> Orchestrator<S> {
         let publisher = memory_publisher_from_env().await;
         let preferences = PreferenceStore::with_database(database.clone(), publisher.clone());
         let settings = MemorySettings::from_env();
-        let memory = Some(MemoryStore::new(database.clone(), settings, publisher));
+        let memory = MemoryStore::new(database.clone(), settings, publisher);
+        let memory = Some(Self::attach_summarizer(memory, maple_config).await);
         let profile = ProfileStore::with_database(database);
 
         Ok((preferences, memory, profile))
     }
 
+    /// Give the memory store a dedicated, stateless brain for its summary
+    /// model, if one is configured.
+    ///
+    /// Falls back to extractive summaries if the brain cannot be created.
+    async fn attach_summarizer(memory: MemoryStore, maple_config: &MapleBrainConfig) -> MemoryStore {
+        let Some(model) = memory.settings().summary_model.clone() else {
+            return memory;
+        };
+
+        match MapleBrain::new(summarizer_config(maple_config, &model)).await {
+            Ok(brain) => {
+                info!("Memory summaries use model {}", model);
+                memory.with_summarizer(Arc::new(brain))
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to initialize summary model {} ({}); using extractive summaries",
+                    model, e
+                );
+                memory
+            }
+        }
+    }
+
     /// Try to create an email client from environment variables.
     /// Returns None if not configured (missing PROTON_USERNAME/PROTON_PASSWORD).
     fn load_email_client_from_env() -> Option<EmailClient> {
@@ -1796,6 +1990,21 @@ impl<S: MessageSender> Orchestrator<S> {
         }
     }
 
+    /// Load the donation wallet and let the profile store use it to check
+    /// Bolt 12 offers.
+    #[cfg(feature = "lightning")]
+    async fn attach_donation_wallet(
+        profile: ProfileStore,
+    ) -> (Option<Arc<dyn InvoiceSource>>, ProfileStore) {
+        match Self::load_donation_wallet_from_env().await {
+            Some(wallet) => {
+                let profile = profile.with_offer_decoder(wallet.clone());
+                (Some(wallet as Arc<dyn InvoiceSource>), profile)
+            }
+            None => (None, profile),
+        }
+    }
+
     /// Try to create a donation wallet from environment variables.
     /// Returns None if not configured (no Lightning backend credentials found).
     ///
@@ -1902,6 +2111,122 @@ impl<S: MessageSender> Orchestrator<S> {
     pub fn profile(&self) -> &ProfileStore {
         &self.profile
     }
+
+    /// Suppress interim status messages ("Searching: ...", tool status).
+    ///
+    /// The typing indicator is still shown and the final response is
+    /// unaffected. Also set by `AMAN_SUPPRESS_STATUS_MESSAGES`.
+    pub fn with_status_messages_suppressed(mut self, suppress: bool) -> Self {
+        self.suppress_status_messages = suppress;
+        self
+    }
+
+    /// Attach routing details (`OutboundMessage::routing_debug`) to every
+    /// response. Also set by `AMAN_DEBUG_ROUTING`; a single message can ask
+    /// for them with `RoutingInfo::debug`.
+    pub fn with_routing_debug(mut self, enabled: bool) -> Self {
+        self.debug_routing = enabled;
+        self
+    }
+
+    /// Hold search and tool results in the brain prompt to `max_chars`
+    /// characters in total, trimming the oldest first (0 disables). Also set
+    /// by `AMAN_CONTEXT_MAX_CHARS`.
+    pub fn with_context_budget(mut self, max_chars: usize) -> Self {
+        self.context_max_chars = max_chars;
+        self
+    }
+
+    /// Condense search and tool results with `summarizer` when they exceed
+    /// the context budget, instead of trimming them. Also set by
+    /// `AMAN_CONTEXT_SUMMARY_MODEL`.
+    pub fn with_result_summarizer(mut self, summarizer: Arc<dyn ResultSummarizer>) -> Self {
+        self.result_summarizer = Some(summarizer);
+        self
+    }
+
+    /// Whether interim status messages are suppressed.
+    pub fn status_messages_suppressed(&self) -> bool {
+        self.suppress_status_messages
+    }
+
+    /// Ingest `.txt`/`.md` attachments from the ingestor's admin numbers
+    /// into the knowledge base. Also set by `AMAN_KB_ADMIN_NUMBERS`.
+    #[cfg(feature = "nostr")]
+    pub fn with_kb_ingestor(mut self, ingestor: KbIngestor) -> Self {
+        self.kb_ingestor = Some(ingestor);
+        self
+    }
+
+    /// Set the knowledge base used to ground responses.
+    pub fn set_knowledge(&mut self, knowledge: Arc<dyn KnowledgeRetriever>, settings: KnowledgeSettings) {
+        self.knowledge = Some(knowledge);
+        self.knowledge_settings = settings;
+    }
+
+    /// Release persistent resources before process exit.
+    ///
+    /// Closes the SQLite pool shared by the preference, memory and profile
+    /// stores so pending writes are flushed. Safe to call when no database
+    /// is configured.
+    pub async fn shutdown(&self) {
+        self.profile.close().await;
+    }
+}
+
+/// Config for the memory summarization brain: the response brain's
+/// endpoint and credentials with the summary model, no history and a
+/// summarization prompt.
+fn summarizer_config(maple_config: &MapleBrainConfig, model: &str) -> MapleBrainConfig {
+    let mut config = maple_config.clone();
+    config.model = model.to_string();
+    config.system_prompt = Some(SUMMARY_SYSTEM_PROMPT.to_string());
+    config.max_history_turns = 0; // Stateless
+    config.temperature = Some(0.2);
+    config.max_tokens = Some(512);
+    config
+}
+
+/// Config for the results summarization brain: the memory summarizer's
+/// config with the results prompt and room for a budget-sized summary.
+fn results_summarizer_config(maple_config: &MapleBrainConfig, model: &str) -> MapleBrainConfig {
+    let mut config = summarizer_config(maple_config, model);
+    config.system_prompt = Some(RESULTS_SUMMARY_SYSTEM_PROMPT.to_string());
+    config.max_tokens = Some(2048);
+    config
+}
+
+/// Results summarizer for `AMAN_CONTEXT_SUMMARY_MODEL`, if set.
+///
+/// Falls back to trimming results if the brain cannot be created.
+async fn load_result_summarizer_from_env(
+    maple_config: &MapleBrainConfig,
+) -> Option<Arc<dyn ResultSummarizer>> {
+    let model = env::var("AMAN_CONTEXT_SUMMARY_MODEL").ok()?;
+    let model = model.trim();
+    if model.is_empty() {
+        return None;
+    }
+
+    match MapleBrain::new(results_summarizer_config(maple_config, model)).await {
+        Ok(brain) => {
+            info!("Over-budget results are summarized with model {}", model);
+            Some(Arc::new(BrainResultSummarizer::new(Arc::new(brain), model)))
+        }
+        Err(e) => {
+            warn!(
+                "Failed to initialize results summary model {} ({}); trimming results instead",
+                model, e
+            );
+            None
+        }
+    }
+}
+
+fn load_knowledge_from_env() -> Option<Arc<dyn KnowledgeRetriever>> {
+    let kb = KnowledgeBase::from_env()?;
+    info!("Knowledge base loaded ({} entries)", kb.len());
+    Some(Arc::new(kb))
 }
 
 fn sqlite_url_from_path(path: &str) -> String {
@@ -1916,7 +2241,7 @@ fn sqlite_url_from_path(path: &str) -> String {
 mod tests {
     use super::*;
     use crate::sender::NoOpSender;
-    use brain_core::InboundAttachment;
+    use brain_core::{InboundAttachment, RoutingInfo};
 
     #[test]
     fn test_history_key_direct() {
@@ -1927,6 +2252,106 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_undetermined_sensitivity_fails_closed_to_maple() {
+        for sensitivity in [None, Some(Sensitivity::Uncertain)] {
+            let resolved = resolve_sensitivity(sensitivity, true);
+            assert_eq!(resolved, Sensitivity::Sensitive);
+            assert!(!PreferenceStore::resolve_agent(UserPreference::Default, resolved));
+        }
+        assert_eq!(
+            resolve_sensitivity(Some(Sensitivity::Insensitive), true),
+            Sensitivity::Insensitive
+        );
+
+        // Fail-open keeps the router's answer
+        assert_eq!(resolve_sensitivity(None, false), Sensitivity::default());
+        assert_eq!(
+            resolve_sensitivity(Some(Sensitivity::Uncertain), false),
+            Sensitivity::Uncertain
+        );
+    }
+
+    #[test]
+    fn test_forget_follows_alternating_responders() {
+        let log = ResponderLog::default();
+        for responder in [Responder::Maple, Responder::Grok, Responder::Maple, Responder::Grok] {
+            log.record("+1234567890", responder);
+        }
+        log.record("group-1", Responder::Maple);
+
+        // Each forget targets the brain that answered that turn, newest first
+        assert_eq!(log.take_last("+1234567890"), Some(Responder::Grok));
+        assert_eq!(log.take_last("+1234567890"), Some(Responder::Maple));
+        assert_eq!(log.take_last("+1234567890"), Some(Responder::Grok));
+        assert_eq!(log.take_last("+1234567890"), Some(Responder::Maple));
+        assert_eq!(log.take_last("+1234567890"), None);
+        assert_eq!(log.take_last("group-1"), Some(Responder::Maple));
+
+        log.record("group-1", Responder::Grok);
+        log.clear("group-1");
+        assert_eq!(log.take_last("group-1"), None);
+    }
+
+    #[test]
+    fn test_fallback_respond_honors_plan_pii() {
+        let json = r#"{"actions": [
+            {"type": "search", "query": "replace lost card", "has_pii": true, "pii_types": ["ssn"]}
+        ]}"#;
+        let plan: RoutingPlan = serde_json::from_str(json).unwrap();
+
+        // Private even with fail-closed routing disabled
+        let (sensitivity, task_hint) = fallback_routing(&plan, false, false);
+        assert_eq!(sensitivity, Sensitivity::Sensitive);
+        assert_eq!(task_hint, TaskHint::default());
+        assert!(!PreferenceStore::resolve_agent(UserPreference::Default, sensitivity));
+    }
+
+    #[test]
+    fn test_fallback_respond_uses_plan_sensitivity() {
+        let search_only = RoutingPlan::new(vec![OrchestratorAction::search("weather nyc")]);
+        assert_eq!(fallback_routing(&search_only, false, true).0, Sensitivity::Sensitive);
+        assert_eq!(fallback_routing(&search_only, false, false).0, Sensitivity::default());
+        assert_eq!(
+            fallback_routing(&search_only, true, false),
+            (Sensitivity::Sensitive, TaskHint::Vision)
+        );
+
+        let json =
+            r#"{"actions": [{"type": "search", "query": "weather", "sensitivity": "insensitive"}]}"#;
+        let stated: RoutingPlan = serde_json::from_str(json).unwrap();
+        assert_eq!(fallback_routing(&stated, false, true).0, Sensitivity::Insensitive);
+    }
+
+    #[test]
+    fn test_routing_debug_only_when_enabled() {
+        let mut message = InboundMessage::direct("+1234567890", "hello", 123);
+        message.routing = Some(RoutingInfo {
+            task_hint: Some(TaskHint::Coding),
+            model_override: Some("qwen2-5-72b".to_string()),
+            memory_prompt: Some("[MEMORY] private notes".to_string()),
+            memory_prompt_hash: Some("abc123".to_string()),
+            memory_source: Some("sqlite".to_string()),
+            ..Default::default()
+        });
+
+        // Off by default
+        assert!(routing_debug_for(false, &message, "maple").is_none());
+
+        // On for every response
+        let debug = routing_debug_for(true, &message, "maple").unwrap();
+        assert_eq!(debug.brain.as_deref(), Some("maple"));
+        assert_eq!(debug.task_hint, Some(TaskHint::Coding));
+        assert_eq!(debug.model.as_deref(), Some("qwen2-5-72b"));
+        assert_eq!(debug.memory_prompt_hash.as_deref(), Some("abc123"));
+        let json = serde_json::to_string(&debug).unwrap();
+        assert!(!json.contains("private notes"));
+
+        // Requested for this message only
+        message.routing.as_mut().unwrap().debug = true;
+        assert!(routing_debug_for(false, &message, "grok").is_some());
+    }
+
     #[test]
     fn test_history_key_group() {
         let message = InboundMessage::group("+1234567890", "hello", 123, "group123");
@@ -1936,6 +2361,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_summarizer_config_uses_summary_model() {
+        let response_config = MapleBrainConfig::new("key", "llama-3.3-70b")
+            .with_system_prompt("You are Aman.")
+            .with_max_history_turns(10);
+
+        let config = summarizer_config(&response_config, "cheap-model");
+        assert_eq!(config.model, "cheap-model");
+        assert_eq!(config.api_key, "key");
+        assert_eq!(config.max_history_turns, 0);
+        assert_eq!(config.system_prompt.as_deref(), Some(SUMMARY_SYSTEM_PROMPT));
+        // The response brain's config is untouched.
+        assert_eq!(response_config.model, "llama-3.3-70b");
+    }
+
+    #[test]
+    fn test_results_summarizer_config_uses_results_prompt() {
+        let response_config = MapleBrainConfig::new("key", "llama-3.3-70b");
+
+        let config = results_summarizer_config(&response_config, "cheap-model");
+        assert_eq!(config.model, "cheap-model");
+        assert_eq!(config.max_history_turns, 0);
+        assert_eq!(config.system_prompt.as_deref(), Some(RESULTS_SUMMARY_SYSTEM_PROMPT));
+    }
+
     #[test]
     fn test_help_text_not_empty() {
         assert!(!HELP_TEXT.is_empty());
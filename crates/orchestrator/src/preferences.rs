@@ -134,11 +134,14 @@ impl PreferenceStore {
 
     /// Resolve which agent to use given preference and sensitivity.
     ///
-    /// Returns `true` for Grok, `false` for Maple.
+    /// Returns `true` for Grok, `false` for Maple. The orchestrator resolves
+    /// sensitivity first and, while failing closed (the default), turns
+    /// uncertain into sensitive; `Uncertain` only reaches this point when
+    /// `AMAN_FAIL_CLOSED=false`.
     pub fn resolve_agent(preference: UserPreference, sensitivity: Sensitivity) -> bool {
         match preference {
             UserPreference::PreferSpeed => {
-                // Use Grok for everything except explicitly sensitive content
+                // Use Grok for everything except sensitive content
                 !matches!(sensitivity, Sensitivity::Sensitive)
             }
             UserPreference::PreferPrivacy => {
@@ -146,8 +149,8 @@ impl PreferenceStore {
                 false
             }
             UserPreference::Default => {
-                // Use Grok for insensitive and uncertain content
-                // Only explicitly sensitive goes to Maple
+                // Grok unless sensitive; uncertain content was already made
+                // sensitive unless fail-closed routing is off
                 !matches!(sensitivity, Sensitivity::Sensitive)
             }
         }
@@ -225,7 +228,7 @@ mod tests {
 
     #[test]
     fn test_resolve_agent_default() {
-        // Default: Grok for insensitive and uncertain, Maple only for sensitive
+        // Default: Maple for sensitive; uncertain only arrives with fail-closed off
         assert!(PreferenceStore::resolve_agent(
             UserPreference::Default,
             Sensitivity::Insensitive
@@ -242,7 +245,7 @@ mod tests {
 
     #[test]
     fn test_resolve_agent_prefer_speed() {
-        // Prefer speed: Grok for insensitive and uncertain, Maple only for sensitive
+        // Prefer speed: Maple only for sensitive
         assert!(PreferenceStore::resolve_agent(
             UserPreference::PreferSpeed,
             Sensitivity::Insensitive
@@ -1,11 +1,20 @@
 //! User profile management for the orchestrator.
 
-use aman_database::{user_profile, Database, ProfileField, UserProfile, ValidationError};
-use aman_database::validation::{validate_bolt12_offer, validate_email, validate_model_length};
+use aman_database::{user, user_profile, Database, ProfileField, UserProfile, ValidationError};
+use aman_database::validation::{
+    validate_bolt12_offer, validate_email, validate_model_length, validate_timezone,
+    validate_units,
+};
+use agent_tools::UnitConverter;
+use serde_json::Value;
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 use tracing::{debug, warn};
 
-use crate::model_selection::MapleModels;
+use crate::actions::OrchestratorAction;
+use crate::donation::OfferDecoder;
+use crate::model_selection::{GrokModels, MapleModels};
 
 /// Errors that can occur during profile operations.
 #[derive(Debug)]
@@ -30,7 +39,7 @@ impl fmt::Display for ProfileError {
             ProfileError::NotConfigured => write!(f, "Profile storage is not configured"),
             ProfileError::InvalidModel(msg) => write!(f, "{}", msg),
             ProfileError::UnknownField(field) => {
-                write!(f, "Unknown field '{}'. Valid fields: default_model, email, bolt12_offer", field)
+                write!(f, "Unknown field '{}'. Valid fields: default_model, email, bolt12_offer, preferred_units, timezone", field)
             }
         }
     }
@@ -50,24 +59,112 @@ impl From<aman_database::DatabaseError> for ProfileError {
     }
 }
 
-/// Known Grok models for validation.
-const GROK_MODELS: &[&str] = &["grok-4-1-fast", "grok-4-1", "grok-3", "grok-3-mini", "grok-4"];
+/// Profile preferences used to fill in tool arguments the user left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolDefaults {
+    /// Preferred measurement system ("metric" or "imperial").
+    pub units: Option<String>,
+    /// Preferred timezone (e.g., "Europe/Paris").
+    pub timezone: Option<String>,
+}
+
+impl ToolDefaults {
+    /// Build defaults from a stored profile.
+    pub fn from_profile(profile: &UserProfile) -> Self {
+        Self {
+            units: profile.preferred_units.clone(),
+            timezone: profile.timezone.clone(),
+        }
+    }
+
+    /// Whether there is nothing to inject.
+    pub fn is_empty(&self) -> bool {
+        self.units.is_none() && self.timezone.is_none()
+    }
+
+    /// Fill in missing arguments for a single tool call.
+    ///
+    /// Arguments the router already set are never overwritten.
+    pub fn apply(&self, tool_name: &str, args: &mut HashMap<String, Value>) {
+        match tool_name {
+            "weather" => {
+                if let Some(units) = &self.units {
+                    args.entry("units".to_string())
+                        .or_insert_with(|| Value::String(units.clone()));
+                }
+            }
+            "world_time" => {
+                if let Some(timezone) = &self.timezone {
+                    args.entry("location".to_string())
+                        .or_insert_with(|| Value::String(timezone.clone()));
+                }
+            }
+            "unit_converter" => {
+                if args.contains_key("to") {
+                    return;
+                }
+                let imperial = self.units.as_deref() == Some("imperial");
+                let target = self
+                    .units
+                    .as_ref()
+                    .and(args.get("from").and_then(Value::as_str))
+                    .and_then(|from| UnitConverter::counterpart_unit(from, imperial));
+                if let Some(target) = target {
+                    args.insert("to".to_string(), Value::String(target.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Fill in missing arguments for every tool call in a plan.
+    pub fn apply_to_actions(&self, actions: &mut [OrchestratorAction]) {
+        if self.is_empty() {
+            return;
+        }
+        for action in actions {
+            if let OrchestratorAction::UseTool { name, args, .. } = action {
+                self.apply(name, args);
+            }
+        }
+    }
+}
 
 /// User profile store for managing personal settings.
 pub struct ProfileStore {
     database: Option<Database>,
+    offer_decoder: Option<Arc<dyn OfferDecoder>>,
 }
 
 impl ProfileStore {
     /// Create a new profile store without database (in-memory only).
     pub fn new() -> Self {
-        Self { database: None }
+        Self {
+            database: None,
+            offer_decoder: None,
+        }
     }
 
     /// Create a profile store with database persistence.
     pub fn with_database(database: Database) -> Self {
         Self {
             database: Some(database),
+            offer_decoder: None,
+        }
+    }
+
+    /// Confirm Bolt 12 offers decode before storing them.
+    ///
+    /// Without a decoder, offers are only checked syntactically.
+    pub fn with_offer_decoder(mut self, decoder: Arc<dyn OfferDecoder>) -> Self {
+        self.offer_decoder = Some(decoder);
+        self
+    }
+
+    /// Close the underlying database pool, if any.
+    pub async fn close(&self) {
+        if let Some(database) = &self.database {
+            database.close().await;
         }
     }
 
@@ -83,6 +180,29 @@ impl ProfileStore {
         }
     }
 
+    /// Get a user's stored default model, if any.
+    pub async fn default_model(&self, sender_id: &str) -> Option<String> {
+        self.get(sender_id).await?.default_model
+    }
+
+    /// Get a user's tool defaults (units, timezone) from their profile.
+    pub async fn tool_defaults(&self, sender_id: &str) -> ToolDefaults {
+        self.get(sender_id)
+            .await
+            .map(|profile| ToolDefaults::from_profile(&profile))
+            .unwrap_or_default()
+    }
+
+    /// Get a user's preferred language from their user record, if any.
+    pub async fn language(&self, sender_id: &str) -> Option<String> {
+        let database = self.database.as_ref()?;
+        user::get_user(database.pool(), sender_id)
+            .await
+            .ok()
+            .map(|user| user.language)
+            .filter(|language| !language.trim().is_empty())
+    }
+
     /// Update a profile field.
     ///
     /// Validates the value before storing.
@@ -94,17 +214,24 @@ impl ProfileStore {
     ) -> Result<(), ProfileError> {
         let database = self.database.as_ref().ok_or(ProfileError::NotConfigured)?;
 
-        // Validate the value if present
-        if let Some(val) = value {
+        // Normalize, then validate the value if present
+        let normalized = value.map(|val| Self::normalize_value(field, val));
+        if let Some(val) = normalized.as_deref() {
             match field {
                 ProfileField::Email => validate_email(val)?,
-                ProfileField::Bolt12Offer => validate_bolt12_offer(val)?,
+                ProfileField::Bolt12Offer => {
+                    validate_bolt12_offer(val)?;
+                    self.check_offer_decodes(val).await?;
+                }
                 ProfileField::DefaultModel => {
                     validate_model_length(val)?;
                     Self::validate_model(val)?;
                 }
+                ProfileField::PreferredUnits => validate_units(val)?,
+                ProfileField::Timezone => validate_timezone(val)?,
             }
         }
+        let value = normalized.as_deref();
 
         user_profile::upsert_profile_field(database.pool(), sender_id, field, value)
             .await
@@ -120,6 +247,28 @@ impl ProfileStore {
         Ok(())
     }
 
+    /// Normalize user input for a field before validating and storing it.
+    fn normalize_value(field: ProfileField, value: &str) -> String {
+        let value = value.trim();
+        match field {
+            // Bech32 is case-insensitive; tools match units exactly
+            ProfileField::Bolt12Offer | ProfileField::PreferredUnits => value.to_lowercase(),
+            ProfileField::Email | ProfileField::DefaultModel | ProfileField::Timezone => {
+                value.to_string()
+            }
+        }
+    }
+
+    /// Reject Bolt 12 offers the wallet cannot decode.
+    async fn check_offer_decodes(&self, offer: &str) -> Result<(), ValidationError> {
+        let Some(decoder) = &self.offer_decoder else {
+            return Ok(());
+        };
+        decoder.decode(offer).await.map(|_| ()).map_err(|e| {
+            ValidationError::InvalidBolt12Offer(format!("offer could not be decoded ({})", e))
+        })
+    }
+
     /// Delete a user's entire profile.
     ///
     /// Returns true if a profile was deleted.
@@ -139,31 +288,17 @@ impl ProfileStore {
 
     /// Validate a model name against known models.
     pub fn validate_model(model: &str) -> Result<(), ProfileError> {
-        let model_lower = model.to_lowercase();
-
-        // Check Maple model aliases
-        let maple_aliases = MapleModels::model_aliases();
-        if maple_aliases.iter().any(|alias| alias.eq_ignore_ascii_case(model)) {
-            return Ok(());
-        }
-
-        // Check full Maple model names
-        for (alias, canonical) in MapleModels::available_models() {
-            if model_lower == canonical.to_lowercase() || model_lower == *alias {
-                return Ok(());
-            }
-        }
-
-        // Check Grok models
-        if GROK_MODELS.iter().any(|m| m.eq_ignore_ascii_case(model)) {
+        if MapleModels::canonical_model(model).is_some()
+            || GrokModels::canonical_model(model).is_some()
+        {
             return Ok(());
         }
 
         // Build helpful error message
-        let available: Vec<String> = maple_aliases
+        let available: Vec<String> = MapleModels::model_aliases()
             .iter()
             .map(|s| s.to_string())
-            .chain(GROK_MODELS.iter().map(|s| s.to_string()))
+            .chain(GrokModels::available_models().iter().map(|s| s.to_string()))
             .collect();
 
         Err(ProfileError::InvalidModel(format!(
@@ -181,7 +316,9 @@ impl ProfileStore {
                     • default_model - Your preferred AI model\n\
                       (llama, deepseek, qwen, mistral, gpt-oss, grok-4-1-fast)\n\
                     • email - Your email address\n\
-                    • bolt12_offer - Lightning payment offer"
+                    • bolt12_offer - Lightning payment offer\n\
+                    • preferred_units - metric or imperial\n\
+                    • timezone - e.g. Europe/Paris"
                 .to_string(),
             Some(p) => {
                 let mut lines = Vec::new();
@@ -212,11 +349,33 @@ impl ProfileStore {
                     lines.push("• Bolt 12 offer: (not set)".to_string());
                 }
 
+                lines.push(format!(
+                    "• Units: {}",
+                    p.preferred_units.as_deref().unwrap_or("(not set)")
+                ));
+                lines.push(format!(
+                    "• Timezone: {}",
+                    p.timezone.as_deref().unwrap_or("(not set)")
+                ));
+
                 lines.join("\n")
             }
         }
     }
 
+    /// Format a user's Bolt 12 offer in full for verification.
+    pub fn format_offer(profile: Option<&UserProfile>) -> String {
+        match profile.and_then(|p| p.bolt12_offer.as_deref()) {
+            Some(offer) => format!(
+                "Your saved Bolt 12 offer:\n\n{}\n\nCheck it matches your wallet before sharing it.",
+                offer
+            ),
+            None => "You don't have a Bolt 12 offer saved. \
+                     Set one with \"set my bolt12 to lno1...\"."
+                .to_string(),
+        }
+    }
+
     /// Parse a field name from user input.
     pub fn parse_field(field: &str) -> Result<ProfileField, ProfileError> {
         ProfileField::from_str(field).ok_or_else(|| ProfileError::UnknownField(field.to_string()))
@@ -232,6 +391,8 @@ impl Default for ProfileStore {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model_selection::ModelSelector;
+    use brain_core::TaskHint;
 
     #[test]
     fn test_validate_model_maple_aliases() {
@@ -275,6 +436,8 @@ mod tests {
             default_model: Some("llama".to_string()),
             email: None,
             bolt12_offer: None,
+            preferred_units: None,
+            timezone: None,
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
         };
@@ -290,6 +453,8 @@ mod tests {
             default_model: Some("grok-4-1-fast".to_string()),
             email: Some("test@example.com".to_string()),
             bolt12_offer: Some("lno1qcp4256ypqpq8q2qqqqqq".to_string()),
+            preferred_units: Some("imperial".to_string()),
+            timezone: Some("America/Chicago".to_string()),
             created_at: "2024-01-01".to_string(),
             updated_at: "2024-01-01".to_string(),
         };
@@ -297,6 +462,25 @@ mod tests {
         assert!(formatted.contains("Default model: grok-4-1-fast"));
         assert!(formatted.contains("Email: test@example.com"));
         assert!(formatted.contains("Bolt 12 offer: lno1qcp4256ypqpq8q2qqqqqq"));
+        assert!(formatted.contains("Units: imperial"));
+        assert!(formatted.contains("Timezone: America/Chicago"));
+    }
+
+    #[test]
+    fn test_format_offer_echoes_full_offer() {
+        let offer = format!("lno1{}", "q".repeat(80));
+        let profile = UserProfile {
+            sender_id: "+1234567890".to_string(),
+            default_model: None,
+            email: None,
+            bolt12_offer: Some(offer.clone()),
+            preferred_units: None,
+            timezone: None,
+            created_at: "2024-01-01".to_string(),
+            updated_at: "2024-01-01".to_string(),
+        };
+        assert!(ProfileStore::format_offer(Some(&profile)).contains(&offer));
+        assert!(ProfileStore::format_offer(None).contains("don't have a Bolt 12 offer"));
     }
 
     #[test]
@@ -313,6 +497,14 @@ mod tests {
             ProfileStore::parse_field("bolt12").unwrap(),
             ProfileField::Bolt12Offer
         );
+        assert_eq!(
+            ProfileStore::parse_field("units").unwrap(),
+            ProfileField::PreferredUnits
+        );
+        assert_eq!(
+            ProfileStore::parse_field("timezone").unwrap(),
+            ProfileField::Timezone
+        );
         assert!(ProfileStore::parse_field("invalid").is_err());
     }
 
@@ -324,4 +516,196 @@ mod tests {
         let err = ProfileError::NotConfigured;
         assert!(err.to_string().contains("not configured"));
     }
+
+    #[tokio::test]
+    async fn test_stored_default_model_changes_generic_selection() {
+        let database = Database::connect_with_pool_size("sqlite::memory:", 1)
+            .await
+            .unwrap();
+        database.migrate().await.unwrap();
+        let store = ProfileStore::with_database(database);
+        let selector = ModelSelector::new();
+        let sender = "+15550000000";
+
+        let before = store.default_model(sender).await;
+        assert_eq!(
+            selector.select_with_profile(false, TaskHint::General, before.as_deref()),
+            "llama-3.3-70b"
+        );
+
+        store
+            .update_field(sender, ProfileField::DefaultModel, Some("qwen"))
+            .await
+            .unwrap();
+        let after = store.default_model(sender).await;
+        assert_eq!(
+            selector.select_with_profile(false, TaskHint::General, after.as_deref()),
+            "qwen2-5-72b"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_language_from_user_record() {
+        let database = Database::connect_with_pool_size("sqlite::memory:", 1)
+            .await
+            .unwrap();
+        database.migrate().await.unwrap();
+        let user = aman_database::User {
+            id: "+15550000000".to_string(),
+            name: "Sam".to_string(),
+            language: "Spanish".to_string(),
+        };
+        user::create_user(database.pool(), &user).await.unwrap();
+        let store = ProfileStore::with_database(database);
+
+        assert_eq!(store.language("+15550000000").await.as_deref(), Some("Spanish"));
+        assert_eq!(store.language("+15551111111").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_units_and_timezone_flow_into_tool_args() {
+        let database = Database::connect_with_pool_size("sqlite::memory:", 1)
+            .await
+            .unwrap();
+        database.migrate().await.unwrap();
+        let store = ProfileStore::with_database(database);
+        let sender = "+15550000000";
+
+        let units = ProfileStore::parse_field("units").unwrap();
+        let timezone = ProfileStore::parse_field("timezone").unwrap();
+        store.update_field(sender, units, Some("Imperial")).await.unwrap();
+        store
+            .update_field(sender, timezone, Some("Europe/Paris"))
+            .await
+            .unwrap();
+        assert!(store.update_field(sender, units, Some("furlongs")).await.is_err());
+
+        let mut actions = vec![
+            OrchestratorAction::use_tool(
+                "weather",
+                HashMap::from([("location".to_string(), Value::from("Austin"))]),
+            ),
+            OrchestratorAction::use_tool("world_time", HashMap::new()),
+            OrchestratorAction::use_tool(
+                "unit_converter",
+                HashMap::from([
+                    ("value".to_string(), Value::from(10)),
+                    ("from".to_string(), Value::from("km")),
+                ]),
+            ),
+            OrchestratorAction::use_tool(
+                "weather",
+                HashMap::from([
+                    ("location".to_string(), Value::from("Oslo")),
+                    ("units".to_string(), Value::from("metric")),
+                ]),
+            ),
+        ];
+        store.tool_defaults(sender).await.apply_to_actions(&mut actions);
+
+        let args: Vec<_> = actions
+            .iter()
+            .map(|action| match action {
+                OrchestratorAction::UseTool { args, .. } => args.clone(),
+                other => panic!("unexpected action {:?}", other),
+            })
+            .collect();
+        assert_eq!(args[0]["units"], "imperial");
+        assert_eq!(args[1]["location"], "Europe/Paris");
+        assert_eq!(args[2]["to"], "mi");
+        // Explicit arguments win over profile defaults
+        assert_eq!(args[3]["units"], "metric");
+
+        // No profile means nothing is injected
+        let mut untouched = vec![OrchestratorAction::use_tool("world_time", HashMap::new())];
+        store.tool_defaults("+15551111111").await.apply_to_actions(&mut untouched);
+        assert!(matches!(
+            &untouched[0],
+            OrchestratorAction::UseTool { args, .. } if args.is_empty()
+        ));
+    }
+
+    /// Decoder that accepts a single known offer.
+    struct MockDecoder {
+        valid_offer: String,
+    }
+
+    #[async_trait::async_trait]
+    impl OfferDecoder for MockDecoder {
+        async fn decode(&self, encoded: &str) -> Result<String, String> {
+            if encoded == self.valid_offer {
+                Ok(r#"{"type":"bolt12 offer"}"#.to_string())
+            } else {
+                Err("invalid bech32 checksum".to_string())
+            }
+        }
+    }
+
+    async fn memory_store() -> ProfileStore {
+        let database = Database::connect_with_pool_size("sqlite::memory:", 1)
+            .await
+            .unwrap();
+        database.migrate().await.unwrap();
+        ProfileStore::with_database(database)
+    }
+
+    #[tokio::test]
+    async fn test_update_email_validation() {
+        let store = memory_store().await;
+        let sender = "+15550000000";
+
+        store
+            .update_field(sender, ProfileField::Email, Some("  sam@example.com "))
+            .await
+            .unwrap();
+        let profile = store.get(sender).await.unwrap();
+        assert_eq!(profile.email.as_deref(), Some("sam@example.com"));
+
+        let err = store
+            .update_field(sender, ProfileField::Email, Some("not-an-email"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Invalid email"));
+        let profile = store.get(sender).await.unwrap();
+        assert_eq!(profile.email.as_deref(), Some("sam@example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_update_bolt12_offer_must_decode() {
+        let valid_offer = format!("lno1{}", "qp".repeat(20));
+        let store = memory_store().await.with_offer_decoder(Arc::new(MockDecoder {
+            valid_offer: valid_offer.clone(),
+        }));
+        let sender = "+15550000000";
+
+        // Stored normalized to lowercase
+        store
+            .update_field(
+                sender,
+                ProfileField::Bolt12Offer,
+                Some(&format!(" {} ", valid_offer.to_uppercase())),
+            )
+            .await
+            .unwrap();
+        let profile = store.get(sender).await.unwrap();
+        assert_eq!(profile.bolt12_offer.as_deref(), Some(valid_offer.as_str()));
+
+        // Well-formed but undecodable
+        let undecodable = format!("lno1{}", "pq".repeat(20));
+        let err = store
+            .update_field(sender, ProfileField::Bolt12Offer, Some(&undecodable))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("could not be decoded"));
+
+        // Malformed offers are rejected before decoding
+        let err = store
+            .update_field(sender, ProfileField::Bolt12Offer, Some("lnbc1notanoffer"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("must start with 'lno1'"));
+
+        let profile = store.get(sender).await.unwrap();
+        assert_eq!(profile.bolt12_offer.as_deref(), Some(valid_offer.as_str()));
+    }
 }
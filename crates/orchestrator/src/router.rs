@@ -7,7 +7,7 @@ use std::path::Path;
 use tracing::{debug, info, trace, warn};
 
 use brain_core::{hash_prompt, Sensitivity, TaskHint};
-use crate::actions::RoutingPlan;
+use crate::actions::{sensitivity_rank, OrchestratorAction, RoutingPlan};
 use crate::error::OrchestratorError;
 
 /// Default path for the router prompt file.
@@ -24,6 +24,7 @@ Output JSON with an "actions" array. Each action has a "type" field.
 Available action types:
 - "search": Real-time search needed. Include "query" field with privacy-safe search terms. Include "message" field with a short, friendly one-liner status update.
 - "clear_context": Clear conversation history. Use this AUTOMATICALLY when the user's new message is about a completely different topic from the recent context. No user notification needed.
+- "forget_last": Forget only the most recent exchange (e.g. "forget that", "undo", "delete my last message"). Optionally include a short "message" confirmation.
 - "help": User is asking about bot capabilities or commands.
 - "respond": Generate a response to the user (should usually be last).
 - "skip": Don't process (e.g., message not meant for bot). Include "reason" field.
@@ -33,6 +34,7 @@ Guidelines:
 - Most messages need just: [{"type": "respond"}]
 - Current events/news need: [{"type": "search", ...}, {"type": "respond"}]
 - Explicit "forget our chat" needs: [{"type": "clear_context"}, {"type": "respond"}]
+- "forget that" / "undo my last message" needs: [{"type": "forget_last"}]
 - "what can you do" needs: [{"type": "help"}]
 - Accidental messages like "?", ".", "k", single random characters: [{"type": "ignore"}]
 - TOPIC CHANGE: If recent context exists and the new message is about a COMPLETELY DIFFERENT topic, add clear_context BEFORE respond. Example: context is about "Minnesota politics" but user asks about "bitcoin price" → clear first.
@@ -118,6 +120,136 @@ fn load_prompt_file(path: impl AsRef<Path>) -> Option<String> {
     }
 }
 
+/// Environment variable listing keyword sensitivity overrides.
+pub const SENSITIVITY_KEYWORDS_ENV: &str = "AMAN_SENSITIVITY_KEYWORDS";
+
+/// Environment variable controlling fail-closed routing.
+pub const FAIL_CLOSED_ENV: &str = "AMAN_FAIL_CLOSED";
+
+/// Whether `AMAN_FAIL_CLOSED` keeps fail-closed routing on (the default).
+fn load_fail_closed_from_env() -> bool {
+    env::var(FAIL_CLOSED_ENV)
+        .map(|value| !matches!(value.trim().to_lowercase().as_str(), "false" | "0" | "no" | "off"))
+        .unwrap_or(true)
+}
+
+/// Operator keywords that force a minimum sensitivity after routing.
+///
+/// Matching is case-insensitive on whole words (or whole phrases), so it
+/// works for keywords in any language. Overrides only ever raise the
+/// router's sensitivity; they never lower it.
+#[derive(Debug, Clone, Default)]
+pub struct SensitivityOverrides {
+    rules: Vec<(String, Sensitivity)>,
+}
+
+impl SensitivityOverrides {
+    /// Create an empty override set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a keyword (or phrase) that forces at least `sensitivity`.
+    pub fn with_keyword(mut self, keyword: &str, sensitivity: Sensitivity) -> Self {
+        let keyword = normalize_words(keyword);
+        if !keyword.trim().is_empty() {
+            self.rules.push((keyword, sensitivity));
+        }
+        self
+    }
+
+    /// Parse a comma-separated list of `keyword` or `keyword:level` entries.
+    ///
+    /// Levels are `sensitive` (the default) or `uncertain`. Entries with an
+    /// unknown level are skipped with a warning.
+    pub fn parse(spec: &str) -> Self {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .fold(Self::new(), |overrides, entry| {
+                let (keyword, level) = match entry.rsplit_once(':') {
+                    Some((keyword, level)) => (keyword, level.trim().to_lowercase()),
+                    None => (entry, "sensitive".to_string()),
+                };
+                match level.as_str() {
+                    "sensitive" => overrides.with_keyword(keyword, Sensitivity::Sensitive),
+                    "uncertain" => overrides.with_keyword(keyword, Sensitivity::Uncertain),
+                    other => {
+                        warn!("Ignoring sensitivity keyword '{}': unknown level '{}'", keyword, other);
+                        overrides
+                    }
+                }
+            })
+    }
+
+    /// Load overrides from `AMAN_SENSITIVITY_KEYWORDS`.
+    pub fn from_env() -> Self {
+        env::var(SENSITIVITY_KEYWORDS_ENV)
+            .map(|spec| Self::parse(&spec))
+            .unwrap_or_default()
+    }
+
+    /// Whether no keywords are configured.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The strongest sensitivity forced by keywords in `text`, if any.
+    pub fn matched(&self, text: &str) -> Option<Sensitivity> {
+        if self.rules.is_empty() {
+            return None;
+        }
+        let text = normalize_words(text);
+        self.rules
+            .iter()
+            .filter(|(keyword, _)| text.contains(keyword.as_str()))
+            .map(|(_, sensitivity)| *sensitivity)
+            .max_by_key(sensitivity_rank)
+    }
+
+    /// Raise the sensitivity of every action in `plan` to the level forced
+    /// by keywords in `text`.
+    ///
+    /// Search and tool actions get the forced level too, so it still applies
+    /// when the plan has no respond action.
+    pub fn apply(&self, plan: &mut RoutingPlan, text: &str) {
+        let Some(forced) = self.matched(text) else {
+            return;
+        };
+        for action in &mut plan.actions {
+            let outranks =
+                |current: &Sensitivity| sensitivity_rank(&forced) > sensitivity_rank(current);
+            match action {
+                OrchestratorAction::Respond { sensitivity, .. }
+                | OrchestratorAction::AskPrivacyChoice { sensitivity, .. }
+                    if outranks(sensitivity) =>
+                {
+                    debug!("Sensitivity keyword raised {:?} to {:?}", sensitivity, forced);
+                    *sensitivity = forced;
+                }
+                OrchestratorAction::Search { privacy, .. }
+                | OrchestratorAction::UseTool { privacy, .. }
+                    if privacy.sensitivity.as_ref().is_none_or(outranks) =>
+                {
+                    privacy.sensitivity = Some(forced);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Lowercase `text` and pad its words with single spaces, so a padded
+/// keyword only matches whole words.
+fn normalize_words(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
 /// Router that uses MapleBrain to analyze messages and determine actions.
 ///
 /// The router is stateless - it doesn't maintain conversation history
@@ -125,6 +257,8 @@ fn load_prompt_file(path: impl AsRef<Path>) -> Option<String> {
 pub struct Router {
     brain: MapleBrain,
     prompt_hash: String,
+    sensitivity_overrides: SensitivityOverrides,
+    fail_closed: bool,
 }
 
 impl Router {
@@ -147,7 +281,43 @@ impl Router {
 
         info!("Router prompt fingerprint: {}", prompt_hash);
 
-        Ok(Self { brain, prompt_hash })
+        let sensitivity_overrides = SensitivityOverrides::from_env();
+        if !sensitivity_overrides.is_empty() {
+            info!("Loaded sensitivity keyword overrides from {}", SENSITIVITY_KEYWORDS_ENV);
+        }
+
+        let fail_closed = load_fail_closed_from_env();
+        if !fail_closed {
+            warn!("Fail-closed routing disabled; undetermined sensitivity may use Grok");
+        }
+
+        Ok(Self {
+            brain,
+            prompt_hash,
+            sensitivity_overrides,
+            fail_closed,
+        })
+    }
+
+    /// Replace the keyword sensitivity overrides.
+    pub fn with_sensitivity_overrides(mut self, overrides: SensitivityOverrides) -> Self {
+        self.sensitivity_overrides = overrides;
+        self
+    }
+
+    /// Route to Maple whenever sensitivity can't be determined (default).
+    ///
+    /// When disabled, failed routing and uncertain sensitivity fall back to
+    /// `Sensitivity::default()`, which may use Grok. Also set by
+    /// `AMAN_FAIL_CLOSED`.
+    pub fn with_fail_closed(mut self, fail_closed: bool) -> Self {
+        self.fail_closed = fail_closed;
+        self
+    }
+
+    /// Whether undetermined sensitivity fails closed to Maple.
+    pub fn fail_closed(&self) -> bool {
+        self.fail_closed
     }
 
     /// Get the router prompt fingerprint.
@@ -195,7 +365,7 @@ impl Router {
         // Create a minimal inbound message for the brain
         let inbound = InboundMessage::direct("router", &formatted_input, 0);
 
-        let fallback = Self::fallback_plan(attachments);
+        let fallback = Self::fallback_plan(attachments, self.fail_closed);
 
         match self.brain.process(inbound).await {
             Ok(response) => {
@@ -207,7 +377,8 @@ impl Router {
                 );
                 debug!("Router response: {}", response.text);
                 match self.parse_plan(&response.text) {
-                    Ok(plan) => {
+                    Ok(mut plan) => {
+                        self.sensitivity_overrides.apply(&mut plan, message_text);
                         trace!(
                             actions_count = plan.actions.len(),
                             parsed_plan = ?plan,
@@ -355,15 +526,22 @@ impl Router {
     }
 
     /// Build a safe fallback plan when routing fails.
-    fn fallback_plan(attachments: &[InboundAttachment]) -> RoutingPlan {
-        let task_hint = if attachments.iter().any(|a| a.is_image()) {
+    fn fallback_plan(attachments: &[InboundAttachment], fail_closed: bool) -> RoutingPlan {
+        let has_images = attachments.iter().any(|a| a.is_image());
+        let task_hint = if has_images {
             TaskHint::Vision
         } else {
             TaskHint::General
         };
 
-        // Fail closed to Maple by marking sensitivity as sensitive.
-        RoutingPlan::respond_with_hint(Sensitivity::Sensitive, task_hint)
+        // Fail closed to Maple by marking sensitivity as sensitive. Images
+        // always stay on Maple, which is the only vision-capable brain.
+        let sensitivity = if fail_closed || has_images {
+            Sensitivity::Sensitive
+        } else {
+            Sensitivity::default()
+        };
+        RoutingPlan::respond_with_hint(sensitivity, task_hint)
     }
 
     /// Extract JSON from a response that may contain markdown or other text.
@@ -457,6 +635,56 @@ mod tests {
     use crate::actions::OrchestratorAction;
     use brain_core::InboundAttachment;
 
+    fn respond_sensitivity(plan: &RoutingPlan) -> Sensitivity {
+        match &plan.actions[0] {
+            OrchestratorAction::Respond { sensitivity, .. } => *sensitivity,
+            other => panic!("expected respond, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sensitivity_keyword_forces_sensitive() {
+        let overrides = SensitivityOverrides::parse("salud, prestamo:sensitive, tal vez:uncertain");
+        let mut plan = RoutingPlan::respond_with_sensitivity(Sensitivity::Insensitive);
+
+        overrides.apply(&mut plan, "Pregunta sobre mi SALUD, por favor");
+        assert_eq!(respond_sensitivity(&plan), Sensitivity::Sensitive);
+    }
+
+    #[test]
+    fn test_sensitivity_keyword_never_downgrades() {
+        let overrides = SensitivityOverrides::parse("tal vez:uncertain");
+
+        let mut plan = RoutingPlan::respond_with_sensitivity(Sensitivity::Sensitive);
+        overrides.apply(&mut plan, "tal vez mañana");
+        assert_eq!(respond_sensitivity(&plan), Sensitivity::Sensitive);
+
+        let mut plan = RoutingPlan::respond_with_sensitivity(Sensitivity::Insensitive);
+        overrides.apply(&mut plan, "tal vez mañana");
+        assert_eq!(respond_sensitivity(&plan), Sensitivity::Uncertain);
+    }
+
+    #[test]
+    fn test_sensitivity_keyword_reaches_gathering_actions() {
+        let overrides = SensitivityOverrides::parse("salud");
+        let mut plan = RoutingPlan::new(vec![
+            OrchestratorAction::search("clinic hours"),
+            OrchestratorAction::use_tool("calculator", Default::default()),
+        ]);
+
+        overrides.apply(&mut plan, "horario de salud");
+        assert!(plan.actions.iter().all(|a| a.sensitivity() == Some(Sensitivity::Sensitive)));
+        assert_eq!(plan.strongest_sensitivity(), Some(Sensitivity::Sensitive));
+    }
+
+    #[test]
+    fn test_sensitivity_keyword_matches_whole_words() {
+        let overrides = SensitivityOverrides::new().with_keyword("hiv", Sensitivity::Sensitive);
+        assert_eq!(overrides.matched("HIV test results?"), Some(Sensitivity::Sensitive));
+        assert_eq!(overrides.matched("archive this"), None);
+        assert!(SensitivityOverrides::parse("word:bogus, ,").is_empty());
+    }
+
     #[test]
     fn test_extract_balanced_json_clean() {
         let input = r#"{"actions": [{"type": "respond"}]}"#;
@@ -616,7 +844,7 @@ mod tests {
 
     #[test]
     fn test_fallback_plan_no_attachments() {
-        let plan = Router::fallback_plan(&[]);
+        let plan = Router::fallback_plan(&[], true);
         assert_eq!(plan.actions.len(), 1);
 
         if let OrchestratorAction::Respond {
@@ -632,13 +860,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_routing_failure_routes_to_maple() {
+        use crate::actions::UserPreference;
+        use crate::preferences::PreferenceStore;
+
+        let plan = Router::fallback_plan(&[], true);
+        let OrchestratorAction::Respond { sensitivity, .. } = &plan.actions[0] else {
+            panic!("Expected Respond action");
+        };
+        for preference in [UserPreference::Default, UserPreference::PreferSpeed] {
+            assert!(!PreferenceStore::resolve_agent(preference, *sensitivity));
+        }
+
+        // Opting out of fail-closed falls back to the default sensitivity
+        let plan = Router::fallback_plan(&[], false);
+        let OrchestratorAction::Respond { sensitivity, .. } = &plan.actions[0] else {
+            panic!("Expected Respond action");
+        };
+        assert_eq!(*sensitivity, Sensitivity::default());
+    }
+
     #[test]
     fn test_fallback_plan_with_image() {
         let attachments = vec![InboundAttachment {
             content_type: "image/png".to_string(),
             ..Default::default()
         }];
-        let plan = Router::fallback_plan(&attachments);
+        let plan = Router::fallback_plan(&attachments, false);
         assert_eq!(plan.actions.len(), 1);
 
         if let OrchestratorAction::Respond {
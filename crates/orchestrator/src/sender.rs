@@ -1,7 +1,11 @@
 //! Message sender trait and implementations.
 
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
 use async_trait::async_trait;
 use brain_core::TextStyle;
+use serde_json::{json, Value};
 
 use crate::error::OrchestratorError;
 use crate::formatting::FormattedMessage;
@@ -156,6 +160,252 @@ impl MessageSender for LoggingSender {
     }
 }
 
+/// A call recorded by [`CapturingSender`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SentCall {
+    /// A text message (styled messages are recorded as their plain text).
+    Message {
+        recipient: String,
+        text: String,
+        is_group: bool,
+    },
+    /// A typing indicator change.
+    Typing {
+        recipient: String,
+        is_group: bool,
+        started: bool,
+    },
+}
+
+/// A message sender for tests that records every call in order.
+///
+/// Clones share the same log, so keep a clone to inspect after handing
+/// the sender to the orchestrator.
+#[derive(Debug, Clone, Default)]
+pub struct CapturingSender {
+    calls: Arc<Mutex<Vec<SentCall>>>,
+}
+
+impl CapturingSender {
+    /// Create an empty capturing sender.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All recorded calls, in order.
+    pub fn calls(&self) -> Vec<SentCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Texts of sent messages, in order.
+    pub fn messages(&self) -> Vec<String> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|call| match call {
+                SentCall::Message { text, .. } => Some(text.clone()),
+                SentCall::Typing { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Typing indicator states (`true` = started), in order.
+    pub fn typing(&self) -> Vec<bool> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|call| match call {
+                SentCall::Typing { started, .. } => Some(*started),
+                SentCall::Message { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Forget all recorded calls.
+    pub fn clear(&self) {
+        self.calls.lock().unwrap().clear();
+    }
+}
+
+#[async_trait]
+impl MessageSender for CapturingSender {
+    async fn send_message(
+        &self,
+        recipient: &str,
+        text: &str,
+        is_group: bool,
+    ) -> Result<(), OrchestratorError> {
+        self.calls.lock().unwrap().push(SentCall::Message {
+            recipient: recipient.to_string(),
+            text: text.to_string(),
+            is_group,
+        });
+        Ok(())
+    }
+
+    async fn set_typing(
+        &self,
+        recipient: &str,
+        is_group: bool,
+        started: bool,
+    ) -> Result<(), OrchestratorError> {
+        self.calls.lock().unwrap().push(SentCall::Typing {
+            recipient: recipient.to_string(),
+            is_group,
+            started,
+        });
+        Ok(())
+    }
+}
+
+/// Default timeout for webhook requests made by [`HttpSender`].
+pub const DEFAULT_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A message sender that POSTs each operation as JSON to a webhook.
+///
+/// Lets the orchestrator drive any transport that can accept an HTTP call
+/// (test harnesses, chat bridges, other messengers). Every request body has
+/// a `type` field:
+///
+/// ```json
+/// {"type": "send_message", "recipient": "+15551234567", "text": "Hi", "is_group": false,
+///  "styles": [{"start": 0, "length": 2, "style": "BOLD"}]}
+/// {"type": "set_typing", "recipient": "+15551234567", "is_group": false, "started": true}
+/// ```
+///
+/// `styles` is only present for styled messages. Any non-2xx response is
+/// reported as [`OrchestratorError::SendFailed`].
+#[derive(Debug, Clone)]
+pub struct HttpSender {
+    client: reqwest::Client,
+    url: String,
+    headers: Vec<(String, String)>,
+    timeout: Duration,
+}
+
+impl HttpSender {
+    /// Create a sender that posts to `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            headers: Vec::new(),
+            timeout: DEFAULT_WEBHOOK_TIMEOUT,
+        }
+    }
+
+    /// Send `Authorization: Bearer <token>` with every request.
+    pub fn with_bearer_token(self, token: impl AsRef<str>) -> Self {
+        self.with_header("Authorization", format!("Bearer {}", token.as_ref()))
+    }
+
+    /// Send an extra header with every request (e.g. an API key header).
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set the per-request timeout (default: 10s).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The webhook URL.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    async fn post(&self, body: Value) -> Result<(), OrchestratorError> {
+        let mut request = self.client.post(&self.url).timeout(self.timeout).json(&body);
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        let response = request.send().await.map_err(|e| {
+            let reason = if e.is_timeout() {
+                format!("timed out after {:?}", self.timeout)
+            } else {
+                e.to_string()
+            };
+            OrchestratorError::SendFailed(format!("webhook request to {} failed: {}", self.url, reason))
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response.text().await.unwrap_or_default();
+            return Err(OrchestratorError::SendFailed(format!(
+                "webhook returned {}: {}",
+                status,
+                detail.trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageSender for HttpSender {
+    async fn send_message(
+        &self,
+        recipient: &str,
+        text: &str,
+        is_group: bool,
+    ) -> Result<(), OrchestratorError> {
+        self.post(json!({
+            "type": "send_message",
+            "recipient": recipient,
+            "text": text,
+            "is_group": is_group,
+        }))
+        .await
+    }
+
+    async fn send_styled_message(
+        &self,
+        recipient: &str,
+        text: &str,
+        styles: &[TextStyle],
+        is_group: bool,
+    ) -> Result<(), OrchestratorError> {
+        let styles: Vec<Value> = styles
+            .iter()
+            .map(|style| {
+                json!({
+                    "start": style.start,
+                    "length": style.length,
+                    "style": style.style,
+                })
+            })
+            .collect();
+        self.post(json!({
+            "type": "send_message",
+            "recipient": recipient,
+            "text": text,
+            "is_group": is_group,
+            "styles": styles,
+        }))
+        .await
+    }
+
+    async fn set_typing(
+        &self,
+        recipient: &str,
+        is_group: bool,
+        started: bool,
+    ) -> Result<(), OrchestratorError> {
+        self.post(json!({
+            "type": "set_typing",
+            "recipient": recipient,
+            "is_group": is_group,
+            "started": started,
+        }))
+        .await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,4 +429,172 @@ mod tests {
         sender.set_typing("+1234567890", false, true).await.unwrap();
         sender.set_typing("+1234567890", false, false).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_capturing_sender_records_in_order() {
+        let sender = CapturingSender::new();
+        let handle = sender.clone();
+
+        sender.set_typing("group-1", true, true).await.unwrap();
+        sender
+            .send_styled_message("group-1", "Hi", &[TextStyle::new(0, 2, "BOLD")], true)
+            .await
+            .unwrap();
+        sender.set_typing("group-1", true, false).await.unwrap();
+
+        assert_eq!(
+            handle.calls(),
+            vec![
+                SentCall::Typing { recipient: "group-1".to_string(), is_group: true, started: true },
+                SentCall::Message { recipient: "group-1".to_string(), text: "Hi".to_string(), is_group: true },
+                SentCall::Typing { recipient: "group-1".to_string(), is_group: true, started: false },
+            ]
+        );
+        assert_eq!(handle.messages(), vec!["Hi".to_string()]);
+        assert_eq!(handle.typing(), vec![true, false]);
+
+        handle.clear();
+        assert!(sender.calls().is_empty());
+    }
+
+    /// A request captured by the mock webhook.
+    #[derive(Debug)]
+    struct Captured {
+        head: String,
+        body: Value,
+    }
+
+    /// Start a mock webhook that answers every request with `status` after
+    /// `delay`, and records what it received.
+    async fn mock_webhook(
+        status: u16,
+        delay: Duration,
+    ) -> (String, std::sync::Arc<std::sync::Mutex<Vec<Captured>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let captured = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = captured.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let recorded = recorded.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    let (head_len, body_len) = loop {
+                        let n = socket.read(&mut buf).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                        if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                            let head = String::from_utf8_lossy(&request[..pos]).to_lowercase();
+                            let body_len = head
+                                .lines()
+                                .find_map(|line| line.strip_prefix("content-length:"))
+                                .and_then(|len| len.trim().parse::<usize>().ok())
+                                .unwrap_or(0);
+                            break (pos + 4, body_len);
+                        }
+                    };
+                    while request.len() < head_len + body_len {
+                        let n = socket.read(&mut buf).await.unwrap_or(0);
+                        if n == 0 {
+                            break;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    recorded.lock().unwrap().push(Captured {
+                        head: String::from_utf8_lossy(&request[..head_len]).to_lowercase(),
+                        body: serde_json::from_slice(&request[head_len..]).unwrap_or(Value::Null),
+                    });
+
+                    tokio::time::sleep(delay).await;
+                    let body = if status == 200 { "" } else { "nope" };
+                    let response = format!(
+                        "HTTP/1.1 {} X\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (url, captured)
+    }
+
+    #[tokio::test]
+    async fn test_http_sender_request_bodies() {
+        let (url, captured) = mock_webhook(200, Duration::ZERO).await;
+        let sender = HttpSender::new(url).with_bearer_token("secret");
+
+        sender.send_message("+15551234567", "Hello", false).await.unwrap();
+        sender
+            .send_styled_message("group-1", "Hi all", &[TextStyle::new(0, 2, "BOLD")], true)
+            .await
+            .unwrap();
+        sender.set_typing("+15551234567", false, true).await.unwrap();
+
+        let captured = captured.lock().unwrap();
+        assert_eq!(captured.len(), 3);
+        assert!(captured.iter().all(|c| c.head.starts_with("post /hook ")));
+        assert!(captured
+            .iter()
+            .all(|c| c.head.contains("authorization: bearer secret")));
+        assert_eq!(
+            captured[0].body,
+            json!({"type": "send_message", "recipient": "+15551234567", "text": "Hello", "is_group": false})
+        );
+        assert_eq!(
+            captured[1].body,
+            json!({
+                "type": "send_message",
+                "recipient": "group-1",
+                "text": "Hi all",
+                "is_group": true,
+                "styles": [{"start": 0, "length": 2, "style": "BOLD"}]
+            })
+        );
+        assert_eq!(
+            captured[2].body,
+            json!({"type": "set_typing", "recipient": "+15551234567", "is_group": false, "started": true})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_http_sender_custom_header() {
+        let (url, captured) = mock_webhook(200, Duration::ZERO).await;
+        let sender = HttpSender::new(url).with_header("X-Api-Key", "k123");
+
+        sender.set_typing("group-1", true, false).await.unwrap();
+
+        let captured = captured.lock().unwrap();
+        assert!(captured[0].head.contains("x-api-key: k123"));
+        assert!(!captured[0].head.contains("authorization:"));
+    }
+
+    #[tokio::test]
+    async fn test_http_sender_error_status() {
+        let (url, _captured) = mock_webhook(503, Duration::ZERO).await;
+        let err = HttpSender::new(url)
+            .send_message("+15551234567", "Hello", false)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(&err, OrchestratorError::SendFailed(msg) if msg.contains("503") && msg.contains("nope")));
+    }
+
+    #[tokio::test]
+    async fn test_http_sender_timeout() {
+        let (url, _captured) = mock_webhook(200, Duration::from_secs(5)).await;
+        let err = HttpSender::new(url)
+            .with_timeout(Duration::from_millis(100))
+            .send_message("+15551234567", "Hello", false)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(&err, OrchestratorError::SendFailed(msg) if msg.contains("timed out")));
+    }
 }
@@ -0,0 +1,143 @@
+//! Stateless brain for tools that call back into the response brain.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use brain_core::{Brain, BrainError, InboundMessage, OutboundMessage};
+use maple_brain::MapleBrain;
+
+/// A brain whose per-conversation history can be dropped.
+#[async_trait]
+pub(crate) trait HistoryBrain: Brain {
+    /// Forget everything kept for `history_key`.
+    async fn clear_history(&self, history_key: &str);
+}
+
+#[async_trait]
+impl HistoryBrain for MapleBrain {
+    async fn clear_history(&self, history_key: &str) {
+        MapleBrain::clear_history(self, history_key).await;
+    }
+}
+
+/// Runs each request on a fresh history key and clears it afterwards.
+///
+/// Tools such as translate send every request under one fixed sender, so
+/// on the shared response brain each call would see earlier callers' text.
+pub(crate) struct ToolBrain<B> {
+    inner: Arc<B>,
+    next_call: AtomicU64,
+}
+
+impl<B: HistoryBrain> ToolBrain<B> {
+    pub(crate) fn new(inner: Arc<B>) -> Self {
+        Self {
+            inner,
+            next_call: AtomicU64::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl<B: HistoryBrain + 'static> Brain for ToolBrain<B> {
+    async fn process(&self, mut message: InboundMessage) -> Result<OutboundMessage, BrainError> {
+        let call = self.next_call.fetch_add(1, Ordering::Relaxed);
+        message.sender = format!("{}#{}", message.sender, call);
+        message.group_id = None;
+        let history_key = message.history_key();
+
+        let response = self.inner.process(message).await;
+        self.inner.clear_history(&history_key).await;
+        response
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn is_ready(&self) -> bool {
+        self.inner.is_ready().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use agent_tools::tools::Translate;
+    use agent_tools::{Tool, ToolArgs};
+    use brain_core::ConversationHistory;
+    use serde_json::Value;
+
+    /// Brain that keeps history like MapleBrain and records full prompts.
+    struct HistoryRecordingBrain {
+        history: ConversationHistory,
+        prompts: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Brain for HistoryRecordingBrain {
+        async fn process(&self, message: InboundMessage) -> Result<OutboundMessage, BrainError> {
+            let key = message.history_key();
+            let mut prompt: Vec<String> = self
+                .history
+                .get(&key)
+                .await
+                .into_iter()
+                .map(|msg| msg.content)
+                .collect();
+            prompt.push(message.text.clone());
+            self.prompts.lock().unwrap().push(prompt.join("\n"));
+
+            let reply = format!("translated: {}", message.text);
+            self.history.add_exchange(&key, &message.text, &reply).await;
+            Ok(OutboundMessage::reply_to(&message, reply))
+        }
+
+        fn name(&self) -> &str {
+            "history-recording"
+        }
+    }
+
+    #[async_trait]
+    impl HistoryBrain for HistoryRecordingBrain {
+        async fn clear_history(&self, history_key: &str) {
+            self.history.clear(history_key).await;
+        }
+    }
+
+    fn translate_args(text: &str, brain: Arc<dyn Brain>) -> ToolArgs {
+        let params: HashMap<String, Value> = [("text", text), ("to", "Spanish")]
+            .iter()
+            .map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+            .collect();
+        ToolArgs::with_brain(params, brain)
+    }
+
+    #[tokio::test]
+    async fn test_translations_do_not_share_history() {
+        let inner = Arc::new(HistoryRecordingBrain {
+            history: ConversationHistory::new(10),
+            prompts: Mutex::new(Vec::new()),
+        });
+        let brain: Arc<dyn Brain> = Arc::new(ToolBrain::new(inner.clone()));
+
+        Translate::new()
+            .execute(translate_args("my account number is 12345", brain.clone()))
+            .await
+            .unwrap();
+        Translate::new()
+            .execute(translate_args("good morning", brain))
+            .await
+            .unwrap();
+
+        let prompts = inner.prompts.lock().unwrap().clone();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts[1].contains("good morning"));
+        assert!(!prompts[1].contains("12345"));
+        assert_eq!(inner.history.sender_count().await, 0);
+    }
+}
@@ -0,0 +1,42 @@
+//! Shutdown signal shared by Aman's HTTP servers.
+//!
+//! The API gateway and the admin web UI both pass [`shutdown_signal`] to
+//! `axum::serve(...).with_graceful_shutdown`, so in-flight requests drain
+//! before the process exits on Ctrl+C or a SIGTERM from the supervisor.
+
+use tracing::{info, warn};
+
+/// Resolve once the process receives SIGINT (Ctrl+C) or SIGTERM.
+///
+/// A signal that cannot be listened for is logged and ignored, so the other
+/// one still triggers shutdown.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            warn!(error = %err, "Failed to listen for Ctrl+C");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                warn!(error = %err, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    info!("Shutdown signal received, draining in-flight requests");
+}
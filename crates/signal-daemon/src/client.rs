@@ -1,8 +1,9 @@
 //! Signal-cli daemon HTTP client.
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -13,6 +14,9 @@ use crate::config::DaemonConfig;
 use crate::error::DaemonError;
 use crate::types::{SendParams, SendResult, TextStyleParam, TypingParams};
 
+/// Per-request timeout for readiness checks.
+const HEALTH_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// JSON-RPC 2.0 request structure.
 #[derive(Debug, Serialize)]
 struct RpcRequest<'a, T: Serialize> {
@@ -57,6 +61,90 @@ enum AccountResponse {
     Object { number: String },
 }
 
+/// State of a send tracked by idempotency key.
+#[derive(Debug, Clone)]
+enum SentKey {
+    /// A send with this key is in progress.
+    Pending,
+    /// Sent at the given time with the given result.
+    Sent(Instant, SendResult),
+}
+
+/// Outcome of claiming an idempotency key before sending.
+enum KeyClaim {
+    /// No recent send; the caller should send.
+    Claimed,
+    /// Already sent within the TTL.
+    AlreadySent(SendResult),
+    /// Another send with the same key is in flight.
+    InFlight,
+}
+
+/// Recently used idempotency keys, shared between clones of a client.
+#[derive(Debug, Default)]
+struct SentKeys {
+    entries: HashMap<String, SentKey>,
+}
+
+impl SentKeys {
+    fn claim(&mut self, key: &str, ttl: Duration) -> KeyClaim {
+        let now = Instant::now();
+        self.entries.retain(|_, entry| match entry {
+            SentKey::Pending => true,
+            SentKey::Sent(at, _) => now.duration_since(*at) < ttl,
+        });
+
+        match self.entries.get(key) {
+            Some(SentKey::Sent(_, result)) => KeyClaim::AlreadySent(result.clone()),
+            Some(SentKey::Pending) => KeyClaim::InFlight,
+            None => {
+                self.entries.insert(key.to_string(), SentKey::Pending);
+                KeyClaim::Claimed
+            }
+        }
+    }
+
+    fn complete(&mut self, key: &str, result: &Result<SendResult, DaemonError>) {
+        match result {
+            Ok(sent) => {
+                self.entries
+                    .insert(key.to_string(), SentKey::Sent(Instant::now(), sent.clone()));
+            }
+            // Release the key so the caller can retry.
+            Err(_) => {
+                self.entries.remove(key);
+            }
+        }
+    }
+}
+
+/// A claimed idempotency key, released again if the send never completes.
+///
+/// Dropping the guard without [`PendingKey::complete`] (e.g. when the send
+/// future is cancelled) frees the key so a retry is not stuck as in flight.
+struct PendingKey<'a> {
+    keys: &'a Mutex<SentKeys>,
+    key: String,
+    completed: bool,
+}
+
+impl PendingKey<'_> {
+    fn complete(mut self, result: &Result<SendResult, DaemonError>) {
+        self.keys.lock().unwrap().complete(&self.key, result);
+        self.completed = true;
+    }
+}
+
+impl Drop for PendingKey<'_> {
+    fn drop(&mut self) {
+        if !self.completed {
+            if let Ok(mut keys) = self.keys.lock() {
+                keys.entries.remove(&self.key);
+            }
+        }
+    }
+}
+
 /// Client for communicating with the signal-cli daemon.
 #[derive(Clone)]
 pub struct SignalClient {
@@ -64,6 +152,7 @@ pub struct SignalClient {
     config: DaemonConfig,
     request_id: Arc<std::sync::atomic::AtomicU64>,
     connected: Arc<AtomicBool>,
+    sent_keys: Arc<Mutex<SentKeys>>,
 }
 
 impl SignalClient {
@@ -79,19 +168,69 @@ impl SignalClient {
             config,
             request_id: Arc::new(std::sync::atomic::AtomicU64::new(1)),
             connected: Arc::new(AtomicBool::new(false)),
+            sent_keys: Arc::new(Mutex::new(SentKeys::default())),
         };
 
-        // Verify connection with health check
-        if client.health_check().await? {
-            client.connected.store(true, Ordering::SeqCst);
-            info!("Connected to signal-cli daemon at {}", client.config.base_url);
-        } else {
-            return Err(DaemonError::HealthCheckFailed);
-        }
+        // Wait for the daemon to pass its health check (it may still be starting)
+        client.wait_until_ready().await?;
+        client.connected.store(true, Ordering::SeqCst);
+        info!("Connected to signal-cli daemon at {}", client.config.base_url);
 
         Ok(client)
     }
 
+    /// Poll the health endpoint until it succeeds or `ready_timeout` elapses.
+    ///
+    /// Returns [`DaemonError::NotReady`] if the daemon answered but never
+    /// reported healthy, and [`DaemonError::Unreachable`] if it never answered.
+    async fn wait_until_ready(&self) -> Result<(), DaemonError> {
+        let url = self.config.check_url();
+        let timeout = self.config.ready_timeout;
+        let start = Instant::now();
+        let mut responded = false;
+        let mut last_error = String::new();
+
+        loop {
+            match self
+                .http
+                .get(&url)
+                .timeout(HEALTH_REQUEST_TIMEOUT)
+                .send()
+                .await
+            {
+                Ok(resp) if resp.status().is_success() => return Ok(()),
+                Ok(resp) => {
+                    responded = true;
+                    debug!("Daemon not ready yet (status {}), retrying...", resp.status());
+                }
+                Err(e) => {
+                    last_error = e.to_string();
+                    debug!("Daemon not reachable yet: {}, retrying...", e);
+                }
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                break;
+            }
+            tokio::time::sleep(self.config.ready_poll_interval.min(timeout - elapsed)).await;
+        }
+
+        if responded {
+            warn!("Daemon at {} did not become ready within {:?}", url, timeout);
+            Err(DaemonError::NotReady {
+                url,
+                waited: start.elapsed(),
+            })
+        } else {
+            warn!("Daemon at {} unreachable: {}", url, last_error);
+            Err(DaemonError::Unreachable {
+                url,
+                reason: last_error,
+            })
+        }
+    }
+
     /// Check if currently connected to the daemon.
     pub fn is_connected(&self) -> bool {
         self.connected.load(Ordering::SeqCst)
@@ -131,13 +270,48 @@ impl SignalClient {
     }
 
     /// Send a message using the full SendParams structure.
+    ///
+    /// If `params.idempotency_key` is set and a send with the same key
+    /// succeeded within the configured TTL, the earlier result is returned
+    /// without sending again. A concurrent send with the same key fails with
+    /// [`DaemonError::SendFailed`]; a failed send releases the key for retry.
     pub async fn send(&self, mut params: SendParams) -> Result<SendResult, DaemonError> {
         // Add account if configured and not already set
         if params.account.is_none() {
             params.account = self.config.account.clone();
         }
 
-        self.rpc_call("send", Some(params)).await
+        let Some(key) = params.idempotency_key.clone() else {
+            return self.rpc_call("send", Some(params)).await;
+        };
+
+        let claim = self
+            .sent_keys
+            .lock()
+            .unwrap()
+            .claim(&key, self.config.idempotency_ttl);
+        match claim {
+            KeyClaim::AlreadySent(result) => {
+                debug!("Suppressing duplicate send for idempotency key {}", key);
+                return Ok(result);
+            }
+            KeyClaim::InFlight => {
+                return Err(DaemonError::SendFailed(format!(
+                    "send with idempotency key {} already in progress",
+                    key
+                )));
+            }
+            KeyClaim::Claimed => {}
+        }
+
+        let pending = PendingKey {
+            keys: &self.sent_keys,
+            key,
+            completed: false,
+        };
+        let result = self.rpc_call("send", Some(params)).await;
+        pending.complete(&result);
+        result
     }
 
     /// Send a text message to a recipient.
@@ -368,3 +542,231 @@ impl std::fmt::Debug for SignalClient {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Behavior of [`mock_daemon`].
+    #[derive(Debug, Clone, Copy, Default)]
+    struct Mock {
+        /// Health checks fail until this much time has elapsed.
+        healthy_after: Duration,
+        /// The first `failures` sends get an HTTP 500.
+        failures: usize,
+        /// Delay before answering each send.
+        send_delay: Duration,
+    }
+
+    /// Mock daemon serving health checks and counting RPC sends.
+    async fn mock_daemon(mock: Mock) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let started = Instant::now();
+        let sends = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = sends.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let counter = counter.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let (status, body) = if request.starts_with("GET") {
+                        if started.elapsed() >= mock.healthy_after {
+                            ("200 OK", String::new())
+                        } else {
+                            ("503 Service Unavailable", String::new())
+                        }
+                    } else {
+                        let count = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                        tokio::time::sleep(mock.send_delay).await;
+                        if count <= mock.failures {
+                            ("500 Internal Server Error", "boom".to_string())
+                        } else {
+                            let result = format!(
+                                r#"{{"jsonrpc":"2.0","result":{{"timestamp":{}}},"id":1}}"#,
+                                count
+                            );
+                            ("200 OK", result)
+                        }
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (format!("http://{}", addr), sends)
+    }
+
+    fn config(base_url: String, timeout: Duration) -> DaemonConfig {
+        DaemonConfig::new(base_url)
+            .with_ready_timeout(timeout)
+            .with_ready_poll_interval(Duration::from_millis(20))
+    }
+
+    #[tokio::test]
+    async fn test_connect_waits_for_daemon_to_become_ready() {
+        let (base_url, _) = mock_daemon(Mock {
+            healthy_after: Duration::from_millis(200),
+            ..Mock::default()
+        })
+        .await;
+        let start = Instant::now();
+
+        let client = SignalClient::connect(config(base_url, Duration::from_secs(5)))
+            .await
+            .unwrap();
+
+        assert!(client.is_connected());
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_connect_reports_not_ready() {
+        let (base_url, _) = mock_daemon(Mock {
+            healthy_after: Duration::from_secs(60),
+            ..Mock::default()
+        })
+        .await;
+
+        let err = SignalClient::connect(config(base_url, Duration::from_millis(150)))
+            .await
+            .unwrap_err();
+
+        match err {
+            DaemonError::NotReady { waited, .. } => {
+                assert!(waited >= Duration::from_millis(150));
+            }
+            other => panic!("expected NotReady, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_reports_unreachable() {
+        // Reserve a port, then close it so nothing is listening.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let err = SignalClient::connect(config(base_url, Duration::from_millis(100)))
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, DaemonError::Unreachable { .. }),
+            "expected Unreachable, got {:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_zero_timeout_checks_once() {
+        let (base_url, _) = mock_daemon(Mock::default()).await;
+        let client = SignalClient::connect(config(base_url, Duration::ZERO))
+            .await
+            .unwrap();
+        assert!(client.is_connected());
+    }
+
+    #[tokio::test]
+    async fn test_retried_send_with_same_key_is_suppressed() {
+        let (base_url, sends) = mock_daemon(Mock::default()).await;
+        let client = SignalClient::connect(DaemonConfig::new(base_url)).await.unwrap();
+        let params = SendParams::text("+15550000000", "hello").with_idempotency_key("reply-1");
+
+        let first = client.send(params.clone()).await.unwrap();
+        let retry = client.send(params).await.unwrap();
+
+        assert_eq!(sends.load(Ordering::SeqCst), 1);
+        assert_eq!(first.timestamp, retry.timestamp);
+
+        // Clones share the key cache; different keys send normally.
+        let other = client.clone();
+        other
+            .send(SendParams::text("+15550000000", "hello").with_idempotency_key("reply-1"))
+            .await
+            .unwrap();
+        other
+            .send(SendParams::text("+15550000000", "hello").with_idempotency_key("reply-2"))
+            .await
+            .unwrap();
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_send_releases_key() {
+        let (base_url, sends) = mock_daemon(Mock {
+            failures: 1,
+            ..Mock::default()
+        })
+        .await;
+        let client = SignalClient::connect(DaemonConfig::new(base_url)).await.unwrap();
+        let params = SendParams::text("+15550000000", "hello").with_idempotency_key("reply-1");
+
+        assert!(client.send(params.clone()).await.is_err());
+        let result = client.send(params).await.unwrap();
+
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+        assert_eq!(result.timestamp, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_send_releases_key() {
+        let (base_url, sends) = mock_daemon(Mock {
+            send_delay: Duration::from_millis(300),
+            ..Mock::default()
+        })
+        .await;
+        let client = SignalClient::connect(DaemonConfig::new(base_url)).await.unwrap();
+        let params = SendParams::text("+15550000000", "hello").with_idempotency_key("reply-1");
+
+        // The caller gives up mid-send, dropping the send future
+        let send = client.send(params.clone());
+        assert!(tokio::time::timeout(Duration::from_millis(50), send).await.is_err());
+
+        // The key is free again rather than stuck in flight
+        client.send(params).await.unwrap();
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_expires() {
+        let (base_url, sends) = mock_daemon(Mock::default()).await;
+        let config = DaemonConfig::new(base_url).with_idempotency_ttl(Duration::from_millis(50));
+        let client = SignalClient::connect(config).await.unwrap();
+        let params = SendParams::text("+15550000000", "hello").with_idempotency_key("reply-1");
+
+        client.send(params.clone()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        client.send(params).await.unwrap();
+
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_sends_without_key_are_not_deduplicated() {
+        let (base_url, sends) = mock_daemon(Mock::default()).await;
+        let client = SignalClient::connect(DaemonConfig::new(base_url)).await.unwrap();
+
+        client.send_text("+15550000000", "hello").await.unwrap();
+        client.send_text("+15550000000", "hello").await.unwrap();
+
+        assert_eq!(sends.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_concurrent_send_with_same_key_is_rejected() {
+        let mut keys = SentKeys::default();
+        let ttl = Duration::from_secs(60);
+        assert!(matches!(keys.claim("k", ttl), KeyClaim::Claimed));
+        assert!(matches!(keys.claim("k", ttl), KeyClaim::InFlight));
+    }
+}
@@ -1,6 +1,16 @@
 //! Configuration types for signal-daemon.
 
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default time to wait for the daemon to become ready when connecting.
+pub const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default interval between readiness checks.
+pub const DEFAULT_READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Default time an idempotency key suppresses duplicate sends.
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(600);
 
 /// Configuration for connecting to the signal-cli daemon.
 #[derive(Debug, Clone)]
@@ -13,6 +23,13 @@ pub struct DaemonConfig {
     /// Path to signal-cli data directory.
     /// Defaults to `~/.local/share/signal-cli` on Linux.
     pub data_dir: PathBuf,
+    /// How long `SignalClient::connect` waits for the daemon to pass its
+    /// health check (e.g. during JVM warmup). Zero means a single check.
+    pub ready_timeout: Duration,
+    /// Interval between readiness checks.
+    pub ready_poll_interval: Duration,
+    /// How long a sent idempotency key suppresses duplicate sends.
+    pub idempotency_ttl: Duration,
 }
 
 impl DaemonConfig {
@@ -22,6 +39,9 @@ impl DaemonConfig {
             base_url: base_url.into(),
             account: None,
             data_dir: default_data_dir(),
+            ready_timeout: DEFAULT_READY_TIMEOUT,
+            ready_poll_interval: DEFAULT_READY_POLL_INTERVAL,
+            idempotency_ttl: DEFAULT_IDEMPOTENCY_TTL,
         }
     }
 
@@ -31,6 +51,9 @@ impl DaemonConfig {
             base_url: base_url.into(),
             account: Some(account.into()),
             data_dir: default_data_dir(),
+            ready_timeout: DEFAULT_READY_TIMEOUT,
+            ready_poll_interval: DEFAULT_READY_POLL_INTERVAL,
+            idempotency_ttl: DEFAULT_IDEMPOTENCY_TTL,
         }
     }
 
@@ -40,6 +63,24 @@ impl DaemonConfig {
         self
     }
 
+    /// Set how long to wait for the daemon to become ready on connect.
+    pub fn with_ready_timeout(mut self, timeout: Duration) -> Self {
+        self.ready_timeout = timeout;
+        self
+    }
+
+    /// Set the interval between readiness checks.
+    pub fn with_ready_poll_interval(mut self, interval: Duration) -> Self {
+        self.ready_poll_interval = interval;
+        self
+    }
+
+    /// Set how long idempotency keys suppress duplicate sends.
+    pub fn with_idempotency_ttl(mut self, ttl: Duration) -> Self {
+        self.idempotency_ttl = ttl;
+        self
+    }
+
     /// Get the RPC endpoint URL.
     pub fn rpc_url(&self) -> String {
         format!("{}/api/v1/rpc", self.base_url)
@@ -1,5 +1,7 @@
 //! Error types for signal-daemon.
 
+use std::time::Duration;
+
 use thiserror::Error;
 
 /// Errors that can occur when interacting with the signal-cli daemon.
@@ -21,9 +23,14 @@ pub enum DaemonError {
     #[error("Connection failed: {0}")]
     Connection(String),
 
-    /// Daemon health check failed.
-    #[error("Health check failed")]
-    HealthCheckFailed,
+    /// Daemon answered but did not pass its health check in time
+    /// (typically still starting up).
+    #[error("Daemon at {url} not ready after {waited:?}")]
+    NotReady { url: String, waited: Duration },
+
+    /// No response from the daemon at all within the readiness timeout.
+    #[error("Daemon at {url} unreachable: {reason}")]
+    Unreachable { url: String, reason: String },
 
     /// SSE stream error.
     #[error("SSE error: {0}")]
@@ -46,9 +46,14 @@ pub mod sse;
 pub mod types;
 
 pub use client::SignalClient;
-pub use config::DaemonConfig;
+pub use config::{
+    DaemonConfig, DEFAULT_IDEMPOTENCY_TTL, DEFAULT_READY_POLL_INTERVAL, DEFAULT_READY_TIMEOUT,
+};
 pub use error::DaemonError;
-pub use process::{spawn_and_connect, DaemonProcess, ProcessConfig, DEFAULT_JAR_PATH};
+pub use process::{
+    spawn_and_connect, spawn_supervised, supervise, DaemonProcess, ManagedDaemon, ProcessConfig,
+    RestartPolicy, Supervised, SupervisorEvent, SupervisorHandle, DEFAULT_JAR_PATH,
+};
 pub use sse::{subscribe, subscribe_with_reconnect, MessageStream, ReconnectConfig};
 pub use types::*;
 
@@ -1,11 +1,15 @@
 //! Process management for spawning signal-cli daemon.
 
+use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use futures::future::BoxFuture;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
 use tokio::time::sleep;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::error::DaemonError;
 use crate::DaemonConfig;
@@ -175,6 +179,15 @@ impl DaemonProcess {
         matches!(self.child.try_wait(), Ok(None))
     }
 
+    /// Describe how the process exited, or `None` if it is still running.
+    pub fn exit_reason(&mut self) -> Option<String> {
+        match self.child.try_wait() {
+            Ok(None) => None,
+            Ok(Some(status)) => Some(status.to_string()),
+            Err(e) => Some(format!("unknown ({})", e)),
+        }
+    }
+
     /// Kill the daemon process.
     pub fn kill(&mut self) -> Result<(), DaemonError> {
         info!("Killing daemon process (PID {})", self.child.id());
@@ -212,3 +225,479 @@ pub async fn spawn_and_connect(
     let client = crate::SignalClient::connect(daemon_config).await?;
     Ok((process, client))
 }
+
+/// Restart policy for a supervised daemon.
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    /// Maximum restarts allowed within `window` before giving up.
+    pub max_restarts: u32,
+    /// Sliding window over which restarts are counted.
+    pub window: Duration,
+    /// Delay before the first restart in a window.
+    pub initial_delay: Duration,
+    /// Maximum delay between restarts.
+    pub max_delay: Duration,
+    /// Backoff multiplier for each further restart in the window.
+    pub backoff_multiplier: f64,
+    /// How often to check whether the process is still running.
+    pub poll_interval: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            window: Duration::from_secs(600),
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            backoff_multiplier: 2.0,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Set the restart cap and the window it applies to.
+    pub fn with_max_restarts(mut self, max_restarts: u32, window: Duration) -> Self {
+        self.max_restarts = max_restarts;
+        self.window = window;
+        self
+    }
+
+    /// Set the initial and maximum restart delay.
+    pub fn with_backoff(mut self, initial_delay: Duration, max_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set how often the process is checked.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Calculate the delay before a restart, given how many restarts already
+    /// happened in the current window.
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let delay_ms = self.initial_delay.as_millis() as f64
+            * self.backoff_multiplier.powi(attempt as i32);
+        let delay = Duration::from_millis(delay_ms as u64);
+        delay.min(self.max_delay)
+    }
+}
+
+/// A process the supervisor can watch and restart.
+///
+/// [`ManagedDaemon`] implements this for signal-cli; tests use fakes.
+pub trait Supervised: Send + 'static {
+    /// Connection produced after a successful (re)start.
+    type Connection: Send + 'static;
+
+    /// Start the process, replacing any previous instance.
+    fn start(&mut self) -> Result<(), DaemonError>;
+
+    /// Wait for the started process to become ready and connect to it.
+    fn connect(&mut self) -> BoxFuture<'_, Result<Self::Connection, DaemonError>>;
+
+    /// Describe how the process exited, or `None` if it is still running.
+    fn exit_reason(&mut self) -> Option<String>;
+
+    /// Stop the process.
+    fn stop(&mut self);
+}
+
+/// Lifecycle events reported by the supervisor.
+#[derive(Debug)]
+pub enum SupervisorEvent<C> {
+    /// The process exited unexpectedly.
+    Exited { reason: String },
+    /// A restart is scheduled after `delay`.
+    Restarting { attempt: u32, delay: Duration },
+    /// The process was restarted; use `connection` from now on.
+    Restarted { attempt: u32, connection: C },
+    /// A restart attempt failed; another will be tried if allowed.
+    RestartFailed { attempt: u32, error: String },
+    /// Too many restarts within the policy window; supervision stopped.
+    GaveUp { restarts: u32 },
+}
+
+/// Handle to a running supervisor.
+///
+/// Dropping the handle stops supervision and the process.
+pub struct SupervisorHandle {
+    shutdown: Option<oneshot::Sender<()>>,
+    task: JoinHandle<()>,
+}
+
+impl SupervisorHandle {
+    /// Stop supervising and stop the process.
+    pub async fn shutdown(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        let _ = (&mut self.task).await;
+    }
+
+    /// Whether the supervisor is still running (it stops after giving up).
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+/// Supervise an already-running process, restarting it on unexpected exit.
+///
+/// Restarts back off exponentially within the policy window. Once
+/// `max_restarts` is reached within the window the supervisor reports
+/// [`SupervisorEvent::GaveUp`] and stops. Events are delivered on the
+/// returned channel; the channel closes when supervision ends.
+pub fn supervise<S: Supervised>(
+    process: S,
+    policy: RestartPolicy,
+) -> (SupervisorHandle, mpsc::UnboundedReceiver<SupervisorEvent<S::Connection>>) {
+    let (events_tx, events_rx) = mpsc::unbounded_channel();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    let task = tokio::spawn(run_supervisor(process, policy, events_tx, shutdown_rx));
+    (
+        SupervisorHandle {
+            shutdown: Some(shutdown_tx),
+            task,
+        },
+        events_rx,
+    )
+}
+
+async fn run_supervisor<S: Supervised>(
+    mut process: S,
+    policy: RestartPolicy,
+    events: mpsc::UnboundedSender<SupervisorEvent<S::Connection>>,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    let mut restarts: VecDeque<Instant> = VecDeque::new();
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = sleep(policy.poll_interval) => {}
+        }
+
+        let Some(reason) = process.exit_reason() else {
+            continue;
+        };
+        warn!("Daemon exited unexpectedly: {}", reason);
+        let _ = events.send(SupervisorEvent::Exited { reason });
+
+        // Keep restarting until one succeeds or the window is exhausted.
+        loop {
+            let now = Instant::now();
+            while restarts
+                .front()
+                .is_some_and(|at| now.duration_since(*at) > policy.window)
+            {
+                restarts.pop_front();
+            }
+            let recent = restarts.len() as u32;
+            if recent >= policy.max_restarts {
+                error!(
+                    "Daemon restarted {} times within {:?}, giving up",
+                    recent, policy.window
+                );
+                let _ = events.send(SupervisorEvent::GaveUp { restarts: recent });
+                process.stop();
+                return;
+            }
+
+            let attempt = recent + 1;
+            let delay = policy.delay_for_attempt(recent);
+            info!("Restarting daemon in {:?} (attempt {})", delay, attempt);
+            let _ = events.send(SupervisorEvent::Restarting { attempt, delay });
+            tokio::select! {
+                _ = &mut shutdown => {
+                    process.stop();
+                    return;
+                }
+                _ = sleep(delay) => {}
+            }
+
+            restarts.push_back(Instant::now());
+            let result = match process.start() {
+                Ok(()) => process.connect().await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(connection) => {
+                    info!("Daemon restarted (attempt {})", attempt);
+                    let _ = events.send(SupervisorEvent::Restarted {
+                        attempt,
+                        connection,
+                    });
+                    break;
+                }
+                Err(e) => {
+                    warn!("Daemon restart attempt {} failed: {}", attempt, e);
+                    let _ = events.send(SupervisorEvent::RestartFailed {
+                        attempt,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    process.stop();
+}
+
+/// A signal-cli daemon that can be restarted by the supervisor.
+pub struct ManagedDaemon {
+    config: ProcessConfig,
+    ready_timeout: Duration,
+    process: Option<DaemonProcess>,
+}
+
+impl ManagedDaemon {
+    /// Create a managed daemon; it is not started until [`Supervised::start`].
+    pub fn new(config: ProcessConfig, ready_timeout: Duration) -> Self {
+        Self {
+            config,
+            ready_timeout,
+            process: None,
+        }
+    }
+
+    /// Get the current process ID, if running.
+    pub fn pid(&self) -> Option<u32> {
+        self.process.as_ref().map(DaemonProcess::pid)
+    }
+}
+
+impl Supervised for ManagedDaemon {
+    type Connection = crate::SignalClient;
+
+    fn start(&mut self) -> Result<(), DaemonError> {
+        self.stop();
+        self.process = Some(DaemonProcess::spawn(self.config.clone())?);
+        Ok(())
+    }
+
+    fn connect(&mut self) -> BoxFuture<'_, Result<crate::SignalClient, DaemonError>> {
+        Box::pin(async move {
+            let process = self
+                .process
+                .as_ref()
+                .ok_or_else(|| DaemonError::Connection("Daemon not started".to_string()))?;
+            process.wait_ready(self.ready_timeout).await?;
+            crate::SignalClient::connect(self.config.to_daemon_config()).await
+        })
+    }
+
+    fn exit_reason(&mut self) -> Option<String> {
+        match self.process.as_mut() {
+            Some(process) => process.exit_reason(),
+            None => Some("not running".to_string()),
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut process) = self.process.take() {
+            if process.is_running() {
+                if let Err(e) = process.kill() {
+                    error!("Failed to stop daemon: {}", e);
+                }
+                let _ = process.wait();
+            }
+        }
+    }
+}
+
+/// Spawn a daemon under a supervisor and return a connected client.
+///
+/// If the daemon crashes it is restarted according to `policy`; each
+/// [`SupervisorEvent::Restarted`] carries a fresh client to use instead of
+/// the one returned here.
+pub async fn spawn_supervised(
+    config: ProcessConfig,
+    ready_timeout: Duration,
+    policy: RestartPolicy,
+) -> Result<
+    (
+        crate::SignalClient,
+        SupervisorHandle,
+        mpsc::UnboundedReceiver<SupervisorEvent<crate::SignalClient>>,
+    ),
+    DaemonError,
+> {
+    let mut daemon = ManagedDaemon::new(config, ready_timeout);
+    daemon.start()?;
+    let client = daemon.connect().await?;
+    let (handle, events) = supervise(daemon, policy);
+    Ok((client, handle, events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct FakeState {
+        running: bool,
+        starts: usize,
+        /// Number of starts after which the process stays up.
+        stable_after: usize,
+        failed_connects: usize,
+        stopped: bool,
+    }
+
+    /// Fake child whose lifetime is scripted through shared state.
+    #[derive(Clone)]
+    struct FakeChild(Arc<Mutex<FakeState>>);
+
+    impl FakeChild {
+        fn crashed(stable_after: usize) -> Self {
+            Self(Arc::new(Mutex::new(FakeState {
+                stable_after,
+                ..Default::default()
+            })))
+        }
+    }
+
+    impl Supervised for FakeChild {
+        type Connection = usize;
+
+        fn start(&mut self) -> Result<(), DaemonError> {
+            let mut state = self.0.lock().unwrap();
+            state.starts += 1;
+            state.running = state.starts >= state.stable_after;
+            Ok(())
+        }
+
+        fn connect(&mut self) -> BoxFuture<'_, Result<usize, DaemonError>> {
+            let mut state = self.0.lock().unwrap();
+            let result = if state.failed_connects > 0 {
+                state.failed_connects -= 1;
+                state.running = false;
+                Err(DaemonError::Connection("not ready".to_string()))
+            } else {
+                Ok(state.starts)
+            };
+            Box::pin(async move { result })
+        }
+
+        fn exit_reason(&mut self) -> Option<String> {
+            let state = self.0.lock().unwrap();
+            (!state.running).then(|| "exit status: 1".to_string())
+        }
+
+        fn stop(&mut self) {
+            let mut state = self.0.lock().unwrap();
+            state.running = false;
+            state.stopped = true;
+        }
+    }
+
+    fn fast_policy(max_restarts: u32) -> RestartPolicy {
+        RestartPolicy::default()
+            .with_max_restarts(max_restarts, Duration::from_secs(60))
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(25))
+            .with_poll_interval(Duration::from_millis(5))
+    }
+
+    async fn next_event<C>(
+        events: &mut mpsc::UnboundedReceiver<SupervisorEvent<C>>,
+    ) -> Option<SupervisorEvent<C>> {
+        tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .expect("supervisor event timed out")
+    }
+
+    #[test]
+    fn test_restart_policy_backoff() {
+        let policy = RestartPolicy::default();
+        assert_eq!(policy.delay_for_attempt(0), Duration::from_secs(1));
+        assert_eq!(policy.delay_for_attempt(1), Duration::from_secs(2));
+        assert_eq!(policy.delay_for_attempt(2), Duration::from_secs(4));
+        assert_eq!(policy.delay_for_attempt(10), Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn test_restarts_after_exit() {
+        let child = FakeChild::crashed(1);
+        let (handle, mut events) = supervise(child.clone(), fast_policy(3));
+
+        assert!(matches!(
+            next_event(&mut events).await,
+            Some(SupervisorEvent::Exited { .. })
+        ));
+        assert!(matches!(
+            next_event(&mut events).await,
+            Some(SupervisorEvent::Restarting { attempt: 1, delay }) if delay == Duration::from_millis(10)
+        ));
+        assert!(matches!(
+            next_event(&mut events).await,
+            Some(SupervisorEvent::Restarted { attempt: 1, connection: 1 })
+        ));
+
+        // The restarted process stays up, so no further events arrive.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(events.try_recv().is_err());
+        assert!(handle.is_running());
+
+        handle.shutdown().await;
+        assert!(child.0.lock().unwrap().stopped);
+    }
+
+    #[tokio::test]
+    async fn test_backoff_and_give_up() {
+        let child = FakeChild::crashed(usize::MAX);
+        let (handle, mut events) = supervise(child.clone(), fast_policy(3));
+
+        let mut delays = Vec::new();
+        let mut gave_up = None;
+        while let Some(event) = next_event(&mut events).await {
+            match event {
+                SupervisorEvent::Restarting { delay, .. } => delays.push(delay),
+                SupervisorEvent::GaveUp { restarts } => gave_up = Some(restarts),
+                _ => {}
+            }
+        }
+
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_millis(10),
+                Duration::from_millis(20),
+                Duration::from_millis(25),
+            ]
+        );
+        assert_eq!(gave_up, Some(3));
+        assert_eq!(child.0.lock().unwrap().starts, 3);
+        assert!(child.0.lock().unwrap().stopped);
+        assert!(!handle.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_failed_connect_is_retried() {
+        let child = FakeChild::crashed(1);
+        child.0.lock().unwrap().failed_connects = 1;
+        let (handle, mut events) = supervise(child.clone(), fast_policy(3));
+
+        let mut seen = Vec::new();
+        loop {
+            match next_event(&mut events).await {
+                Some(SupervisorEvent::Restarted { attempt, connection }) => {
+                    assert_eq!((attempt, connection), (2, 2));
+                    break;
+                }
+                Some(SupervisorEvent::RestartFailed { attempt, error }) => {
+                    seen.push(attempt);
+                    assert!(error.contains("not ready"));
+                }
+                Some(_) => {}
+                None => panic!("supervisor stopped early"),
+            }
+        }
+        assert_eq!(seen, vec![1]);
+
+        handle.shutdown().await;
+    }
+}
@@ -54,6 +54,10 @@ pub struct Envelope {
     /// Typing indicator.
     #[serde(default)]
     pub typing_message: Option<TypingMessage>,
+
+    /// Edit of a previously sent message.
+    #[serde(default)]
+    pub edit_message: Option<EditMessage>,
 }
 
 /// A data message containing the actual message content.
@@ -95,6 +99,39 @@ pub struct DataMessage {
     /// Mentions in the message.
     #[serde(default)]
     pub mentions: Vec<Mention>,
+
+    /// Deletion of a previously sent message ("delete for everyone").
+    #[serde(default)]
+    pub remote_delete: Option<RemoteDelete>,
+}
+
+impl DataMessage {
+    /// The reaction this message removes, if it is a reaction removal.
+    pub fn reaction_removal(&self) -> Option<&Reaction> {
+        self.reaction.as_ref().filter(|reaction| reaction.is_remove)
+    }
+}
+
+/// An edit replacing the content of a previously sent message.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditMessage {
+    /// Timestamp of the message being edited.
+    #[serde(default)]
+    pub target_sent_timestamp: u64,
+
+    /// The replacement message content.
+    #[serde(default)]
+    pub data_message: DataMessage,
+}
+
+/// Deletion of a previously sent message.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteDelete {
+    /// Timestamp of the deleted message.
+    #[serde(default)]
+    pub timestamp: u64,
 }
 
 /// Information about a group.
@@ -41,6 +41,13 @@ pub struct SendParams {
     /// Text style formatting.
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub text_style: Vec<TextStyleParam>,
+
+    /// Client-supplied idempotency key.
+    ///
+    /// Not sent to the daemon; `SignalClient` uses it to suppress duplicate
+    /// sends (e.g. retries) with the same key within its idempotency TTL.
+    #[serde(skip)]
+    pub idempotency_key: Option<String>,
 }
 
 impl SendParams {
@@ -81,6 +88,12 @@ impl SendParams {
         self
     }
 
+    /// Set an idempotency key so retried sends are not delivered twice.
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
     /// Add text styles for formatting.
     pub fn with_styles(mut self, styles: Vec<TextStyleParam>) -> Self {
         self.text_style = styles;
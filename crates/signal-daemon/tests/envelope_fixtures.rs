@@ -0,0 +1,93 @@
+//! Deserialization tests against captured signal-cli JSON samples.
+//!
+//! Fixtures in `tests/fixtures/` are `receive` events as emitted by the
+//! signal-cli daemon's SSE stream (identifying details replaced).
+
+use serde_json::Value;
+use signal_daemon::ReceiveEvent;
+
+const EDIT_MESSAGE: &str = include_str!("fixtures/edit_message.json");
+const REACTION_REMOVAL: &str = include_str!("fixtures/reaction_removal.json");
+const REMOTE_DELETE: &str = include_str!("fixtures/remote_delete.json");
+const VIEW_ONCE: &str = include_str!("fixtures/view_once.json");
+
+fn parse(fixture: &str) -> ReceiveEvent {
+    serde_json::from_str(fixture).expect("fixture should deserialize")
+}
+
+/// Serialize, deserialize again, and check nothing typed was lost.
+fn assert_round_trip(fixture: &str) {
+    let first = serde_json::to_value(parse(fixture)).unwrap();
+    let second: ReceiveEvent = serde_json::from_value(first.clone()).unwrap();
+    assert_eq!(serde_json::to_value(second).unwrap(), first);
+}
+
+#[test]
+fn test_edit_message() {
+    let envelope = parse(EDIT_MESSAGE).envelope;
+
+    assert!(envelope.data_message.is_none());
+    let edit = envelope.edit_message.expect("edit message should be typed");
+    assert_eq!(edit.target_sent_timestamp, 1700000000000);
+    assert_eq!(edit.data_message.timestamp, 1700000005000);
+    assert_eq!(
+        edit.data_message.message.as_deref(),
+        Some("What's the weather in Berlin?")
+    );
+    assert_round_trip(EDIT_MESSAGE);
+}
+
+#[test]
+fn test_reaction_removal() {
+    let envelope = parse(REACTION_REMOVAL).envelope;
+    let data = envelope.data_message.expect("data message");
+
+    let removal = data.reaction_removal().expect("reaction removal should be typed");
+    assert_eq!(removal.emoji, "👍");
+    assert_eq!(removal.target_author.as_deref(), Some("+15557654321"));
+    assert_eq!(removal.target_sent_timestamp, 1700000001000);
+    assert!(data.message.is_none());
+    assert_round_trip(REACTION_REMOVAL);
+}
+
+#[test]
+fn test_reaction_is_not_removal() {
+    let fixture = REACTION_REMOVAL.replace("\"isRemove\": true", "\"isRemove\": false");
+    let data = parse(&fixture).envelope.data_message.unwrap();
+    assert!(data.reaction.is_some());
+    assert!(data.reaction_removal().is_none());
+}
+
+#[test]
+fn test_remote_delete() {
+    let data = parse(REMOTE_DELETE).envelope.data_message.unwrap();
+
+    let delete = data.remote_delete.expect("remote delete should be typed");
+    assert_eq!(delete.timestamp, 1700000000000);
+    assert_round_trip(REMOTE_DELETE);
+}
+
+#[test]
+fn test_view_once() {
+    let data = parse(VIEW_ONCE).envelope.data_message.unwrap();
+
+    assert!(data.view_once);
+    assert_eq!(data.attachments.len(), 1);
+    assert_eq!(data.attachments[0].content_type, "image/jpeg");
+    assert_eq!(data.attachments[0].width, Some(1024));
+    assert_round_trip(VIEW_ONCE);
+}
+
+#[test]
+fn test_serialized_field_names_match_signal_cli() {
+    let value = serde_json::to_value(parse(EDIT_MESSAGE)).unwrap();
+    let edit: &Value = &value["envelope"]["editMessage"];
+    assert_eq!(edit["targetSentTimestamp"], 1700000000000u64);
+    assert_eq!(edit["dataMessage"]["message"], "What's the weather in Berlin?");
+
+    let value = serde_json::to_value(parse(REMOTE_DELETE)).unwrap();
+    assert_eq!(
+        value["envelope"]["dataMessage"]["remoteDelete"]["timestamp"],
+        1700000000000u64
+    );
+}
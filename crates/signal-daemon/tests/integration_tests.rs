@@ -252,11 +252,12 @@ mod daemon_connection_tests {
     /// Test connection failure to non-existent daemon.
     #[tokio::test]
     async fn test_connect_failure() {
-        let config = DaemonConfig::new("http://127.0.0.1:59999");
+        let config = DaemonConfig::new("http://127.0.0.1:59999")
+            .with_ready_timeout(Duration::from_millis(500));
         let result = SignalClient::connect(config).await;
         assert!(result.is_err());
         match result.unwrap_err() {
-            DaemonError::Http(_) => {} // Expected
+            DaemonError::Unreachable { .. } => {} // Expected
             e => panic!("Unexpected error type: {:?}", e),
         }
     }
@@ -0,0 +1,20 @@
+-- Language-aware FTS: index a normalized `search_text` column and keep each
+-- chunk's document language so queries can be scoped per language.
+-- FTS5 tables can't gain columns, so the table is rebuilt from `chunks`.
+-- Existing rows are indexed with their raw text; `POST /kb/reindex` normalizes them.
+DROP TABLE IF EXISTS chunks_fts;
+
+CREATE VIRTUAL TABLE chunks_fts USING fts5(
+  search_text,
+  text UNINDEXED,
+  doc_id UNINDEXED,
+  chunk_id UNINDEXED,
+  title UNINDEXED,
+  lang UNINDEXED
+);
+
+INSERT INTO chunks_fts (search_text, text, doc_id, chunk_id, title, lang)
+SELECT chunks.text, chunks.text, chunks.doc_id, chunks.chunk_id, docs.title, docs.lang
+FROM chunks
+LEFT JOIN docs ON docs.doc_id = chunks.doc_id
+WHERE chunks.text IS NOT NULL AND chunks.text != '';
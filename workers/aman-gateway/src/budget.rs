@@ -0,0 +1,394 @@
+//! Global context budget shared by every prompt injection.
+//!
+//! Memory, KB and the system prompt each have their own caps, but together
+//! with the client's history they can still exceed a model's context window.
+//! [`ContextBudget`] trims the assembled [`PromptParts`] to a total character
+//! budget, shrinking the lowest-priority section first.
+
+use serde_json::Value;
+use worker::{console_error, Env};
+
+use crate::{
+    env_string, env_usize, inject_knowledge, inject_memory, inject_system_prompt, truncate_text,
+    ChatMessage, KB_CONTEXT_SUFFIX,
+};
+
+/// Characters added when KB context is appended to a system message.
+const KNOWLEDGE_SEPARATOR_CHARS: usize = 2;
+
+/// A section of the assembled prompt that can be trimmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Section {
+    System,
+    Memory,
+    Knowledge,
+    History,
+}
+
+impl Section {
+    /// Highest priority first; `History` is trimmed first by default.
+    pub(crate) const DEFAULT_PRIORITIES: [Section; 4] =
+        [Section::System, Section::Knowledge, Section::Memory, Section::History];
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "system" => Some(Self::System),
+            "memory" => Some(Self::Memory),
+            "knowledge" | "kb" => Some(Self::Knowledge),
+            "history" => Some(Self::History),
+            _ => None,
+        }
+    }
+}
+
+/// Prompt sections before they are injected into the request messages.
+#[derive(Debug, Clone)]
+pub(crate) struct PromptParts {
+    pub(crate) system: String,
+    pub(crate) memory: Option<String>,
+    pub(crate) knowledge: Option<String>,
+    /// Client messages; system messages and everything from the last user
+    /// message on are kept.
+    pub(crate) messages: Vec<ChatMessage>,
+}
+
+impl PromptParts {
+    /// Inject system prompt, memory and KB into the client messages.
+    pub(crate) fn into_messages(self) -> Vec<ChatMessage> {
+        let messages = inject_system_prompt(self.messages, &self.system);
+        let messages = inject_memory(messages, self.memory);
+        inject_knowledge(messages, self.knowledge)
+    }
+
+    /// Upper bound on the characters the assembled messages will contain.
+    pub(crate) fn total_chars(&self) -> usize {
+        [Section::System, Section::Memory, Section::Knowledge, Section::History]
+            .into_iter()
+            .map(|section| self.section_chars(section))
+            .sum::<usize>()
+            + self.pinned_chars()
+    }
+
+    fn section_chars(&self, section: Section) -> usize {
+        match section {
+            Section::System => self.system.trim().chars().count(),
+            Section::Memory => self.memory.as_deref().map_or(0, |m| m.chars().count()),
+            Section::Knowledge => self
+                .knowledge
+                .as_deref()
+                .map_or(0, |k| k.chars().count() + KNOWLEDGE_SEPARATOR_CHARS),
+            Section::History => self.messages[..self.pinned_start()]
+                .iter()
+                .filter(|msg| msg.role != "system")
+                .map(message_chars)
+                .sum(),
+        }
+    }
+
+    /// Characters in messages that are never trimmed: client system messages
+    /// and the current turn.
+    fn pinned_chars(&self) -> usize {
+        let pinned_start = self.pinned_start();
+        self.messages
+            .iter()
+            .enumerate()
+            .filter(|(index, msg)| *index >= pinned_start || msg.role == "system")
+            .map(|(_, msg)| message_chars(msg))
+            .sum()
+    }
+
+    /// Index of the last user message, which starts the current turn.
+    fn pinned_start(&self) -> usize {
+        self.messages
+            .iter()
+            .rposition(|msg| msg.role == "user")
+            .unwrap_or(self.messages.len().saturating_sub(1))
+    }
+
+    /// Shrink `section` by at least `overflow` characters, if it can.
+    fn shrink(&mut self, section: Section, overflow: usize) {
+        let max_chars = self.section_chars(section).saturating_sub(overflow);
+        match section {
+            Section::System => self.system = truncate_text(self.system.trim(), max_chars),
+            Section::Memory => {
+                self.memory = self
+                    .memory
+                    .take()
+                    .and_then(|memory| trim_lines(&memory, max_chars));
+            }
+            Section::Knowledge => {
+                let max_chars = max_chars.saturating_sub(KNOWLEDGE_SEPARATOR_CHARS);
+                self.knowledge = self
+                    .knowledge
+                    .take()
+                    .and_then(|knowledge| trim_knowledge(&knowledge, max_chars));
+            }
+            Section::History => self.drop_oldest(overflow),
+        }
+    }
+
+    /// Index of the oldest trimmable history message, skipping system messages.
+    fn oldest_turn(&self) -> Option<usize> {
+        self.messages[..self.pinned_start()]
+            .iter()
+            .position(|msg| msg.role != "system")
+    }
+
+    /// Drop the oldest history messages until `overflow` chars are freed.
+    fn drop_oldest(&mut self, overflow: usize) {
+        let mut freed = 0;
+        while freed < overflow {
+            let Some(index) = self.oldest_turn() else {
+                break;
+            };
+            freed += message_chars(&self.messages.remove(index));
+        }
+        // Tool results can't outlive the assistant message that requested them
+        while let Some(index) = self.oldest_turn() {
+            if self.messages[index].role != "tool" {
+                break;
+            }
+            self.messages.remove(index);
+        }
+    }
+}
+
+/// Total character budget with per-section priorities.
+#[derive(Debug, Clone)]
+pub(crate) struct ContextBudget {
+    max_chars: usize,
+    /// Highest priority first.
+    priorities: Vec<Section>,
+}
+
+impl ContextBudget {
+    /// Create a budget; `max_chars == 0` disables trimming.
+    ///
+    /// Sections missing from `priorities` are trimmed before any listed one.
+    pub(crate) fn new(max_chars: usize, priorities: &[Section]) -> Self {
+        let mut ordered: Vec<Section> = Vec::new();
+        for section in priorities {
+            if !ordered.contains(section) {
+                ordered.push(*section);
+            }
+        }
+        for section in Section::DEFAULT_PRIORITIES {
+            if !ordered.contains(&section) {
+                ordered.push(section);
+            }
+        }
+        Self {
+            max_chars,
+            priorities: ordered,
+        }
+    }
+
+    pub(crate) fn from_env(env: &Env) -> Self {
+        let priorities = env_string(env, "CONTEXT_PRIORITIES")
+            .map(|value| {
+                value
+                    .split(',')
+                    .filter(|item| !item.trim().is_empty())
+                    .filter_map(|item| {
+                        let parsed = Section::parse(item);
+                        if parsed.is_none() {
+                            console_error!("Invalid CONTEXT_PRIORITIES entry: {item}");
+                        }
+                        parsed
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_else(|| Section::DEFAULT_PRIORITIES.to_vec());
+        Self::new(env_usize(env, "CONTEXT_MAX_CHARS", 48_000), &priorities)
+    }
+
+    /// Trim `parts` to the budget, lowest-priority section first.
+    ///
+    /// The current turn (from the last user message on) is never trimmed, so
+    /// an oversized user message can still exceed the budget.
+    pub(crate) fn fit(&self, parts: &mut PromptParts) {
+        if self.max_chars == 0 {
+            return;
+        }
+        for section in self.priorities.iter().rev() {
+            let total = parts.total_chars();
+            if total <= self.max_chars {
+                return;
+            }
+            parts.shrink(*section, total - self.max_chars);
+        }
+    }
+}
+
+fn message_chars(message: &ChatMessage) -> usize {
+    match &message.content {
+        Value::String(text) => text.chars().count(),
+        Value::Null => 0,
+        other => other.to_string().chars().count(),
+    }
+}
+
+/// Keep whole leading lines within `max_chars`, truncating the first line
+/// if nothing else fits.
+fn trim_lines(text: &str, max_chars: usize) -> Option<String> {
+    let mut kept = String::new();
+    let mut used = 0;
+    for line in text.lines() {
+        let add = line.chars().count() + usize::from(!kept.is_empty());
+        if used + add > max_chars {
+            break;
+        }
+        if !kept.is_empty() {
+            kept.push('\n');
+        }
+        kept.push_str(line);
+        used += add;
+    }
+    if kept.trim().is_empty() {
+        kept = truncate_text(text, max_chars);
+    }
+    (!kept.trim().is_empty()).then_some(kept)
+}
+
+/// Drop trailing KB hits, keeping the closing marker; `None` once no hit fits.
+fn trim_knowledge(text: &str, max_chars: usize) -> Option<String> {
+    let body = text.trim_end().strip_suffix(KB_CONTEXT_SUFFIX)?.trim_end();
+    let max_body = max_chars.checked_sub(KB_CONTEXT_SUFFIX.chars().count() + 1)?;
+    let kept = trim_lines(body, max_body)?;
+    kept.lines()
+        .any(|line| line.starts_with("- ["))
+        .then(|| format!("{kept}\n{KB_CONTEXT_SUFFIX}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assembled_chars(messages: &[ChatMessage]) -> usize {
+        messages.iter().map(message_chars).sum()
+    }
+
+    fn parts() -> PromptParts {
+        let kb_lines = (0..5)
+            .map(|i| format!("- [Doc {i}] {}", "k".repeat(200)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut messages = Vec::new();
+        for i in 0..10 {
+            messages.push(ChatMessage::text("user", format!("question {i} {}", "u".repeat(300))));
+            messages.push(ChatMessage::text("assistant", "a".repeat(300)));
+        }
+        messages.push(ChatMessage::text("user", "current question"));
+        PromptParts {
+            system: "You are Aman. ".repeat(20),
+            memory: Some((0..10).map(|i| format!("memory line {i}")).collect::<Vec<_>>().join("\n")),
+            knowledge: Some(format!("[KNOWLEDGE BASE CONTEXT]\nAnswer using sources.\n{kb_lines}\n{KB_CONTEXT_SUFFIX}")),
+            messages,
+        }
+    }
+
+    #[test]
+    fn test_fit_stays_within_budget() {
+        for max_chars in [4000, 1500, 800, 400] {
+            let budget = ContextBudget::new(max_chars, &Section::DEFAULT_PRIORITIES);
+            let mut parts = parts();
+            assert!(parts.total_chars() > max_chars);
+
+            budget.fit(&mut parts);
+            let messages = parts.into_messages();
+
+            assert!(assembled_chars(&messages) <= max_chars, "budget {max_chars}");
+            let last = messages.last().unwrap();
+            assert_eq!(last.content, Value::String("current question".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_fit_trims_lowest_priority_first() {
+        let budget = ContextBudget::new(2000, &Section::DEFAULT_PRIORITIES);
+        let mut parts = parts();
+        let system = parts.system.clone();
+        let knowledge = parts.knowledge.clone();
+
+        budget.fit(&mut parts);
+
+        // History absorbed the overflow; higher-priority sections are intact
+        assert!(parts.messages.len() < 21);
+        assert_eq!(parts.system, system);
+        assert_eq!(parts.knowledge, knowledge);
+        assert!(parts.total_chars() <= 2000);
+    }
+
+    #[test]
+    fn test_fit_respects_custom_priorities() {
+        let budget = ContextBudget::new(7000, &[Section::History, Section::System]);
+        let mut parts = parts();
+        let history_len = parts.messages.len();
+
+        budget.fit(&mut parts);
+
+        // Knowledge and memory are unlisted, so they go before history
+        assert_eq!(parts.messages.len(), history_len);
+        assert!(parts.memory.is_none());
+        let knowledge = parts.knowledge.as_deref().unwrap_or_default();
+        assert!(knowledge.is_empty() || knowledge.ends_with(KB_CONTEXT_SUFFIX));
+        assert!(parts.total_chars() <= 7000);
+    }
+
+    #[test]
+    fn test_trim_knowledge_keeps_closing_marker() {
+        let text = format!("[KNOWLEDGE BASE CONTEXT]\n- [A] one\n- [B] two\n{KB_CONTEXT_SUFFIX}");
+        let trimmed = trim_knowledge(&text, text.chars().count() - 5).unwrap();
+        assert_eq!(trimmed, format!("[KNOWLEDGE BASE CONTEXT]\n- [A] one\n{KB_CONTEXT_SUFFIX}"));
+        assert!(trim_knowledge(&text, 40).is_none());
+    }
+
+    #[test]
+    fn test_drop_oldest_never_orphans_tool_results() {
+        let mut parts = PromptParts {
+            system: String::new(),
+            memory: None,
+            knowledge: None,
+            messages: vec![
+                ChatMessage::text("assistant", "calling tool"),
+                ChatMessage::text("tool", "result"),
+                ChatMessage::text("assistant", "done"),
+                ChatMessage::text("user", "next"),
+            ],
+        };
+        parts.drop_oldest(1);
+        assert_eq!(parts.messages[0].role, "assistant");
+        assert_eq!(parts.messages.len(), 2);
+    }
+
+    #[test]
+    fn test_fit_keeps_client_system_messages() {
+        let mut parts = PromptParts {
+            system: String::new(),
+            memory: None,
+            knowledge: None,
+            messages: vec![
+                ChatMessage::text("system", "Answer in French."),
+                ChatMessage::text("user", "u".repeat(100)),
+                ChatMessage::text("assistant", "a".repeat(100)),
+                ChatMessage::text("system", "Be brief."),
+                ChatMessage::text("user", "current question"),
+            ],
+        };
+        assert_eq!(parts.section_chars(Section::History), 200);
+
+        ContextBudget::new(100, &Section::DEFAULT_PRIORITIES).fit(&mut parts);
+
+        let roles: Vec<&str> = parts.messages.iter().map(|msg| msg.role.as_str()).collect();
+        assert_eq!(roles, ["system", "system", "user"]);
+        assert!(parts.total_chars() <= 100);
+    }
+
+    #[test]
+    fn test_zero_budget_disables_trimming() {
+        let mut parts = parts();
+        let total = parts.total_chars();
+        ContextBudget::new(0, &Section::DEFAULT_PRIORITIES).fit(&mut parts);
+        assert_eq!(parts.total_chars(), total);
+    }
+}
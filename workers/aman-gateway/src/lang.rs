@@ -0,0 +1,166 @@
+//! Language-aware normalization for KB search.
+//!
+//! `chunks_fts` stores each chunk's document `lang` next to a normalized
+//! `search_text` column. Arabic words are normalized the same way at index
+//! and query time (diacritics, letter variants and the definite article are
+//! folded), and Arabic queries use prefix terms so suffixed forms still
+//! match. Queries are scoped to their detected language when possible.
+
+/// Language code for Arabic documents and queries.
+pub(crate) const ARABIC: &str = "ar";
+/// Language code for Latin-script queries.
+pub(crate) const ENGLISH: &str = "en";
+
+/// Article and conjunction prefixes folded off Arabic words, longest first.
+const ARABIC_ARTICLES: &[&str] = &["وال", "بال", "فال", "كال", "لل", "ال"];
+
+/// Minimum characters left after stripping an Arabic article.
+const ARABIC_MIN_STEM_CHARS: usize = 2;
+
+fn is_arabic(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{0600}'..='\u{06FF}'
+            | '\u{0750}'..='\u{077F}'
+            | '\u{08A0}'..='\u{08FF}'
+            | '\u{FB50}'..='\u{FDFF}'
+            | '\u{FE70}'..='\u{FEFF}'
+    )
+}
+
+/// Arabic short vowels, dagger alef and tatweel.
+fn is_arabic_mark(ch: char) -> bool {
+    matches!(ch, '\u{064B}'..='\u{065F}' | '\u{0670}' | '\u{0640}')
+}
+
+/// Detect a query's language from its script.
+///
+/// Returns [`ARABIC`] when Arabic letters dominate, [`ENGLISH`] for
+/// Latin-script text, and `None` for other scripts.
+pub(crate) fn detect_lang(text: &str) -> Option<&'static str> {
+    let (mut arabic, mut latin) = (0usize, 0usize);
+    for ch in text.chars().filter(|ch| ch.is_alphabetic()) {
+        if is_arabic(ch) {
+            arabic += 1;
+        } else if (ch as u32) < 0x250 {
+            latin += 1;
+        }
+    }
+    match (arabic, latin) {
+        (0, 0) => None,
+        (arabic, latin) if arabic >= latin => Some(ARABIC),
+        _ => Some(ENGLISH),
+    }
+}
+
+/// Base language of a tag (`"ar-EG"` -> `"ar"`).
+pub(crate) fn base_lang(lang: &str) -> String {
+    lang.trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// Whether a document in `doc_lang` is in scope for `query_lang`.
+///
+/// Documents without a language are always in scope.
+pub(crate) fn lang_matches(doc_lang: Option<&str>, query_lang: &str) -> bool {
+    match doc_lang.map(base_lang) {
+        Some(lang) if !lang.is_empty() => lang == query_lang,
+        _ => true,
+    }
+}
+
+/// Whether queries in `lang` should use prefix terms.
+pub(crate) fn uses_prefix_terms(lang: Option<&str>) -> bool {
+    lang == Some(ARABIC)
+}
+
+/// Normalize a single word for search.
+///
+/// Arabic words drop diacritics and tatweel, fold alef/yeh/teh marbuta
+/// variants, and lose a leading definite article. Other words are
+/// lowercased.
+pub(crate) fn normalize_word(word: &str) -> String {
+    if !word.chars().any(is_arabic) {
+        return word.to_lowercase();
+    }
+
+    let folded: String = word
+        .chars()
+        .filter(|ch| !is_arabic_mark(*ch))
+        .map(|ch| match ch {
+            'أ' | 'إ' | 'آ' | 'ٱ' => 'ا',
+            'ى' => 'ي',
+            'ة' => 'ه',
+            other => other,
+        })
+        .collect();
+
+    for article in ARABIC_ARTICLES {
+        if let Some(stem) = folded.strip_prefix(article) {
+            if stem.chars().count() >= ARABIC_MIN_STEM_CHARS {
+                return stem.to_string();
+            }
+        }
+    }
+    folded
+}
+
+/// Normalize text for the `search_text` FTS column.
+///
+/// Words are normalized with [`normalize_word`]; separators are kept.
+pub(crate) fn normalize_for_search(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut word = String::new();
+    for ch in text.chars() {
+        if ch.is_alphanumeric() || is_arabic_mark(ch) {
+            word.push(ch);
+            continue;
+        }
+        if !word.is_empty() {
+            out.push_str(&normalize_word(&word));
+            word.clear();
+        }
+        out.push(ch);
+    }
+    out.push_str(&normalize_word(&word));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_lang() {
+        assert_eq!(detect_lang("ما هي حقوق اللاجئين؟"), Some(ARABIC));
+        assert_eq!(detect_lang("What are refugee rights?"), Some(ENGLISH));
+        assert_eq!(detect_lang("كيف أستخدم VPN"), Some(ARABIC));
+        assert_eq!(detect_lang("Что такое"), None);
+        assert_eq!(detect_lang("123 ?"), None);
+    }
+
+    #[test]
+    fn test_normalize_arabic_words() {
+        // Diacritics, hamza-on-alef and the definite article are folded
+        assert_eq!(normalize_word("الأَمَان"), "امان");
+        assert_eq!(normalize_word("والمدرسة"), "مدرسه");
+        assert_eq!(normalize_word("إلى"), "الي");
+        assert_eq!(normalize_word("VPN"), "vpn");
+        assert_eq!(
+            normalize_for_search("(الأمان) الرقمي، مهم."),
+            "(امان) رقمي، مهم."
+        );
+    }
+
+    #[test]
+    fn test_lang_matches() {
+        assert!(lang_matches(Some("ar-EG"), ARABIC));
+        assert!(lang_matches(Some("AR"), ARABIC));
+        assert!(!lang_matches(Some("en"), ARABIC));
+        assert!(lang_matches(None, ARABIC));
+        assert!(lang_matches(Some(""), ENGLISH));
+    }
+}
@@ -0,0 +1,239 @@
+//! NIP-44 v2 payload decryption for KB events tagged `enc=nip44-v2`.
+//!
+//! Implements the spec directly (secp256k1 ECDH + HKDF-SHA256, ChaCha20,
+//! HMAC-SHA256 over nonce || ciphertext) so payloads published by standard
+//! Nostr tooling can be read without pulling a full Nostr SDK into the worker.
+
+use base64::Engine;
+use chacha20::cipher::{KeyIvInit, StreamCipher};
+use chacha20::ChaCha20;
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const TAG: &str = "nip44-v2";
+
+const VERSION: u8 = 2;
+const SALT: &[u8] = b"nip44-v2";
+const NONCE_LEN: usize = 32;
+const MAC_LEN: usize = 32;
+const MIN_PLAINTEXT_LEN: usize = 1;
+const MAX_PLAINTEXT_LEN: usize = 65535;
+const MAX_PADDED_LEN: usize = padded_len(MAX_PLAINTEXT_LEN);
+/// Decoded payload bounds: version + nonce + padded (2-byte length + padding) + mac.
+const MIN_PAYLOAD_LEN: usize = 1 + NONCE_LEN + 2 + 32 + MAC_LEN;
+const MAX_PAYLOAD_LEN: usize = 1 + NONCE_LEN + 2 + MAX_PADDED_LEN + MAC_LEN;
+
+/// Per-message keys expanded from the conversation key and nonce.
+struct MessageKeys {
+    chacha_key: [u8; 32],
+    chacha_nonce: [u8; 12],
+    hmac_key: [u8; 32],
+}
+
+/// Symmetric key shared by a sender/recipient pair.
+pub struct ConversationKey([u8; 32]);
+
+impl ConversationKey {
+    /// Derive from our secret key and the peer's x-only public key.
+    ///
+    /// Symmetric: `derive(a, B) == derive(b, A)`.
+    pub fn derive(secret_key: &[u8; 32], public_key: &[u8; 32]) -> Result<Self, String> {
+        let secret = k256::SecretKey::from_slice(secret_key)
+            .map_err(|_| "invalid secret key".to_string())?;
+        let mut sec1 = [0u8; 33];
+        sec1[0] = 0x02;
+        sec1[1..].copy_from_slice(public_key);
+        let public = k256::PublicKey::from_sec1_bytes(&sec1)
+            .map_err(|_| "invalid public key".to_string())?;
+
+        let shared = k256::ecdh::diffie_hellman(secret.to_nonzero_scalar(), public.as_affine());
+        let (prk, _) = Hkdf::<Sha256>::extract(Some(SALT), shared.raw_secret_bytes());
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&prk);
+        Ok(Self(key))
+    }
+
+    fn message_keys(&self, nonce: &[u8]) -> Result<MessageKeys, String> {
+        let hkdf = Hkdf::<Sha256>::from_prk(&self.0).map_err(|_| "invalid conversation key")?;
+        let mut okm = [0u8; 76];
+        hkdf.expand(nonce, &mut okm)
+            .map_err(|_| "message key expansion failed")?;
+        let mut keys = MessageKeys {
+            chacha_key: [0u8; 32],
+            chacha_nonce: [0u8; 12],
+            hmac_key: [0u8; 32],
+        };
+        keys.chacha_key.copy_from_slice(&okm[..32]);
+        keys.chacha_nonce.copy_from_slice(&okm[32..44]);
+        keys.hmac_key.copy_from_slice(&okm[44..]);
+        Ok(keys)
+    }
+}
+
+/// Decrypt a base64 NIP-44 v2 payload.
+pub fn decrypt(key: &ConversationKey, payload: &str) -> Result<String, String> {
+    let payload = payload.trim();
+    if payload.starts_with('#') {
+        return Err("unsupported NIP-44 encoding".to_string());
+    }
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .map_err(|err| format!("invalid base64: {err}"))?;
+    if !(MIN_PAYLOAD_LEN..=MAX_PAYLOAD_LEN).contains(&data.len()) {
+        return Err(format!("invalid payload length: {}", data.len()));
+    }
+    if data[0] != VERSION {
+        return Err(format!("unsupported NIP-44 version: {}", data[0]));
+    }
+
+    let nonce = &data[1..1 + NONCE_LEN];
+    let (ciphertext, mac) = data[1 + NONCE_LEN..].split_at(data.len() - 1 - NONCE_LEN - MAC_LEN);
+    let keys = key.message_keys(nonce)?;
+
+    let mut hmac = Hmac::<Sha256>::new_from_slice(&keys.hmac_key)
+        .map_err(|_| "invalid HMAC key".to_string())?;
+    hmac.update(nonce);
+    hmac.update(ciphertext);
+    hmac.verify_slice(mac)
+        .map_err(|_| "invalid MAC".to_string())?;
+
+    let mut padded = ciphertext.to_vec();
+    ChaCha20::new(&keys.chacha_key.into(), &keys.chacha_nonce.into()).apply_keystream(&mut padded);
+    unpad(&padded)
+}
+
+/// Encrypt `plaintext` with an explicit 32-byte nonce.
+#[cfg(test)]
+pub fn encrypt_with_nonce(
+    key: &ConversationKey,
+    plaintext: &str,
+    nonce: &[u8; NONCE_LEN],
+) -> Result<String, String> {
+    let mut padded = pad(plaintext)?;
+    let keys = key.message_keys(nonce)?;
+    ChaCha20::new(&keys.chacha_key.into(), &keys.chacha_nonce.into()).apply_keystream(&mut padded);
+
+    let mut hmac = Hmac::<Sha256>::new_from_slice(&keys.hmac_key)
+        .map_err(|_| "invalid HMAC key".to_string())?;
+    hmac.update(nonce);
+    hmac.update(&padded);
+    let mac = hmac.finalize().into_bytes();
+
+    let mut data = Vec::with_capacity(1 + NONCE_LEN + padded.len() + MAC_LEN);
+    data.push(VERSION);
+    data.extend_from_slice(nonce);
+    data.extend_from_slice(&padded);
+    data.extend_from_slice(&mac);
+    Ok(base64::engine::general_purpose::STANDARD.encode(data))
+}
+
+/// x-only public key for a secret key.
+#[cfg(test)]
+pub fn x_only_public_key(secret_key: &[u8; 32]) -> [u8; 32] {
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    let secret = k256::SecretKey::from_slice(secret_key).unwrap();
+    let point = secret.public_key().to_encoded_point(true);
+    let mut x = [0u8; 32];
+    x.copy_from_slice(&point.as_bytes()[1..]);
+    x
+}
+
+/// Padded plaintext length: 32-byte minimum, then power-of-two chunks.
+const fn padded_len(len: usize) -> usize {
+    if len <= 32 {
+        return 32;
+    }
+    let next_power = 1usize << (usize::BITS - (len - 1).leading_zeros());
+    let chunk = if next_power <= 256 {
+        32
+    } else {
+        next_power / 8
+    };
+    chunk * ((len - 1) / chunk + 1)
+}
+
+#[cfg(test)]
+fn pad(plaintext: &str) -> Result<Vec<u8>, String> {
+    let bytes = plaintext.as_bytes();
+    if !(MIN_PLAINTEXT_LEN..=MAX_PLAINTEXT_LEN).contains(&bytes.len()) {
+        return Err(format!("invalid plaintext length: {}", bytes.len()));
+    }
+    let mut padded = Vec::with_capacity(2 + padded_len(bytes.len()));
+    padded.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    padded.extend_from_slice(bytes);
+    padded.resize(2 + padded_len(bytes.len()), 0);
+    Ok(padded)
+}
+
+fn unpad(padded: &[u8]) -> Result<String, String> {
+    let len = u16::from_be_bytes([padded[0], padded[1]]) as usize;
+    if len < MIN_PLAINTEXT_LEN || padded.len() != 2 + padded_len(len) {
+        return Err("invalid padding".to_string());
+    }
+    String::from_utf8(padded[2..2 + len].to_vec()).map_err(|err| format!("invalid utf8: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_bytes(last: u8) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        key[31] = last;
+        key
+    }
+
+    #[test]
+    fn test_spec_vector() {
+        // First `encrypt_decrypt` vector from the NIP-44 spec
+        let sec1 = key_bytes(1);
+        let sec2 = key_bytes(2);
+        let key = ConversationKey::derive(&sec1, &x_only_public_key(&sec2)).unwrap();
+        assert_eq!(
+            hex::encode(key.0),
+            "c41c775356fd92eadc63ff5a0dc1da211b268cbea22316767095b2871ea1412d"
+        );
+
+        let payload = "AgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABee0G5VSK0/9YypIObAtDKfYEAjD35uVkHyB0F4DwrcNaCXlCWZKaArsGrY6M9wnuTMxWfp1RTN9Xga8no+kF5Vsb";
+        assert_eq!(
+            encrypt_with_nonce(&key, "a", &key_bytes(1)).unwrap(),
+            payload
+        );
+        assert_eq!(decrypt(&key, payload).unwrap(), "a");
+    }
+
+    #[test]
+    fn test_roundtrip_is_symmetric_and_authenticated() {
+        let (alice, bob) = (key_bytes(3), key_bytes(4));
+        let sender = ConversationKey::derive(&alice, &x_only_public_key(&bob)).unwrap();
+        let recipient = ConversationKey::derive(&bob, &x_only_public_key(&alice)).unwrap();
+
+        let text = "مرحبا ".repeat(40);
+        let payload = encrypt_with_nonce(&sender, &text, &[9u8; 32]).unwrap();
+        assert_eq!(decrypt(&recipient, &payload).unwrap(), text);
+
+        let stranger = ConversationKey::derive(&key_bytes(5), &x_only_public_key(&alice)).unwrap();
+        assert_eq!(decrypt(&stranger, &payload).unwrap_err(), "invalid MAC");
+        assert!(decrypt(&recipient, "#unsupported").is_err());
+    }
+
+    #[test]
+    fn test_padded_len() {
+        assert_eq!(padded_len(1), 32);
+        assert_eq!(padded_len(32), 32);
+        assert_eq!(padded_len(33), 64);
+        assert_eq!(padded_len(257), 320);
+        assert_eq!(padded_len(65535), 65536);
+    }
+
+    #[test]
+    fn test_max_length_plaintext_roundtrips() {
+        let public_key = x_only_public_key(&key_bytes(4));
+        let key = ConversationKey::derive(&key_bytes(3), &public_key).unwrap();
+        let text = "a".repeat(MAX_PLAINTEXT_LEN);
+        let payload = encrypt_with_nonce(&key, &text, &[7u8; 32]).unwrap();
+        assert_eq!(MAX_PAYLOAD_LEN, 65603);
+        assert_eq!(decrypt(&key, &payload).unwrap(), text);
+    }
+}